@@ -2,7 +2,10 @@
 //! This contains all the General-Traits for Tunneload that are used in a wide variety of
 //! places and that dont really have one single place to exist in other than this Crate
 
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    net::{IpAddr, SocketAddr},
+};
 
 use async_trait::async_trait;
 use general::Name;
@@ -88,6 +91,34 @@ pub trait Receiver: Send + std::fmt::Debug {
 
         Ok(())
     }
+
+    /// Returns the IP-Address of the other Side of the Connection, if
+    /// it is known
+    fn peer_ip(&self) -> Option<IpAddr> {
+        None
+    }
+
+    /// Returns the full Address of the other Side of the Connection, if
+    /// it is known
+    fn peer_address(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Returns the local Address of the Connection, if it is known
+    fn local_address(&self) -> Option<SocketAddr> {
+        None
+    }
+
+    /// Checks if the Data of the Connection is encrypted using TLS
+    fn is_tls(&self) -> bool {
+        false
+    }
+
+    /// Returns the Name of the Entrypoint that accepted the Connection,
+    /// if it is known
+    fn entrypoint(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// The Bounds needed to register a new Entity on the
@@ -115,6 +146,18 @@ impl Receiver for tokio::net::TcpStream {
     async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         AsyncReadExt::read(self, buf).await
     }
+
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_addr().ok().map(|addr| addr.ip())
+    }
+
+    fn peer_address(&self) -> Option<SocketAddr> {
+        self.peer_addr().ok()
+    }
+
+    fn local_address(&self) -> Option<SocketAddr> {
+        self.local_addr().ok()
+    }
 }
 #[async_trait]
 impl Sender for tokio::net::TcpStream {
@@ -131,6 +174,18 @@ impl Receiver for tokio::net::tcp::OwnedReadHalf {
     async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         AsyncReadExt::read(self, buf).await
     }
+
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_addr().ok().map(|addr| addr.ip())
+    }
+
+    fn peer_address(&self) -> Option<SocketAddr> {
+        self.peer_addr().ok()
+    }
+
+    fn local_address(&self) -> Option<SocketAddr> {
+        self.local_addr().ok()
+    }
 }
 #[async_trait]
 impl Sender for tokio::net::tcp::OwnedWriteHalf {
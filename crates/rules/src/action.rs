@@ -1,12 +1,25 @@
+use general::Name;
 use plugins::ActionPluginInstance;
 
 use serde::Serialize;
 use stream_httparse::{Request, Response};
 
+use crate::{BodyTransform, ConnectionInfo, EarlyResponse, Pattern};
+
 mod basic_auth;
 mod compress;
 mod cors;
+mod forward_auth;
+pub use forward_auth::{AuthAddressError, ForwardAuth};
+mod ip_list;
+pub use ip_list::IpListOpts;
+mod rate_limit;
+pub use rate_limit::{RateLimit, RateLimitKey};
+mod redirect;
+pub use redirect::{RedirectRegexOpts, RedirectSchemeOpts, RedirectStatus};
 mod remove_prefix;
+mod rewrite;
+pub use rewrite::ReplacePathRegexOpts;
 
 /// The Options to configure CORS
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -30,8 +43,17 @@ pub struct CorsOpts {
 pub enum Action {
     /// This does nothing and is the default Action
     Noop,
-    /// Removes the provided Prefix from the Path of the Request
-    RemovePrefix(String),
+    /// Removes the first of the provided Prefixes, that the Path of the
+    /// Request starts with
+    RemovePrefix(Vec<String>),
+    /// Adds the provided Prefix to the Path of the Request
+    AddPrefix(String),
+    /// Removes the first Match of the Patterns at the start of the Path
+    StripPrefixRegex(Vec<Pattern>),
+    /// Replaces the Path of the Request
+    ReplacePath(String),
+    /// Replaces the Path of the Request, if it matches the Pattern
+    ReplacePathRegex(ReplacePathRegexOpts),
     /// Adds the List of Headers to every Request or Response
     AddHeaders(Vec<(String, String)>),
     /// Compresses the Response-Body
@@ -40,6 +62,18 @@ pub enum Action {
     Cors(CorsOpts),
     /// Allows for very basic Authentication of Requests and Users
     BasicAuth(htpasswd::Htpasswd),
+    /// Rejects all the Requests from IPs outside of the given Networks
+    IpAllowList(IpListOpts),
+    /// Rejects all the Requests from IPs in the given Networks
+    IpDenyList(IpListOpts),
+    /// Redirects all the Requests to another Scheme
+    RedirectScheme(RedirectSchemeOpts),
+    /// Redirects all the Requests whose URL matches a Pattern
+    RedirectRegex(RedirectRegexOpts),
+    /// Authenticates all the Requests using an external Auth-Service
+    ForwardAuth(ForwardAuth),
+    /// Limits the Rate of Requests and rejects the ones above the Limit
+    RateLimit(RateLimit),
     /// This holds an arbitrary Plugin
     Plugin(ActionPluginInstance),
 }
@@ -54,22 +88,51 @@ impl Action {
         Action::BasicAuth(htpasswd::load(htpasswd_str.as_ref()))
     }
 
-    /// Applies the Action to the given Request
-    pub fn apply_req<'a, 'b>(&self, req: &mut Request<'a>) -> Result<(), Response<'b>>
+    /// Applies the Action to the given Request, that was received on a
+    /// Connection described by the given Info and matched by the Rule
+    /// with the given Name
+    pub async fn apply_req<'a, 'b>(
+        &self,
+        req: &mut Request<'a>,
+        info: &ConnectionInfo,
+        rule: &Name,
+    ) -> Result<(), EarlyResponse<'b>>
     where
         'a: 'b,
     {
         match *self {
             Self::Noop => Ok(()),
-            Self::RemovePrefix(ref prefix) => {
-                remove_prefix::apply_req(req, prefix);
+            Self::RemovePrefix(ref prefixes) => {
+                remove_prefix::apply_req(req, prefixes);
+                Ok(())
+            }
+            Self::AddPrefix(ref prefix) => {
+                rewrite::apply_add_prefix(req, prefix);
+                Ok(())
+            }
+            Self::StripPrefixRegex(ref patterns) => {
+                rewrite::apply_strip_prefix_regex(req, patterns);
+                Ok(())
+            }
+            Self::ReplacePath(ref path) => {
+                rewrite::apply_replace_path(req, path);
+                Ok(())
+            }
+            Self::ReplacePathRegex(ref opts) => {
+                rewrite::apply_replace_path_regex(req, opts);
                 Ok(())
             }
             Self::AddHeaders(_) => Ok(()),
             Self::Compress => Ok(()),
             Self::Cors(_) => Ok(()),
-            Self::BasicAuth(ref creds) => basic_auth::apply_req(req, creds),
-            Self::Plugin(ref instance) => instance.apply_req(req),
+            Self::BasicAuth(ref creds) => basic_auth::apply_req(req, creds).map_err(Into::into),
+            Self::IpAllowList(ref opts) => ip_list::apply_allow(req, info, opts),
+            Self::IpDenyList(ref opts) => ip_list::apply_deny(req, info, opts),
+            Self::RedirectScheme(ref opts) => redirect::apply_scheme(req, info, opts),
+            Self::RedirectRegex(ref opts) => redirect::apply_regex(req, info, opts),
+            Self::ForwardAuth(ref auth) => forward_auth::apply_req(req, auth).await,
+            Self::RateLimit(ref limit) => rate_limit::apply_req(req, info, rule, limit),
+            Self::Plugin(ref instance) => instance.apply_req(req).map_err(Into::into),
         }
     }
 
@@ -82,7 +145,8 @@ impl Action {
     {
         match *self {
             Self::Noop => {}
-            Self::RemovePrefix(_) => {}
+            Self::RemovePrefix(_) | Self::AddPrefix(_) | Self::StripPrefixRegex(_) => {}
+            Self::ReplacePath(_) | Self::ReplacePathRegex(_) => {}
             Self::AddHeaders(ref headers) => {
                 for (key, value) in headers {
                     resp.add_header(key.as_str(), value.as_str());
@@ -95,9 +159,41 @@ impl Action {
                 cors::apply_req(req, resp, opts);
             }
             Self::BasicAuth(_) => {}
+            Self::IpAllowList(_) | Self::IpDenyList(_) => {}
+            Self::RedirectScheme(_) | Self::RedirectRegex(_) => {}
+            Self::ForwardAuth(_) => {}
+            Self::RateLimit(_) => {}
             Self::Plugin(ref instance) => instance.apply_resp(req, resp),
         }
     }
+
+    /// Checks if the Action can be applied to a Response whose Body is
+    /// streamed, otherwise it needs the whole Response at once
+    pub fn streams_body(&self) -> bool {
+        !matches!(self, Self::Plugin(_))
+    }
+
+    /// Applies the Action to the Head of a Response whose Body is
+    /// streamed and returns the Transform for the Body, if the Action
+    /// modifies the Body
+    pub fn apply_resp_stream<'a, 'b, 'c>(
+        &'a self,
+        req: &Request<'_>,
+        resp: &'b mut Response<'c>,
+    ) -> Option<Box<dyn BodyTransform>>
+    where
+        'a: 'b,
+        'a: 'c,
+        'c: 'b,
+    {
+        match *self {
+            Self::Compress => compress::apply_stream(req, resp),
+            _ => {
+                self.apply_resp(req, resp);
+                None
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -106,8 +202,8 @@ mod tests {
 
     use stream_httparse::{Headers, Method, StatusCode};
 
-    #[test]
-    fn apply_req_add_header() {
+    #[tokio::test]
+    async fn apply_req_add_header() {
         let headers = Headers::new();
         let mut req = Request::new(
             "HTTP/1.1",
@@ -120,7 +216,12 @@ mod tests {
         // This is expected to do nothing, as the AddHeader Action only performs
         // actions on Responses not Requests
         let action = Action::AddHeaders(vec![("Test-1".to_owned(), "Value-1".to_owned())]);
-        assert_eq!(false, action.apply_req(&mut req).is_err());
+        let info = ConnectionInfo::default();
+        let rule = Name::new("test", general::Group::Internal);
+        assert_eq!(
+            false,
+            action.apply_req(&mut req, &info, &rule).await.is_err()
+        );
         assert_eq!(headers, *req.headers());
     }
     #[test]
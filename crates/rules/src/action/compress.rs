@@ -1,4 +1,6 @@
-use stream_httparse::{header::HeaderValue, Request, Response};
+use stream_httparse::{header::HeaderValue, Request, Response, StatusCode};
+
+use crate::BodyTransform;
 
 use flate2::{write::GzEncoder, Compression};
 use std::io::prelude::*;
@@ -13,6 +15,15 @@ fn is_compression_enabled(tokens: &str) -> bool {
     false
 }
 
+/// Checks if the Client accepts a gzip compressed Body
+fn accepts_gzip(req: &Request<'_>) -> bool {
+    match req.headers().get("Accept-Encoding") {
+        Some(HeaderValue::Str(ref value)) => is_compression_enabled(value),
+        Some(HeaderValue::StrRef(value)) => is_compression_enabled(value),
+        _ => false,
+    }
+}
+
 pub fn apply_req(req: &Request<'_>, resp: &mut Response<'_>) {
     if resp.headers().get("Content-Encoding").is_some() {
         return;
@@ -20,24 +31,9 @@ pub fn apply_req(req: &Request<'_>, resp: &mut Response<'_>) {
     if resp.headers().get("Transfer-Encoding").is_some() {
         return;
     }
-    match req.headers().get("Accept-Encoding") {
-        Some(value) => {
-            let tmp: &str = match value {
-                HeaderValue::Str(ref value) => value,
-                HeaderValue::StrRef(value) => value,
-                _ => {
-                    return;
-                }
-            };
-
-            if !is_compression_enabled(tmp) {
-                return;
-            }
-        }
-        None => {
-            return;
-        }
-    };
+    if !accepts_gzip(req) {
+        return;
+    }
 
     let mut e = GzEncoder::new(Vec::with_capacity(resp.body().len()), Compression::fast());
     e.write_all(resp.body())
@@ -50,11 +46,56 @@ pub fn apply_req(req: &Request<'_>, resp: &mut Response<'_>) {
     resp.add_header("content-length", body_length);
 }
 
+/// Compresses the Body of a Response while it is being streamed
+struct GzipTransform {
+    encoder: GzEncoder<Vec<u8>>,
+}
+
+impl BodyTransform for GzipTransform {
+    fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        self.encoder
+            .write_all(data)
+            .expect("We should always be able to write the Body to the Encoder");
+        std::mem::take(self.encoder.get_mut())
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        self.encoder
+            .try_finish()
+            .expect("The Encoder should always work");
+        std::mem::take(self.encoder.get_mut())
+    }
+}
+
+/// Marks the Head of a streamed Response as compressed and returns the
+/// Transform that compresses its Body, the Length of the compressed
+/// Body is not known in advance
+pub fn apply_stream(req: &Request<'_>, resp: &mut Response<'_>) -> Option<Box<dyn BodyTransform>> {
+    if resp.headers().get("Content-Encoding").is_some() {
+        return None;
+    }
+    if matches!(
+        resp.status_code(),
+        StatusCode::NoContent | StatusCode::NotModified
+    ) {
+        return None;
+    }
+    if !accepts_gzip(req) {
+        return None;
+    }
+
+    resp.add_header("content-encoding", "gzip");
+    Some(Box::new(GzipTransform {
+        encoder: GzEncoder::new(Vec::new(), Compression::fast()),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use stream_httparse::{Headers, Method, StatusCode};
+    use flate2::read::GzDecoder;
+    use stream_httparse::{Headers, Method};
 
     #[test]
     fn apply_valid() {
@@ -221,4 +262,51 @@ mod tests {
         assert_eq!(None, resp.headers().get("Content-Encoding"));
         assert_eq!(None, resp.headers().get("content-length"));
     }
+
+    #[test]
+    fn apply_stream_chunked() {
+        let mut req_headers = Headers::new();
+        req_headers.set("Accept-Encoding", "gzip, deflate, br");
+        let req = Request::new(
+            "HTTP/1.1",
+            Method::GET,
+            "/some/path",
+            req_headers,
+            "".as_bytes(),
+        );
+        let mut resp_headers = Headers::new();
+        resp_headers.set("Transfer-Encoding", "chunked");
+        let mut resp = Response::new("HTTP/1.1", StatusCode::OK, resp_headers, Vec::new());
+
+        let mut transform = apply_stream(&req, &mut resp).unwrap();
+        assert_eq!(
+            Some(&HeaderValue::StrRef("gzip")),
+            resp.headers().get("content-encoding")
+        );
+
+        let mut compressed = transform.transform("some ".as_bytes());
+        compressed.extend(transform.transform("test data".as_bytes()));
+        compressed.extend(transform.finish());
+
+        let mut decompressed = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!("some test data", decompressed);
+    }
+
+    #[test]
+    fn apply_stream_not_accepted() {
+        let req = Request::new(
+            "HTTP/1.1",
+            Method::GET,
+            "/some/path",
+            Headers::new(),
+            "".as_bytes(),
+        );
+        let mut resp = Response::new("HTTP/1.1", StatusCode::OK, Headers::new(), Vec::new());
+
+        assert_eq!(true, apply_stream(&req, &mut resp).is_none());
+        assert_eq!(None, resp.headers().get("content-encoding"));
+    }
 }
@@ -0,0 +1,499 @@
+use std::{fmt::Display, time::Duration};
+
+use serde::Serialize;
+use stream_httparse::{streaming_parser::RespParser, Headers, Request, Response, StatusCode};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::EarlyResponse;
+
+/// The default Time after which a Request to the Auth-Service is
+/// considered to have failed
+const DEFAULT_AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The maximum Size of the Response of the Auth-Service, including its
+/// Head, larger Responses are treated as a failed Request
+const MAX_AUTH_RESPONSE: usize = 64 * 1024;
+
+/// The Headers that only apply to a single Connection or are replaced
+/// for the Auth-Request and are therefore never forwarded
+const SKIPPED_HEADERS: [&str; 10] = [
+    "Host",
+    "Content-Length",
+    "Connection",
+    "Keep-Alive",
+    "Proxy-Authenticate",
+    "Proxy-Authorization",
+    "TE",
+    "Trailer",
+    "Transfer-Encoding",
+    "Upgrade",
+];
+
+/// The X-Forwarded Headers, which are always forwarded to the
+/// Auth-Service if they are set
+const FORWARDED_HEADERS: [&str; 3] = ["X-Forwarded-For", "X-Forwarded-Proto", "X-Forwarded-Host"];
+
+/// The Error returned when the Address of an Auth-Service can not be used
+#[derive(Debug, PartialEq)]
+pub enum AuthAddressError {
+    /// The Address does not start with "http://", as TLS is not supported
+    /// for Auth-Services
+    UnsupportedScheme(String),
+    /// The Address does not contain a Host
+    MissingHost(String),
+}
+impl Display for AuthAddressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            AuthAddressError::UnsupportedScheme(ref a) => {
+                write!(f, "Unsupported Scheme for Auth-Service: {}", a)
+            }
+            AuthAddressError::MissingHost(ref a) => {
+                write!(f, "Missing Host for Auth-Service: {}", a)
+            }
+        }
+    }
+}
+impl std::error::Error for AuthAddressError {}
+
+/// Authenticates every Request using an external Auth-Service, which is
+/// asked with a GET-Request first and either accepts the Request using
+/// a 2xx Response or rejects it with any other Response
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ForwardAuth {
+    address: String,
+    #[serde(skip)]
+    authority: String,
+    #[serde(skip)]
+    path: String,
+    request_headers: Vec<String>,
+    response_headers: Vec<String>,
+    timeout: Duration,
+}
+
+impl ForwardAuth {
+    /// Creates a new ForwardAuth for the given Address of the Auth-Service.
+    ///
+    /// Only "http://" Addresses are supported, as the Auth-Service is
+    /// connected to without TLS.
+    ///
+    /// Only the Request-Headers are forwarded to the Auth-Service, all the
+    /// Headers are forwarded if this is empty. The Response-Headers are
+    /// copied from the Response of the Auth-Service onto the Request
+    pub fn new(
+        address: &str,
+        request_headers: Vec<String>,
+        response_headers: Vec<String>,
+    ) -> Result<Self, AuthAddressError> {
+        let without_scheme = address
+            .strip_prefix("http://")
+            .ok_or_else(|| AuthAddressError::UnsupportedScheme(address.to_owned()))?;
+        let (authority, path) = match without_scheme.find('/') {
+            Some(index) => without_scheme.split_at(index),
+            None => (without_scheme, "/"),
+        };
+        if authority.is_empty() {
+            return Err(AuthAddressError::MissingHost(address.to_owned()));
+        }
+
+        Ok(Self {
+            address: address.to_owned(),
+            authority: authority.to_owned(),
+            path: path.to_owned(),
+            request_headers,
+            response_headers,
+            timeout: DEFAULT_AUTH_TIMEOUT,
+        })
+    }
+
+    /// Sets the Time after which a Request to the Auth-Service is
+    /// considered to have failed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The Address that is used to connect to the Auth-Service
+    fn connect_address(&self) -> String {
+        let has_port = match self.authority.rfind(':') {
+            Some(index) => !self.authority[index..].contains(']'),
+            None => false,
+        };
+
+        if has_port {
+            self.authority.clone()
+        } else {
+            format!("{}:80", self.authority)
+        }
+    }
+
+    /// Serializes the Request that is sent to the Auth-Service for the
+    /// given Request
+    fn auth_request(&self, req: &Request<'_>) -> Vec<u8> {
+        let mut result = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nX-Forwarded-Method: {}\r\nX-Forwarded-Uri: {}\r\n",
+            self.path,
+            self.authority,
+            req.method().serialize(),
+            req.path()
+        )
+        .into_bytes();
+
+        if self.request_headers.is_empty() {
+            // The Headers can not be iterated directly, so they are
+            // serialized and then filtered line by line
+            let mut raw_headers = Vec::new();
+            req.headers().serialize(&mut raw_headers);
+
+            for line in raw_headers.split(|b| *b == b'\n') {
+                let key = match std::str::from_utf8(line)
+                    .ok()
+                    .and_then(|l| l.split_once(':'))
+                {
+                    Some((key, _)) => key,
+                    None => continue,
+                };
+                if SKIPPED_HEADERS.iter().any(|s| s.eq_ignore_ascii_case(key)) {
+                    continue;
+                }
+
+                result.extend_from_slice(line);
+                result.push(b'\n');
+            }
+        } else {
+            let names = self
+                .request_headers
+                .iter()
+                .map(|n| n.as_str())
+                .chain(FORWARDED_HEADERS.iter().copied());
+            for name in names {
+                if SKIPPED_HEADERS.iter().any(|s| s.eq_ignore_ascii_case(name)) {
+                    continue;
+                }
+                if let Some(value) = req.headers().get(name) {
+                    result.extend_from_slice(
+                        format!("{}: {}\r\n", name, value.to_string()).as_bytes(),
+                    );
+                }
+            }
+        }
+
+        result.extend_from_slice(b"\r\n");
+        result
+    }
+
+    /// Sends the Auth-Request and reads the Response of the Auth-Service
+    async fn send(&self, auth_req: &[u8]) -> Option<Response<'static>> {
+        let mut connection = TcpStream::connect(self.connect_address()).await.ok()?;
+        connection.write_all(auth_req).await.ok()?;
+
+        let mut parser = RespParser::new_capacity(1024);
+        let mut buffer = [0; 2048];
+        // The Bytes after the Head, that were not consumed by the Parser
+        let mut rest = Vec::new();
+        let mut total = 0;
+        loop {
+            let n_read = connection.read(&mut buffer).await.ok()?;
+            if n_read == 0 {
+                break;
+            }
+            total += n_read;
+            if total > MAX_AUTH_RESPONSE {
+                return None;
+            }
+
+            let (done, left) = parser.block_parse(&buffer[..n_read]);
+            if done {
+                rest.extend_from_slice(&buffer[n_read - left..n_read]);
+                break;
+            }
+        }
+
+        let response = parser.finish_owned().ok()?;
+        if response.headers().get("Content-Length").is_some() {
+            return Some(response);
+        }
+
+        // The Parser only reads Bodies with a Content-Length, any other Body
+        // ends with the Connection, as the Request asked to close it
+        let limit = (MAX_AUTH_RESPONSE - total) as u64;
+        let n_read = (&mut connection)
+            .take(limit + 1)
+            .read_to_end(&mut rest)
+            .await
+            .ok()?;
+        if n_read as u64 > limit {
+            return None;
+        }
+        let body = if response.is_chunked() {
+            decode_chunked(&rest)?
+        } else {
+            rest
+        };
+
+        Some(Response::new(
+            "HTTP/1.1",
+            response.status_code().clone(),
+            response.headers().clone(),
+            body,
+        ))
+    }
+}
+
+/// Decodes the given chunked Body, returns None if it is malformed or
+/// incomplete
+fn decode_chunked(mut raw: &[u8]) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    loop {
+        let line_end = raw.windows(2).position(|w| w == b"\r\n")?;
+        let line = std::str::from_utf8(&raw[..line_end]).ok()?;
+        // Chunk-Extensions are ignored
+        let size = line.split(';').next()?.trim();
+        let size = usize::from_str_radix(size, 16).ok()?;
+        raw = &raw[line_end + 2..];
+
+        // The Trailers after the last Chunk are ignored
+        if size == 0 {
+            return Some(result);
+        }
+        if raw.len() < size + 2 || &raw[size..size + 2] != b"\r\n" {
+            return None;
+        }
+
+        result.extend_from_slice(&raw[..size]);
+        raw = &raw[size + 2..];
+    }
+}
+
+fn error_response(protocol: &str) -> Response<'_> {
+    let mut headers = Headers::new();
+    headers.set("Content-Length", 0);
+
+    Response::new(protocol, StatusCode::InternalServerError, headers, vec![])
+}
+
+/// Asks the Auth-Service whether the Request should be accepted, an
+/// accepted Request receives the configured Headers from the Response
+/// and a rejected one is answered with the Response of the Auth-Service
+pub async fn apply_req<'a>(
+    req: &mut Request<'a>,
+    auth: &ForwardAuth,
+) -> Result<(), EarlyResponse<'a>> {
+    let auth_req = auth.auth_request(req);
+    let auth_resp = match tokio::time::timeout(auth.timeout, auth.send(&auth_req)).await {
+        Ok(Some(r)) => r,
+        _ => {
+            tracing::error!("Requesting Auth-Service: {}", auth.address);
+            return Err(error_response(req.protocol()).into());
+        }
+    };
+
+    if !auth_resp.status_code().serialize().starts_with('2') {
+        // The Body was already read completely, so it is sent with a
+        // fixed Length to the Client
+        let mut headers = auth_resp.headers().clone();
+        headers.remove("Connection");
+        headers.remove("Keep-Alive");
+        headers.remove("Transfer-Encoding");
+        headers.set("Content-Length", auth_resp.body().len());
+
+        return Err(Response::new(
+            req.protocol(),
+            auth_resp.status_code().clone(),
+            headers,
+            auth_resp.body().to_vec(),
+        )
+        .into());
+    }
+
+    for name in auth.response_headers.iter() {
+        // Every Value sent by the Client is removed, as only the first one
+        // would be replaced otherwise
+        while req.headers().get(name.as_str()).is_some() {
+            req.header_mut().remove(name.clone());
+        }
+        if let Some(value) = auth_resp.headers().get(name.as_str()) {
+            req.header_mut().set(name.clone(), value.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::Method;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Starts an Auth-Service that answers a single Request with the
+    /// given raw Response and returns the Address as well as the Request
+    /// it received
+    async fn auth_service(response: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}/verify", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let (mut connection, _) = listener.accept().await.unwrap();
+
+            let mut received = Vec::new();
+            let mut buffer = [0; 1024];
+            while !received.ends_with(b"\r\n\r\n") {
+                let n_read = connection.read(&mut buffer).await.unwrap();
+                received.extend_from_slice(&buffer[..n_read]);
+            }
+
+            connection.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(received).unwrap()
+        });
+
+        (address, handle)
+    }
+
+    fn request() -> Request<'static> {
+        let mut headers = Headers::new();
+        headers.set("Authorization", "Bearer token");
+        headers.set("X-User", "spoofed");
+        Request::new("HTTP/1.1", Method::POST, "/api?id=1", headers, &[])
+    }
+
+    #[test]
+    fn invalid_address() {
+        assert_eq!(
+            Err(AuthAddressError::UnsupportedScheme(
+                "https://auth".to_owned()
+            )),
+            ForwardAuth::new("https://auth", vec![], vec![])
+        );
+        assert_eq!(
+            Err(AuthAddressError::MissingHost("http:///verify".to_owned())),
+            ForwardAuth::new("http:///verify", vec![], vec![])
+        );
+    }
+
+    #[tokio::test]
+    async fn accepted_copies_headers() {
+        let (address, handle) =
+            auth_service("HTTP/1.1 200 OK\r\nX-User: admin\r\nContent-Length: 0\r\n\r\n").await;
+        let auth = ForwardAuth::new(
+            &address,
+            vec!["Authorization".to_owned()],
+            vec!["X-User".to_owned()],
+        )
+        .unwrap();
+
+        let mut req = request();
+        assert_eq!(Ok(()), apply_req(&mut req, &auth).await);
+        assert_eq!(
+            Some("admin".to_owned()),
+            req.headers().get("X-User").map(|v| v.to_string())
+        );
+
+        let received = handle.await.unwrap();
+        assert_eq!(true, received.starts_with("GET /verify HTTP/1.1\r\n"));
+        assert_eq!(true, received.contains("X-Forwarded-Method: POST\r\n"));
+        assert_eq!(true, received.contains("X-Forwarded-Uri: /api?id=1\r\n"));
+        assert_eq!(true, received.contains("Authorization: Bearer token\r\n"));
+        assert_eq!(false, received.contains("X-User"));
+    }
+
+    #[tokio::test]
+    async fn duplicated_headers_replaced() {
+        let (address, _) =
+            auth_service("HTTP/1.1 200 OK\r\nX-User: admin\r\nContent-Length: 0\r\n\r\n").await;
+        let auth = ForwardAuth::new(&address, vec![], vec!["X-User".to_owned()]).unwrap();
+
+        let mut req = request();
+        req.header_mut().append("X-User", "second");
+        assert_eq!(Ok(()), apply_req(&mut req, &auth).await);
+
+        let mut raw_headers = Vec::new();
+        req.headers().serialize(&mut raw_headers);
+        let raw_headers = String::from_utf8(raw_headers).unwrap();
+        assert_eq!(1, raw_headers.matches("X-User").count());
+        assert_eq!(true, raw_headers.contains("X-User: admin\r\n"));
+
+        let (address, _) = auth_service("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").await;
+        let auth = ForwardAuth::new(&address, vec![], vec!["X-User".to_owned()]).unwrap();
+
+        let mut req = request();
+        req.header_mut().append("X-User", "second");
+        assert_eq!(Ok(()), apply_req(&mut req, &auth).await);
+        assert_eq!(None, req.headers().get("X-User"));
+    }
+
+    #[tokio::test]
+    async fn oversized_response_fails() {
+        let response = format!(
+            "HTTP/1.1 401 Unauthorized\r\nConnection: close\r\n\r\n{}",
+            "a".repeat(MAX_AUTH_RESPONSE)
+        );
+        let (address, _) = auth_service(Box::leak(response.into_boxed_str())).await;
+        let auth = ForwardAuth::new(&address, vec![], vec![]).unwrap();
+
+        let mut req = request();
+        assert_eq!(
+            Err(error_response("HTTP/1.1").into()),
+            apply_req(&mut req, &auth).await
+        );
+    }
+
+    #[tokio::test]
+    async fn chunked_rejection_decoded() {
+        let (address, _) = auth_service(
+            "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nden\r\n3;ext=1\r\nied\r\n0\r\n\r\n",
+        )
+        .await;
+        let auth = ForwardAuth::new(&address, vec![], vec!["X-User".to_owned()]).unwrap();
+
+        let mut req = request();
+        let response = match apply_req(&mut req, &auth).await {
+            Err(EarlyResponse::Response(r)) => r,
+            _ => panic!("The Request should have been rejected"),
+        };
+
+        assert_eq!(&StatusCode::Unauthorized, response.status_code());
+        assert_eq!(b"denied", response.body());
+        assert_eq!(
+            Some("6".to_owned()),
+            response
+                .headers()
+                .get("Content-Length")
+                .map(|v| v.to_string())
+        );
+        assert_eq!(None, response.headers().get("Transfer-Encoding"));
+        assert_eq!(
+            Some("Bearer".to_owned()),
+            response
+                .headers()
+                .get("WWW-Authenticate")
+                .map(|v| v.to_string())
+        );
+    }
+
+    #[test]
+    fn decode_chunked_malformed() {
+        assert_eq!(None, decode_chunked(b"6\r\ndenied"));
+        assert_eq!(None, decode_chunked(b"2\r\ndenied\r\n0\r\n\r\n"));
+        assert_eq!(None, decode_chunked(b"x\r\n\r\n"));
+    }
+
+    #[tokio::test]
+    async fn unreachable_service() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+
+        let auth = ForwardAuth::new(&address, vec![], vec![]).unwrap();
+
+        let mut req = request();
+        assert_eq!(
+            Err(error_response("HTTP/1.1").into()),
+            apply_req(&mut req, &auth).await
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::net::IpAddr;
+
+use ipnetwork::IpNetwork;
+use serde::Serialize;
+use stream_httparse::{Headers, Request, Response, StatusCode};
+
+use crate::{ConnectionInfo, EarlyResponse};
+
+/// The Options for the IP-Allow- and IP-Deny-Lists
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct IpListOpts {
+    /// The Networks in the List, which may be IPv4 or IPv6
+    pub networks: Vec<IpNetwork>,
+    /// Uses the Address at this Depth in the X-Forwarded-For Header,
+    /// counted from the right, instead of the Address of the Client
+    pub depth: Option<usize>,
+}
+
+impl IpListOpts {
+    /// Checks if the given IP is part of any of the Networks
+    fn contains(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|net| net.contains(ip))
+    }
+}
+
+/// Determines the IP of the Request that is checked against the List.
+///
+/// If the X-Forwarded-For Chain is shorter than the Depth, the Client
+/// connected without going through all the Proxies and its own IP is used
+fn source_ip(req: &Request<'_>, info: &ConnectionInfo, depth: Option<usize>) -> Option<IpAddr> {
+    let depth = match depth {
+        Some(d) if d > 0 => d,
+        _ => return info.client_ip().copied(),
+    };
+
+    let mut forwarded_for: Vec<&str> = match req.headers().get("X-Forwarded-For") {
+        Some(value) => value
+            .try_as_str_ref()?
+            .split(',')
+            .map(|a| a.trim())
+            .filter(|a| !a.is_empty())
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // The Address of the Client itself was added by the Load-Balancer and
+    // is not counted, like the Depth is used by other Proxies
+    let client_ip = info.client_ip().map(|ip| ip.to_string());
+    if client_ip.is_some() && forwarded_for.last().copied() == client_ip.as_deref() {
+        forwarded_for.pop();
+    }
+
+    match forwarded_for.len().checked_sub(depth) {
+        Some(index) => forwarded_for[index].parse().ok(),
+        None => info.client_ip().copied(),
+    }
+}
+
+fn forbidden_response(protocol: &str) -> Response<'_> {
+    let mut headers = Headers::new();
+    headers.set("Content-Length", 0);
+
+    Response::new(protocol, StatusCode::Forbidden, headers, vec![])
+}
+
+/// Rejects every Request whose IP is not part of the List or can not be
+/// determined
+pub fn apply_allow<'a>(
+    req: &Request<'a>,
+    info: &ConnectionInfo,
+    opts: &IpListOpts,
+) -> Result<(), EarlyResponse<'a>> {
+    match source_ip(req, info, opts.depth) {
+        Some(ip) if opts.contains(ip) => Ok(()),
+        _ => Err(forbidden_response(req.protocol()).into()),
+    }
+}
+
+/// Rejects every Request whose IP is part of the List, as well as the
+/// Requests whose IP can not be determined from the X-Forwarded-For
+/// Header, if a Depth is used
+pub fn apply_deny<'a>(
+    req: &Request<'a>,
+    info: &ConnectionInfo,
+    opts: &IpListOpts,
+) -> Result<(), EarlyResponse<'a>> {
+    match source_ip(req, info, opts.depth) {
+        Some(ip) if !opts.contains(ip) => Ok(()),
+        None if opts.depth.unwrap_or(0) == 0 => Ok(()),
+        _ => Err(forbidden_response(req.protocol()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::Method;
+
+    use super::*;
+
+    fn opts(networks: &[&str], depth: Option<usize>) -> IpListOpts {
+        IpListOpts {
+            networks: networks.iter().map(|n| n.parse().unwrap()).collect(),
+            depth,
+        }
+    }
+
+    fn request(forwarded_for: Option<&'static str>) -> Request<'static> {
+        let mut headers = Headers::new();
+        if let Some(value) = forwarded_for {
+            headers.set("X-Forwarded-For", value);
+        }
+        Request::new("HTTP/1.1", Method::GET, "/", headers, &[])
+    }
+
+    fn info(ip: &str) -> ConnectionInfo {
+        ConnectionInfo::new(Some(ip.parse().unwrap()))
+    }
+
+    #[test]
+    fn allow_client_ip() {
+        let list = opts(&["10.0.0.0/8", "2001:db8::/32"], None);
+        let req = request(None);
+
+        assert_eq!(true, apply_allow(&req, &info("10.1.2.3"), &list).is_ok());
+        assert_eq!(true, apply_allow(&req, &info("2001:db8::1"), &list).is_ok());
+        assert_eq!(
+            true,
+            apply_allow(&req, &info("192.168.0.1"), &list).is_err()
+        );
+        assert_eq!(
+            true,
+            apply_allow(&req, &ConnectionInfo::default(), &list).is_err()
+        );
+    }
+
+    #[test]
+    fn deny_client_ip() {
+        let list = opts(&["192.168.0.0/16"], None);
+        let req = request(None);
+
+        assert_eq!(true, apply_deny(&req, &info("192.168.0.1"), &list).is_err());
+        assert_eq!(true, apply_deny(&req, &info("10.0.0.1"), &list).is_ok());
+    }
+
+    #[test]
+    fn forwarded_for_depth() {
+        let req = request(Some("10.0.0.1, 192.168.0.1, 172.16.0.1"));
+        let proxy = info("172.16.0.1");
+
+        let list = opts(&["192.168.0.0/16"], Some(1));
+        assert_eq!(true, apply_allow(&req, &proxy, &list).is_ok());
+
+        let list = opts(&["10.0.0.0/8"], Some(2));
+        assert_eq!(true, apply_allow(&req, &proxy, &list).is_ok());
+
+        let list = opts(&["10.0.0.0/8"], Some(3));
+        assert_eq!(true, apply_allow(&req, &proxy, &list).is_err());
+    }
+
+    #[test]
+    fn deny_short_chain() {
+        let list = opts(&["192.168.0.0/16"], Some(2));
+
+        // A Client connecting directly only has its own IP in the Chain
+        let req = request(Some("192.168.0.1"));
+        assert_eq!(true, apply_deny(&req, &info("192.168.0.1"), &list).is_err());
+        assert_eq!(true, apply_deny(&req, &info("10.0.0.1"), &list).is_ok());
+
+        let list = opts(&["192.168.0.0/16"], Some(1));
+        let req = request(Some("invalid, 172.16.0.1"));
+        assert_eq!(true, apply_deny(&req, &info("172.16.0.1"), &list).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use general::Name;
+use serde::{Deserialize, Serialize};
+use stream_httparse::{Headers, Request};
+
+use crate::{ConnectionInfo, EarlyResponse};
+
+/// The Maximum Number of Buckets a single RateLimit keeps at once
+const MAX_BUCKETS: usize = 10_000;
+/// The Part of the Buckets that is freed at once, when there is no space
+/// for a new Bucket, so the Buckets only have to be scanned every so often
+const EVICTION_FRACTION: usize = 10;
+
+/// The Source of the Key that determines which Bucket a Request uses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum RateLimitKey {
+    /// Every Client-IP has its own Bucket
+    ClientIP,
+    /// Every Value of the Header with the given Name has its own Bucket
+    Header {
+        /// The Name of the Header
+        name: String,
+    },
+    /// All the Requests matched by a Rule share one Bucket
+    Rule,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Limits the Rate of Requests using Token-Buckets, which are refilled
+/// with `average` Tokens every `period` and hold up to `burst` Tokens.
+///
+/// The Buckets belong to this Instance, so a Middleware that is recreated,
+/// because its Configuration was reloaded, starts with full Buckets again
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimit {
+    average: u64,
+    period: Duration,
+    burst: u64,
+    key: RateLimitKey,
+    #[serde(skip)]
+    max_buckets: usize,
+    #[serde(skip)]
+    buckets: Arc<Mutex<HashMap<String, Bucket>>>,
+}
+
+impl PartialEq for RateLimit {
+    fn eq(&self, other: &Self) -> bool {
+        self.average == other.average
+            && self.period == other.period
+            && self.burst == other.burst
+            && self.key == other.key
+    }
+}
+
+impl RateLimit {
+    /// Creates a new RateLimit, an average of 0 disables the Limit and
+    /// the Burst is at least 1
+    pub fn new(average: u64, period: Duration, burst: u64, key: RateLimitKey) -> Self {
+        Self {
+            average,
+            period,
+            burst: burst.max(1),
+            key,
+            max_buckets: MAX_BUCKETS,
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// The Number of Tokens added to every Bucket per Second
+    fn rate(&self) -> f64 {
+        self.average as f64 / self.period.as_secs_f64()
+    }
+
+    /// Removes the Buckets that are full again, as they behave just like
+    /// new ones, and the least recently used ones if that does not free
+    /// up enough space for the next Buckets
+    fn evict(&self, buckets: &mut HashMap<String, Bucket>, now: Instant) {
+        let rate = self.rate();
+        let burst = self.burst as f64;
+        buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens + elapsed * rate < burst
+        });
+
+        let batch = (self.max_buckets / EVICTION_FRACTION).max(1);
+        let target = self.max_buckets.saturating_sub(batch);
+        if buckets.len() <= target {
+            return;
+        }
+
+        let mut by_age: Vec<(Instant, &String)> = buckets
+            .iter()
+            .map(|(key, bucket)| (bucket.updated, key))
+            .collect();
+        let count = by_age.len() - target;
+        by_age.select_nth_unstable_by_key(count - 1, |(updated, _)| *updated);
+        let oldest: Vec<String> = by_age[..count]
+            .iter()
+            .map(|(_, key)| (*key).clone())
+            .collect();
+        for key in oldest {
+            buckets.remove(&key);
+        }
+    }
+
+    /// Takes a Token from the Bucket with the given Key, if the Bucket is
+    /// empty returns the Time until the next Token is available
+    fn take(&self, key: String, now: Instant) -> Result<(), Duration> {
+        if self.average == 0 || self.period.is_zero() {
+            return Ok(());
+        }
+        let rate = self.rate();
+
+        let mut buckets = self.buckets.lock().expect("Locking Rate-Limit Buckets");
+        if !buckets.contains_key(&key) && buckets.len() >= self.max_buckets {
+            self.evict(&mut buckets, now);
+        }
+
+        let burst = self.burst as f64;
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+/// Takes a Token for the Request and rejects it with "429 Too Many
+/// Requests" if there was none left. Requests without a Key are not
+/// limited, as they would otherwise all share a single Bucket
+pub fn apply_req<'a>(
+    req: &Request<'a>,
+    info: &ConnectionInfo,
+    rule: &Name,
+    limit: &RateLimit,
+) -> Result<(), EarlyResponse<'a>> {
+    let key = match &limit.key {
+        RateLimitKey::ClientIP => info.client_ip().map(|ip| ip.to_string()),
+        RateLimitKey::Header { name } => req
+            .headers()
+            .get(name.as_str())
+            .map(|value| value.to_string()),
+        RateLimitKey::Rule => Some(rule.to_string()),
+    };
+    let key = match key {
+        Some(k) => k,
+        None => return Ok(()),
+    };
+
+    let wait = match limit.take(key, Instant::now()) {
+        Ok(_) => return Ok(()),
+        Err(w) => w,
+    };
+
+    // Retry-After only allows whole Seconds, so this is rounded up to
+    // not have the Client retry too early
+    let mut retry_after = wait.as_secs();
+    if wait.subsec_nanos() > 0 || retry_after == 0 {
+        retry_after += 1;
+    }
+
+    let mut headers = Headers::new();
+    headers.set("Retry-After", retry_after.to_string());
+    headers.set("Content-Length", 0);
+
+    Err(EarlyResponse::Status {
+        protocol: req.protocol(),
+        status: "429 Too Many Requests",
+        headers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use general::Group;
+    use stream_httparse::Method;
+
+    use super::*;
+
+    fn request(headers: Headers<'_>) -> Request<'_> {
+        Request::new("HTTP/1.1", Method::GET, "/", headers, &[])
+    }
+
+    #[test]
+    fn missing_key_not_limited() {
+        let limit = RateLimit::new(1, Duration::from_secs(10), 1, RateLimitKey::ClientIP);
+        let rule = Name::new("test", Group::Internal);
+        let info = ConnectionInfo::default();
+        let req = request(Headers::new());
+
+        for _ in 0..3 {
+            assert_eq!(Ok(()), apply_req(&req, &info, &rule, &limit));
+        }
+        assert_eq!(0, limit.buckets.lock().unwrap().len());
+    }
+
+    #[test]
+    fn refills_bucket() {
+        let limit = RateLimit::new(2, Duration::from_secs(1), 1, RateLimitKey::Rule);
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), limit.take("test".to_owned(), now));
+        assert_eq!(
+            Err(Duration::from_millis(500)),
+            limit.take("test".to_owned(), now)
+        );
+        assert_eq!(
+            Ok(()),
+            limit.take("test".to_owned(), now + Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn allows_burst() {
+        let limit = RateLimit::new(1, Duration::from_secs(10), 3, RateLimitKey::Rule);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(Ok(()), limit.take("test".to_owned(), now));
+        }
+        assert_eq!(true, limit.take("test".to_owned(), now).is_err());
+    }
+
+    #[test]
+    fn bounded_buckets() {
+        let mut limit = RateLimit::new(1, Duration::from_secs(60), 1, RateLimitKey::Rule);
+        limit.max_buckets = 2;
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), limit.take("first".to_owned(), now));
+        assert_eq!(
+            Ok(()),
+            limit.take("second".to_owned(), now + Duration::from_secs(1))
+        );
+        assert_eq!(
+            Ok(()),
+            limit.take("third".to_owned(), now + Duration::from_secs(2))
+        );
+
+        let buckets = limit.buckets.lock().unwrap();
+        assert_eq!(2, buckets.len());
+        assert_eq!(false, buckets.contains_key("first"));
+    }
+
+    #[test]
+    fn evicts_in_batches() {
+        let mut limit = RateLimit::new(1, Duration::from_secs(60), 1, RateLimitKey::Rule);
+        limit.max_buckets = 20;
+        let now = Instant::now();
+
+        for i in 0..21 {
+            let at = now + Duration::from_secs(i);
+            assert_eq!(Ok(()), limit.take(format!("key-{}", i), at));
+        }
+        {
+            let buckets = limit.buckets.lock().unwrap();
+            assert_eq!(19, buckets.len());
+            assert_eq!(false, buckets.contains_key("key-0"));
+            assert_eq!(false, buckets.contains_key("key-1"));
+            assert_eq!(true, buckets.contains_key("key-2"));
+        }
+
+        // There is still space left from the last Eviction
+        assert_eq!(
+            Ok(()),
+            limit.take("key-21".to_owned(), now + Duration::from_secs(21))
+        );
+        let buckets = limit.buckets.lock().unwrap();
+        assert_eq!(20, buckets.len());
+        assert_eq!(true, buckets.contains_key("key-2"));
+    }
+
+    #[test]
+    fn rejects_with_retry_after() {
+        let limit = RateLimit::new(
+            1,
+            Duration::from_secs(10),
+            1,
+            RateLimitKey::Header {
+                name: "X-User".to_owned(),
+            },
+        );
+        let rule = Name::new("test", Group::Internal);
+        let info = ConnectionInfo::default();
+
+        let mut headers = Headers::new();
+        headers.set("X-User", "first");
+        let first = request(headers);
+        let mut headers = Headers::new();
+        headers.set("X-User", "second");
+        let second = request(headers);
+
+        assert_eq!(Ok(()), apply_req(&first, &info, &rule, &limit));
+        assert_eq!(Ok(()), apply_req(&second, &info, &rule, &limit));
+
+        let mut headers = Headers::new();
+        headers.set("Retry-After", "10".to_owned());
+        headers.set("Content-Length", 0);
+        assert_eq!(
+            Err(EarlyResponse::Status {
+                protocol: "HTTP/1.1",
+                status: "429 Too Many Requests",
+                headers,
+            }),
+            apply_req(&first, &info, &rule, &limit)
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use serde::Serialize;
+use stream_httparse::{Headers, Request};
+
+use crate::{ConnectionInfo, EarlyResponse, Pattern};
+
+/// The Status-Code used for a Redirect
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum RedirectStatus {
+    /// 301, Clients may change the Method to GET
+    MovedPermanently,
+    /// 302, Clients may change the Method to GET
+    Found,
+    /// 307, the Method and Body of the Request are kept
+    TemporaryRedirect,
+    /// 308, the Method and Body of the Request are kept
+    PermanentRedirect,
+}
+
+impl RedirectStatus {
+    /// Returns the Status for the given Status-Code, if it is one of the
+    /// supported Redirects
+    pub fn from_code(code: u16) -> Option<Self> {
+        match code {
+            301 => Some(Self::MovedPermanently),
+            302 => Some(Self::Found),
+            307 => Some(Self::TemporaryRedirect),
+            308 => Some(Self::PermanentRedirect),
+            _ => None,
+        }
+    }
+
+    fn status_line(&self) -> &'static str {
+        match self {
+            Self::MovedPermanently => "301 Moved Permanently",
+            Self::Found => "302 Found",
+            Self::TemporaryRedirect => "307 Temporary Redirect",
+            Self::PermanentRedirect => "308 Permanent Redirect",
+        }
+    }
+}
+
+/// The Options for redirecting Requests to another Scheme
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RedirectSchemeOpts {
+    /// The Scheme to redirect to, like "https"
+    pub scheme: String,
+    /// The Port to redirect to, the default Port of the Scheme is used
+    /// if this is not set
+    pub port: Option<u16>,
+    /// The Status used for the Redirect
+    pub status: RedirectStatus,
+}
+
+/// The Options for redirecting Requests whose URL matches a Pattern
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RedirectRegexOpts {
+    /// The Pattern the full URL of the Request is matched against
+    pub regex: Pattern,
+    /// The URL to redirect to, which may reference the captured Groups
+    /// like "${1}"
+    pub replacement: String,
+    /// The Status used for the Redirect
+    pub status: RedirectStatus,
+}
+
+/// Returns the Host of the Request without its Port
+fn request_host(req: &Request<'_>) -> Option<String> {
+    let host = req.headers().get("Host")?.to_string();
+
+    let without_port = match host.rfind(':') {
+        // IPv6-Addresses contain Colons themselves, but are enclosed in
+        // Brackets when used as a Host
+        Some(index) if !host[index..].contains(']') => &host[..index],
+        _ => host.as_str(),
+    };
+    Some(without_port.to_owned())
+}
+
+fn redirect_response<'a>(
+    protocol: &'a str,
+    location: String,
+    status: RedirectStatus,
+) -> EarlyResponse<'a> {
+    let mut headers = Headers::new();
+    headers.set("Location", location);
+    headers.set("Content-Length", 0);
+
+    EarlyResponse::Status {
+        protocol,
+        status: status.status_line(),
+        headers,
+    }
+}
+
+/// The Response for a Request without a Host, which can not be redirected
+fn missing_host(protocol: &str) -> EarlyResponse<'_> {
+    let mut headers = Headers::new();
+    headers.set("Content-Length", 0);
+
+    EarlyResponse::Status {
+        protocol,
+        status: "400 Bad Request",
+        headers,
+    }
+}
+
+/// Redirects the Request to the configured Scheme, if it does not
+/// already use it
+pub fn apply_scheme<'a>(
+    req: &Request<'a>,
+    info: &ConnectionInfo,
+    opts: &RedirectSchemeOpts,
+) -> Result<(), EarlyResponse<'a>> {
+    let scheme = opts.scheme.to_ascii_lowercase();
+    if info.scheme() == scheme {
+        return Ok(());
+    }
+
+    let host = match request_host(req) {
+        Some(h) => h,
+        None => return Err(missing_host(req.protocol())),
+    };
+
+    let port = match (scheme.as_str(), opts.port) {
+        (_, None) | ("http", Some(80)) | ("https", Some(443)) => String::new(),
+        (_, Some(port)) => format!(":{}", port),
+    };
+
+    let location = format!("{}://{}{}{}", scheme, host, port, req.path());
+    Err(redirect_response(req.protocol(), location, opts.status))
+}
+
+/// Redirects the Request to the Replacement, if its full URL matches the
+/// Pattern
+pub fn apply_regex<'a>(
+    req: &Request<'a>,
+    info: &ConnectionInfo,
+    opts: &RedirectRegexOpts,
+) -> Result<(), EarlyResponse<'a>> {
+    let host = match req.headers().get("Host") {
+        Some(h) => h.to_string(),
+        None => return Err(missing_host(req.protocol())),
+    };
+
+    let url = format!("{}://{}{}", info.scheme(), host, req.path());
+    if !opts.regex.is_match(&url) {
+        return Ok(());
+    }
+
+    let location = opts.regex.replace_all(&url, &opts.replacement);
+    Err(redirect_response(req.protocol(), location, opts.status))
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::Method;
+
+    use super::*;
+
+    fn request(host: &'static str, path: &'static str) -> Request<'static> {
+        let mut headers = Headers::new();
+        headers.set("Host", host);
+        Request::new("HTTP/1.1", Method::GET, path, headers, &[])
+    }
+
+    fn location(result: Result<(), EarlyResponse<'_>>) -> Option<(&'static str, String)> {
+        match result {
+            Err(EarlyResponse::Status {
+                status, headers, ..
+            }) => Some((status, headers.get("Location")?.to_string())),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn scheme_to_https() {
+        let opts = RedirectSchemeOpts {
+            scheme: "https".to_owned(),
+            port: None,
+            status: RedirectStatus::PermanentRedirect,
+        };
+        let req = request("example.com:8080", "/api?id=1");
+
+        assert_eq!(
+            Some((
+                "308 Permanent Redirect",
+                "https://example.com/api?id=1".to_owned()
+            )),
+            location(apply_scheme(&req, &ConnectionInfo::default(), &opts))
+        );
+
+        let tls = ConnectionInfo::default().with_tls(true);
+        assert_eq!(Ok(()), apply_scheme(&req, &tls, &opts));
+
+        let forwarded = ConnectionInfo::default().with_forwarded_scheme(Some("https".to_owned()));
+        assert_eq!(Ok(()), apply_scheme(&req, &forwarded, &opts));
+    }
+
+    #[test]
+    fn scheme_ignores_forwarded_header() {
+        let opts = RedirectSchemeOpts {
+            scheme: "https".to_owned(),
+            port: None,
+            status: RedirectStatus::PermanentRedirect,
+        };
+        let mut headers = Headers::new();
+        headers.set("Host", "example.com");
+        headers.set("X-Forwarded-Proto", "https");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+
+        assert_eq!(
+            Some(("308 Permanent Redirect", "https://example.com/".to_owned())),
+            location(apply_scheme(&req, &ConnectionInfo::default(), &opts))
+        );
+    }
+
+    #[test]
+    fn scheme_without_host() {
+        let opts = RedirectSchemeOpts {
+            scheme: "https".to_owned(),
+            port: None,
+            status: RedirectStatus::PermanentRedirect,
+        };
+        let req = Request::new("HTTP/1.0", Method::GET, "/", Headers::new(), &[]);
+
+        assert_eq!(
+            Err(missing_host("HTTP/1.0")),
+            apply_scheme(&req, &ConnectionInfo::default(), &opts)
+        );
+    }
+
+    #[test]
+    fn scheme_with_port() {
+        let opts = RedirectSchemeOpts {
+            scheme: "https".to_owned(),
+            port: Some(8443),
+            status: RedirectStatus::Found,
+        };
+        let req = request("[::1]", "/");
+
+        assert_eq!(
+            Some(("302 Found", "https://[::1]:8443/".to_owned())),
+            location(apply_scheme(&req, &ConnectionInfo::default(), &opts))
+        );
+    }
+
+    #[test]
+    fn regex_replacement() {
+        let opts = RedirectRegexOpts {
+            regex: Pattern::new("^http://old.example.com/(.*)").unwrap(),
+            replacement: "https://new.example.com/${1}".to_owned(),
+            status: RedirectStatus::MovedPermanently,
+        };
+
+        let req = request("old.example.com", "/docs/intro");
+        assert_eq!(
+            Some((
+                "301 Moved Permanently",
+                "https://new.example.com/docs/intro".to_owned()
+            )),
+            location(apply_regex(&req, &ConnectionInfo::default(), &opts))
+        );
+
+        let req = request("other.example.com", "/docs/intro");
+        assert_eq!(Ok(()), apply_regex(&req, &ConnectionInfo::default(), &opts));
+    }
+}
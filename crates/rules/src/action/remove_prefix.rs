@@ -1,18 +1,19 @@
 use stream_httparse::Request;
 
-pub fn apply_req(req: &mut Request, prefix: &str) {
-    let prefix_len = prefix.len();
-    let req_path_len = req.path().len();
+use super::rewrite::{join_query, replace_all, split_query};
 
-    if prefix_len > req_path_len {
-        return;
-    }
-    let path = req.path();
-    if &path[0..prefix_len] != prefix {
-        return;
-    }
+/// Removes the first of the Prefixes the Path of the Request starts with
+/// and keeps it in the X-Forwarded-Prefix Header
+pub fn apply_req(req: &mut Request, prefixes: &[String]) {
+    let (path, query) = split_query(req.path());
+    let prefix = match prefixes.iter().find(|p| path.starts_with(p.as_str())) {
+        Some(p) => p,
+        None => return,
+    };
+    let n_path = join_query(&path[prefix.len()..], query);
 
-    req.set_path_owned(req.path()[prefix_len..].to_owned());
+    req.set_path_owned(n_path);
+    replace_all(req, "X-Forwarded-Prefix", prefix.to_owned());
 }
 
 #[cfg(test)]
@@ -31,7 +32,7 @@ mod tests {
             "".as_bytes(),
         );
 
-        apply_req(&mut req, "/api");
+        apply_req(&mut req, &["/api".to_owned()]);
         assert_eq!("/test", req.path());
     }
     #[test]
@@ -44,14 +45,53 @@ mod tests {
             "".as_bytes(),
         );
 
-        apply_req(&mut req, "/api");
+        apply_req(&mut req, &["/api".to_owned()]);
+        assert_eq!("/test", req.path());
+    }
+    #[test]
+    fn req_chained_prefixes() {
+        let mut req = Request::new(
+            "HTTP/1.1",
+            Method::GET,
+            "/api/test",
+            Headers::new(),
+            "".as_bytes(),
+        );
+
+        apply_req(&mut req, &["/api".to_owned()]);
+        apply_req(&mut req, &["/v1".to_owned()]);
         assert_eq!("/test", req.path());
+        assert_eq!(
+            Some("/api".to_owned()),
+            req.headers()
+                .get("X-Forwarded-Prefix")
+                .map(|v| v.to_string())
+        );
     }
     #[test]
     fn req_path_shorter_than_prefix() {
         let mut req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), "".as_bytes());
 
-        apply_req(&mut req, "/api");
+        apply_req(&mut req, &["/api".to_owned()]);
         assert_eq!("/", req.path());
     }
+    #[test]
+    fn req_multiple_prefixes() {
+        let mut req = Request::new(
+            "HTTP/1.1",
+            Method::GET,
+            "/v2/users?id=1",
+            Headers::new(),
+            "".as_bytes(),
+        );
+
+        apply_req(&mut req, &["/v1".to_owned(), "/v2".to_owned()]);
+        assert_eq!("/users?id=1", req.path());
+        assert_eq!(
+            Some("/v2".to_owned()),
+            req.headers()
+                .get("X-Forwarded-Prefix")
+                .map(|v| v.to_string())
+        );
+    }
 }
@@ -0,0 +1,200 @@
+use serde::Serialize;
+use stream_httparse::Request;
+
+use crate::Pattern;
+
+/// The Options for replacing the Path of Requests that match a Pattern
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReplacePathRegexOpts {
+    /// The Pattern the Path of the Request is matched against
+    pub regex: Pattern,
+    /// The new Path, which may reference the captured Groups like "${1}"
+    pub replacement: String,
+}
+
+/// Splits the given Path into the actual Path and its Query, if it has one
+pub(super) fn split_query(path: &str) -> (&str, Option<&str>) {
+    match path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path, None),
+    }
+}
+
+/// Joins the Path and Query back together, while making sure that the
+/// Path starts with a "/"
+pub(super) fn join_query(path: &str, query: Option<&str>) -> String {
+    let mut result = String::with_capacity(path.len() + 1);
+    if !path.starts_with('/') {
+        result.push('/');
+    }
+    result.push_str(path);
+
+    if let Some(query) = query {
+        result.push('?');
+        result.push_str(query);
+    }
+    result
+}
+
+/// Replaces every Value of the Header with the given one, as only the
+/// first Value would be updated otherwise
+pub(super) fn replace_all(req: &mut Request<'_>, key: &'static str, value: String) {
+    while req.headers().get(key).is_some() {
+        req.header_mut().remove(key);
+    }
+    req.header_mut().set(key, value);
+}
+
+/// Adds the Prefix in front of the Path of the Request
+pub fn apply_add_prefix(req: &mut Request<'_>, prefix: &str) {
+    let (path, query) = split_query(req.path());
+    let n_path = join_query(&format!("{}{}", prefix, path), query);
+
+    req.set_path_owned(n_path);
+}
+
+/// Removes the first Match of the Patterns from the Path of the Request,
+/// if it is at the start of the Path
+pub fn apply_strip_prefix_regex(req: &mut Request<'_>, patterns: &[Pattern]) {
+    let (path, query) = split_query(req.path());
+    let prefix = match patterns.iter().find_map(|p| p.find_prefix(path)) {
+        Some(p) => p.to_owned(),
+        None => return,
+    };
+    let n_path = join_query(&path[prefix.len()..], query);
+
+    req.set_path_owned(n_path);
+    replace_all(req, "X-Forwarded-Prefix", prefix);
+}
+
+/// Replaces the Path of the Request and keeps the original one in the
+/// X-Replaced-Path Header
+pub fn apply_replace_path(req: &mut Request<'_>, n_path: &str) {
+    let (path, query) = split_query(req.path());
+    let original = path.to_owned();
+    let n_path = join_query(n_path, query);
+
+    req.set_path_owned(n_path);
+    replace_all(req, "X-Replaced-Path", original);
+}
+
+/// Replaces the Path of the Request, if it matches the Pattern, and keeps
+/// the original one in the X-Replaced-Path Header
+pub fn apply_replace_path_regex(req: &mut Request<'_>, opts: &ReplacePathRegexOpts) {
+    let (path, query) = split_query(req.path());
+    if !opts.regex.is_match(path) {
+        return;
+    }
+    let original = path.to_owned();
+    let n_path = join_query(&opts.regex.replace_all(path, &opts.replacement), query);
+
+    req.set_path_owned(n_path);
+    replace_all(req, "X-Replaced-Path", original);
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::{Headers, Method};
+
+    use super::*;
+
+    fn request(path: &'static str) -> Request<'static> {
+        Request::new("HTTP/1.1", Method::GET, path, Headers::new(), &[])
+    }
+
+    #[test]
+    fn add_prefix_keeps_query() {
+        let mut req = request("/users?id=1");
+
+        apply_add_prefix(&mut req, "/api");
+        assert_eq!("/api/users?id=1", req.path());
+    }
+
+    #[test]
+    fn strip_prefix_regex() {
+        let patterns = vec![
+            Pattern::new("/v[0-9]+").unwrap(),
+            Pattern::new("/users/[a-z]+").unwrap(),
+        ];
+
+        let mut req = request("/users/admin?full=true");
+        apply_strip_prefix_regex(&mut req, &patterns);
+        assert_eq!("/?full=true", req.path());
+        assert_eq!(
+            Some("/users/admin".to_owned()),
+            req.headers()
+                .get("X-Forwarded-Prefix")
+                .map(|v| v.to_string())
+        );
+
+        let mut req = request("/api/v2");
+        apply_strip_prefix_regex(&mut req, &patterns);
+        assert_eq!("/api/v2", req.path());
+        assert_eq!(None, req.headers().get("X-Forwarded-Prefix"));
+    }
+
+    #[test]
+    fn replace_path() {
+        let mut req = request("/old/path?id=1");
+
+        apply_replace_path(&mut req, "/new");
+        assert_eq!("/new?id=1", req.path());
+        assert_eq!(
+            Some("/old/path".to_owned()),
+            req.headers().get("X-Replaced-Path").map(|v| v.to_string())
+        );
+    }
+
+    #[test]
+    fn replace_path_regex() {
+        let opts = ReplacePathRegexOpts {
+            regex: Pattern::new("^/docs/(.*)").unwrap(),
+            replacement: "/static/docs/${1}".to_owned(),
+        };
+
+        let mut req = request("/docs/intro?lang=en");
+        apply_replace_path_regex(&mut req, &opts);
+        assert_eq!("/static/docs/intro?lang=en", req.path());
+        assert_eq!(
+            Some("/docs/intro".to_owned()),
+            req.headers().get("X-Replaced-Path").map(|v| v.to_string())
+        );
+
+        let mut req = request("/api/docs");
+        apply_replace_path_regex(&mut req, &opts);
+        assert_eq!("/api/docs", req.path());
+        assert_eq!(None, req.headers().get("X-Replaced-Path"));
+    }
+
+    #[test]
+    fn chained_rewrites_keep_headers() {
+        let mut headers = Headers::new();
+        headers.set("X-Replaced-Path", "/spoofed");
+        let mut req = Request::new("HTTP/1.1", Method::GET, "/v1/docs", headers, &[]);
+
+        apply_strip_prefix_regex(&mut req, &[Pattern::new("/v[0-9]+").unwrap()]);
+        apply_strip_prefix_regex(&mut req, &[Pattern::new("/api").unwrap()]);
+        assert_eq!("/docs", req.path());
+        assert_eq!(
+            Some("/v1".to_owned()),
+            req.headers()
+                .get("X-Forwarded-Prefix")
+                .map(|v| v.to_string())
+        );
+
+        apply_replace_path(&mut req, "/new");
+        apply_replace_path_regex(
+            &mut req,
+            &ReplacePathRegexOpts {
+                regex: Pattern::new("^/other").unwrap(),
+                replacement: "/".to_owned(),
+            },
+        );
+        assert_eq!(
+            Some("/docs".to_owned()),
+            req.headers().get("X-Replaced-Path").map(|v| v.to_string())
+        );
+        req.header_mut().remove("X-Replaced-Path");
+        assert_eq!(None, req.headers().get("X-Replaced-Path"));
+    }
+}
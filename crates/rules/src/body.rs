@@ -0,0 +1,110 @@
+/// Transforms the Body of a Response while it is streamed to the Client,
+/// one Part at a time, so that the whole Body never has to be buffered
+pub trait BodyTransform: Send {
+    /// Transforms the next Part of the Body and returns the Data that
+    /// should be sent in its place, which may be empty
+    fn transform(&mut self, data: &[u8]) -> Vec<u8>;
+
+    /// Called once the whole Body was passed to the Transform and
+    /// returns all the Data that is still left to send
+    fn finish(&mut self) -> Vec<u8>;
+}
+
+/// All the Transforms that should be applied to a single Body, in the
+/// Order of the Middlewares that created them
+#[derive(Default)]
+pub struct BodyPipeline {
+    transforms: Vec<Box<dyn BodyTransform>>,
+}
+
+impl std::fmt::Debug for BodyPipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BodyPipeline ({} Transforms)", self.transforms.len())
+    }
+}
+
+impl BodyPipeline {
+    /// Creates a new empty Pipeline, which leaves the Body unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the Transform to the End of the Pipeline
+    pub fn push(&mut self, transform: Box<dyn BodyTransform>) {
+        self.transforms.push(transform);
+    }
+
+    /// Checks if the Pipeline contains no Transforms at all
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// Passes the next Part of the Body through all the Transforms
+    pub fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+        let mut current = data.to_vec();
+        for transform in self.transforms.iter_mut() {
+            current = transform.transform(&current);
+        }
+        current
+    }
+
+    /// Finishes all the Transforms, where the remaining Data of every
+    /// Transform is still passed through all the following ones
+    pub fn finish(&mut self) -> Vec<u8> {
+        let mut current = Vec::new();
+        for transform in self.transforms.iter_mut() {
+            let mut next = transform.transform(&current);
+            next.extend(transform.finish());
+            current = next;
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Uppercases the Data and appends a Marker once it finishes
+    struct Upper;
+    impl BodyTransform for Upper {
+        fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+            data.to_ascii_uppercase()
+        }
+        fn finish(&mut self) -> Vec<u8> {
+            b"!".to_vec()
+        }
+    }
+
+    /// Holds back all the Data until it finishes
+    #[derive(Default)]
+    struct Delay(Vec<u8>);
+    impl BodyTransform for Delay {
+        fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+            self.0.extend_from_slice(data);
+            Vec::new()
+        }
+        fn finish(&mut self) -> Vec<u8> {
+            std::mem::take(&mut self.0)
+        }
+    }
+
+    #[test]
+    fn empty_pipeline() {
+        let mut pipeline = BodyPipeline::new();
+        assert_eq!(true, pipeline.is_empty());
+        assert_eq!(b"data".to_vec(), pipeline.transform(b"data"));
+        assert_eq!(Vec::<u8>::new(), pipeline.finish());
+    }
+
+    #[test]
+    fn chained_transforms() {
+        let mut pipeline = BodyPipeline::new();
+        pipeline.push(Box::new(Delay::default()));
+        pipeline.push(Box::new(Upper));
+
+        assert_eq!(Vec::<u8>::new(), pipeline.transform(b"some "));
+        assert_eq!(Vec::<u8>::new(), pipeline.transform(b"data"));
+        assert_eq!(b"SOME DATA!".to_vec(), pipeline.finish());
+    }
+}
@@ -0,0 +1,188 @@
+use std::{fmt::Display, sync::Arc};
+
+use general::Name;
+use general_traits::ConfigItem;
+use serde::Serialize;
+
+use crate::Rule;
+
+/// A Conflict between two Rules, that most likely results in
+/// Requests not being routed the way they were intended to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum RuleConflict {
+    /// The Rule will never be used, because every Request that it
+    /// matches is already matched by a Rule with a higher Priority
+    Shadowed {
+        /// The Rule that will never be used
+        rule: Name,
+        /// The Rule with the higher Priority
+        by: Name,
+    },
+    /// Both Rules have the same Priority and may match the same
+    /// Requests, which one is used depends on the Order in which they
+    /// were added
+    Tie {
+        /// The Rule that is currently used for these Requests
+        first: Name,
+        /// The Rule that is currently not used for these Requests
+        second: Name,
+    },
+}
+
+impl Display for RuleConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Shadowed { rule, by } => {
+                write!(f, "Rule '{}' is shadowed by Rule '{}'", rule, by)
+            }
+            Self::Tie { first, second } => write!(
+                f,
+                "Rule '{}' and Rule '{}' have the same Priority and overlapping Matchers",
+                first, second
+            ),
+        }
+    }
+}
+
+/// Finds all the Conflicts between the given Rules, which are expected
+/// to be sorted by their Priority, like they are in the Rule-List.
+///
+/// A shadowed Rule is only reported once, for the first Rule that
+/// shadows it
+pub fn find_conflicts(rules: &[Arc<Rule>]) -> Vec<RuleConflict> {
+    let mut result = Vec::new();
+
+    for (index, rule) in rules.iter().enumerate() {
+        for other in rules[..index].iter() {
+            if other.priority() == rule.priority() {
+                if other.entry_points().overlaps(rule.entry_points())
+                    && other.matcher().overlaps(rule.matcher())
+                {
+                    result.push(RuleConflict::Tie {
+                        first: other.name().clone(),
+                        second: rule.name().clone(),
+                    });
+                }
+                continue;
+            }
+
+            if other.entry_points().covers(rule.entry_points())
+                && other.matcher().covers(rule.matcher())
+            {
+                result.push(RuleConflict::Shadowed {
+                    rule: rule.name().clone(),
+                    by: other.name().clone(),
+                });
+                break;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Shared};
+
+    use crate::{Matcher, Service};
+
+    use super::*;
+
+    fn rule(name: &str, priority: u32, matcher: Matcher) -> Arc<Rule> {
+        Arc::new(Rule::new(
+            Name::new(name, Group::Internal),
+            priority,
+            matcher,
+            vec![],
+            Shared::new(Service::new(Name::new("service", Group::Internal), vec![])),
+        ))
+    }
+
+    #[test]
+    fn no_conflicts() {
+        let rules = vec![
+            rule("first", 2, Matcher::Domain("example.com".to_owned())),
+            rule("second", 1, Matcher::Domain("example.net".to_owned())),
+        ];
+
+        assert_eq!(Vec::<RuleConflict>::new(), find_conflicts(&rules));
+    }
+
+    #[test]
+    fn shadowed() {
+        let rules = vec![
+            rule("catch-all", 10, Matcher::PathPrefix("/".to_owned())),
+            rule("api", 5, Matcher::PathPrefix("/api".to_owned())),
+            rule("api-v1", 1, Matcher::PathPrefix("/api/v1".to_owned())),
+        ];
+
+        assert_eq!(
+            vec![
+                RuleConflict::Shadowed {
+                    rule: Name::new("api", Group::Internal),
+                    by: Name::new("catch-all", Group::Internal),
+                },
+                RuleConflict::Shadowed {
+                    rule: Name::new("api-v1", Group::Internal),
+                    by: Name::new("catch-all", Group::Internal),
+                },
+            ],
+            find_conflicts(&rules)
+        );
+    }
+
+    #[test]
+    fn more_specific_is_not_shadowed() {
+        let rules = vec![
+            rule("api-v1", 10, Matcher::PathPrefix("/api/v1".to_owned())),
+            rule("api", 5, Matcher::PathPrefix("/api".to_owned())),
+        ];
+
+        assert_eq!(Vec::<RuleConflict>::new(), find_conflicts(&rules));
+    }
+
+    #[test]
+    fn tie() {
+        let rules = vec![
+            rule("first", 1, Matcher::PathPrefix("/api".to_owned())),
+            rule(
+                "second",
+                1,
+                Matcher::And(vec![
+                    Matcher::Domain("example.com".to_owned()),
+                    Matcher::PathPrefix("/api/v1".to_owned()),
+                ]),
+            ),
+            rule("third", 1, Matcher::PathPrefix("/static".to_owned())),
+        ];
+
+        assert_eq!(
+            vec![RuleConflict::Tie {
+                first: Name::new("first", Group::Internal),
+                second: Name::new("second", Group::Internal),
+            }],
+            find_conflicts(&rules)
+        );
+    }
+
+    #[test]
+    fn different_entry_points() {
+        let mut internal = Rule::new(
+            Name::new("internal", Group::Internal),
+            10,
+            Matcher::PathPrefix("/".to_owned()),
+            vec![],
+            Shared::new(Service::new(Name::new("service", Group::Internal), vec![])),
+        );
+        internal.set_entry_points(vec!["internal".to_owned()]);
+
+        let rules = vec![
+            Arc::new(internal),
+            rule("api", 5, Matcher::PathPrefix("/api".to_owned())),
+        ];
+
+        assert_eq!(Vec::<RuleConflict>::new(), find_conflicts(&rules));
+    }
+}
@@ -0,0 +1,96 @@
+use std::net::{IpAddr, SocketAddr};
+
+/// Contains all the Information about the Connection on which a
+/// Request was received, that is not part of the Request itself
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConnectionInfo {
+    client_ip: Option<IpAddr>,
+    client_addr: Option<SocketAddr>,
+    local_addr: Option<SocketAddr>,
+    tls: bool,
+    entrypoint: Option<String>,
+    forwarded_scheme: Option<String>,
+}
+
+impl ConnectionInfo {
+    /// Creates a new ConnectionInfo for a Connection from the given
+    /// Client-IP, if it is known
+    pub fn new(client_ip: Option<IpAddr>) -> Self {
+        Self {
+            client_ip,
+            client_addr: None,
+            local_addr: None,
+            tls: false,
+            entrypoint: None,
+            forwarded_scheme: None,
+        }
+    }
+
+    /// Sets the Addresses of the Client and the local Address the Client
+    /// connected to, these may have been passed on by a Proxy in front of
+    /// Tunneload using the PROXY-Protocol
+    pub fn with_addresses(
+        mut self,
+        client_addr: Option<SocketAddr>,
+        local_addr: Option<SocketAddr>,
+    ) -> Self {
+        self.client_addr = client_addr;
+        self.local_addr = local_addr;
+        self
+    }
+
+    /// Marks the Connection as using TLS
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Sets the Name of the Entrypoint that accepted the Connection
+    pub fn with_entrypoint(mut self, entrypoint: Option<String>) -> Self {
+        self.entrypoint = entrypoint;
+        self
+    }
+
+    /// Sets the Scheme the Client used, as reported by a trusted Proxy in
+    /// front of Tunneload
+    pub fn with_forwarded_scheme(mut self, scheme: Option<String>) -> Self {
+        self.forwarded_scheme = scheme;
+        self
+    }
+
+    /// Returns the IP-Address of the Client, if it is known
+    pub fn client_ip(&self) -> Option<&IpAddr> {
+        self.client_ip.as_ref()
+    }
+
+    /// Returns the full Address of the Client, if it is known
+    pub fn client_addr(&self) -> Option<&SocketAddr> {
+        self.client_addr.as_ref()
+    }
+
+    /// Returns the local Address the Client connected to, if it is known
+    pub fn local_addr(&self) -> Option<&SocketAddr> {
+        self.local_addr.as_ref()
+    }
+
+    /// Checks if the Connection uses TLS
+    pub fn is_tls(&self) -> bool {
+        self.tls
+    }
+
+    /// Returns the Scheme the Client used, which is the one reported by a
+    /// trusted Proxy or otherwise depends on whether the Connection uses TLS
+    pub fn scheme(&self) -> &str {
+        match self.forwarded_scheme.as_deref() {
+            Some(scheme) => scheme,
+            None if self.tls => "https",
+            None => "http",
+        }
+    }
+
+    /// Returns the Name of the Entrypoint that accepted the Connection,
+    /// if it is known
+    pub fn entrypoint(&self) -> Option<&str> {
+        self.entrypoint.as_deref()
+    }
+}
@@ -0,0 +1,74 @@
+use stream_httparse::{Headers, Response};
+
+/// A Response that a Middleware sends to the Client directly, instead of
+/// forwarding the Request to the Service
+#[derive(Debug, PartialEq)]
+pub enum EarlyResponse<'a> {
+    /// A regular Response
+    Response(Response<'a>),
+    /// A Response without a Body, whose Status is given as the raw
+    /// Status-Line, which allows for Status-Codes that are not known to
+    /// stream_httparse, like "429 Too Many Requests"
+    Status {
+        /// The Protocol of the Response
+        protocol: &'a str,
+        /// The Status-Line of the Response without the Protocol
+        status: &'static str,
+        /// The Headers of the Response
+        headers: Headers<'a>,
+    },
+}
+
+impl<'a> EarlyResponse<'a> {
+    /// Serializes the Response and returns the Data as a tuple of the
+    /// form (HTTP-Head, HTTP-Body)
+    pub fn serialize(&self) -> (Vec<u8>, &[u8]) {
+        match self {
+            Self::Response(response) => response.serialize(),
+            Self::Status {
+                protocol,
+                status,
+                headers,
+            } => {
+                let mut head = Vec::with_capacity(protocol.len() + status.len() + 64);
+                head.extend_from_slice(protocol.as_bytes());
+                head.push(b' ');
+                head.extend_from_slice(status.as_bytes());
+                head.extend_from_slice(b"\r\n");
+                headers.serialize(&mut head);
+                head.extend_from_slice(b"\r\n");
+
+                (head, &[])
+            }
+        }
+    }
+}
+
+impl<'a> From<Response<'a>> for EarlyResponse<'a> {
+    fn from(response: Response<'a>) -> Self {
+        Self::Response(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_status() {
+        let mut headers = Headers::new();
+        headers.set("Retry-After", 2);
+        let response = EarlyResponse::Status {
+            protocol: "HTTP/1.1",
+            status: "429 Too Many Requests",
+            headers,
+        };
+
+        let (head, body) = response.serialize();
+        assert_eq!(
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 2\r\n\r\n".as_bytes(),
+            &head[..]
+        );
+        assert_eq!(true, body.is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use serde::Serialize;
+
+/// The Names of the Entrypoints a Rule applies to, where an empty
+/// List means that the Rule applies to all Entrypoints
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct EntryPoints(Vec<String>);
+
+impl EntryPoints {
+    /// Creates a new List from the given Names
+    pub fn new(names: Vec<String>) -> Self {
+        Self(names)
+    }
+
+    /// Returns the Names of the Entrypoints
+    pub fn names(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Checks if Connections accepted by the given Entrypoint are allowed.
+    /// Connections of Acceptors without a Name, like the Tunneler, are
+    /// allowed by every List
+    pub fn allows(&self, entrypoint: Option<&str>) -> bool {
+        match entrypoint {
+            Some(name) => self.0.is_empty() || self.0.iter().any(|n| n == name),
+            None => true,
+        }
+    }
+
+    /// Checks if there is any Entrypoint that is allowed by both Lists
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.0.is_empty() || other.0.is_empty() || self.0.iter().any(|n| other.0.contains(n))
+    }
+
+    /// Checks if every Entrypoint allowed by the other List is also
+    /// allowed by this one
+    pub fn covers(&self, other: &Self) -> bool {
+        if self.0.is_empty() {
+            return true;
+        }
+
+        !other.0.is_empty() && other.0.iter().all(|n| self.0.contains(n))
+    }
+}
+
+impl From<Vec<String>> for EntryPoints {
+    fn from(names: Vec<String>) -> Self {
+        Self::new(names)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry_points(names: &[&str]) -> EntryPoints {
+        EntryPoints::new(names.iter().map(|n| n.to_string()).collect())
+    }
+
+    #[test]
+    fn allows() {
+        assert_eq!(true, entry_points(&[]).allows(None));
+        assert_eq!(true, entry_points(&[]).allows(Some("web")));
+        assert_eq!(true, entry_points(&["web"]).allows(Some("web")));
+        assert_eq!(false, entry_points(&["web"]).allows(Some("websecure")));
+        assert_eq!(true, entry_points(&["web"]).allows(None));
+    }
+
+    #[test]
+    fn overlaps() {
+        assert_eq!(true, entry_points(&[]).overlaps(&entry_points(&["web"])));
+        assert_eq!(
+            true,
+            entry_points(&["web", "internal"]).overlaps(&entry_points(&["internal"]))
+        );
+        assert_eq!(
+            false,
+            entry_points(&["web"]).overlaps(&entry_points(&["websecure"]))
+        );
+    }
+
+    #[test]
+    fn covers() {
+        assert_eq!(true, entry_points(&[]).covers(&entry_points(&["web"])));
+        assert_eq!(false, entry_points(&["web"]).covers(&entry_points(&[])));
+        assert_eq!(
+            true,
+            entry_points(&["web", "internal"]).covers(&entry_points(&["internal"]))
+        );
+        assert_eq!(
+            false,
+            entry_points(&["web"]).covers(&entry_points(&["web", "internal"]))
+        );
+    }
+}
@@ -4,14 +4,32 @@
 mod manager;
 pub use manager::{new, ReadManager};
 
+mod connection_info;
+pub use connection_info::ConnectionInfo;
+
+mod entry_points;
+pub use entry_points::EntryPoints;
+
 mod matcher;
-pub use matcher::Matcher;
+pub use matcher::{Matcher, Pattern};
 
 mod service;
-pub use service::{ConnectError, Service};
+pub use service::{
+    ConnectError, Endpoint, HashKey, HealthCheck, OutlierDetection, ProxyProtocol, Service,
+    ServiceProtocol, ServiceTimeouts, ServiceTls, Strategy,
+};
 
 mod action;
-pub use action::{Action, CorsOpts};
+pub use action::{
+    Action, AuthAddressError, CorsOpts, ForwardAuth, IpListOpts, RateLimit, RateLimitKey,
+    RedirectRegexOpts, RedirectSchemeOpts, RedirectStatus, ReplacePathRegexOpts,
+};
+
+mod early_response;
+pub use early_response::EarlyResponse;
+
+mod body;
+pub use body::{BodyPipeline, BodyTransform};
 
 mod middleware;
 pub use middleware::Middleware;
@@ -22,5 +40,14 @@ pub use middleware_list::MiddlewareList;
 mod rule;
 pub use rule::{Rule, RuleTLS};
 
+mod tcp_rule;
+pub use tcp_rule::{TcpMatcher, TcpRule, TcpRuleTLS};
+
+mod udp_rule;
+pub use udp_rule::UdpRule;
+
+mod conflict;
+pub use conflict::{find_conflicts, RuleConflict};
+
 pub mod parser;
 pub mod rule_list;
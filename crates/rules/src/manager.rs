@@ -1,4 +1,4 @@
-use crate::{rule_list, Rule};
+use crate::{rule_list, ConnectionInfo, Rule};
 
 use std::sync::Arc;
 
@@ -21,13 +21,14 @@ pub fn new() -> (ReadManager, RuleListWriteHandle) {
 }
 
 impl ReadManager {
-    /// Searches for a Rule that matches the given Request
+    /// Searches for a Rule that matches the given Request, which
+    /// was received on a Connection described by the given Info
     ///
     /// # Returns:
     /// * None if no Rule matches the Request
     /// * Some(rule) the first Rule that matched
-    pub fn match_req(&self, req: &Request) -> Option<Arc<Rule>> {
-        self.rules.find(req)
+    pub fn match_req(&self, req: &Request, info: &ConnectionInfo) -> Option<Arc<Rule>> {
+        self.rules.find(req, info)
     }
 
     /// Attempts to load all the currently visible Rules
@@ -1,6 +1,13 @@
+use ipnetwork::IpNetwork;
 use serde::Serialize;
 use stream_httparse::Request;
 
+use crate::ConnectionInfo;
+
+mod overlap;
+mod pattern;
+pub use pattern::Pattern;
+
 /// Used to determine if a Request matches certain
 /// criteria
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -12,22 +19,60 @@ pub enum Matcher {
     /// Evaluates all of the internal Matchers until one
     /// of them returns true
     Or(Vec<Matcher>),
+    /// Inverts the Result of the internal Matcher
+    Not(Box<Matcher>),
     /// Matches the Domain("Host"-Header) of the Request
     /// against the given Domain
     Domain(String),
+    /// Matches the Domain("Host"-Header) of the Request
+    /// against a Wildcard-Domain, like "*.example.com", where
+    /// the Wildcard covers exactly one Label
+    WildcardDomain(String),
+    /// Matches the Domain("Host"-Header) of the Request
+    /// against the given Pattern
+    HostRegexp(Pattern),
     /// Matches the Path of the Request against the given
     /// Prefix
     PathPrefix(String),
+    /// Matches the Path of the Request, without the Query,
+    /// against the given Pattern
+    PathRegexp(Pattern),
+    /// Matches the Method of the Request against the given
+    /// Method
+    Method(String),
+    /// Matches the Path of the Request, without the Query,
+    /// exactly against the given Path
+    Path(String),
+    /// Matches if the Request contains the given Header
+    /// (first) with exactly the given Value (second)
+    Header(String, String),
+    /// Matches if the Request contains the given Header
+    /// (first) with a Value that matches the Pattern (second)
+    HeaderRegexp(String, Pattern),
+    /// Matches if the Query of the Request contains the given
+    /// Key (first) with the given Value (second)
+    Query(String, String),
+    /// Matches if the IP of the Client is part of the given
+    /// Network
+    ClientIP(IpNetwork),
 }
 
 impl Matcher {
     /// Checks if the current Rule matches the given
-    /// HTTP-Request
+    /// HTTP-Request, without any Information about the
+    /// Connection it was received on
     pub fn matches(&self, req: &Request) -> bool {
+        self.matches_with(req, &ConnectionInfo::default())
+    }
+
+    /// Checks if the current Rule matches the given
+    /// HTTP-Request, that was received on a Connection
+    /// described by the given Info
+    pub fn matches_with(&self, req: &Request, info: &ConnectionInfo) -> bool {
         match *self {
             Self::And(ref matchers) => {
                 for tmp in matchers {
-                    if !tmp.matches(req) {
+                    if !tmp.matches_with(req, info) {
                         return false;
                     }
                 }
@@ -36,27 +81,63 @@ impl Matcher {
             }
             Self::Or(ref matchers) => {
                 for tmp in matchers {
-                    if tmp.matches(req) {
+                    if tmp.matches_with(req, info) {
                         return true;
                     }
                 }
 
                 false
             }
+            Self::Not(ref matcher) => !matcher.matches_with(req, info),
             Self::Domain(ref domain) => match req.headers().get("Host") {
                 Some(value) => value == domain,
                 None => false,
             },
+            Self::WildcardDomain(ref wildcard) => {
+                match req.headers().get("Host").and_then(|v| v.try_as_str_ref()) {
+                    Some(host) => wildcard_matches(wildcard, host_without_port(host)),
+                    None => false,
+                }
+            }
+            Self::HostRegexp(ref pattern) => {
+                match req.headers().get("Host").and_then(|v| v.try_as_str_ref()) {
+                    Some(host) => pattern.is_match(host_without_port(host)),
+                    None => false,
+                }
+            }
             Self::PathPrefix(ref path) => {
                 let path_length = path.len();
                 let req_path_length = req.path().len();
 
                 req_path_length >= path_length && &req.path()[0..path_length] == path.as_str()
             }
+            Self::PathRegexp(ref pattern) => pattern.is_match(path_without_query(req)),
+            Self::Method(ref method) => req.method().serialize() == method,
+            Self::Path(ref path) => path_without_query(req) == path,
+            Self::Header(ref key, ref value) => match req.headers().get(key.as_str()) {
+                Some(header) => header == value,
+                None => false,
+            },
+            Self::HeaderRegexp(ref key, ref pattern) => match req.headers().get(key.as_str()) {
+                Some(header) => pattern.is_match(&header.to_string()),
+                None => false,
+            },
+            Self::Query(ref key, ref value) => match req.path().split_once('?') {
+                Some((_, query)) => form_urlencoded::parse(query.as_bytes())
+                    .any(|(k, v)| k == key.as_str() && v == value.as_str()),
+                None => false,
+            },
+            Self::ClientIP(ref network) => match info.client_ip() {
+                Some(ip) => network.contains(*ip),
+                None => false,
+            },
         }
     }
 
-    /// Returns the Domain that belongs to this Matcher
+    /// Returns the Domain that belongs to this Matcher.
+    ///
+    /// Wildcard-Domains are returned in the same Form as they
+    /// would appear in a Certificate, like "*.example.com"
     pub fn get_host(&self) -> Option<String> {
         match *self {
             Self::And(ref matchers) | Self::Or(ref matchers) => {
@@ -67,12 +148,45 @@ impl Matcher {
                 }
                 None
             }
-            Self::Domain(ref domain) => Some(domain.to_owned()),
+            Self::Domain(ref domain) | Self::WildcardDomain(ref domain) => Some(domain.to_owned()),
             _ => None,
         }
     }
 }
 
+/// Returns the Path of the Request without the Query-Part
+fn path_without_query<'a>(req: &'a Request) -> &'a str {
+    match req.path().split_once('?') {
+        Some((path, _)) => path,
+        None => req.path(),
+    }
+}
+
+/// Returns the Host without the Port, if it has one
+fn host_without_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        // IPv6-Addresses contain Colons themselves, so only a numeric
+        // Part after the last Colon is treated as the Port
+        Some((name, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    }
+}
+
+/// Checks if the Host is covered by the Wildcard, like "*.example.com",
+/// which only covers a single Label in place of the "*". Domains are
+/// compared case-insensitive
+pub(crate) fn wildcard_matches(wildcard: &str, host: &str) -> bool {
+    let suffix = match wildcard.strip_prefix('*') {
+        Some(s) => s.to_ascii_lowercase(),
+        None => return false,
+    };
+
+    match host.to_ascii_lowercase().strip_suffix(suffix.as_str()) {
+        Some(label) => !label.is_empty() && !label.contains('.'),
+        None => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +315,201 @@ mod tests {
         assert_eq!(false, rule.matches(&req));
     }
 
+    #[test]
+    fn matcher_not_matching() {
+        let mut headers = Headers::new();
+        headers.set("Host", "other.net");
+        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, "".as_bytes());
+
+        let rule = Matcher::Not(Box::new(Matcher::PathPrefix("/api/".to_owned())));
+
+        assert_eq!(true, rule.matches(&req));
+    }
+    #[test]
+    fn matcher_not_not_matching() {
+        let mut headers = Headers::new();
+        headers.set("Host", "other.net");
+        let req = Request::new("HTTP/1.1", Method::GET, "/api/test", headers, "".as_bytes());
+
+        let rule = Matcher::Not(Box::new(Matcher::PathPrefix("/api/".to_owned())));
+
+        assert_eq!(false, rule.matches(&req));
+    }
+
+    #[test]
+    fn get_host_not_domain() {
+        let matcher = Matcher::Not(Box::new(Matcher::Domain("test".to_owned())));
+        assert_eq!(None, matcher.get_host());
+    }
+    #[test]
+    fn matcher_method() {
+        let req = Request::new("HTTP/1.1", Method::POST, "/test", Headers::new(), &[]);
+
+        assert_eq!(true, Matcher::Method("POST".to_owned()).matches(&req));
+        assert_eq!(false, Matcher::Method("GET".to_owned()).matches(&req));
+    }
+
+    #[test]
+    fn matcher_path_ignores_query() {
+        let req = Request::new(
+            "HTTP/1.1",
+            Method::GET,
+            "/api/test?key=value",
+            Headers::new(),
+            &[],
+        );
+
+        assert_eq!(true, Matcher::Path("/api/test".to_owned()).matches(&req));
+        assert_eq!(false, Matcher::Path("/api/".to_owned()).matches(&req));
+    }
+
+    #[test]
+    fn matcher_header() {
+        let mut headers = Headers::new();
+        headers.set("X-Api-Version", "2");
+        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, &[]);
+
+        assert_eq!(
+            true,
+            Matcher::Header("X-Api-Version".to_owned(), "2".to_owned()).matches(&req)
+        );
+        assert_eq!(
+            false,
+            Matcher::Header("X-Api-Version".to_owned(), "1".to_owned()).matches(&req)
+        );
+        assert_eq!(
+            false,
+            Matcher::Header("X-Other".to_owned(), "2".to_owned()).matches(&req)
+        );
+    }
+
+    #[test]
+    fn matcher_header_regexp() {
+        let mut headers = Headers::new();
+        headers.set("X-Api-Version", "v12");
+        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, &[]);
+
+        let rule = Matcher::HeaderRegexp(
+            "X-Api-Version".to_owned(),
+            Pattern::new("^v[0-9]+$").unwrap(),
+        );
+        assert_eq!(true, rule.matches(&req));
+
+        let rule = Matcher::HeaderRegexp("X-Api-Version".to_owned(), Pattern::new("^v1$").unwrap());
+        assert_eq!(false, rule.matches(&req));
+    }
+
+    #[test]
+    fn matcher_query() {
+        let req = Request::new(
+            "HTTP/1.1",
+            Method::GET,
+            "/test?first=1&second=some%20value",
+            Headers::new(),
+            &[],
+        );
+
+        assert_eq!(
+            true,
+            Matcher::Query("second".to_owned(), "some value".to_owned()).matches(&req)
+        );
+        assert_eq!(
+            false,
+            Matcher::Query("first".to_owned(), "2".to_owned()).matches(&req)
+        );
+
+        let req = Request::new("HTTP/1.1", Method::GET, "/test", Headers::new(), &[]);
+        assert_eq!(
+            false,
+            Matcher::Query("first".to_owned(), "1".to_owned()).matches(&req)
+        );
+    }
+
+    #[test]
+    fn matcher_client_ip() {
+        let req = Request::new("HTTP/1.1", Method::GET, "/test", Headers::new(), &[]);
+        let rule = Matcher::ClientIP("10.0.0.0/8".parse().unwrap());
+
+        let info = ConnectionInfo::new(Some("10.1.2.3".parse().unwrap()));
+        assert_eq!(true, rule.matches_with(&req, &info));
+
+        let info = ConnectionInfo::new(Some("192.168.2.3".parse().unwrap()));
+        assert_eq!(false, rule.matches_with(&req, &info));
+
+        assert_eq!(false, rule.matches(&req));
+    }
+
+    #[test]
+    fn matcher_wildcard_domain() {
+        let rule = Matcher::WildcardDomain("*.example.com".to_owned());
+
+        let mut headers = Headers::new();
+        headers.set("Host", "tenant.example.com");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(true, rule.matches(&req));
+
+        let mut headers = Headers::new();
+        headers.set("Host", "example.com");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(false, rule.matches(&req));
+
+        let mut headers = Headers::new();
+        headers.set("Host", "a.tenant.example.com");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(false, rule.matches(&req));
+    }
+
+    #[test]
+    fn matcher_wildcard_domain_with_port() {
+        let rule = Matcher::WildcardDomain("*.example.com".to_owned());
+
+        let mut headers = Headers::new();
+        headers.set("Host", "a.example.com:8443");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(true, rule.matches(&req));
+    }
+
+    #[test]
+    fn matcher_wildcard_domain_case_insensitive() {
+        let rule = Matcher::WildcardDomain("*.Example.com".to_owned());
+
+        let mut headers = Headers::new();
+        headers.set("Host", "Tenant.EXAMPLE.com");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(true, rule.matches(&req));
+    }
+
+    #[test]
+    fn matcher_host_regexp() {
+        let rule = Matcher::HostRegexp(Pattern::new("^[a-z]+\\.example\\.com$").unwrap());
+
+        let mut headers = Headers::new();
+        headers.set("Host", "tenant.example.com");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(true, rule.matches(&req));
+
+        let mut headers = Headers::new();
+        headers.set("Host", "tenant1.example.com");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(false, rule.matches(&req));
+
+        let mut headers = Headers::new();
+        headers.set("Host", "tenant.example.com:8080");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+        assert_eq!(true, rule.matches(&req));
+    }
+
+    #[test]
+    fn matcher_path_regexp() {
+        let rule = Matcher::PathRegexp(Pattern::new("^/api/v[0-9]+/$").unwrap());
+
+        let req = Request::new("HTTP/1.1", Method::GET, "/api/v2/?q=1", Headers::new(), &[]);
+        assert_eq!(true, rule.matches(&req));
+
+        let req = Request::new("HTTP/1.1", Method::GET, "/api/vx/", Headers::new(), &[]);
+        assert_eq!(false, rule.matches(&req));
+    }
+
     #[test]
     fn get_host_domain() {
         let matcher = Matcher::Domain("test".to_owned());
@@ -237,4 +546,17 @@ mod tests {
         let matcher = Matcher::Or(vec![Matcher::PathPrefix("test".to_owned())]);
         assert_eq!(None, matcher.get_host());
     }
+    #[test]
+    fn get_host_wildcard_domain() {
+        let matcher = Matcher::And(vec![
+            Matcher::PathPrefix("test".to_owned()),
+            Matcher::WildcardDomain("*.example.com".to_owned()),
+        ]);
+        assert_eq!(Some("*.example.com".to_owned()), matcher.get_host());
+    }
+    #[test]
+    fn get_host_host_regexp() {
+        let matcher = Matcher::HostRegexp(Pattern::new("^.*$").unwrap());
+        assert_eq!(None, matcher.get_host());
+    }
 }
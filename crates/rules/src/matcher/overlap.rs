@@ -0,0 +1,177 @@
+//! Static Analysis of Matchers, used to detect Rules that conflict
+//! with each other.
+//!
+//! Both checks are conservative: `covers` only returns true if it can
+//! prove that the Matcher covers the other one and `overlaps` only
+//! returns false if it can prove that the two Matchers are disjoint
+
+use super::{wildcard_matches, Matcher};
+
+impl Matcher {
+    /// Checks if every Request matched by the other Matcher is also
+    /// matched by this Matcher
+    pub fn covers(&self, other: &Matcher) -> bool {
+        if self == other {
+            return true;
+        }
+
+        match (self, other) {
+            (_, Self::Or(others)) => others.iter().all(|o| self.covers(o)),
+            (Self::And(matchers), _) => matchers.iter().all(|m| m.covers(other)),
+            (Self::Or(matchers), _) => matchers.iter().any(|m| m.covers(other)),
+            (_, Self::And(others)) => others.iter().any(|o| self.covers(o)),
+            // Every Request-Path in origin-form starts with a '/'
+            (Self::PathPrefix(prefix), _) if prefix.is_empty() || prefix == "/" => true,
+            (Self::WildcardDomain(wildcard), Self::Domain(domain)) => {
+                wildcard_matches(wildcard, domain)
+            }
+            (Self::HostRegexp(pattern), Self::Domain(domain)) => pattern.is_match(domain),
+            (Self::PathPrefix(prefix), Self::PathPrefix(o_prefix))
+            | (Self::PathPrefix(prefix), Self::Path(o_prefix)) => o_prefix.starts_with(prefix),
+            (Self::PathRegexp(pattern), Self::Path(path)) => pattern.is_match(path),
+            (Self::ClientIP(network), Self::ClientIP(o_network)) => {
+                network.prefix() <= o_network.prefix() && network.contains(o_network.network())
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks if there could be a Request that is matched by both
+    /// this and the other Matcher
+    pub fn overlaps(&self, other: &Matcher) -> bool {
+        match (self, other) {
+            (Self::Or(matchers), _) => matchers.iter().any(|m| m.overlaps(other)),
+            (_, Self::Or(others)) => others.iter().any(|o| self.overlaps(o)),
+            (Self::And(matchers), _) => matchers.iter().all(|m| m.overlaps(other)),
+            (_, Self::And(others)) => others.iter().all(|o| self.overlaps(o)),
+            (Self::Domain(domain), Self::Domain(o_domain)) => domain == o_domain,
+            (Self::Domain(domain), Self::WildcardDomain(wildcard))
+            | (Self::WildcardDomain(wildcard), Self::Domain(domain)) => {
+                wildcard_matches(wildcard, domain)
+            }
+            (Self::Domain(domain), Self::HostRegexp(pattern))
+            | (Self::HostRegexp(pattern), Self::Domain(domain)) => pattern.is_match(domain),
+            (Self::WildcardDomain(wildcard), Self::WildcardDomain(o_wildcard)) => {
+                wildcard == o_wildcard
+            }
+            (Self::PathPrefix(prefix), Self::PathPrefix(o_prefix)) => {
+                prefix.starts_with(o_prefix.as_str()) || o_prefix.starts_with(prefix.as_str())
+            }
+            (Self::Path(path), Self::PathPrefix(prefix))
+            | (Self::PathPrefix(prefix), Self::Path(path)) => {
+                // The Prefix is matched against the Path including the Query
+                let only_query = prefix
+                    .strip_prefix(path.as_str())
+                    .map(|rest| rest.starts_with('?'));
+                path.starts_with(prefix.as_str()) || only_query == Some(true)
+            }
+            (Self::Path(path), Self::Path(o_path)) => path == o_path,
+            (Self::Path(path), Self::PathRegexp(pattern))
+            | (Self::PathRegexp(pattern), Self::Path(path)) => pattern.is_match(path),
+            (Self::Method(method), Self::Method(o_method)) => method == o_method,
+            (Self::Header(key, value), Self::Header(o_key, o_value)) => {
+                !key.eq_ignore_ascii_case(o_key) || value == o_value
+            }
+            (Self::ClientIP(network), Self::ClientIP(o_network)) => {
+                network.contains(o_network.network()) || o_network.contains(network.network())
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::Pattern;
+
+    fn domain(raw: &str) -> Matcher {
+        Matcher::Domain(raw.to_owned())
+    }
+    fn prefix(raw: &str) -> Matcher {
+        Matcher::PathPrefix(raw.to_owned())
+    }
+
+    #[test]
+    fn covers_prefix() {
+        assert_eq!(true, prefix("/api").covers(&prefix("/api/v1")));
+        assert_eq!(false, prefix("/api/v1").covers(&prefix("/api")));
+        assert_eq!(true, prefix("/").covers(&domain("example.com")));
+    }
+
+    #[test]
+    fn covers_and() {
+        let specific = Matcher::And(vec![domain("example.com"), prefix("/api/v1")]);
+        let general = Matcher::And(vec![domain("example.com"), prefix("/api")]);
+
+        assert_eq!(true, general.covers(&specific));
+        assert_eq!(false, specific.covers(&general));
+        assert_eq!(true, domain("example.com").covers(&specific));
+    }
+
+    #[test]
+    fn covers_or() {
+        let both = Matcher::Or(vec![domain("example.com"), domain("example.net")]);
+
+        assert_eq!(true, both.covers(&domain("example.net")));
+        assert_eq!(false, domain("example.net").covers(&both));
+        assert_eq!(true, both.covers(&both.clone()));
+    }
+
+    #[test]
+    fn covers_host_patterns() {
+        let wildcard = Matcher::WildcardDomain("*.example.com".to_owned());
+        let regex = Matcher::HostRegexp(Pattern::new("^[a-z]+\\.example\\.com$").unwrap());
+
+        assert_eq!(true, wildcard.covers(&domain("api.example.com")));
+        assert_eq!(false, wildcard.covers(&domain("example.com")));
+        assert_eq!(true, regex.covers(&domain("api.example.com")));
+    }
+
+    #[test]
+    fn covers_not() {
+        let not = Matcher::Not(Box::new(domain("example.com")));
+
+        assert_eq!(false, not.covers(&domain("example.net")));
+        assert_eq!(true, not.covers(&not.clone()));
+    }
+
+    #[test]
+    fn overlaps_disjoint() {
+        assert_eq!(
+            false,
+            domain("example.com").overlaps(&domain("example.net"))
+        );
+        assert_eq!(false, prefix("/api").overlaps(&prefix("/static")));
+        assert_eq!(
+            false,
+            Matcher::Method("GET".to_owned()).overlaps(&Matcher::Method("POST".to_owned()))
+        );
+        assert_eq!(
+            false,
+            Matcher::Path("/api".to_owned()).overlaps(&prefix("/api/v1"))
+        );
+    }
+
+    #[test]
+    fn overlaps_and() {
+        let first = Matcher::And(vec![domain("example.com"), prefix("/api")]);
+        let second = Matcher::And(vec![domain("example.com"), prefix("/api/v1")]);
+        let third = Matcher::And(vec![domain("example.net"), prefix("/api")]);
+
+        assert_eq!(true, first.overlaps(&second));
+        assert_eq!(false, first.overlaps(&third));
+    }
+
+    #[test]
+    fn overlaps_unknown() {
+        let not = Matcher::Not(Box::new(domain("example.com")));
+
+        assert_eq!(true, not.overlaps(&domain("example.com")));
+        assert_eq!(
+            true,
+            domain("example.com").overlaps(&Matcher::Method("GET".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use regex::Regex;
+use serde::Serialize;
+
+/// A Regular-Expression that is compiled once, when the Matcher
+/// is created, and then reused for every Request
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Compiles the given raw Expression
+    pub fn new(raw: &str) -> Result<Self, regex::Error> {
+        Regex::new(raw).map(Self)
+    }
+
+    /// Checks if the given Value matches the Pattern
+    pub fn is_match(&self, value: &str) -> bool {
+        self.0.is_match(value)
+    }
+
+    /// Replaces all the Matches in the given Value with the Replacement,
+    /// which may reference the captured Groups like "${1}"
+    pub fn replace_all(&self, value: &str, replacement: &str) -> String {
+        self.0.replace_all(value, replacement).into_owned()
+    }
+
+    /// Returns the Match of the Pattern at the start of the given Value,
+    /// if there is a non-empty one
+    pub fn find_prefix<'a>(&self, value: &'a str) -> Option<&'a str> {
+        self.0
+            .find(value)
+            .filter(|m| m.start() == 0 && !m.as_str().is_empty())
+            .map(|m| m.as_str())
+    }
+
+    /// Returns the raw Expression this Pattern was compiled from
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_match() {
+        let pattern = Pattern::new("^v[0-9]+$").unwrap();
+
+        assert_eq!(true, pattern.is_match("v12"));
+        assert_eq!(false, pattern.is_match("version"));
+    }
+
+    #[test]
+    fn find_prefix() {
+        let pattern = Pattern::new("/v[0-9]+").unwrap();
+
+        assert_eq!(Some("/v2"), pattern.find_prefix("/v2/users"));
+        assert_eq!(None, pattern.find_prefix("/api/v2"));
+    }
+
+    #[test]
+    fn invalid_expression() {
+        assert_eq!(true, Pattern::new("v[0-9").is_err());
+    }
+
+    #[test]
+    fn equal_by_expression() {
+        assert_eq!(Pattern::new("a+").unwrap(), Pattern::new("a+").unwrap());
+        assert_ne!(Pattern::new("a+").unwrap(), Pattern::new("a*").unwrap());
+    }
+}
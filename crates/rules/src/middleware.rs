@@ -1,4 +1,4 @@
-use crate::Action;
+use crate::{Action, BodyTransform, ConnectionInfo, EarlyResponse};
 
 use general::Name;
 use general_traits::{ConfigItem, DefaultConfig};
@@ -21,9 +21,14 @@ impl Middleware {
         Self { name, action }
     }
 
-    /// Applies the Middleware to the given Request
-    pub fn apply_req<'a>(&self, req: &mut Request<'a>) -> Result<(), Response<'a>> {
-        self.action.apply_req(req)
+    /// Applies the Middleware to the given Request, see `Action::apply_req`
+    pub async fn apply_req<'a>(
+        &self,
+        req: &mut Request<'a>,
+        info: &ConnectionInfo,
+        rule: &Name,
+    ) -> Result<(), EarlyResponse<'a>> {
+        self.action.apply_req(req, info, rule).await
     }
     /// Applies the Middleware to the given Response
     pub fn apply_resp<'a, 'b, 'c>(&'a self, req: &Request<'_>, resp: &'b mut Response<'c>)
@@ -34,6 +39,20 @@ impl Middleware {
     {
         self.action.apply_resp(req, resp)
     }
+    /// Applies the Middleware to the Head of a Response whose Body is
+    /// streamed, see `Action::apply_resp_stream`
+    pub fn apply_resp_stream<'a, 'b, 'c>(
+        &'a self,
+        req: &Request<'_>,
+        resp: &'b mut Response<'c>,
+    ) -> Option<Box<dyn BodyTransform>>
+    where
+        'a: 'b,
+        'a: 'c,
+        'c: 'b,
+    {
+        self.action.apply_resp_stream(req, resp)
+    }
 
     /// Returns the Name of the Middleware
     pub fn get_name(&self) -> &Name {
@@ -1,5 +1,5 @@
-use crate::Middleware;
-use general::Shared;
+use crate::{BodyPipeline, ConnectionInfo, EarlyResponse, Middleware};
+use general::{Name, Shared};
 
 use stream_httparse::{Request, Response};
 
@@ -14,17 +14,24 @@ pub struct MiddlewareList {
 }
 
 impl MiddlewareList {
-    /// Applies all the middlewares to the Request, until one returns
-    /// a Response to be send directly
+    /// Applies all the middlewares to the Request, that was received on
+    /// a Connection described by the given Info and matched by the Rule
+    /// with the given Name, until one returns a Response to be send
+    /// directly
     ///
     /// # Returns
     /// * Ok: Everything can proceed as normal and all the middlewares were
     /// successfully applied
     /// * Err(response): Some middleware returned early with an Response that
     /// should be returned immediately
-    pub fn apply_middlewares_req<'a>(&self, req: &mut Request<'a>) -> Result<(), Response<'a>> {
+    pub async fn apply_middlewares_req<'a>(
+        &self,
+        req: &mut Request<'a>,
+        info: &ConnectionInfo,
+        rule: &Name,
+    ) -> Result<(), EarlyResponse<'a>> {
         for middleware in self.middlewares.iter() {
-            middleware.apply_req(req)?;
+            middleware.apply_req(req, info, rule).await?;
         }
 
         Ok(())
@@ -44,6 +51,36 @@ impl MiddlewareList {
             middleware.apply_resp(req, resp);
         }
     }
+
+    /// Checks if all the Middlewares can be applied to a Response whose
+    /// Body is streamed
+    pub fn streams_resp(&self) -> bool {
+        self.middlewares
+            .iter()
+            .all(|middleware| middleware.get_action().streams_body())
+    }
+
+    /// Applies all the Middlewares to the Head of a Response whose Body
+    /// is streamed and returns the Pipeline that should be used to
+    /// transform the Body
+    pub fn apply_middlewares_resp_stream<'a, 'b, 'c>(
+        &'a self,
+        req: &Request<'_>,
+        resp: &'b mut Response<'c>,
+    ) -> BodyPipeline
+    where
+        'a: 'b,
+        'a: 'c,
+        'c: 'b,
+    {
+        let mut pipeline = BodyPipeline::new();
+        for middleware in self.middlewares.iter() {
+            if let Some(transform) = middleware.apply_resp_stream(req, resp) {
+                pipeline.push(transform);
+            }
+        }
+        pipeline
+    }
 }
 
 impl From<&[Shared<Middleware>]> for MiddlewareList {
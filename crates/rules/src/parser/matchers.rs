@@ -1,83 +1,573 @@
-use crate::Matcher;
+use std::fmt::Display;
 
-mod find;
+use ipnetwork::IpNetwork;
 
-/// Is simply used to get the inner part of something that is wrapped with
-/// paranthese
-fn inner_scope(raw: &str) -> &str {
-    let tmp = match raw.strip_prefix('(') {
-        Some(r) => r,
-        None => {
-            return raw;
-        }
-    };
+use crate::{Matcher, Pattern, TcpMatcher};
 
-    match tmp.strip_suffix(')') {
-        Some(r) => r,
-        None => raw,
-    }
-}
+mod tokenizer;
+use tokenizer::{Token, TokenKind};
 
 /// The Error returned when Parsing Matchers
 #[derive(Debug, PartialEq)]
 pub enum ParseMatcherError {
-    /// The given String was in an Invalid-Format and could not be parsed as a
-    /// valid Matcher
-    Invalid,
+    /// The Rule contained a Character that is not valid at the given
+    /// Position
+    UnexpectedCharacter {
+        /// The Byte-Offset of the Character in the Rule
+        position: usize,
+        /// The Character itself
+        character: char,
+    },
+    /// An Argument was opened with a Quote but never closed
+    UnterminatedString {
+        /// The Byte-Offset of the opening Quote
+        position: usize,
+    },
+    /// Found a Token that is not allowed at the given Position
+    UnexpectedToken {
+        /// The Byte-Offset of the Token in the Rule
+        position: usize,
+        /// The Token that was found
+        found: String,
+        /// A Description of what was expected instead
+        expected: &'static str,
+    },
+    /// The Rule ended while still expecting more Tokens
+    UnexpectedEnd {
+        /// The Byte-Offset at which the Rule ended
+        position: usize,
+        /// A Description of what was expected instead
+        expected: &'static str,
+    },
+    /// The Arguments given to a Matcher are not valid for it
+    InvalidArguments {
+        /// The Key of the Matcher
+        key: String,
+        /// The Byte-Offset of the Key in the Rule
+        position: usize,
+        /// Why the Arguments are invalid
+        reason: String,
+    },
     /// The specified Matcher is unknown to the Load-Balancer
     UnknownMatcher {
         /// The Unknown-Key that was specified
         key: String,
+        /// The Byte-Offset of the Key in the Rule
+        position: usize,
     },
 }
 
-/// Parses a raw String that defines matchers
-pub fn parse_matchers(raw: &str) -> Result<Matcher, ParseMatcherError> {
-    let cleaned = raw.replace(' ', "");
-    let raw = cleaned.as_str();
-
-    match find::split(raw) {
-        Some((first_part, second_part, combinator)) => {
-            let first_part = inner_scope(first_part);
-            let first = match parse_matchers(first_part) {
-                Ok(v) => v,
-                Err(_) => {
-                    return Err(ParseMatcherError::Invalid);
+impl Display for ParseMatcherError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "Unexpected Character '{}' at position {}",
+                character, position
+            ),
+            Self::UnterminatedString { position } => {
+                write!(f, "Unterminated Argument starting at position {}", position)
+            }
+            Self::UnexpectedToken {
+                position,
+                found,
+                expected,
+            } => write!(
+                f,
+                "Unexpected {} at position {}, expected {}",
+                found, position, expected
+            ),
+            Self::UnexpectedEnd { position, expected } => write!(
+                f,
+                "Unexpected end of Rule at position {}, expected {}",
+                position, expected
+            ),
+            Self::InvalidArguments {
+                key,
+                position,
+                reason,
+            } => write!(
+                f,
+                "Invalid Arguments for '{}' at position {}: {}",
+                key, position, reason
+            ),
+            Self::UnknownMatcher { key, position } => {
+                write!(f, "Unknown Matcher '{}' at position {}", key, position)
+            }
+        }
+    }
+}
+impl std::error::Error for ParseMatcherError {}
+
+/// The Syntax-Tree of a Rule, before the individual Matchers have
+/// been validated
+#[derive(Debug)]
+enum Expr<'a> {
+    And(Vec<Expr<'a>>),
+    Or(Vec<Expr<'a>>),
+    Not(Box<Expr<'a>>),
+    Call {
+        key: &'a str,
+        position: usize,
+        args: Vec<&'a str>,
+    },
+}
+
+impl Expr<'_> {
+    fn into_matcher(self) -> Result<Matcher, ParseMatcherError> {
+        match self {
+            Self::And(parts) => Ok(Matcher::And(
+                parts
+                    .into_iter()
+                    .map(Expr::into_matcher)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Self::Or(parts) => Ok(Matcher::Or(
+                parts
+                    .into_iter()
+                    .map(Expr::into_matcher)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Self::Not(inner) => Ok(Matcher::Not(Box::new(inner.into_matcher()?))),
+            Self::Call {
+                key,
+                position,
+                args,
+            } => create_matcher(key, position, args),
+        }
+    }
+
+    fn into_tcp_matcher(self) -> Result<TcpMatcher, ParseMatcherError> {
+        match self {
+            Self::And(parts) => Ok(TcpMatcher::And(
+                parts
+                    .into_iter()
+                    .map(Expr::into_tcp_matcher)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Self::Or(parts) => Ok(TcpMatcher::Or(
+                parts
+                    .into_iter()
+                    .map(Expr::into_tcp_matcher)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Self::Not(inner) => Ok(TcpMatcher::Not(Box::new(inner.into_tcp_matcher()?))),
+            Self::Call {
+                key,
+                position,
+                args,
+            } => create_tcp_matcher(key, position, args),
+        }
+    }
+}
+
+/// Combines the Matchers created for the individual Arguments of a
+/// single Call, like "Host(`a`, `b`)", which matches if any of them
+/// match
+fn any_of(mut matchers: Vec<Matcher>) -> Matcher {
+    if matchers.len() == 1 {
+        matchers.remove(0)
+    } else {
+        Matcher::Or(matchers)
+    }
+}
+
+/// Converts a Traefik-v2 Host-Template, like "{subdomain:[a-z]+}.example.com",
+/// into a Regular-Expression that matches the entire Host.
+///
+/// Everything outside of the Braces is matched literally and Variables
+/// without an explicit Pattern match a single Label
+fn host_template(raw: &str) -> Result<String, String> {
+    let mut result = String::from("(?i)^");
+
+    let mut rest = raw;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&regex::escape(&rest[..start]));
+
+        // Find the matching closing Brace, as the Pattern itself may
+        // contain Braces as well, like in "[a-z]{3}"
+        let mut depth = 0;
+        let mut end = None;
+        for (index, c) in rest[start..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(start + index);
+                        break;
+                    }
                 }
+                _ => {}
             };
+        }
+        let end = end.ok_or_else(|| format!("unclosed Variable in '{}'", raw))?;
 
-            let second_part = inner_scope(second_part);
-            let second = match parse_matchers(second_part) {
-                Ok(v) => v,
-                Err(_) => {
-                    return Err(ParseMatcherError::Invalid);
+        let variable = &rest[start + 1..end];
+        let (name, pattern) = match variable.split_once(':') {
+            Some((name, pattern)) => (name, pattern),
+            None => (variable, "[^.]+"),
+        };
+        if name.is_empty() || pattern.is_empty() {
+            return Err(format!("invalid Variable '{{{}}}' in '{}'", variable, raw));
+        }
+
+        result.push_str("(?:");
+        result.push_str(pattern);
+        result.push(')');
+
+        rest = &rest[end + 1..];
+    }
+    result.push_str(&regex::escape(rest));
+    result.push('$');
+
+    Ok(result)
+}
+
+/// Creates the actual Matcher for a single Call with all of its
+/// Arguments
+fn create_matcher(
+    key: &str,
+    position: usize,
+    args: Vec<&str>,
+) -> Result<Matcher, ParseMatcherError> {
+    let invalid = |reason: &str| ParseMatcherError::InvalidArguments {
+        key: key.to_string(),
+        position,
+        reason: reason.to_string(),
+    };
+
+    match key {
+        "Host" => {
+            let mut matchers = Vec::with_capacity(args.len());
+            for arg in args {
+                if !arg.contains('*') {
+                    matchers.push(Matcher::Domain(arg.to_owned()));
+                    continue;
                 }
-            };
 
-            match combinator {
-                find::Combinator::And => Ok(Matcher::And(vec![first, second])),
-                find::Combinator::Or => Ok(Matcher::Or(vec![first, second])),
+                // Only a single leading Wildcard-Label is supported, like
+                // in "*.example.com"
+                match arg.strip_prefix("*.") {
+                    Some(rest) if !rest.is_empty() && !rest.contains('*') => {
+                        matchers.push(Matcher::WildcardDomain(arg.to_owned()));
+                    }
+                    _ => {
+                        return Err(invalid(&format!(
+                            "'{}' is not a valid Wildcard-Domain",
+                            arg
+                        )))
+                    }
+                };
             }
+
+            Ok(any_of(matchers))
         }
-        None => {
-            let (key, raw_inner) = find::inner(raw).ok_or(ParseMatcherError::Invalid)?;
+        "HostRegexp" => {
+            let mut matchers = Vec::with_capacity(args.len());
+            for arg in args {
+                let pattern = host_template(arg)
+                    .and_then(|raw| Pattern::new(&raw).map_err(|e| e.to_string()))
+                    .map_err(|e| invalid(&e))?;
+                matchers.push(Matcher::HostRegexp(pattern));
+            }
 
-            let inner = raw_inner
-                .split('`')
-                .nth(1)
-                .ok_or(ParseMatcherError::Invalid)?;
+            Ok(any_of(matchers))
+        }
+        "PathRegexp" => {
+            let mut matchers = Vec::with_capacity(args.len());
+            for arg in args {
+                let pattern = Pattern::new(arg).map_err(|e| invalid(&e.to_string()))?;
+                matchers.push(Matcher::PathRegexp(pattern));
+            }
 
-            match key {
-                "Host" => Ok(Matcher::Domain(inner.to_owned())),
-                "PathPrefix" => Ok(Matcher::PathPrefix(inner.to_owned())),
-                _ => Err(ParseMatcherError::UnknownMatcher {
-                    key: key.to_string(),
-                }),
+            Ok(any_of(matchers))
+        }
+        "PathPrefix" => Ok(any_of(
+            args.into_iter()
+                .map(|arg| Matcher::PathPrefix(arg.to_owned()))
+                .collect(),
+        )),
+        "Path" => Ok(any_of(
+            args.into_iter()
+                .map(|arg| Matcher::Path(arg.to_owned()))
+                .collect(),
+        )),
+        "Method" => Ok(any_of(
+            args.into_iter()
+                .map(|arg| Matcher::Method(arg.to_uppercase()))
+                .collect(),
+        )),
+        "Header" | "Headers" => match args[..] {
+            [name, value] => Ok(Matcher::Header(name.to_owned(), value.to_owned())),
+            _ => Err(invalid("expected a Header-Name and a Value")),
+        },
+        "HeaderRegexp" | "HeadersRegexp" => match args[..] {
+            [name, raw] => {
+                let pattern = Pattern::new(raw).map_err(|e| invalid(&e.to_string()))?;
+                Ok(Matcher::HeaderRegexp(name.to_owned(), pattern))
+            }
+            _ => Err(invalid("expected a Header-Name and a Regular-Expression")),
+        },
+        "Query" => {
+            // Either a List of "key=value" Pairs or a single Key and Value
+            let pairs: Option<Vec<(&str, &str)>> =
+                args.iter().map(|arg| arg.split_once('=')).collect();
+            match (pairs, &args[..]) {
+                (Some(pairs), _) => Ok(any_of(
+                    pairs
+                        .into_iter()
+                        .map(|(k, v)| Matcher::Query(k.to_owned(), v.to_owned()))
+                        .collect(),
+                )),
+                (None, [k, v]) => Ok(Matcher::Query((*k).to_owned(), (*v).to_owned())),
+                (None, _) => Err(invalid("expected `key=value` Pairs or a Key and a Value")),
             }
         }
+        "ClientIP" => {
+            let mut matchers = Vec::with_capacity(args.len());
+            for arg in args {
+                let network: IpNetwork = arg
+                    .parse()
+                    .map_err(|_| invalid(&format!("'{}' is not a valid IP or CIDR", arg)))?;
+                matchers.push(Matcher::ClientIP(network));
+            }
+
+            Ok(any_of(matchers))
+        }
+        _ => Err(ParseMatcherError::UnknownMatcher {
+            key: key.to_string(),
+            position,
+        }),
     }
 }
 
+/// Creates the actual TcpMatcher for a single Call with all of its
+/// Arguments
+fn create_tcp_matcher(
+    key: &str,
+    position: usize,
+    args: Vec<&str>,
+) -> Result<TcpMatcher, ParseMatcherError> {
+    let invalid = |reason: &str| ParseMatcherError::InvalidArguments {
+        key: key.to_string(),
+        position,
+        reason: reason.to_string(),
+    };
+
+    let mut matchers = Vec::with_capacity(args.len());
+    match key {
+        "HostSNI" => {
+            for arg in args {
+                if arg == "*" {
+                    matchers.push(TcpMatcher::Any);
+                    continue;
+                }
+                if !arg.contains('*') {
+                    matchers.push(TcpMatcher::HostSNI(arg.to_owned()));
+                    continue;
+                }
+
+                match arg.strip_prefix("*.") {
+                    Some(rest) if !rest.is_empty() && !rest.contains('*') => {
+                        matchers.push(TcpMatcher::WildcardSNI(arg.to_owned()));
+                    }
+                    _ => {
+                        return Err(invalid(&format!(
+                            "'{}' is not a valid Wildcard-Domain",
+                            arg
+                        )))
+                    }
+                };
+            }
+        }
+        "ClientIP" => {
+            for arg in args {
+                let network: IpNetwork = arg
+                    .parse()
+                    .map_err(|_| invalid(&format!("'{}' is not a valid IP or CIDR", arg)))?;
+                matchers.push(TcpMatcher::ClientIP(network));
+            }
+        }
+        _ => {
+            return Err(ParseMatcherError::UnknownMatcher {
+                key: key.to_string(),
+                position,
+            })
+        }
+    };
+
+    if matchers.len() == 1 {
+        Ok(matchers.remove(0))
+    } else {
+        Ok(TcpMatcher::Or(matchers))
+    }
+}
+
+/// A simple recursive-descent Parser for the Rule-Grammar
+///
+/// ```text
+/// or    = and ( "||" and )*
+/// and   = unary ( "&&" unary )*
+/// unary = "!" unary | "(" or ")" | Ident "(" Value ( "," Value )* ")"
+/// ```
+struct RuleParser<'a> {
+    tokens: Vec<Token<'a>>,
+    index: usize,
+    end: usize,
+}
+
+impl<'a> RuleParser<'a> {
+    fn peek(&self) -> Option<TokenKind<'a>> {
+        self.tokens.get(self.index).map(|t| t.kind)
+    }
+
+    /// Returns the next Token or an Error stating what was expected
+    /// instead, if the Rule already ended
+    fn next(
+        &mut self,
+        expected: &'static str,
+    ) -> Result<(TokenKind<'a>, usize), ParseMatcherError> {
+        match self.tokens.get(self.index) {
+            Some(token) => {
+                self.index += 1;
+                Ok((token.kind, token.position))
+            }
+            None => Err(ParseMatcherError::UnexpectedEnd {
+                position: self.end,
+                expected,
+            }),
+        }
+    }
+
+    fn unexpected(
+        kind: TokenKind<'_>,
+        position: usize,
+        expected: &'static str,
+    ) -> ParseMatcherError {
+        ParseMatcherError::UnexpectedToken {
+            position,
+            found: kind.to_string(),
+            expected,
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr<'a>, ParseMatcherError> {
+        let mut parts = vec![self.and()?];
+        while self.peek() == Some(TokenKind::Or) {
+            self.index += 1;
+            parts.push(self.and()?);
+        }
+
+        if parts.len() == 1 {
+            Ok(parts.remove(0))
+        } else {
+            Ok(Expr::Or(parts))
+        }
+    }
+
+    fn and(&mut self) -> Result<Expr<'a>, ParseMatcherError> {
+        let mut parts = vec![self.unary()?];
+        while self.peek() == Some(TokenKind::And) {
+            self.index += 1;
+            parts.push(self.unary()?);
+        }
+
+        if parts.len() == 1 {
+            Ok(parts.remove(0))
+        } else {
+            Ok(Expr::And(parts))
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr<'a>, ParseMatcherError> {
+        match self.next("a Matcher")? {
+            (TokenKind::Not, _) => Ok(Expr::Not(Box::new(self.unary()?))),
+            (TokenKind::OpenParen, _) => {
+                let inner = self.or()?;
+                match self.next("')'")? {
+                    (TokenKind::CloseParen, _) => Ok(inner),
+                    (kind, position) => Err(Self::unexpected(kind, position, "')'")),
+                }
+            }
+            (TokenKind::Ident(key), position) => {
+                match self.next("'('")? {
+                    (TokenKind::OpenParen, _) => {}
+                    (kind, position) => return Err(Self::unexpected(kind, position, "'('")),
+                };
+
+                let mut args = Vec::new();
+                loop {
+                    match self.next("an Argument")? {
+                        (TokenKind::Value(arg), _) => args.push(arg),
+                        (kind, position) => {
+                            return Err(Self::unexpected(kind, position, "an Argument"))
+                        }
+                    };
+
+                    match self.next("',' or ')'")? {
+                        (TokenKind::Comma, _) => continue,
+                        (TokenKind::CloseParen, _) => break,
+                        (kind, position) => {
+                            return Err(Self::unexpected(kind, position, "',' or ')'"))
+                        }
+                    };
+                }
+
+                Ok(Expr::Call {
+                    key,
+                    position,
+                    args,
+                })
+            }
+            (kind, position) => Err(Self::unexpected(kind, position, "a Matcher")),
+        }
+    }
+}
+
+/// Parses a raw String that defines matchers, using the same Syntax
+/// as Traefik-v2 Rules.
+///
+/// `&&` binds stronger than `||`, `!` negates the following Matcher and
+/// Parentheses can be used to group Matchers
+pub fn parse_matchers(raw: &str) -> Result<Matcher, ParseMatcherError> {
+    parse_expr(raw)?.into_matcher()
+}
+
+/// Parses a raw String that defines the Matchers of a TcpRule, using the
+/// same Syntax as Traefik-v2 TCP-Rules.
+///
+/// Only "HostSNI" and "ClientIP" are supported, where "HostSNI(`*`)" matches
+/// every Connection, including the ones without TLS
+pub fn parse_tcp_matchers(raw: &str) -> Result<TcpMatcher, ParseMatcherError> {
+    parse_expr(raw)?.into_tcp_matcher()
+}
+
+/// Parses the raw String into its Syntax-Tree
+fn parse_expr(raw: &str) -> Result<Expr<'_>, ParseMatcherError> {
+    let tokens = tokenizer::tokenize(raw)?;
+
+    let mut parser = RuleParser {
+        tokens,
+        index: 0,
+        end: raw.len(),
+    };
+    let expr = parser.or()?;
+
+    if let Some(token) = parser.tokens.get(parser.index) {
+        return Err(RuleParser::unexpected(
+            token.kind,
+            token.position,
+            "'&&' or '||'",
+        ));
+    }
+
+    Ok(expr)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,17 +658,230 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_and_binds_stronger_than_or() {
+        let input = "Host(`example.com`) || Host(`example.net`) && PathPrefix(`/api/`)";
+
+        assert_eq!(
+            Ok(Matcher::Or(vec![
+                Matcher::Domain("example.com".to_owned()),
+                Matcher::And(vec![
+                    Matcher::Domain("example.net".to_owned()),
+                    Matcher::PathPrefix("/api/".to_owned())
+                ]),
+            ])),
+            parse_matchers(input)
+        );
+    }
+
+    #[test]
+    fn parse_chained_and() {
+        let input = "Host(`example.com`) && PathPrefix(`/api/`) && PathPrefix(`/api/v1/`)";
+
+        assert_eq!(
+            Ok(Matcher::And(vec![
+                Matcher::Domain("example.com".to_owned()),
+                Matcher::PathPrefix("/api/".to_owned()),
+                Matcher::PathPrefix("/api/v1/".to_owned())
+            ])),
+            parse_matchers(input)
+        );
+    }
+
+    #[test]
+    fn parse_negation() {
+        let input = "Host(`example.com`) && !(PathPrefix(`/admin/`) || !PathPrefix(`/`))";
+
+        assert_eq!(
+            Ok(Matcher::And(vec![
+                Matcher::Domain("example.com".to_owned()),
+                Matcher::Not(Box::new(Matcher::Or(vec![
+                    Matcher::PathPrefix("/admin/".to_owned()),
+                    Matcher::Not(Box::new(Matcher::PathPrefix("/".to_owned())))
+                ]))),
+            ])),
+            parse_matchers(input)
+        );
+    }
+
+    #[test]
+    fn parse_multiple_arguments() {
+        assert_eq!(
+            Ok(Matcher::Or(vec![
+                Matcher::Domain("example.com".to_owned()),
+                Matcher::Domain("example.net".to_owned())
+            ])),
+            parse_matchers("Host(`example.com`, `example.net`)")
+        );
+    }
+
+    #[test]
+    fn parse_double_quoted_argument() {
+        assert_eq!(
+            Ok(Matcher::Domain("example.com".to_owned())),
+            parse_matchers("Host(\"example.com\")")
+        );
+    }
+
+    #[test]
+    fn parse_method_path_and_header() {
+        let input = "Method(`get`, `POST`) && Path(`/api`) && Headers(`X-Version`, `2`)";
+
+        assert_eq!(
+            Ok(Matcher::And(vec![
+                Matcher::Or(vec![
+                    Matcher::Method("GET".to_owned()),
+                    Matcher::Method("POST".to_owned())
+                ]),
+                Matcher::Path("/api".to_owned()),
+                Matcher::Header("X-Version".to_owned(), "2".to_owned()),
+            ])),
+            parse_matchers(input)
+        );
+    }
+
+    #[test]
+    fn parse_header_regexp() {
+        assert_eq!(
+            Ok(Matcher::HeaderRegexp(
+                "X-Version".to_owned(),
+                Pattern::new("v[0-9]+").unwrap()
+            )),
+            parse_matchers("HeadersRegexp(`X-Version`, `v[0-9]+`)")
+        );
+
+        assert_eq!(
+            true,
+            matches!(
+                parse_matchers("HeaderRegexp(`X-Version`, `v[0-9`)"),
+                Err(ParseMatcherError::InvalidArguments { position: 0, .. })
+            )
+        );
+    }
+
+    #[test]
+    fn parse_query() {
+        assert_eq!(
+            Ok(Matcher::Or(vec![
+                Matcher::Query("a".to_owned(), "1".to_owned()),
+                Matcher::Query("b".to_owned(), "".to_owned())
+            ])),
+            parse_matchers("Query(`a=1`, `b=`)")
+        );
+
+        assert_eq!(
+            Ok(Matcher::Query("a".to_owned(), "1".to_owned())),
+            parse_matchers("Query(`a`, `1`)")
+        );
+
+        assert_eq!(
+            true,
+            matches!(
+                parse_matchers("Query(`a`)"),
+                Err(ParseMatcherError::InvalidArguments { .. })
+            )
+        );
+    }
+
+    #[test]
+    fn parse_client_ip() {
+        assert_eq!(
+            Ok(Matcher::Or(vec![
+                Matcher::ClientIP("10.0.0.0/8".parse().unwrap()),
+                Matcher::ClientIP("::1".parse().unwrap())
+            ])),
+            parse_matchers("ClientIP(`10.0.0.0/8`, `::1`)")
+        );
+
+        assert_eq!(
+            Err(ParseMatcherError::InvalidArguments {
+                key: "ClientIP".to_owned(),
+                position: 3,
+                reason: "'10.0.0/33' is not a valid IP or CIDR".to_owned()
+            }),
+            parse_matchers("!( ClientIP(`10.0.0/33`))")
+        );
+    }
+
+    #[test]
+    fn parse_wildcard_host() {
+        assert_eq!(
+            Ok(Matcher::Or(vec![
+                Matcher::WildcardDomain("*.example.com".to_owned()),
+                Matcher::Domain("example.com".to_owned())
+            ])),
+            parse_matchers("Host(`*.example.com`, `example.com`)")
+        );
+
+        assert_eq!(
+            Err(ParseMatcherError::InvalidArguments {
+                key: "Host".to_owned(),
+                position: 0,
+                reason: "'api.*.example.com' is not a valid Wildcard-Domain".to_owned()
+            }),
+            parse_matchers("Host(`api.*.example.com`)")
+        );
+    }
+
+    #[test]
+    fn parse_host_regexp() {
+        assert_eq!(
+            Ok(Matcher::HostRegexp(
+                Pattern::new("(?i)^(?:[a-z]{2,})\\.customer\\.example\\.com$").unwrap()
+            )),
+            parse_matchers("HostRegexp(`{subdomain:[a-z]{2,}}.customer.example.com`)")
+        );
+
+        assert_eq!(
+            Ok(Matcher::HostRegexp(
+                Pattern::new("(?i)^(?:[^.]+)\\.example\\.com$").unwrap()
+            )),
+            parse_matchers("HostRegexp(`{subdomain}.example.com`)")
+        );
+
+        assert_eq!(
+            true,
+            matches!(
+                parse_matchers("HostRegexp(`{subdomain.example.com`)"),
+                Err(ParseMatcherError::InvalidArguments { .. })
+            )
+        );
+    }
+
+    #[test]
+    fn parse_path_regexp() {
+        assert_eq!(
+            Ok(Matcher::PathRegexp(Pattern::new("^/api/v[0-9]+/").unwrap())),
+            parse_matchers("PathRegexp(`^/api/v[0-9]+/`)")
+        );
+
+        assert_eq!(
+            true,
+            matches!(
+                parse_matchers("PathRegexp(`^/api/v[0-9+/`)"),
+                Err(ParseMatcherError::InvalidArguments { .. })
+            )
+        );
+    }
+
     #[test]
     fn parse_invalid_pair_first_missing() {
         assert_eq!(
-            Err(ParseMatcherError::Invalid),
+            Err(ParseMatcherError::UnexpectedEnd {
+                position: 22,
+                expected: "a Matcher"
+            }),
             parse_matchers("PathPrefix(`/api/`) &&")
         );
     }
     #[test]
     fn parse_invalid_pair_second_missing() {
         assert_eq!(
-            Err(ParseMatcherError::Invalid),
+            Err(ParseMatcherError::UnexpectedToken {
+                position: 0,
+                found: "'&&'".to_owned(),
+                expected: "a Matcher"
+            }),
             parse_matchers("&& PathPrefix(`/api/`)")
         );
     }
@@ -186,7 +889,10 @@ mod tests {
     #[test]
     fn parse_invalid_missing_closing_bracket() {
         assert_eq!(
-            Err(ParseMatcherError::Invalid),
+            Err(ParseMatcherError::UnexpectedEnd {
+                position: 74,
+                expected: "')'"
+            }),
             parse_matchers(
                 "Domain(`example.net`) && (PathPrefix(`/api/`) || PathPrefix(`/dashboard/`)"
             )
@@ -196,13 +902,82 @@ mod tests {
     #[test]
     fn parse_invalid() {
         assert_eq!(
-            Err(ParseMatcherError::Invalid),
+            Err(ParseMatcherError::UnexpectedEnd {
+                position: 18,
+                expected: "',' or ')'"
+            }),
             parse_matchers("Host(`example.net`")
         );
 
         assert_eq!(
-            Err(ParseMatcherError::Invalid),
+            Err(ParseMatcherError::UnexpectedToken {
+                position: 4,
+                found: "'`example.net`'".to_owned(),
+                expected: "'('"
+            }),
             parse_matchers("Host`example.net`)")
         );
     }
+
+    #[test]
+    fn parse_invalid_trailing_token() {
+        assert_eq!(
+            Err(ParseMatcherError::UnexpectedToken {
+                position: 20,
+                found: "'PathPrefix'".to_owned(),
+                expected: "'&&' or '||'"
+            }),
+            parse_matchers("Host(`example.net`) PathPrefix(`/`)")
+        );
+    }
+
+    #[test]
+    fn parse_invalid_no_arguments() {
+        assert_eq!(
+            Err(ParseMatcherError::UnexpectedToken {
+                position: 5,
+                found: "')'".to_owned(),
+                expected: "an Argument"
+            }),
+            parse_matchers("Host()")
+        );
+    }
+
+    #[test]
+    fn parse_unknown_matcher() {
+        assert_eq!(
+            Err(ParseMatcherError::UnknownMatcher {
+                key: "Other".to_owned(),
+                position: 23
+            }),
+            parse_matchers("Host(`example.net`) && Other(`test`)")
+        );
+    }
+
+    #[test]
+    fn parse_tcp_host_sni() {
+        assert_eq!(
+            Ok(TcpMatcher::Or(vec![
+                TcpMatcher::HostSNI("db.example.com".to_owned()),
+                TcpMatcher::WildcardSNI("*.example.net".to_owned()),
+            ])),
+            parse_tcp_matchers("HostSNI(`db.example.com`, `*.example.net`)")
+        );
+    }
+
+    #[test]
+    fn parse_tcp_catch_all() {
+        assert_eq!(Ok(TcpMatcher::Any), parse_tcp_matchers("HostSNI(`*`)"));
+    }
+
+    #[test]
+    fn parse_tcp_unknown_matcher() {
+        assert_eq!(
+            Err(ParseMatcherError::UnknownMatcher {
+                key: "Host".to_owned(),
+                position: 0,
+            }),
+            parse_tcp_matchers("Host(`example.com`)")
+        );
+    }
 }
@@ -0,0 +1,194 @@
+use std::fmt::Display;
+
+use super::ParseMatcherError;
+
+/// The different Kinds of Tokens that make up a Matcher-Rule
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TokenKind<'a> {
+    /// The Name of a Matcher, like "Host" or "PathPrefix"
+    Ident(&'a str),
+    /// A quoted Argument, without the surrounding Quotes
+    Value(&'a str),
+    /// "("
+    OpenParen,
+    /// ")"
+    CloseParen,
+    /// ","
+    Comma,
+    /// "&&"
+    And,
+    /// "||"
+    Or,
+    /// "!"
+    Not,
+}
+
+impl Display for TokenKind<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(ident) => write!(f, "'{}'", ident),
+            Self::Value(value) => write!(f, "'`{}`'", value),
+            Self::OpenParen => write!(f, "'('"),
+            Self::CloseParen => write!(f, "')'"),
+            Self::Comma => write!(f, "','"),
+            Self::And => write!(f, "'&&'"),
+            Self::Or => write!(f, "'||'"),
+            Self::Not => write!(f, "'!'"),
+        }
+    }
+}
+
+/// A single Token together with the Byte-Offset at which it starts
+/// in the raw Input
+#[derive(Debug, PartialEq)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    pub position: usize,
+}
+
+/// Splits the raw Rule into its Tokens, skipping all the Whitespace
+/// in between them
+pub fn tokenize(raw: &str) -> Result<Vec<Token<'_>>, ParseMatcherError> {
+    let mut result = Vec::new();
+    let mut chars = raw.char_indices().peekable();
+
+    while let Some((position, character)) = chars.next() {
+        let kind = match character {
+            c if c.is_whitespace() => continue,
+            '(' => TokenKind::OpenParen,
+            ')' => TokenKind::CloseParen,
+            ',' => TokenKind::Comma,
+            '!' => TokenKind::Not,
+            '&' | '|' => match chars.next_if(|(_, next)| *next == character) {
+                Some(_) if character == '&' => TokenKind::And,
+                Some(_) => TokenKind::Or,
+                None => {
+                    return Err(ParseMatcherError::UnexpectedCharacter {
+                        position,
+                        character,
+                    })
+                }
+            },
+            '`' | '"' => {
+                let start = position + 1;
+                let end = loop {
+                    match chars.next() {
+                        Some((end, c)) if c == character => break end,
+                        Some(_) => {}
+                        None => return Err(ParseMatcherError::UnterminatedString { position }),
+                    };
+                };
+
+                TokenKind::Value(&raw[start..end])
+            }
+            c if is_ident_char(c) => {
+                let mut end = raw.len();
+                while let Some((index, c)) = chars.peek() {
+                    if !is_ident_char(*c) {
+                        end = *index;
+                        break;
+                    }
+                    chars.next();
+                }
+
+                TokenKind::Ident(&raw[position..end])
+            }
+            _ => {
+                return Err(ParseMatcherError::UnexpectedCharacter {
+                    position,
+                    character,
+                })
+            }
+        };
+
+        result.push(Token { kind, position });
+    }
+
+    Ok(result)
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_matcher() {
+        assert_eq!(
+            Ok(vec![
+                Token {
+                    kind: TokenKind::Ident("Host"),
+                    position: 0
+                },
+                Token {
+                    kind: TokenKind::OpenParen,
+                    position: 4
+                },
+                Token {
+                    kind: TokenKind::Value("example.com"),
+                    position: 5
+                },
+                Token {
+                    kind: TokenKind::CloseParen,
+                    position: 18
+                },
+            ]),
+            tokenize("Host(`example.com`)")
+        );
+    }
+
+    #[test]
+    fn operators_and_whitespace() {
+        let kinds: Vec<TokenKind> = tokenize(" ! a && b ||\tc , ")
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect();
+
+        assert_eq!(
+            vec![
+                TokenKind::Not,
+                TokenKind::Ident("a"),
+                TokenKind::And,
+                TokenKind::Ident("b"),
+                TokenKind::Or,
+                TokenKind::Ident("c"),
+                TokenKind::Comma,
+            ],
+            kinds
+        );
+    }
+
+    #[test]
+    fn value_keeps_inner_whitespace() {
+        assert_eq!(
+            Ok(vec![Token {
+                kind: TokenKind::Value("some value"),
+                position: 0
+            }]),
+            tokenize("\"some value\"")
+        );
+    }
+
+    #[test]
+    fn single_ampersand() {
+        assert_eq!(
+            Err(ParseMatcherError::UnexpectedCharacter {
+                position: 2,
+                character: '&'
+            }),
+            tokenize("a & b")
+        );
+    }
+
+    #[test]
+    fn unterminated_value() {
+        assert_eq!(
+            Err(ParseMatcherError::UnterminatedString { position: 5 }),
+            tokenize("Host(`example.com)")
+        );
+    }
+}
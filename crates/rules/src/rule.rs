@@ -1,4 +1,4 @@
-use crate::{Matcher, Middleware, Service};
+use crate::{ConnectionInfo, EntryPoints, Matcher, Middleware, Service};
 
 use general::{Name, Shared};
 use general_traits::ConfigItem;
@@ -29,6 +29,7 @@ pub struct Rule {
     middlewares: Vec<Shared<Middleware>>,
     service: Shared<Service>,
     tls: RuleTLS,
+    entry_points: EntryPoints,
 }
 
 impl Rule {
@@ -47,6 +48,7 @@ impl Rule {
             middlewares,
             service,
             tls: RuleTLS::None,
+            entry_points: EntryPoints::default(),
         }
     }
 
@@ -55,6 +57,17 @@ impl Rule {
         self.tls = val;
     }
 
+    /// Restricts the Rule to the Entrypoints with the given Names,
+    /// an empty List allows all Entrypoints
+    pub fn set_entry_points(&mut self, names: Vec<String>) {
+        self.entry_points = EntryPoints::new(names);
+    }
+
+    /// Returns the Entrypoints this Rule is restricted to
+    pub fn entry_points(&self) -> &EntryPoints {
+        &self.entry_points
+    }
+
     /// Returns the Priority of the Rule
     pub fn priority(&self) -> u32 {
         self.priority
@@ -76,9 +89,10 @@ impl Rule {
         &self.tls
     }
 
-    /// Checks if the Rule matches for the given Request
-    pub fn matches(&self, req: &Request) -> bool {
-        self.matcher.matches(req)
+    /// Checks if the Rule matches for the given Request, that was
+    /// received on a Connection described by the given Info
+    pub fn matches(&self, req: &Request, info: &ConnectionInfo) -> bool {
+        self.entry_points.allows(info.entrypoint()) && self.matcher.matches_with(req, info)
     }
 
     /// Returns the Rule's Middleware List
@@ -123,7 +137,7 @@ mod tests {
             )),
         );
 
-        assert_eq!(true, rule.matches(&req));
+        assert_eq!(true, rule.matches(&req, &ConnectionInfo::default()));
     }
     #[test]
     fn test_1_matches_invalid() {
@@ -142,7 +156,7 @@ mod tests {
             )),
         );
 
-        assert_eq!(false, rule.matches(&req));
+        assert_eq!(false, rule.matches(&req, &ConnectionInfo::default()));
     }
 
     #[test]
@@ -165,7 +179,7 @@ mod tests {
             )),
         );
 
-        assert_eq!(true, rule.matches(&req));
+        assert_eq!(true, rule.matches(&req, &ConnectionInfo::default()));
     }
     #[test]
     fn test_2_matches_invalid_1() {
@@ -187,7 +201,7 @@ mod tests {
             )),
         );
 
-        assert_eq!(false, rule.matches(&req));
+        assert_eq!(false, rule.matches(&req, &ConnectionInfo::default()));
     }
     #[test]
     fn test_2_matches_invalid_2() {
@@ -209,6 +223,6 @@ mod tests {
             )),
         );
 
-        assert_eq!(false, rule.matches(&req));
+        assert_eq!(false, rule.matches(&req, &ConnectionInfo::default()));
     }
 }
@@ -1,6 +1,6 @@
 //! Contains the Datatypes for the Left-Right inspired List of Rules
 
-use crate::Rule;
+use crate::{ConnectionInfo, Rule};
 
 use general::Name;
 use general_traits::ConfigItem;
@@ -9,6 +9,9 @@ use stream_httparse::Request;
 use left_right::{Absorb, ReadHandle, WriteHandle};
 use std::sync::Arc;
 
+mod index;
+use index::RuleIndex;
+
 // The OP-Log type
 enum ListOp {
     Add(Rule),
@@ -18,54 +21,65 @@ enum ListOp {
     Clear,
 }
 
-impl Absorb<ListOp> for Vec<Arc<Rule>> {
+// The Index is only rebuilt for the Operations that finish a Batch of
+// Updates (Sort, Remove and Clear), as every public Function of the
+// Write-Handle ends with one of those, before publishing the Changes
+impl Absorb<ListOp> for RuleIndex {
     fn absorb_first(&mut self, operation: &mut ListOp, _: &Self) {
+        let rules = self.rules_mut();
         match operation {
             ListOp::Add(n_rule) => {
-                self.push(Arc::new(n_rule.clone()));
+                rules.push(Arc::new(n_rule.clone()));
             }
             ListOp::Set(n_rule) => {
-                if let Some(index) = self.iter().position(|x| x.name() == n_rule.name()) {
-                    self.remove(index);
+                if let Some(index) = rules.iter().position(|x| x.name() == n_rule.name()) {
+                    rules.remove(index);
                 }
 
-                self.push(Arc::new(n_rule.clone()));
+                rules.push(Arc::new(n_rule.clone()));
             }
             ListOp::Remove(name) => {
-                if let Some(index) = self.iter().position(|x| x.name() == name) {
-                    self.remove(index);
+                if let Some(index) = rules.iter().position(|x| x.name() == name) {
+                    rules.remove(index);
                 }
+                self.rebuild();
             }
             ListOp::Sort => {
-                self.sort_by(|a, b| b.priority().cmp(&a.priority()));
+                rules.sort_by(|a, b| b.priority().cmp(&a.priority()));
+                self.rebuild();
             }
             ListOp::Clear => {
-                self.clear();
+                rules.clear();
+                self.rebuild();
             }
         };
     }
     fn absorb_second(&mut self, operation: ListOp, _: &Self) {
+        let rules = self.rules_mut();
         match operation {
             ListOp::Add(n_rule) => {
-                self.push(Arc::new(n_rule));
+                rules.push(Arc::new(n_rule));
             }
             ListOp::Set(n_rule) => {
-                if let Some(index) = self.iter().position(|x| x.name() == n_rule.name()) {
-                    self.remove(index);
+                if let Some(index) = rules.iter().position(|x| x.name() == n_rule.name()) {
+                    rules.remove(index);
                 }
 
-                self.push(Arc::new(n_rule));
+                rules.push(Arc::new(n_rule));
             }
             ListOp::Remove(name) => {
-                if let Some(index) = self.iter().position(|x| x.name() == &name) {
-                    self.remove(index);
+                if let Some(index) = rules.iter().position(|x| x.name() == &name) {
+                    rules.remove(index);
                 }
+                self.rebuild();
             }
             ListOp::Sort => {
-                self.sort_by(|a, b| b.priority().cmp(&a.priority()));
+                rules.sort_by(|a, b| b.priority().cmp(&a.priority()));
+                self.rebuild();
             }
             ListOp::Clear => {
-                self.clear();
+                rules.clear();
+                self.rebuild();
             }
         };
     }
@@ -79,7 +93,7 @@ impl Absorb<ListOp> for Vec<Arc<Rule>> {
 
 /// The Write-Half of the Rule-List, this is used to perform
 /// any sort of Updates needed on the List
-pub struct RuleListWriteHandle(WriteHandle<Vec<Arc<Rule>>, ListOp>);
+pub struct RuleListWriteHandle(WriteHandle<RuleIndex, ListOp>);
 impl RuleListWriteHandle {
     /// This function also sorts the list and then
     /// publishes the result
@@ -137,25 +151,26 @@ impl RuleListWriteHandle {
     pub fn publish(&mut self) {
         self.0.publish();
     }
+
+    /// Loads all the currently published Rules from the List
+    pub fn clone_all_rules(&self) -> Vec<Arc<Rule>> {
+        match self.0.enter() {
+            Some(guard) => guard.rules().to_vec(),
+            None => Vec::new(),
+        }
+    }
 }
 
 /// The Reader Part for the List of Rules
-pub struct RuleListReader(ReadHandle<Vec<Arc<Rule>>>);
+pub struct RuleListReader(ReadHandle<RuleIndex>);
 impl RuleListReader {
     /// Attempts to find a Rule that matches against the given
     /// Request. If multiple Rules match against the Request
     /// the one with the highest Priority will be returned
-    pub fn find(&self, req: &Request) -> Option<Arc<Rule>> {
+    pub fn find(&self, req: &Request, info: &ConnectionInfo) -> Option<Arc<Rule>> {
         self.0
             .enter()
-            .map(|rules| {
-                for rule in rules.iter() {
-                    if rule.matches(req) {
-                        return Some(rule.clone());
-                    }
-                }
-                None
-            })
+            .map(|index| index.find(req, info).cloned())
             .unwrap_or(None)
     }
 
@@ -166,7 +181,7 @@ impl RuleListReader {
     pub fn clone_all_rules(&self) -> Option<Vec<Arc<Rule>>> {
         self.0
             .enter()
-            .map(|rules| Some(rules.clone()))
+            .map(|index| Some(index.rules().to_vec()))
             .unwrap_or(None)
     }
 }
@@ -180,7 +195,7 @@ impl Clone for RuleListReader {
 
 /// Creates a new Write/Read pair
 pub fn new() -> (RuleListWriteHandle, RuleListReader) {
-    let (write, read) = left_right::new::<Vec<Arc<Rule>>, ListOp>();
+    let (write, read) = left_right::new::<RuleIndex, ListOp>();
 
     (RuleListWriteHandle(write), RuleListReader(read))
 }
@@ -0,0 +1,331 @@
+use std::{borrow::Cow, collections::HashMap, sync::Arc};
+
+use stream_httparse::Request;
+
+use crate::{ConnectionInfo, Matcher, Rule};
+
+/// A Key under which a Rule can be found in the Index, every Request
+/// that matches the Rule is guaranteed to also match at least one of
+/// its Keys
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Key {
+    Host(String),
+    Prefix(String),
+}
+
+/// Determines the Keys for the given Matcher, returns None if the
+/// Matcher can not be narrowed down to a Set of Keys
+fn index_keys(matcher: &Matcher) -> Option<Vec<Key>> {
+    match matcher {
+        Matcher::Domain(domain) => Some(vec![Key::Host(domain.clone())]),
+        Matcher::PathPrefix(prefix) => Some(vec![Key::Prefix(prefix.clone())]),
+        Matcher::Or(matchers) => {
+            let mut keys = Vec::new();
+            for tmp in matchers {
+                keys.extend(index_keys(tmp)?);
+            }
+            Some(keys)
+        }
+        Matcher::And(matchers) => {
+            // Every part of the And has to match, so the Keys of any single
+            // part are enough. Hosts are preferred as they are more selective
+            let mut result: Option<Vec<Key>> = None;
+            for keys in matchers.iter().filter_map(index_keys) {
+                let only_hosts = keys.iter().all(|k| matches!(k, Key::Host(_)));
+                if only_hosts {
+                    return Some(keys);
+                }
+                if result.is_none() {
+                    result = Some(keys);
+                }
+            }
+            result
+        }
+        _ => None,
+    }
+}
+
+/// A Byte-wise Trie that stores the Positions of all Rules for a
+/// given Path-Prefix
+#[derive(Debug, Clone, Default)]
+struct PrefixTrie {
+    rules: Vec<usize>,
+    children: Vec<(u8, PrefixTrie)>,
+}
+
+impl PrefixTrie {
+    fn insert(&mut self, prefix: &[u8], position: usize) {
+        let (first, rest) = match prefix.split_first() {
+            Some(s) => s,
+            None => {
+                self.rules.push(position);
+                return;
+            }
+        };
+
+        let index = match self.children.binary_search_by_key(first, |(b, _)| *b) {
+            Ok(i) => i,
+            Err(i) => {
+                self.children.insert(i, (*first, PrefixTrie::default()));
+                i
+            }
+        };
+        self.children[index].1.insert(rest, position);
+    }
+
+    /// Calls the given Function with the Rules of every Prefix of
+    /// the given Path
+    fn walk<'a, F>(&'a self, path: &[u8], mut func: F)
+    where
+        F: FnMut(&'a [usize]),
+    {
+        let mut current = self;
+        let mut rest = path;
+        loop {
+            if !current.rules.is_empty() {
+                func(&current.rules);
+            }
+
+            let (first, tmp_rest) = match rest.split_first() {
+                Some(s) => s,
+                None => return,
+            };
+            current = match current.children.binary_search_by_key(first, |(b, _)| *b) {
+                Ok(i) => &current.children[i].1,
+                Err(_) => return,
+            };
+            rest = tmp_rest;
+        }
+    }
+}
+
+/// The priority-sorted List of Rules together with an Index that
+/// allows finding the matching Rule for a Request without checking
+/// every single Rule.
+///
+/// The Index stores the Positions of the Rules in the sorted List, so
+/// the Rule with the lowest Position among all matching Candidates is
+/// the same one a linear scan would have found
+#[derive(Debug, Clone, Default)]
+pub struct RuleIndex {
+    rules: Vec<Arc<Rule>>,
+    hosts: HashMap<String, Vec<usize>>,
+    prefixes: PrefixTrie,
+    fallback: Vec<usize>,
+}
+
+impl RuleIndex {
+    /// Returns all the Rules, sorted by their Priority
+    pub fn rules(&self) -> &[Arc<Rule>] {
+        &self.rules
+    }
+
+    /// Gives mutable access to the List of Rules.
+    ///
+    /// The Index has to be rebuilt, using `rebuild`, once all the
+    /// modifications are done
+    pub fn rules_mut(&mut self) -> &mut Vec<Arc<Rule>> {
+        &mut self.rules
+    }
+
+    /// The Number of Rules
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Rebuilds the Index based on the current List of Rules
+    pub fn rebuild(&mut self) {
+        self.hosts.clear();
+        self.prefixes = PrefixTrie::default();
+        self.fallback.clear();
+
+        for (position, rule) in self.rules.iter().enumerate() {
+            let mut keys = match index_keys(rule.matcher()) {
+                Some(k) => k,
+                None => {
+                    self.fallback.push(position);
+                    continue;
+                }
+            };
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                match key {
+                    Key::Host(host) => self.hosts.entry(host).or_default().push(position),
+                    Key::Prefix(prefix) => self.prefixes.insert(prefix.as_bytes(), position),
+                };
+            }
+        }
+    }
+
+    /// Finds the Rule with the highest Priority that matches the given
+    /// Request
+    pub fn find(&self, req: &Request, info: &ConnectionInfo) -> Option<&Arc<Rule>> {
+        let mut best: Option<usize> = None;
+        let mut check = |candidates: &[usize]| {
+            for &position in candidates {
+                if matches!(best, Some(b) if position >= b) {
+                    return;
+                }
+                if self.rules[position].matches(req, info) {
+                    best = Some(position);
+                    return;
+                }
+            }
+        };
+
+        if let Some(raw_host) = req.headers().get("Host") {
+            let host = match raw_host.try_as_str_ref() {
+                Some(h) => Cow::Borrowed(h),
+                None => Cow::Owned(raw_host.to_string()),
+            };
+            if let Some(candidates) = self.hosts.get(host.as_ref()) {
+                check(candidates);
+            }
+        }
+        self.prefixes.walk(req.path().as_bytes(), &mut check);
+        check(&self.fallback);
+
+        best.map(|position| &self.rules[position])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Name, Shared};
+    use general_traits::ConfigItem;
+    use stream_httparse::{Headers, Method};
+
+    use crate::Service;
+
+    use super::*;
+
+    fn rule(name: &str, priority: u32, matcher: Matcher) -> Arc<Rule> {
+        Arc::new(Rule::new(
+            Name::new(name, Group::Internal),
+            priority,
+            matcher,
+            vec![],
+            Shared::new(Service::new(Name::new("service", Group::Internal), vec![])),
+        ))
+    }
+
+    fn index(rules: Vec<Arc<Rule>>) -> RuleIndex {
+        let mut index = RuleIndex::default();
+        *index.rules_mut() = rules;
+        index.rebuild();
+        index
+    }
+
+    fn find_name(index: &RuleIndex, host: &str, path: &str) -> Option<String> {
+        let mut headers = Headers::new();
+        headers.set("Host", host);
+        let req = Request::new("HTTP/1.1", Method::GET, path, headers, &[]);
+
+        index
+            .find(&req, &ConnectionInfo::default())
+            .map(|r| r.name().to_string())
+    }
+
+    #[test]
+    fn host_and_prefix() {
+        let index = index(vec![
+            rule(
+                "api",
+                3,
+                Matcher::And(vec![
+                    Matcher::Domain("example.com".to_owned()),
+                    Matcher::PathPrefix("/api/".to_owned()),
+                ]),
+            ),
+            rule("other-host", 2, Matcher::Domain("example.net".to_owned())),
+            rule("prefix", 1, Matcher::PathPrefix("/static/".to_owned())),
+        ]);
+
+        assert_eq!(
+            Some("api@internal".to_owned()),
+            find_name(&index, "example.com", "/api/test")
+        );
+        assert_eq!(None, find_name(&index, "example.com", "/other"));
+        assert_eq!(
+            Some("other-host@internal".to_owned()),
+            find_name(&index, "example.net", "/static/file")
+        );
+        assert_eq!(
+            Some("prefix@internal".to_owned()),
+            find_name(&index, "example.org", "/static/file")
+        );
+    }
+
+    #[test]
+    fn keeps_priority_across_index_parts() {
+        let index = index(vec![
+            rule("fallback", 10, Matcher::Method("POST".to_owned())),
+            rule("short-prefix", 5, Matcher::PathPrefix("/".to_owned())),
+            rule("long-prefix", 4, Matcher::PathPrefix("/api/".to_owned())),
+            rule("host", 3, Matcher::Domain("example.com".to_owned())),
+        ]);
+
+        assert_eq!(
+            Some("short-prefix@internal".to_owned()),
+            find_name(&index, "example.com", "/api/test")
+        );
+    }
+
+    #[test]
+    fn or_is_indexed_under_every_host() {
+        let index = index(vec![rule(
+            "multi",
+            1,
+            Matcher::Or(vec![
+                Matcher::Domain("example.com".to_owned()),
+                Matcher::Domain("example.net".to_owned()),
+            ]),
+        )]);
+
+        assert_eq!(
+            Some("multi@internal".to_owned()),
+            find_name(&index, "example.net", "/")
+        );
+        assert_eq!(None, find_name(&index, "example.org", "/"));
+    }
+
+    #[test]
+    fn not_indexable_uses_fallback() {
+        let index = index(vec![rule(
+            "not",
+            1,
+            Matcher::Not(Box::new(Matcher::Domain("example.com".to_owned()))),
+        )]);
+
+        assert_eq!(
+            Some("not@internal".to_owned()),
+            find_name(&index, "example.net", "/")
+        );
+        assert_eq!(None, find_name(&index, "example.com", "/"));
+    }
+
+    #[test]
+    fn index_keys_and_prefers_host() {
+        let matcher = Matcher::And(vec![
+            Matcher::PathPrefix("/api/".to_owned()),
+            Matcher::Domain("example.com".to_owned()),
+        ]);
+
+        assert_eq!(
+            Some(vec![Key::Host("example.com".to_owned())]),
+            index_keys(&matcher)
+        );
+    }
+
+    #[test]
+    fn index_keys_or_with_unindexable() {
+        let matcher = Matcher::Or(vec![
+            Matcher::PathPrefix("/api/".to_owned()),
+            Matcher::Method("GET".to_owned()),
+        ]);
+
+        assert_eq!(None, index_keys(&matcher));
+    }
+}
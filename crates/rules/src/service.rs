@@ -1,9 +1,42 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    future::Future,
+};
 
 use general::{Group, Name};
 use general_traits::{ConfigItem, DefaultConfig};
 
 use serde::Serialize;
+use stream_httparse::{Headers, Method, Request};
+
+use crate::ConnectionInfo;
+
+mod health;
+use health::Health;
+pub use health::HealthCheck;
+
+mod outlier;
+pub use outlier::OutlierDetection;
+
+mod protocol;
+pub use protocol::ServiceProtocol;
+
+mod strategy;
+use strategy::BalanceState;
+pub use strategy::{Endpoint, HashKey, Strategy};
+
+mod timeouts;
+pub use timeouts::ServiceTimeouts;
+
+mod tls;
+pub use tls::ServiceTls;
+
+mod proxy_protocol;
+pub use proxy_protocol::ProxyProtocol;
+
+/// The maximum Number of Addresses that are tried by `connect_with`
+/// before giving up
+const MAX_CONNECT_ATTEMPTS: usize = 3;
 
 /// The Error returned by the Service when it fails to establish
 /// an outgoing connection
@@ -30,11 +63,35 @@ pub struct Service {
     name: Name,
     addresses: Vec<String>,
     current: std::sync::atomic::AtomicUsize,
+    strategy: Option<Strategy>,
+    health_check: Option<HealthCheck>,
+    health: Health,
+    timeouts: Option<ServiceTimeouts>,
+    outlier_detection: Option<OutlierDetection>,
+    protocol: Option<ServiceProtocol>,
+    tls: Option<ServiceTls>,
+    proxy_protocol: Option<ProxyProtocol>,
+    #[serde(skip)]
+    balance: BalanceState,
 }
 
 impl Clone for Service {
     fn clone(&self) -> Self {
-        Service::new(self.name.clone(), self.addresses.clone())
+        let mut result = Service::new(self.name.clone(), self.addresses.clone());
+        result.strategy = self.strategy.clone();
+        result.health_check = self.health_check.clone();
+        result.timeouts = self.timeouts.clone();
+        result.outlier_detection = self.outlier_detection.clone();
+        result.protocol = self.protocol;
+        result.tls = self.tls.clone();
+        result.proxy_protocol = self.proxy_protocol;
+        for index in 0..self.addresses.len() {
+            result
+                .health
+                .set_healthy(index, self.health.is_healthy(index));
+            result.balance.inherit(index, &self.balance, index);
+        }
+        result
     }
 }
 
@@ -47,13 +104,178 @@ impl PartialEq for Service {
 impl Service {
     /// Creates a New Service instance with the given Name and Destinations
     pub fn new(name: Name, destinations: Vec<String>) -> Self {
+        let balance = BalanceState::new(destinations.len());
+        let health = Health::new(destinations.len());
+
         Self {
             name,
             addresses: destinations,
             current: std::sync::atomic::AtomicUsize::new(0),
+            strategy: None,
+            health_check: None,
+            health,
+            timeouts: None,
+            outlier_detection: None,
+            protocol: None,
+            tls: None,
+            proxy_protocol: None,
+            balance,
+        }
+    }
+
+    /// Takes over the Configuration as well as the Health and Ejections
+    /// of the Addresses from the previous Version of this Service.
+    ///
+    /// The Strategy, Health-Check, Timeouts, Outlier-Detection, Protocol,
+    /// TLS-Configuration and PROXY-Protocol are only taken over if they are not set on this
+    /// Service, as they may be configured by a different Configurator
+    /// than the one providing the Addresses
+    pub fn inherit(&mut self, previous: &Service) {
+        if self.strategy.is_none() {
+            self.strategy = previous.strategy.clone();
+        }
+        if self.health_check.is_none() {
+            self.health_check = previous.health_check.clone();
+        }
+        if self.timeouts.is_none() {
+            self.timeouts = previous.timeouts.clone();
+        }
+        if self.outlier_detection.is_none() {
+            self.outlier_detection = previous.outlier_detection.clone();
+        }
+        if self.protocol.is_none() {
+            self.protocol = previous.protocol;
+        }
+        if self.tls.is_none() {
+            self.tls = previous.tls.clone();
+        }
+        if self.proxy_protocol.is_none() {
+            self.proxy_protocol = previous.proxy_protocol;
+        }
+
+        for (index, address) in self.addresses.iter().enumerate() {
+            if let Some(p_index) = previous.addresses.iter().position(|a| a == address) {
+                self.health
+                    .set_healthy(index, previous.health.is_healthy(p_index));
+                self.balance.inherit(index, &previous.balance, p_index);
+            }
         }
     }
 
+    /// Sets the Strategy used to select the Address for a Request
+    pub fn set_strategy(&mut self, strategy: Strategy) {
+        self.strategy = Some(strategy);
+    }
+
+    /// Returns the Strategy that was explicitly configured for this
+    /// Service, if there is none, Round-Robin is used
+    pub fn strategy(&self) -> Option<&Strategy> {
+        self.strategy.as_ref()
+    }
+
+    /// Sets the Health-Check used to actively check the Addresses
+    pub fn set_health_check(&mut self, health_check: HealthCheck) {
+        self.health_check = Some(health_check);
+    }
+
+    /// Returns the Health-Check that was configured for this Service,
+    /// if there is none, every Address is considered healthy
+    pub fn health_check(&self) -> Option<&HealthCheck> {
+        self.health_check.as_ref()
+    }
+
+    /// Sets the Timeouts that overwrite the global Timeouts for
+    /// this Service
+    pub fn set_timeouts(&mut self, timeouts: ServiceTimeouts) {
+        self.timeouts = Some(timeouts);
+    }
+
+    /// Returns the Timeouts that were configured for this Service, if
+    /// there are none, the global Timeouts are used
+    pub fn timeouts(&self) -> Option<&ServiceTimeouts> {
+        self.timeouts.as_ref()
+    }
+
+    /// Sets the Settings used to eject Addresses that keep failing
+    pub fn set_outlier_detection(&mut self, outlier_detection: OutlierDetection) {
+        self.outlier_detection = Some(outlier_detection);
+    }
+
+    /// Returns the Outlier-Detection that was configured for this
+    /// Service, if there is none, the default Settings are used
+    pub fn outlier_detection(&self) -> Option<&OutlierDetection> {
+        self.outlier_detection.as_ref()
+    }
+
+    /// Sets the Protocol used to forward Requests to the Addresses
+    pub fn set_protocol(&mut self, protocol: ServiceProtocol) {
+        self.protocol = Some(protocol);
+    }
+
+    /// Returns the Protocol used to forward Requests to the Addresses,
+    /// if none was configured, HTTP/1.1 is used
+    pub fn protocol(&self) -> ServiceProtocol {
+        self.protocol.unwrap_or_default()
+    }
+
+    /// Sets the TLS-Configuration for the Connections to the Addresses
+    pub fn set_tls(&mut self, tls: ServiceTls) {
+        self.tls = Some(tls);
+    }
+
+    /// Returns the TLS-Configuration that was configured for this
+    /// Service, which is only used if its Protocol uses TLS
+    pub fn tls(&self) -> Option<&ServiceTls> {
+        self.tls.as_ref()
+    }
+
+    /// Sets the Version of the PROXY-Protocol used to pass on the
+    /// Addresses of the Client to the Addresses of the Service
+    pub fn set_proxy_protocol(&mut self, version: ProxyProtocol) {
+        self.proxy_protocol = Some(version);
+    }
+
+    /// Returns the Version of the PROXY-Protocol that should be sent
+    /// when connecting to the Addresses, if any
+    pub fn proxy_protocol(&self) -> Option<ProxyProtocol> {
+        self.proxy_protocol
+    }
+
+    /// Checks if the Address at the given Index is currently
+    /// considered healthy
+    pub fn is_healthy(&self, index: usize) -> bool {
+        self.health.is_healthy(index)
+    }
+
+    /// Marks the Address at the given Index as healthy or unhealthy,
+    /// unhealthy Addresses are not used for Requests
+    pub fn set_healthy(&self, index: usize, healthy: bool) {
+        self.health.set_healthy(index, healthy);
+    }
+
+    /// Returns the Indices of the Addresses that should be used for
+    /// Requests, which are the healthy Addresses that are not ejected.
+    ///
+    /// If there is no such Address, the healthy ones or even all of them
+    /// are returned, as a possibly failing Request is better than no
+    /// Request at all
+    fn candidates(&self) -> Vec<usize> {
+        let healthy = self.health.healthy();
+        if healthy.is_empty() {
+            return (0..self.addresses.len()).collect();
+        }
+
+        let available: Vec<usize> = healthy
+            .iter()
+            .copied()
+            .filter(|i| !self.balance.is_ejected(*i))
+            .collect();
+        if available.is_empty() {
+            return healthy;
+        }
+        available
+    }
+
     /// Returns whether or not the Service is an internal
     /// service
     pub fn is_internal(&self) -> bool {
@@ -73,7 +295,8 @@ impl Service {
 
     /// Gets the next Address to be used for a request
     pub fn round_robin(&self) -> Option<&str> {
-        let length = self.addresses.len();
+        let candidates = self.candidates();
+        let length = candidates.len();
         if length == 0 {
             return None;
         }
@@ -83,11 +306,119 @@ impl Service {
             % length;
         Some(
             self.addresses
-                .get(index)
+                .get(candidates[index])
                 .expect("The loaded Index is always in bounds because of the Module"),
         )
     }
 
+    /// Selects the Address for the given Request using the configured
+    /// Strategy
+    pub fn select(&self, req: &Request, info: &ConnectionInfo) -> Option<Endpoint> {
+        self.select_except(req, info, &[])
+    }
+
+    /// Selects the Address for the given Request like `select`, but
+    /// never selects one of the Addresses at the given Indices
+    pub fn select_except(
+        &self,
+        req: &Request,
+        info: &ConnectionInfo,
+        excluded: &[usize],
+    ) -> Option<Endpoint> {
+        let mut candidates = self.candidates();
+        candidates.retain(|i| !excluded.contains(i));
+        let strategy = self.strategy.as_ref().unwrap_or(&Strategy::RoundRobin);
+
+        strategy.select_from(&self.addresses, &candidates, &self.balance, req, info)
+    }
+
+    /// Reports the Failure of a Request to the given Endpoint, which
+    /// may eject the Address for some time
+    pub fn report_failure(&self, endpoint: &Endpoint) {
+        let default = OutlierDetection::default();
+        let settings = self.outlier_detection.as_ref().unwrap_or(&default);
+        if let Some(duration) = endpoint.report_failure(settings) {
+            tracing::warn!(
+                "Ejecting Address '{}' of Service '{}' for {:?}",
+                self.addresses[endpoint.index()],
+                self.name,
+                duration
+            );
+        }
+    }
+
+    /// Selects the Address for the given Request, using `select`, and
+    /// then connects to it. If connecting fails, the next Address is
+    /// tried, until `MAX_CONNECT_ATTEMPTS` Addresses were tried.
+    ///
+    /// The returned Endpoint should be kept around as long as the
+    /// Request is being handled
+    pub async fn connect_with(
+        &self,
+        req: &Request<'_>,
+        info: &ConnectionInfo,
+    ) -> Result<(tokio::net::TcpStream, Endpoint), ConnectError> {
+        self.connect_using(req, info, |address| {
+            tokio::net::TcpStream::connect(address.to_owned())
+        })
+        .await
+    }
+
+    /// Works like `connect_with`, but uses the given Function to
+    /// obtain the Connection for a selected Address, which allows
+    /// for things like reusing existing Connections
+    pub async fn connect_using<C, F, Fut>(
+        &self,
+        req: &Request<'_>,
+        info: &ConnectionInfo,
+        connect: F,
+    ) -> Result<(C, Endpoint), ConnectError>
+    where
+        F: Fn(&str) -> Fut,
+        Fut: Future<Output = std::io::Result<C>>,
+    {
+        let mut tried = Vec::new();
+        let mut last_error = ConnectError::NoEndpoint;
+
+        while tried.len() < MAX_CONNECT_ATTEMPTS {
+            let endpoint = match self.select_except(req, info, &tried) {
+                Some(e) => e,
+                None => break,
+            };
+
+            let address = &self.addresses[endpoint.index()];
+            match connect(address).await {
+                Ok(c) => return Ok((c, endpoint)),
+                Err(e) => {
+                    tracing::warn!("Connecting to '{}': {}", address, e);
+                    self.report_failure(&endpoint);
+                    tried.push(endpoint.index());
+                    last_error = ConnectError::IO(e);
+                }
+            };
+        }
+
+        Err(last_error)
+    }
+
+    /// Works like `connect_using`, but for Connections that do not consist
+    /// of Requests, like raw TCP-Connections or UDP-Sessions, where the
+    /// Address can only be selected based on the Info of the Connection
+    pub async fn connect_raw<C, F, Fut>(
+        &self,
+        info: &ConnectionInfo,
+        connect: F,
+    ) -> Result<(C, Endpoint), ConnectError>
+    where
+        F: Fn(&str) -> Fut,
+        Fut: Future<Output = std::io::Result<C>>,
+    {
+        // Strategies based on the Request, like Sticky-Sessions, fall back
+        // to their normal Selection for an empty Request
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        self.connect_using(&req, info, connect).await
+    }
+
     /// Automatically gets the next Address from the Service
     /// using `round_robin` and then connects to it
     pub async fn connect(&self) -> Result<tokio::net::TcpStream, ConnectError> {
@@ -112,17 +443,14 @@ impl ConfigItem for Service {
 }
 impl DefaultConfig for Service {
     fn default_name(name: Name) -> Self {
-        Self {
-            name,
-            addresses: Vec::new(),
-            current: std::sync::atomic::AtomicUsize::new(0),
-        }
+        Self::new(name, Vec::new())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use general::Group;
+    use stream_httparse::{Headers, Method};
 
     use super::*;
 
@@ -172,6 +500,231 @@ mod tests {
         assert_eq!(Some("test2"), tmp.round_robin());
     }
 
+    #[test]
+    fn select_default_round_robin() {
+        let tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        assert_eq!(Some(0), tmp.select(&req, &info).map(|e| e.index()));
+        assert_eq!(Some(1), tmp.select(&req, &info).map(|e| e.index()));
+    }
+    #[test]
+    fn clone_keeps_strategy() {
+        let mut tmp = Service::new(Name::new("test", Group::Internal), vec![]);
+        tmp.set_strategy(Strategy::Random);
+
+        assert_eq!(Some(&Strategy::Random), tmp.clone().strategy());
+    }
+    #[test]
+    fn inherit_timeouts() {
+        let timeouts = ServiceTimeouts {
+            connect_ms: Some(100),
+            ..Default::default()
+        };
+        let mut previous = Service::new(Name::new("test", Group::Internal), vec![]);
+        previous.set_timeouts(timeouts.clone());
+
+        let mut tmp = Service::new(Name::new("test", Group::Internal), vec![]);
+        tmp.inherit(&previous);
+        assert_eq!(Some(&timeouts), tmp.timeouts());
+        assert_eq!(Some(&timeouts), tmp.clone().timeouts());
+    }
+
+    #[test]
+    fn inherit_protocol() {
+        let mut previous = Service::new(Name::new("test", Group::Internal), vec![]);
+        previous.set_protocol(ServiceProtocol::H2c);
+
+        let mut tmp = Service::new(Name::new("test", Group::Internal), vec![]);
+        assert_eq!(ServiceProtocol::Http1, tmp.protocol());
+        tmp.inherit(&previous);
+        assert_eq!(ServiceProtocol::H2c, tmp.protocol());
+        assert_eq!(ServiceProtocol::H2c, tmp.clone().protocol());
+    }
+
+    #[test]
+    fn inherit_tls() {
+        let tls = ServiceTls {
+            server_name: Some("example.com".to_owned()),
+            ..Default::default()
+        };
+        let mut previous = Service::new(Name::new("test", Group::Internal), vec![]);
+        previous.set_tls(tls.clone());
+
+        let mut tmp = Service::new(Name::new("test", Group::Internal), vec![]);
+        assert_eq!(None, tmp.tls());
+        tmp.inherit(&previous);
+        assert_eq!(Some(&tls), tmp.tls());
+        assert_eq!(Some(&tls), tmp.clone().tls());
+    }
+
+    #[test]
+    fn inherit_proxy_protocol() {
+        let mut previous = Service::new(Name::new("test", Group::Internal), vec![]);
+        previous.set_proxy_protocol(ProxyProtocol::V2);
+
+        let mut tmp = Service::new(Name::new("test", Group::Internal), vec![]);
+        assert_eq!(None, tmp.proxy_protocol());
+        tmp.inherit(&previous);
+        assert_eq!(Some(ProxyProtocol::V2), tmp.proxy_protocol());
+        assert_eq!(Some(ProxyProtocol::V2), tmp.clone().proxy_protocol());
+    }
+
+    #[test]
+    fn select_skips_unhealthy() {
+        let tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        tmp.set_healthy(0, false);
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        assert_eq!(Some(1), tmp.select(&req, &info).map(|e| e.index()));
+        assert_eq!(Some(1), tmp.select(&req, &info).map(|e| e.index()));
+        assert_eq!(Some("test2"), tmp.round_robin());
+    }
+    #[test]
+    fn select_all_unhealthy() {
+        let tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        tmp.set_healthy(0, false);
+        tmp.set_healthy(1, false);
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        assert_eq!(Some(0), tmp.select(&req, &info).map(|e| e.index()));
+        assert_eq!(Some(1), tmp.select(&req, &info).map(|e| e.index()));
+    }
+    #[test]
+    fn select_except_skips_excluded() {
+        let tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        for _ in 0..3 {
+            let selected = tmp.select_except(&req, &info, &[0]);
+            assert_eq!(Some(1), selected.map(|e| e.index()));
+        }
+        assert_eq!(true, tmp.select_except(&req, &info, &[0, 1]).is_none());
+    }
+    #[test]
+    fn select_skips_ejected() {
+        let tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        let endpoint = tmp.select(&req, &info).unwrap();
+        assert_eq!(0, endpoint.index());
+        for _ in 0..OutlierDetection::default().consecutive_failures {
+            tmp.report_failure(&endpoint);
+        }
+
+        for _ in 0..3 {
+            assert_eq!(Some(1), tmp.select(&req, &info).map(|e| e.index()));
+        }
+    }
+
+    #[test]
+    fn configured_outlier_detection() {
+        let mut tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        tmp.set_outlier_detection(OutlierDetection {
+            consecutive_failures: 1,
+            ..Default::default()
+        });
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        let endpoint = tmp.select(&req, &info).unwrap();
+        assert_eq!(0, endpoint.index());
+        tmp.report_failure(&endpoint);
+
+        assert_eq!(Some(1), tmp.select(&req, &info).map(|e| e.index()));
+        assert_eq!(Some(1), tmp.select(&req, &info).map(|e| e.index()));
+    }
+
+    #[tokio::test]
+    async fn connect_with_retries() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec![
+                "127.0.0.1:1".to_owned(),
+                listener.local_addr().unwrap().to_string(),
+            ],
+        );
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        let (_, endpoint) = tmp.connect_with(&req, &info).await.unwrap();
+        assert_eq!(1, endpoint.index());
+    }
+
+    #[test]
+    fn inherit_health() {
+        let mut previous = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        previous.set_health_check(HealthCheck::default());
+        previous.set_healthy(1, false);
+
+        let mut tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test2".to_owned(), "test3".to_owned()],
+        );
+        tmp.inherit(&previous);
+
+        assert_eq!(Some(&HealthCheck::default()), tmp.health_check());
+        assert_eq!(false, tmp.is_healthy(0));
+        assert_eq!(true, tmp.is_healthy(1));
+    }
+
+    #[test]
+    fn inherit_ejections() {
+        let previous = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test1".to_owned(), "test2".to_owned()],
+        );
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        let endpoint = previous.select(&req, &info).unwrap();
+        assert_eq!(0, endpoint.index());
+        for _ in 0..OutlierDetection::default().consecutive_failures {
+            previous.report_failure(&endpoint);
+        }
+
+        let mut tmp = Service::new(
+            Name::new("test", Group::Internal),
+            vec!["test2".to_owned(), "test1".to_owned()],
+        );
+        tmp.inherit(&previous);
+        for _ in 0..3 {
+            assert_eq!(Some(0), tmp.select(&req, &info).map(|e| e.index()));
+        }
+
+        let cloned = previous.clone();
+        for _ in 0..3 {
+            assert_eq!(Some(1), cloned.select(&req, &info).map(|e| e.index()));
+        }
+    }
+
     #[test]
     fn partial_eq_same() {
         assert_eq!(
@@ -0,0 +1,149 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{ser::SerializeSeq, Deserialize, Serialize};
+
+fn default_interval() -> u64 {
+    10_000
+}
+fn default_timeout() -> u64 {
+    3_000
+}
+fn default_healthy_threshold() -> u32 {
+    2
+}
+fn default_unhealthy_threshold() -> u32 {
+    3
+}
+
+/// The Configuration for actively checking the Health of the
+/// Addresses of a Service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthCheck {
+    /// The Path that should be requested using HTTP, if this is not
+    /// set, a successful TCP-Connection is enough
+    #[serde(default)]
+    pub path: Option<String>,
+    /// The Interval between two Checks, in Milliseconds
+    #[serde(default = "default_interval")]
+    pub interval_ms: u64,
+    /// The Time after which a single Check is considered to have
+    /// failed, in Milliseconds
+    #[serde(default = "default_timeout")]
+    pub timeout_ms: u64,
+    /// The Number of consecutive successful Checks needed for an
+    /// unhealthy Address to be considered healthy again
+    #[serde(default = "default_healthy_threshold")]
+    pub healthy_threshold: u32,
+    /// The Number of consecutive failed Checks needed for a healthy
+    /// Address to be considered unhealthy
+    #[serde(default = "default_unhealthy_threshold")]
+    pub unhealthy_threshold: u32,
+}
+
+impl Default for HealthCheck {
+    fn default() -> Self {
+        Self {
+            path: None,
+            interval_ms: default_interval(),
+            timeout_ms: default_timeout(),
+            healthy_threshold: default_healthy_threshold(),
+            unhealthy_threshold: default_unhealthy_threshold(),
+        }
+    }
+}
+
+impl HealthCheck {
+    /// The Interval between two Checks
+    pub fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.interval_ms)
+    }
+
+    /// The Timeout for a single Check
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.timeout_ms)
+    }
+}
+
+/// The Health of all the Addresses of a Service, every Address is
+/// considered healthy until it is marked otherwise
+#[derive(Debug)]
+pub(crate) struct Health(Box<[AtomicBool]>);
+
+impl Health {
+    pub fn new(addresses: usize) -> Self {
+        Self((0..addresses).map(|_| AtomicBool::new(true)).collect())
+    }
+
+    pub fn is_healthy(&self, index: usize) -> bool {
+        self.0
+            .get(index)
+            .map(|h| h.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+
+    pub fn set_healthy(&self, index: usize, healthy: bool) {
+        if let Some(tmp) = self.0.get(index) {
+            tmp.store(healthy, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the Indices of all the healthy Addresses
+    pub fn healthy(&self) -> Vec<usize> {
+        (0..self.0.len()).filter(|i| self.is_healthy(*i)).collect()
+    }
+}
+
+impl Serialize for Health {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for index in 0..self.0.len() {
+            seq.serialize_element(&self.is_healthy(index))?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let parsed: HealthCheck = serde_json::from_value(serde_json::json!({
+            "path": "/health",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            HealthCheck {
+                path: Some("/health".to_owned()),
+                ..Default::default()
+            },
+            parsed
+        );
+    }
+
+    #[test]
+    fn healthy_indices() {
+        let health = Health::new(3);
+        health.set_healthy(1, false);
+
+        assert_eq!(vec![0, 2], health.healthy());
+        assert_eq!(false, health.is_healthy(1));
+        assert_eq!(false, health.is_healthy(5));
+    }
+
+    #[test]
+    fn serialize() {
+        let health = Health::new(2);
+        health.set_healthy(0, false);
+
+        assert_eq!(
+            serde_json::json!([false, true]),
+            serde_json::to_value(&health).unwrap()
+        );
+    }
+}
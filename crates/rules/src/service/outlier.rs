@@ -0,0 +1,248 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+fn default_consecutive_failures() -> u32 {
+    5
+}
+fn default_base_ejection() -> u64 {
+    10_000
+}
+fn default_max_ejection() -> u64 {
+    5 * 60_000
+}
+
+/// The Configuration for passively ejecting the Addresses of a Service
+/// that keep failing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutlierDetection {
+    /// The Number of consecutive Failures after which an Address is
+    /// ejected
+    #[serde(default = "default_consecutive_failures")]
+    pub consecutive_failures: u32,
+    /// The Time for which an Address is ejected the first time, every
+    /// following Ejection, without a Success in between, doubles it, in
+    /// Milliseconds
+    #[serde(default = "default_base_ejection")]
+    pub base_ejection_ms: u64,
+    /// The longest Time for which an Address is ejected, in Milliseconds
+    #[serde(default = "default_max_ejection")]
+    pub max_ejection_ms: u64,
+}
+
+impl Default for OutlierDetection {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: default_consecutive_failures(),
+            base_ejection_ms: default_base_ejection(),
+            max_ejection_ms: default_max_ejection(),
+        }
+    }
+}
+
+impl OutlierDetection {
+    /// The Time for which an Address is ejected the first time
+    pub fn base_ejection(&self) -> Duration {
+        Duration::from_millis(self.base_ejection_ms)
+    }
+
+    /// The longest Time for which an Address is ejected
+    pub fn max_ejection(&self) -> Duration {
+        Duration::from_millis(self.max_ejection_ms)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct AddressOutlier {
+    failures: u32,
+    ejections: u32,
+    ejected_until: Option<Instant>,
+}
+
+/// Passively tracks the Failures of the Addresses of a Service and
+/// temporarily ejects Addresses that keep failing
+#[derive(Debug)]
+pub(crate) struct Outliers(Box<[Mutex<AddressOutlier>]>);
+
+impl Outliers {
+    pub fn new(addresses: usize) -> Self {
+        Self((0..addresses).map(|_| Mutex::default()).collect())
+    }
+
+    /// Takes over the Failures, Back-Off and Ejection of the Address at
+    /// the given Index of the previous Outliers
+    pub fn inherit(&self, index: usize, previous: &Outliers, p_index: usize) {
+        let (target, source) = match (self.0.get(index), previous.0.get(p_index)) {
+            (Some(t), Some(s)) => (t, s),
+            _ => return,
+        };
+        let state = source
+            .lock()
+            .expect("The Lock should always be available")
+            .clone();
+        *target.lock().expect("The Lock should always be available") = state;
+    }
+
+    /// Checks if the Address at the given Index is currently ejected
+    pub fn is_ejected(&self, index: usize, now: Instant) -> bool {
+        match self.0.get(index) {
+            Some(tmp) => {
+                let state = tmp.lock().expect("The Lock should always be available");
+                matches!(state.ejected_until, Some(until) if until > now)
+            }
+            None => false,
+        }
+    }
+
+    /// Records a successful Request for the Address, which resets
+    /// its Failures and Back-Off
+    pub fn report_success(&self, index: usize) {
+        if let Some(tmp) = self.0.get(index) {
+            let mut state = tmp.lock().expect("The Lock should always be available");
+            state.failures = 0;
+            state.ejections = 0;
+        }
+    }
+
+    /// Records a failed Request for the Address and returns the
+    /// Duration for which it was ejected, if this Failure resulted
+    /// in an Ejection with the given Settings
+    pub fn report_failure(
+        &self,
+        index: usize,
+        now: Instant,
+        settings: &OutlierDetection,
+    ) -> Option<Duration> {
+        let mut state = self
+            .0
+            .get(index)?
+            .lock()
+            .expect("The Lock should always be available");
+
+        state.failures += 1;
+        if state.failures < settings.consecutive_failures {
+            return None;
+        }
+
+        let max_ejection = settings.max_ejection();
+        let duration = settings
+            .base_ejection()
+            .checked_mul(2u32.saturating_pow(state.ejections))
+            .unwrap_or(max_ejection)
+            .min(max_ejection);
+
+        state.failures = 0;
+        state.ejections += 1;
+        state.ejected_until = Some(now + duration);
+        Some(duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ejects_after_failures() {
+        let outliers = Outliers::new(2);
+        let now = Instant::now();
+        let settings = OutlierDetection::default();
+
+        for _ in 0..settings.consecutive_failures - 1 {
+            assert_eq!(None, outliers.report_failure(0, now, &settings));
+        }
+        assert_eq!(false, outliers.is_ejected(0, now));
+
+        assert_eq!(
+            Some(settings.base_ejection()),
+            outliers.report_failure(0, now, &settings)
+        );
+        assert_eq!(true, outliers.is_ejected(0, now));
+        assert_eq!(false, outliers.is_ejected(1, now));
+        assert_eq!(
+            false,
+            outliers.is_ejected(0, now + settings.base_ejection())
+        );
+    }
+
+    #[test]
+    fn exponential_back_off() {
+        let outliers = Outliers::new(1);
+        let now = Instant::now();
+        let settings = OutlierDetection::default();
+
+        let eject = || {
+            let mut result = None;
+            for _ in 0..settings.consecutive_failures {
+                result = outliers.report_failure(0, now, &settings);
+            }
+            result
+        };
+        assert_eq!(Some(settings.base_ejection()), eject());
+        assert_eq!(Some(settings.base_ejection() * 2), eject());
+        assert_eq!(Some(settings.base_ejection() * 4), eject());
+        for _ in 0..10 {
+            eject();
+        }
+        assert_eq!(Some(settings.max_ejection()), eject());
+    }
+
+    #[test]
+    fn inherit_state() {
+        let previous = Outliers::new(2);
+        let now = Instant::now();
+        let settings = OutlierDetection::default();
+        for _ in 0..settings.consecutive_failures {
+            previous.report_failure(1, now, &settings);
+        }
+
+        let outliers = Outliers::new(1);
+        outliers.inherit(0, &previous, 1);
+        assert_eq!(true, outliers.is_ejected(0, now));
+        for _ in 0..settings.consecutive_failures - 1 {
+            outliers.report_failure(0, now, &settings);
+        }
+        assert_eq!(
+            Some(settings.base_ejection() * 2),
+            outliers.report_failure(0, now, &settings)
+        );
+    }
+
+    #[test]
+    fn configured_settings() {
+        let outliers = Outliers::new(1);
+        let now = Instant::now();
+        let settings = OutlierDetection {
+            consecutive_failures: 2,
+            base_ejection_ms: 100,
+            max_ejection_ms: 150,
+        };
+
+        assert_eq!(None, outliers.report_failure(0, now, &settings));
+        assert_eq!(
+            Some(Duration::from_millis(100)),
+            outliers.report_failure(0, now, &settings)
+        );
+        outliers.report_failure(0, now, &settings);
+        assert_eq!(
+            Some(Duration::from_millis(150)),
+            outliers.report_failure(0, now, &settings)
+        );
+    }
+
+    #[test]
+    fn success_resets() {
+        let outliers = Outliers::new(1);
+        let now = Instant::now();
+        let settings = OutlierDetection::default();
+
+        for _ in 0..settings.consecutive_failures - 1 {
+            outliers.report_failure(0, now, &settings);
+        }
+        outliers.report_success(0);
+        assert_eq!(None, outliers.report_failure(0, now, &settings));
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+/// The Protocol used to forward Requests to the Addresses of a Service
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceProtocol {
+    /// Plain HTTP/1.1, with one Request per Connection at a time
+    #[default]
+    Http1,
+    /// HTTP/1.1 over TLS, with one Request per Connection at a time
+    Https,
+    /// Plain HTTP/2 with prior Knowledge, where all Requests share
+    /// a single Connection
+    H2c,
+    /// HTTP/2 over TLS, where all Requests share a single Connection
+    H2,
+}
+
+impl ServiceProtocol {
+    /// Parses the Protocol from its Name, which is one of "http1",
+    /// "https", "h2c" or "h2"
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "http1" => Some(Self::Http1),
+            "https" => Some(Self::Https),
+            "h2c" => Some(Self::H2c),
+            "h2" => Some(Self::H2),
+            _ => None,
+        }
+    }
+
+    /// Checks if the Protocol is a Version of HTTP/2
+    pub fn is_http2(&self) -> bool {
+        matches!(self, Self::H2c | Self::H2)
+    }
+
+    /// Checks if the Protocol uses TLS for the Connections
+    pub fn is_tls(&self) -> bool {
+        matches!(self, Self::Https | Self::H2)
+    }
+
+    /// The URI-Scheme used for Requests with this Protocol
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            Self::Http1 | Self::H2c => "http",
+            Self::Https | Self::H2 => "https",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Some(ServiceProtocol::Http1),
+            ServiceProtocol::parse("http1")
+        );
+        assert_eq!(
+            Some(ServiceProtocol::Https),
+            ServiceProtocol::parse("https")
+        );
+        assert_eq!(Some(ServiceProtocol::H2c), ServiceProtocol::parse("h2c"));
+        assert_eq!(Some(ServiceProtocol::H2), ServiceProtocol::parse("h2"));
+        assert_eq!(None, ServiceProtocol::parse("http3"));
+    }
+
+    #[test]
+    fn deserialize() {
+        let protocol: ServiceProtocol = serde_json::from_str("\"h2c\"").unwrap();
+        assert_eq!(ServiceProtocol::H2c, protocol);
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// The Version of the PROXY-Protocol used to pass on the Addresses of
+/// the Client when connecting to the Addresses of a Service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyProtocol {
+    /// The human-readable Version 1
+    V1,
+    /// The binary Version 2
+    V2,
+}
+
+impl ProxyProtocol {
+    /// Parses the Version from its Number, which is either 1 or 2
+    pub fn from_version(version: u8) -> Option<Self> {
+        match version {
+            1 => Some(Self::V1),
+            2 => Some(Self::V2),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_version() {
+        assert_eq!(Some(ProxyProtocol::V1), ProxyProtocol::from_version(1));
+        assert_eq!(Some(ProxyProtocol::V2), ProxyProtocol::from_version(2));
+        assert_eq!(None, ProxyProtocol::from_version(3));
+    }
+}
@@ -0,0 +1,481 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
+    hash::{Hash, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use stream_httparse::Request;
+
+use crate::ConnectionInfo;
+
+use super::outlier::{OutlierDetection, Outliers};
+
+/// The Source of the Key that is used to select an Address when
+/// using Consistent-Hashing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum HashKey {
+    /// Uses the IP of the Client
+    ClientIP,
+    /// Uses the Value of the Header with the given Name
+    Header {
+        /// The Name of the Header
+        name: String,
+    },
+    /// Uses the Value of the Cookie with the given Name
+    Cookie {
+        /// The Name of the Cookie
+        name: String,
+    },
+}
+
+impl HashKey {
+    /// Loads the Value for this Key from the given Request
+    fn load(&self, req: &Request, info: &ConnectionInfo) -> Option<String> {
+        match self {
+            Self::ClientIP => info.client_ip().map(|ip| ip.to_string()),
+            Self::Header { name } => req.headers().get(name.as_str()).map(|v| v.to_string()),
+            Self::Cookie { name } => {
+                let cookies = req.headers().get("Cookie")?.to_string();
+                cookies
+                    .split(';')
+                    .filter_map(|raw| raw.trim().split_once('='))
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.to_owned())
+            }
+        }
+    }
+}
+
+/// The Strategy used by a Service to select the Address that should
+/// receive the next Request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Strategy {
+    /// Cycles through all the Addresses
+    #[default]
+    RoundRobin,
+    /// Cycles through all the Addresses, but sends a Number of Requests
+    /// to every Address according to its Weight. Addresses without a
+    /// Weight have a Weight of 1 and Addresses with a Weight of 0 are
+    /// never used
+    WeightedRoundRobin {
+        /// The Weights for the Addresses
+        weights: BTreeMap<String, u32>,
+    },
+    /// Picks the Address with the fewest Requests currently in flight
+    LeastOutstanding,
+    /// Picks two random Addresses and uses the one with fewer Requests
+    /// currently in flight
+    PowerOfTwoChoices,
+    /// Picks a random Address
+    Random,
+    /// Hashes the given Key, so that Requests with the same Key are
+    /// always sent to the same Address as long as it is available.
+    /// Requests without the Key fall back to Round-Robin
+    ConsistentHash {
+        /// The Key to hash
+        key: HashKey,
+    },
+}
+
+/// The State needed to balance the Requests across the Addresses
+/// of a single Service
+#[derive(Debug)]
+pub(crate) struct BalanceState {
+    current: AtomicUsize,
+    outstanding: Arc<[AtomicUsize]>,
+    outliers: Arc<Outliers>,
+}
+
+impl BalanceState {
+    pub fn new(addresses: usize) -> Self {
+        Self {
+            current: AtomicUsize::new(0),
+            outstanding: (0..addresses).map(|_| AtomicUsize::new(0)).collect(),
+            outliers: Arc::new(Outliers::new(addresses)),
+        }
+    }
+
+    /// Takes over the Outlier-State of the Address at the given Index
+    /// of the previous State
+    pub fn inherit(&self, index: usize, previous: &BalanceState, p_index: usize) {
+        self.outliers.inherit(index, &previous.outliers, p_index);
+    }
+
+    /// Checks if the Address at the given Index is currently ejected,
+    /// because of too many consecutive Failures
+    pub fn is_ejected(&self, index: usize) -> bool {
+        self.outliers.is_ejected(index, Instant::now())
+    }
+
+    fn next(&self) -> usize {
+        self.current.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn load(&self, index: usize) -> usize {
+        self.outstanding[index].load(Ordering::Relaxed)
+    }
+
+    /// Marks the Address at the given Index as having one more
+    /// Request in flight, until the returned Endpoint is dropped
+    fn acquire(&self, index: usize) -> Endpoint {
+        self.outstanding[index].fetch_add(1, Ordering::Relaxed);
+
+        Endpoint {
+            index,
+            outstanding: self.outstanding.clone(),
+            outliers: self.outliers.clone(),
+        }
+    }
+}
+
+/// A single Address that was selected for a Request, this counts as
+/// an outstanding Request for the Address until it is dropped
+#[derive(Debug)]
+pub struct Endpoint {
+    index: usize,
+    outstanding: Arc<[AtomicUsize]>,
+    outliers: Arc<Outliers>,
+}
+
+impl Endpoint {
+    /// The Index of the selected Address in the Addresses of the
+    /// Service
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Reports that a Request to this Address succeeded
+    pub fn report_success(&self) {
+        self.outliers.report_success(self.index);
+    }
+
+    /// Reports that a Request to this Address failed, returns the
+    /// Duration for which the Address was ejected, if it reached the
+    /// Number of consecutive Failures for an Ejection
+    pub fn report_failure(&self, settings: &OutlierDetection) -> Option<Duration> {
+        self.outliers
+            .report_failure(self.index, Instant::now(), settings)
+    }
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        self.outstanding[self.index].fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Strategy {
+    /// Selects one of the given Addresses for the Request
+    #[cfg(test)]
+    pub(crate) fn select(
+        &self,
+        addresses: &[String],
+        state: &BalanceState,
+        req: &Request,
+        info: &ConnectionInfo,
+    ) -> Option<Endpoint> {
+        let candidates: Vec<usize> = (0..addresses.len()).collect();
+        self.select_from(addresses, &candidates, state, req, info)
+    }
+
+    /// Selects one of the Addresses at the given candidate Indices
+    /// for the Request
+    pub(crate) fn select_from(
+        &self,
+        addresses: &[String],
+        candidates: &[usize],
+        state: &BalanceState,
+        req: &Request,
+        info: &ConnectionInfo,
+    ) -> Option<Endpoint> {
+        let length = candidates.len();
+        if length == 0 {
+            return None;
+        }
+
+        let index = match self {
+            Self::RoundRobin => candidates[state.next() % length],
+            Self::WeightedRoundRobin { weights } => {
+                let weight =
+                    |index: &usize| weights.get(&addresses[*index]).copied().unwrap_or(1) as usize;
+
+                let total: usize = candidates.iter().map(weight).sum();
+                if total == 0 {
+                    return None;
+                }
+
+                let mut point = state.next() % total;
+                *candidates.iter().find(|index| {
+                    let tmp = weight(index);
+                    if point < tmp {
+                        return true;
+                    }
+                    point -= tmp;
+                    false
+                })?
+            }
+            Self::LeastOutstanding => {
+                // Starts at a different Address every time, so that ties
+                // are spread across all the Addresses
+                let start = state.next() % length;
+                (0..length)
+                    .map(|i| candidates[(start + i) % length])
+                    .min_by_key(|i| state.load(*i))?
+            }
+            Self::PowerOfTwoChoices => {
+                if length == 1 {
+                    candidates[0]
+                } else {
+                    let mut rng = rand::thread_rng();
+                    let first = rng.gen_range(0..length);
+                    let mut second = rng.gen_range(0..length - 1);
+                    if second >= first {
+                        second += 1;
+                    }
+
+                    let (first, second) = (candidates[first], candidates[second]);
+                    if state.load(second) < state.load(first) {
+                        second
+                    } else {
+                        first
+                    }
+                }
+            }
+            Self::Random => candidates[rand::thread_rng().gen_range(0..length)],
+            Self::ConsistentHash { key } => match key.load(req, info) {
+                Some(value) => rendezvous(&value, addresses, candidates),
+                None => candidates[state.next() % length],
+            },
+        };
+
+        Some(state.acquire(index))
+    }
+}
+
+/// Picks the Address with the highest Score for the given Key, which
+/// only moves the Keys of an Address, once that Address is removed
+fn rendezvous(key: &str, addresses: &[String], candidates: &[usize]) -> usize {
+    let score = |index: &usize| {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        addresses[*index].hash(&mut hasher);
+        hasher.finish()
+    };
+
+    candidates.iter().copied().max_by_key(score).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::{Headers, Method};
+
+    use super::*;
+
+    fn addresses(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("address-{}", i)).collect()
+    }
+
+    fn select(strategy: &Strategy, addresses: &[String], state: &BalanceState) -> Option<usize> {
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+
+        strategy
+            .select(addresses, state, &req, &ConnectionInfo::default())
+            .map(|e| e.index())
+    }
+
+    #[test]
+    fn no_addresses() {
+        let state = BalanceState::new(0);
+
+        assert_eq!(None, select(&Strategy::RoundRobin, &[], &state));
+        assert_eq!(None, select(&Strategy::Random, &[], &state));
+    }
+
+    #[test]
+    fn weighted_round_robin() {
+        let addresses = addresses(3);
+        let state = BalanceState::new(3);
+        let mut weights = BTreeMap::new();
+        weights.insert("address-0".to_owned(), 2);
+        weights.insert("address-2".to_owned(), 0);
+        let strategy = Strategy::WeightedRoundRobin { weights };
+
+        let selected: Vec<_> = (0..6)
+            .map(|_| select(&strategy, &addresses, &state))
+            .collect();
+        assert_eq!(
+            vec![Some(0), Some(0), Some(1), Some(0), Some(0), Some(1)],
+            selected
+        );
+    }
+
+    #[test]
+    fn least_outstanding() {
+        let addresses = addresses(3);
+        let state = BalanceState::new(3);
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        let first = Strategy::LeastOutstanding
+            .select(&addresses, &state, &req, &info)
+            .unwrap();
+        let second = Strategy::LeastOutstanding
+            .select(&addresses, &state, &req, &info)
+            .unwrap();
+        assert_ne!(first.index(), second.index());
+
+        let third = Strategy::LeastOutstanding
+            .select(&addresses, &state, &req, &info)
+            .unwrap();
+        let free = 3 - first.index() - second.index();
+        assert_eq!(free, third.index());
+
+        // Once the Request is done, the Address is free again
+        let third_index = third.index();
+        drop(third);
+        assert_eq!(0, state.load(third_index));
+    }
+
+    #[test]
+    fn power_of_two_choices() {
+        let addresses = addresses(2);
+        let state = BalanceState::new(2);
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        let busy = state.acquire(0);
+        for _ in 0..10 {
+            let selected = Strategy::PowerOfTwoChoices
+                .select(&addresses, &state, &req, &info)
+                .unwrap();
+            assert_eq!(1, selected.index());
+        }
+        drop(busy);
+    }
+
+    #[test]
+    fn random_in_bounds() {
+        let addresses = addresses(3);
+        let state = BalanceState::new(3);
+
+        for _ in 0..20 {
+            let selected = select(&Strategy::Random, &addresses, &state).unwrap();
+            assert_eq!(true, selected < 3);
+        }
+    }
+
+    #[test]
+    fn consistent_hash_header() {
+        let addresses = addresses(5);
+        let state = BalanceState::new(5);
+        let strategy = Strategy::ConsistentHash {
+            key: HashKey::Header {
+                name: "X-User".to_owned(),
+            },
+        };
+        let info = ConnectionInfo::default();
+
+        let select_user = |user: &str, addresses: &[String]| {
+            let mut headers = Headers::new();
+            headers.set("X-User", user.to_owned());
+            let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+            strategy
+                .select(addresses, &state, &req, &info)
+                .map(|e| addresses[e.index()].clone())
+        };
+
+        let first = select_user("user-1", &addresses);
+        assert_eq!(first, select_user("user-1", &addresses));
+
+        // Removing a different Address does not move the Key
+        let remaining: Vec<String> = addresses
+            .iter()
+            .filter(|a| Some(*a) != first.as_ref())
+            .take(3)
+            .chain(first.iter())
+            .cloned()
+            .collect();
+        assert_eq!(first, select_user("user-1", &remaining));
+    }
+
+    #[test]
+    fn hash_key_cookie() {
+        let mut headers = Headers::new();
+        headers.set("Cookie", "other=1; session=abc; last=2");
+        let req = Request::new("HTTP/1.1", Method::GET, "/", headers, &[]);
+
+        let key = HashKey::Cookie {
+            name: "session".to_owned(),
+        };
+        assert_eq!(
+            Some("abc".to_owned()),
+            key.load(&req, &ConnectionInfo::default())
+        );
+    }
+
+    #[test]
+    fn hash_key_client_ip() {
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::new(Some("10.0.0.1".parse().unwrap()));
+
+        assert_eq!(
+            Some("10.0.0.1".to_owned()),
+            HashKey::ClientIP.load(&req, &info)
+        );
+        assert_eq!(
+            None,
+            HashKey::ClientIP.load(&req, &ConnectionInfo::default())
+        );
+    }
+
+    #[test]
+    fn only_candidates() {
+        let addresses = addresses(3);
+        let state = BalanceState::new(3);
+        let req = Request::new("HTTP/1.1", Method::GET, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        for strategy in [
+            Strategy::RoundRobin,
+            Strategy::LeastOutstanding,
+            Strategy::PowerOfTwoChoices,
+            Strategy::Random,
+        ] {
+            for _ in 0..5 {
+                let selected = strategy
+                    .select_from(&addresses, &[0, 2], &state, &req, &info)
+                    .unwrap();
+                assert_ne!(1, selected.index());
+            }
+        }
+    }
+
+    #[test]
+    fn deserialize() {
+        let strategy: Strategy = serde_json::from_value(serde_json::json!({
+            "type": "ConsistentHash",
+            "key": {
+                "type": "Cookie",
+                "name": "session",
+            },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            Strategy::ConsistentHash {
+                key: HashKey::Cookie {
+                    name: "session".to_owned()
+                }
+            },
+            strategy
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// The Timeouts for the Requests to a Service, which overwrite the
+/// globally configured Timeouts if they are set.
+///
+/// The Header-Read and Keep-Alive Timeouts are only known once a Request
+/// was matched to the Service and therefore apply to the next Request on
+/// the same Connection
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServiceTimeouts {
+    /// The Time allowed for receiving the Headers of a Request, in
+    /// Milliseconds
+    #[serde(default)]
+    pub header_read_ms: Option<u64>,
+    /// The Time allowed between two Reads of the Body of a Request, in
+    /// Milliseconds
+    #[serde(default)]
+    pub body_read_ms: Option<u64>,
+    /// The Time a Keep-Alive Connection may be idle between Requests, in
+    /// Milliseconds
+    #[serde(default)]
+    pub keep_alive_idle_ms: Option<u64>,
+    /// The Time allowed for establishing a Connection, in Milliseconds
+    #[serde(default)]
+    pub connect_ms: Option<u64>,
+    /// The Time allowed for receiving the Response, after the Request
+    /// was sent, in Milliseconds
+    #[serde(default)]
+    pub response_ms: Option<u64>,
+    /// The Time allowed between two Reads of the Body of the Response, in
+    /// Milliseconds
+    #[serde(default)]
+    pub response_read_ms: Option<u64>,
+}
+
+impl ServiceTimeouts {
+    /// The Time allowed for receiving the Headers of a Request
+    pub fn header_read(&self) -> Option<Duration> {
+        self.header_read_ms.map(Duration::from_millis)
+    }
+
+    /// The Time allowed between two Reads of the Body of a Request
+    pub fn body_read(&self) -> Option<Duration> {
+        self.body_read_ms.map(Duration::from_millis)
+    }
+
+    /// The Time a Keep-Alive Connection may be idle between Requests
+    pub fn keep_alive_idle(&self) -> Option<Duration> {
+        self.keep_alive_idle_ms.map(Duration::from_millis)
+    }
+
+    /// The Time allowed for establishing a Connection
+    pub fn connect(&self) -> Option<Duration> {
+        self.connect_ms.map(Duration::from_millis)
+    }
+
+    /// The Time allowed for receiving the Response
+    pub fn response(&self) -> Option<Duration> {
+        self.response_ms.map(Duration::from_millis)
+    }
+
+    /// The Time allowed between two Reads of the Body of the Response
+    pub fn response_read(&self) -> Option<Duration> {
+        self.response_read_ms.map(Duration::from_millis)
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+/// The TLS-Configuration for the Connections to the Addresses of a
+/// Service, which is used if the Protocol of the Service uses TLS
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ServiceTls {
+    /// The Name sent using SNI and expected in the Certificate of the
+    /// Service, if not set the Host of the Address is used
+    #[serde(default)]
+    pub server_name: Option<String>,
+    /// The PEM-encoded Certificates of the CAs that are trusted for the
+    /// Service, if not set the public Root-CAs are trusted
+    #[serde(default)]
+    pub ca: Option<String>,
+    /// The PEM-encoded Certificate-Chain presented to the Service
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    /// The PEM-encoded Private-Key for the Client-Certificate
+    #[serde(default, skip_serializing)]
+    pub client_key: Option<String>,
+    /// Disables the Verification of the Certificate of the Service,
+    /// this should only ever be used for Development
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+}
+
+impl ServiceTls {
+    /// Returns the PEM-encoded Client-Certificate and Private-Key, if
+    /// both of them are set
+    pub fn client_auth(&self) -> Option<(&str, &str)> {
+        match (self.client_cert.as_deref(), self.client_key.as_deref()) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_auth() {
+        let mut tmp = ServiceTls {
+            client_cert: Some("cert".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(None, tmp.client_auth());
+
+        tmp.client_key = Some("key".to_owned());
+        assert_eq!(Some(("cert", "key")), tmp.client_auth());
+    }
+
+    #[test]
+    fn key_not_serialized() {
+        let tmp = ServiceTls {
+            client_key: Some("secret".to_owned()),
+            ..Default::default()
+        };
+
+        let serialized = serde_json::to_string(&tmp).unwrap();
+        assert_eq!(false, serialized.contains("secret"));
+    }
+}
@@ -0,0 +1,240 @@
+use general::{Name, Shared};
+use general_traits::ConfigItem;
+use ipnetwork::IpNetwork;
+use serde::Serialize;
+
+use crate::{matcher::wildcard_matches, ConnectionInfo, EntryPoints, Service};
+
+/// Used to determine if a TCP-Connection matches certain criteria,
+/// based on the Server-Name the Client requested using SNI
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", content = "c")]
+pub enum TcpMatcher {
+    /// Evaluates all the internal Matchers and only returns
+    /// true if all of them evalutate to true
+    And(Vec<TcpMatcher>),
+    /// Evaluates all of the internal Matchers until one
+    /// of them returns true
+    Or(Vec<TcpMatcher>),
+    /// Inverts the Result of the internal Matcher
+    Not(Box<TcpMatcher>),
+    /// Matches every Connection, even ones without TLS,
+    /// written as "HostSNI(`*`)"
+    Any,
+    /// Matches the Server-Name of the Connection against
+    /// the given Domain
+    HostSNI(String),
+    /// Matches the Server-Name of the Connection against a
+    /// Wildcard-Domain, like "*.example.com", where the
+    /// Wildcard covers exactly one Label
+    WildcardSNI(String),
+    /// Matches if the IP of the Client is part of the given
+    /// Network
+    ClientIP(IpNetwork),
+}
+
+impl TcpMatcher {
+    /// Checks if the Matcher matches a Connection that requested the
+    /// given Server-Name and is described by the given Info
+    pub fn matches(&self, sni: Option<&str>, info: &ConnectionInfo) -> bool {
+        match self {
+            Self::And(matchers) => matchers.iter().all(|m| m.matches(sni, info)),
+            Self::Or(matchers) => matchers.iter().any(|m| m.matches(sni, info)),
+            Self::Not(inner) => !inner.matches(sni, info),
+            Self::Any => true,
+            Self::HostSNI(domain) => match sni {
+                Some(name) => domain.eq_ignore_ascii_case(name),
+                None => false,
+            },
+            Self::WildcardSNI(wildcard) => match sni {
+                Some(name) => wildcard_matches(wildcard, &name.to_ascii_lowercase()),
+                None => false,
+            },
+            Self::ClientIP(network) => match info.client_ip() {
+                Some(ip) => network.contains(*ip),
+                None => false,
+            },
+        }
+    }
+
+    /// Checks if the Matcher depends on the Server-Name of the Connection
+    pub fn uses_sni(&self) -> bool {
+        match self {
+            Self::And(matchers) | Self::Or(matchers) => matchers.iter().any(|m| m.uses_sni()),
+            Self::Not(inner) => inner.uses_sni(),
+            Self::HostSNI(_) | Self::WildcardSNI(_) => true,
+            Self::Any | Self::ClientIP(_) => false,
+        }
+    }
+}
+
+/// How the TLS of the Connections matching a TcpRule is handled
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum TcpRuleTLS {
+    /// The Connection is forwarded as is, which is also used
+    /// for Connections without TLS
+    None,
+    /// The Connection must use TLS, which is forwarded to the
+    /// Service without decrypting it
+    Passthrough,
+    /// The Connection must use TLS, which is terminated by the
+    /// Load-Balancer, before forwarding the decrypted Data to
+    /// the Service
+    Terminate,
+}
+
+/// A TcpRule routes entire TCP-Connections to a Service, instead of
+/// single HTTP-Requests like a normal Rule
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TcpRule {
+    name: Name,
+    priority: u32,
+    matcher: TcpMatcher,
+    service: Shared<Service>,
+    tls: TcpRuleTLS,
+    entry_points: EntryPoints,
+}
+
+impl TcpRule {
+    /// Creates a new TcpRule from the given Parameters
+    pub fn new(name: Name, priority: u32, matcher: TcpMatcher, service: Shared<Service>) -> Self {
+        Self {
+            name,
+            priority,
+            matcher,
+            service,
+            tls: TcpRuleTLS::None,
+            entry_points: EntryPoints::default(),
+        }
+    }
+
+    /// Sets how the TLS of the matching Connections is handled
+    pub fn set_tls(&mut self, tls: TcpRuleTLS) {
+        self.tls = tls;
+    }
+
+    /// Restricts the Rule to the Entrypoints with the given Names,
+    /// an empty List allows all Entrypoints
+    pub fn set_entry_points(&mut self, names: Vec<String>) {
+        self.entry_points = EntryPoints::new(names);
+    }
+
+    /// Returns the Entrypoints this Rule is restricted to
+    pub fn entry_points(&self) -> &EntryPoints {
+        &self.entry_points
+    }
+
+    /// Returns the Priority of the Rule
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Returns the Service that all the Connections
+    /// should be forwarded to
+    pub fn service(&self) -> std::sync::Arc<Service> {
+        self.service.get()
+    }
+
+    /// Returns the Matcher for the Rule
+    pub fn matcher(&self) -> &TcpMatcher {
+        &self.matcher
+    }
+
+    /// Returns how the TLS of the Connections is handled
+    pub fn tls(&self) -> &TcpRuleTLS {
+        &self.tls
+    }
+
+    /// Checks if the ClientHello of a Connection is needed to determine
+    /// whether or not it matches this Rule
+    pub fn needs_client_hello(&self) -> bool {
+        self.tls != TcpRuleTLS::None || self.matcher.uses_sni()
+    }
+
+    /// Checks if the Rule matches a Connection that requested the given
+    /// Server-Name and is described by the given Info.
+    ///
+    /// Rules that handle TLS only match Connections using TLS
+    pub fn matches(&self, sni: Option<&str>, info: &ConnectionInfo) -> bool {
+        if !self.entry_points.allows(info.entrypoint()) {
+            return false;
+        }
+        if self.tls != TcpRuleTLS::None && !info.is_tls() {
+            return false;
+        }
+
+        self.matcher.matches(sni, info)
+    }
+}
+
+impl ConfigItem for TcpRule {
+    fn name(&self) -> &Name {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::Group;
+
+    use super::*;
+
+    fn rule(matcher: TcpMatcher, tls: TcpRuleTLS) -> TcpRule {
+        let mut tmp = TcpRule::new(
+            Name::new("test-rule", Group::Internal),
+            1,
+            matcher,
+            Shared::new(Service::new(
+                Name::new("test-service", Group::Internal),
+                vec![],
+            )),
+        );
+        tmp.set_tls(tls);
+        tmp
+    }
+
+    #[test]
+    fn host_sni() {
+        let matcher = TcpMatcher::HostSNI("db.example.com".to_owned());
+        let info = ConnectionInfo::default();
+
+        assert_eq!(true, matcher.matches(Some("DB.example.com"), &info));
+        assert_eq!(false, matcher.matches(Some("other.example.com"), &info));
+        assert_eq!(false, matcher.matches(None, &info));
+    }
+
+    #[test]
+    fn wildcard_sni() {
+        let matcher = TcpMatcher::WildcardSNI("*.example.com".to_owned());
+        let info = ConnectionInfo::default();
+
+        assert_eq!(true, matcher.matches(Some("db.example.com"), &info));
+        assert_eq!(false, matcher.matches(Some("example.com"), &info));
+    }
+
+    #[test]
+    fn catch_all_without_tls() {
+        let plain = ConnectionInfo::default();
+        let tls = ConnectionInfo::default().with_tls(true);
+
+        let tmp = rule(TcpMatcher::Any, TcpRuleTLS::None);
+        assert_eq!(true, tmp.matches(None, &plain));
+        assert_eq!(true, tmp.matches(Some("example.com"), &tls));
+
+        let tmp = rule(TcpMatcher::Any, TcpRuleTLS::Passthrough);
+        assert_eq!(false, tmp.matches(None, &plain));
+        assert_eq!(true, tmp.matches(None, &tls));
+    }
+
+    #[test]
+    fn restricted_entry_points() {
+        let mut tmp = rule(TcpMatcher::Any, TcpRuleTLS::None);
+        tmp.set_entry_points(vec!["postgres".to_owned()]);
+
+        let matching = ConnectionInfo::default().with_entrypoint(Some("postgres".to_owned()));
+        let other = ConnectionInfo::default().with_entrypoint(Some("redis".to_owned()));
+        assert_eq!(true, tmp.matches(None, &matching));
+        assert_eq!(false, tmp.matches(None, &other));
+        assert_eq!(true, tmp.matches(None, &ConnectionInfo::default()));
+    }
+}
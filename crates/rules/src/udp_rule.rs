@@ -0,0 +1,61 @@
+use general::{Name, Shared};
+use general_traits::ConfigItem;
+use serde::Serialize;
+
+use crate::{EntryPoints, Service};
+
+/// A UdpRule forwards all the Datagrams received by the UDP-Acceptor to
+/// a Service, as Datagrams contain nothing that could be matched against
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct UdpRule {
+    name: Name,
+    priority: u32,
+    service: Shared<Service>,
+    entry_points: EntryPoints,
+}
+
+impl UdpRule {
+    /// Creates a new UdpRule from the given Parameters
+    pub fn new(name: Name, priority: u32, service: Shared<Service>) -> Self {
+        Self {
+            name,
+            priority,
+            service,
+            entry_points: EntryPoints::default(),
+        }
+    }
+
+    /// Restricts the Rule to the Entrypoints with the given Names,
+    /// an empty List allows all Entrypoints
+    pub fn set_entry_points(&mut self, names: Vec<String>) {
+        self.entry_points = EntryPoints::new(names);
+    }
+
+    /// Returns the Entrypoints this Rule is restricted to
+    pub fn entry_points(&self) -> &EntryPoints {
+        &self.entry_points
+    }
+
+    /// Returns the Priority of the Rule
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Returns the Service that all the Datagrams
+    /// should be forwarded to
+    pub fn service(&self) -> std::sync::Arc<Service> {
+        self.service.get()
+    }
+
+    /// Checks if the Rule applies to Datagrams received by the given
+    /// Entrypoint
+    pub fn matches(&self, entrypoint: Option<&str>) -> bool {
+        self.entry_points.allows(entrypoint)
+    }
+}
+
+impl ConfigItem for UdpRule {
+    fn name(&self) -> &Name {
+        &self.name
+    }
+}
@@ -1,9 +1,8 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use general::{Group, Name};
+use plugins::{ActionPluginInstance, Plugin};
+use rules::{Action, ConnectionInfo};
 use stream_httparse::{Headers, Request};
-use tunneload::{
-    plugins::{ActionPluginInstance, Plugin},
-    rules::Action,
-};
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("Create Request", |b| {
@@ -20,7 +19,10 @@ fn criterion_benchmark(c: &mut Criterion) {
     });
 
     c.bench_function("Action - StripPrefix", |b| {
-        let action = Action::RemovePrefix("/test".to_string());
+        let action = Action::RemovePrefix(vec!["/test".to_string()]);
+        let info = ConnectionInfo::default();
+        let rule = Name::new("test_rule", Group::Internal);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
 
         b.iter(|| {
             let mut request = Request::new(
@@ -31,14 +33,16 @@ fn criterion_benchmark(c: &mut Criterion) {
                 &[],
             );
 
-            action.apply_req(&mut request).unwrap();
+            runtime
+                .block_on(action.apply_req(&mut request, &info, &rule))
+                .unwrap();
         })
     });
 
     c.bench_function("Plugin - StripPrefix", |b| {
         let data = std::fs::read("./tests/plugins/strip_prefix.wasm").unwrap();
 
-        let plugin = Plugin::new("test_name".to_owned(), &data).unwrap();
+        let plugin = Plugin::new(Name::new("test_name", Group::Internal), &data).unwrap();
         let instance: ActionPluginInstance = plugin.create_instance("/test".to_owned()).unwrap();
 
         b.iter(|| {
@@ -0,0 +1,81 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use general::{Group, Name, Shared};
+use rules::{ConnectionInfo, Matcher, Rule, Service};
+use stream_httparse::{Headers, Request};
+
+/// Creates the given Number of Rules, where every Rule matches on its
+/// own Domain and Path-Prefix, similar to a large Ingress-Setup
+fn create_rules(count: usize) -> Vec<Rule> {
+    (0..count)
+        .map(|index| {
+            Rule::new(
+                Name::new(format!("rule-{}", index), Group::Internal),
+                (index % 10) as u32,
+                Matcher::And(vec![
+                    Matcher::Domain(format!("service-{}.example.com", index)),
+                    Matcher::PathPrefix(format!("/api/{}/", index)),
+                ]),
+                vec![],
+                Shared::new(Service::new(
+                    Name::new(format!("service-{}", index), Group::Internal),
+                    vec![],
+                )),
+            )
+        })
+        .collect()
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Rule-Matching");
+
+    for count in [10, 100, 1000, 5000] {
+        let (read, mut write) = rules::new();
+        write.add_slice(create_rules(count));
+        let all_rules = read.get_all_rules().unwrap();
+
+        let info = ConnectionInfo::default();
+        let host = format!("service-{}.example.com", count / 2);
+        let path = format!("/api/{}/test", count / 2);
+
+        group.bench_with_input(BenchmarkId::new("Linear", count), &count, |b, _| {
+            b.iter(|| {
+                let mut headers = Headers::new();
+                headers.set("Host", host.as_str());
+                let request = Request::new(
+                    "HTTP/1.1",
+                    stream_httparse::Method::GET,
+                    &path,
+                    headers,
+                    &[],
+                );
+
+                let matched = all_rules
+                    .iter()
+                    .find(|rule| rule.matches(&request, &info))
+                    .cloned();
+                black_box(matched);
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("Indexed", count), &count, |b, _| {
+            b.iter(|| {
+                let mut headers = Headers::new();
+                headers.set("Host", host.as_str());
+                let request = Request::new(
+                    "HTTP/1.1",
+                    stream_httparse::Method::GET,
+                    &path,
+                    headers,
+                    &[],
+                );
+
+                black_box(read.match_req(&request, &info));
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
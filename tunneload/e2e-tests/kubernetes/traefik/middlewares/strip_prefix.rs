@@ -70,7 +70,7 @@ async fn strip_prefix() {
         .find(|m| m.get_name() == &expected_name)
         .expect("The Middleware should have been loaded");
     match strip_prefix_middleware.get_action() {
-        rules::Action::RemovePrefix(prefix) if prefix == "/test" => {}
+        rules::Action::RemovePrefix(prefixes) if prefixes == &["/test".to_owned()] => {}
         _ => assert!(false),
     };
 }
@@ -1,3 +1,5 @@
+use std::net::IpAddr;
+
 use general_traits::Receiver as ReceiverTrait;
 
 use async_trait::async_trait;
@@ -5,11 +7,21 @@ use async_trait::async_trait;
 #[derive(Debug)]
 pub struct Receiver {
     chunks: Vec<Vec<u8>>,
+    peer_ip: Option<IpAddr>,
 }
 
 impl Receiver {
     pub fn new() -> Self {
-        Self { chunks: Vec::new() }
+        Self {
+            chunks: Vec::new(),
+            peer_ip: None,
+        }
+    }
+
+    /// Sets the IP-Address that will be reported as the
+    /// other Side of the Connection
+    pub fn set_peer_ip(&mut self, ip: IpAddr) {
+        self.peer_ip = Some(ip);
     }
 
     /// Adds a new chunk to the end of the internal
@@ -21,6 +33,10 @@ impl Receiver {
 
 #[async_trait]
 impl ReceiverTrait for Receiver {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_ip
+    }
+
     async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         let chunk = match self.chunks.first_mut() {
             Some(f) => f,
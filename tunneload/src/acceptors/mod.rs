@@ -1,7 +1,9 @@
 //! Handles the way requests "enter" the load-balancer
 //! so via Webserver directly or tunneler for example
 
+pub mod tcp;
 pub mod tunneler;
+pub mod udp;
 pub mod webserver;
 
 #[cfg(test)]
@@ -0,0 +1,248 @@
+//! Extracts the Server-Name from the ClientHello of a TLS-Connection,
+//! without actually terminating the TLS-Connection
+
+/// The Content-Type of TLS-Records containing Handshake-Messages
+const HANDSHAKE_RECORD: u8 = 0x16;
+/// The Type of the ClientHello Handshake-Message
+const CLIENT_HELLO: u8 = 0x01;
+/// The Type of the Server-Name Extension
+const SERVER_NAME_EXTENSION: u16 = 0x0000;
+/// The Type of a DNS-Hostname in the Server-Name Extension
+const HOST_NAME: u8 = 0x00;
+
+/// The Result of parsing the Start of a Connection
+#[derive(Debug, PartialEq)]
+pub enum ClientHello {
+    /// More Data is needed to decide
+    Incomplete,
+    /// The Connection does not start with a TLS-ClientHello
+    NotTls,
+    /// The Connection starts with a TLS-ClientHello, which requested
+    /// the given Server-Name, if any
+    Tls(Option<String>),
+}
+
+/// A simple Reader over a Slice that returns None once the Data
+/// runs out
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.data.len() < length {
+            return None;
+        }
+
+        let (result, rest) = self.data.split_at(length);
+        self.data = rest;
+        Some(result)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|raw| raw[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|raw| u16::from_be_bytes([raw[0], raw[1]]))
+    }
+
+    fn u24(&mut self) -> Option<usize> {
+        self.take(3)
+            .map(|raw| ((raw[0] as usize) << 16) | ((raw[1] as usize) << 8) | raw[2] as usize)
+    }
+}
+
+/// Collects the Handshake-Data from all the complete TLS-Records at the
+/// Start of the Data, as a ClientHello may span multiple Records.
+///
+/// Returns None if the Data does not start with Handshake-Records
+fn handshake_data(data: &[u8]) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    let mut reader = Reader { data };
+
+    loop {
+        let content_type = match reader.u8() {
+            Some(c) => c,
+            None => return Some(result),
+        };
+        if content_type != HANDSHAKE_RECORD {
+            return None;
+        }
+
+        let version = match reader.u16() {
+            Some(v) => v,
+            None => return Some(result),
+        };
+        if version >> 8 != 0x03 {
+            return None;
+        }
+
+        let length = match reader.u16() {
+            Some(l) => l as usize,
+            None => return Some(result),
+        };
+        match reader.take(length) {
+            Some(fragment) => result.extend_from_slice(fragment),
+            None => return Some(result),
+        };
+    }
+}
+
+/// Extracts the Server-Name from the Extensions of a ClientHello
+fn server_name(mut extensions: Reader<'_>) -> Option<String> {
+    while let Some(ext_type) = extensions.u16() {
+        let length = extensions.u16()? as usize;
+        let data = extensions.take(length)?;
+        if ext_type != SERVER_NAME_EXTENSION {
+            continue;
+        }
+
+        let mut ext = Reader { data };
+        let list_length = ext.u16()? as usize;
+        let mut list = Reader {
+            data: ext.take(list_length)?,
+        };
+        while let Some(name_type) = list.u8() {
+            let length = list.u16()? as usize;
+            let name = list.take(length)?;
+            if name_type == HOST_NAME {
+                return std::str::from_utf8(name).ok().map(|n| n.to_owned());
+            }
+        }
+    }
+
+    None
+}
+
+/// Skips all the Fields of the ClientHello-Body in front of the
+/// Extensions and returns the raw Extensions
+fn extensions(mut hello: Reader<'_>) -> Option<&[u8]> {
+    // Version and Random
+    hello.take(2 + 32)?;
+    let session_id = hello.u8()? as usize;
+    hello.take(session_id)?;
+    let cipher_suites = hello.u16()? as usize;
+    hello.take(cipher_suites)?;
+    let compression = hello.u8()? as usize;
+    hello.take(compression)?;
+    let length = hello.u16()? as usize;
+    hello.take(length)
+}
+
+/// Parses the given Data, received at the Start of a Connection, as a
+/// TLS-ClientHello
+pub fn parse(data: &[u8]) -> ClientHello {
+    if data.is_empty() {
+        return ClientHello::Incomplete;
+    }
+
+    let handshake = match handshake_data(data) {
+        Some(h) => h,
+        None => return ClientHello::NotTls,
+    };
+
+    let mut reader = Reader { data: &handshake };
+    match reader.u8() {
+        Some(CLIENT_HELLO) => {}
+        Some(_) => return ClientHello::NotTls,
+        None => return ClientHello::Incomplete,
+    };
+    let length = match reader.u24() {
+        Some(l) => l,
+        None => return ClientHello::Incomplete,
+    };
+    let body = match reader.take(length) {
+        Some(b) => b,
+        None => return ClientHello::Incomplete,
+    };
+
+    match extensions(Reader { data: body }) {
+        Some(data) => ClientHello::Tls(server_name(Reader { data })),
+        None => ClientHello::Tls(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a TLS-Record containing a ClientHello with the given
+    /// Server-Name
+    fn client_hello(sni: Option<&str>) -> Vec<u8> {
+        let mut extensions = Vec::new();
+        if let Some(name) = sni {
+            let name = name.as_bytes();
+            extensions.extend_from_slice(&SERVER_NAME_EXTENSION.to_be_bytes());
+            extensions.extend_from_slice(&((name.len() + 5) as u16).to_be_bytes());
+            extensions.extend_from_slice(&((name.len() + 3) as u16).to_be_bytes());
+            extensions.push(HOST_NAME);
+            extensions.extend_from_slice(&(name.len() as u16).to_be_bytes());
+            extensions.extend_from_slice(name);
+        }
+
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[0; 32]);
+        body.push(0);
+        body.extend_from_slice(&[0x00, 0x02, 0x13, 0x01]);
+        body.extend_from_slice(&[0x01, 0x00]);
+        body.extend_from_slice(&(extensions.len() as u16).to_be_bytes());
+        body.extend_from_slice(&extensions);
+
+        let mut handshake = vec![CLIENT_HELLO];
+        handshake.extend_from_slice(&(body.len() as u32).to_be_bytes()[1..]);
+        handshake.extend_from_slice(&body);
+
+        let mut record = vec![HANDSHAKE_RECORD, 0x03, 0x01];
+        record.extend_from_slice(&(handshake.len() as u16).to_be_bytes());
+        record.extend_from_slice(&handshake);
+        record
+    }
+
+    #[test]
+    fn with_sni() {
+        assert_eq!(
+            ClientHello::Tls(Some("db.example.com".to_owned())),
+            parse(&client_hello(Some("db.example.com")))
+        );
+    }
+
+    #[test]
+    fn without_sni() {
+        assert_eq!(ClientHello::Tls(None), parse(&client_hello(None)));
+    }
+
+    #[test]
+    fn incomplete() {
+        let data = client_hello(Some("db.example.com"));
+
+        assert_eq!(ClientHello::Incomplete, parse(&[]));
+        assert_eq!(ClientHello::Incomplete, parse(&data[..3]));
+        assert_eq!(ClientHello::Incomplete, parse(&data[..data.len() - 1]));
+    }
+
+    #[test]
+    fn split_across_records() {
+        let data = client_hello(Some("db.example.com"));
+        let handshake = &data[5..];
+        let (first, second) = handshake.split_at(20);
+
+        let mut split = vec![HANDSHAKE_RECORD, 0x03, 0x01];
+        split.extend_from_slice(&(first.len() as u16).to_be_bytes());
+        split.extend_from_slice(first);
+        split.extend_from_slice(&[HANDSHAKE_RECORD, 0x03, 0x01]);
+        split.extend_from_slice(&(second.len() as u16).to_be_bytes());
+        split.extend_from_slice(second);
+
+        assert_eq!(
+            ClientHello::Tls(Some("db.example.com".to_owned())),
+            parse(&split)
+        );
+    }
+
+    #[test]
+    fn not_tls() {
+        assert_eq!(ClientHello::NotTls, parse(b"GET / HTTP/1.1\r\n"));
+        assert_eq!(ClientHello::NotTls, parse(b"\x16\x01\x00"));
+    }
+}
@@ -0,0 +1,13 @@
+//! Accepts raw TCP-Connections and forwards them to Services, based on
+//! the TcpRules and the Server-Name requested by TLS-Connections
+
+mod client_hello;
+
+mod prefixed;
+pub use prefixed::PrefixedStream;
+
+mod server;
+pub use server::Server;
+
+mod setup;
+pub use setup::setup;
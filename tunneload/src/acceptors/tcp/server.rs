@@ -0,0 +1,323 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use rules::{ConnectionInfo, TcpRuleTLS};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+use tokio_rustls::TlsAcceptor;
+
+use lazy_static::lazy_static;
+use prometheus::Registry;
+
+use super::{
+    client_hello::{self, ClientHello},
+    PrefixedStream,
+};
+use crate::{
+    configurator::TcpRuleList,
+    tls,
+    util::proxy_protocol::{self, ProxyAddresses},
+};
+
+/// The maximum Time to wait for the ClientHello of a Connection, after
+/// which the Connection is treated as not using TLS
+const CLIENT_HELLO_TIMEOUT: Duration = Duration::from_secs(3);
+/// The maximum Size of the ClientHello that will be buffered
+const MAX_CLIENT_HELLO_SIZE: usize = 16 * 1024;
+
+lazy_static! {
+    static ref TOTAL_CONS: prometheus::IntCounter = prometheus::IntCounter::new(
+        "tcp_con_total",
+        "The total Number of connections received by the TCP-Acceptor"
+    )
+    .expect("Creating a Metric should never fail");
+    static ref UNROUTED_CONS: prometheus::IntCounter = prometheus::IntCounter::new(
+        "tcp_con_unrouted",
+        "The Number of connections received by the TCP-Acceptor that matched no TCP-Rule"
+    )
+    .expect("Creating a Metric should never fail");
+}
+
+/// Reads from the Connection until the ClientHello has been received or
+/// it is clear that the Connection does not use TLS.
+///
+/// Returns all the Data read, which still needs to be forwarded
+async fn read_client_hello<S>(con: &mut S) -> (Vec<u8>, ClientHello)
+where
+    S: AsyncRead + Unpin,
+{
+    let mut buffer = Vec::new();
+    let read = async {
+        let mut chunk = [0; 2048];
+        loop {
+            let result = client_hello::parse(&buffer);
+            if result != ClientHello::Incomplete || buffer.len() >= MAX_CLIENT_HELLO_SIZE {
+                return result;
+            }
+
+            match con.read(&mut chunk).await {
+                Ok(0) | Err(_) => return ClientHello::NotTls,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            };
+        }
+    };
+
+    let result = match tokio::time::timeout(CLIENT_HELLO_TIMEOUT, read).await {
+        Ok(ClientHello::Incomplete) | Err(_) => ClientHello::NotTls,
+        Ok(r) => r,
+    };
+    (buffer, result)
+}
+
+/// Copies all the Data between the two Connections until both are closed
+async fn forward<C, S>(mut client: C, mut service: S)
+where
+    C: AsyncRead + AsyncWrite + Unpin,
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    if let Err(e) = tokio::io::copy_bidirectional(&mut client, &mut service).await {
+        tracing::debug!("Forwarding TCP-Connection: {}", e);
+    }
+}
+
+/// The Acceptor for raw TCP-Connections on a single given Port
+pub struct Server {
+    address: String,
+    port: u32,
+    entrypoint: Option<String>,
+    rules: TcpRuleList,
+    tls_conf: tls::ConfigManager,
+}
+
+impl Server {
+    /// Creates a new Server instance that is ready to start on
+    /// the given Port
+    pub fn new(port: u32, reg: Registry, rules: TcpRuleList, tls_conf: tls::ConfigManager) -> Self {
+        if let Err(e) = reg.register(Box::new(TOTAL_CONS.clone())) {
+            tracing::error!("Registering Total-Connections TCP Metric: {:?}", e);
+        }
+        if let Err(e) = reg.register(Box::new(UNROUTED_CONS.clone())) {
+            tracing::error!("Registering Unrouted-Connections TCP Metric: {:?}", e);
+        }
+
+        Self {
+            address: "0.0.0.0".to_owned(),
+            port,
+            entrypoint: None,
+            rules,
+            tls_conf,
+        }
+    }
+
+    /// Listens on the given Address instead of all the Interfaces
+    pub fn with_address(mut self, address: String) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Sets the Name of the Entrypoint this Server is for, which allows
+    /// Rules to be restricted to it
+    pub fn with_entrypoint(mut self, name: String) -> Self {
+        self.entrypoint = Some(name);
+        self
+    }
+
+    /// Finds the TcpRule for a single Connection and forwards the
+    /// Connection to its Service
+    #[tracing::instrument(skip(rules, tls_conf))]
+    async fn handle_con(
+        mut con: TcpStream,
+        rules: TcpRuleList,
+        tls_conf: tls::ConfigManager,
+        entrypoint: Option<String>,
+    ) {
+        TOTAL_CONS.inc();
+
+        let client_addr = con.peer_addr().ok();
+        let local_addr = con.local_addr().ok();
+
+        // Protocols, where the Server sends the first Message, would
+        // otherwise wait for the Timeout on every Connection
+        let needs_client_hello = rules
+            .get_all()
+            .iter()
+            .any(|r| r.entry_points().allows(entrypoint.as_deref()) && r.needs_client_hello());
+        let (prefix, hello) = if needs_client_hello {
+            read_client_hello(&mut con).await
+        } else {
+            (Vec::new(), ClientHello::NotTls)
+        };
+        let sni = match &hello {
+            ClientHello::Tls(name) => name.as_deref(),
+            _ => None,
+        };
+
+        let info = ConnectionInfo::new(client_addr.map(|a| a.ip()))
+            .with_addresses(client_addr, local_addr)
+            .with_tls(matches!(hello, ClientHello::Tls(_)))
+            .with_entrypoint(entrypoint);
+
+        let rule = match rules.find(sni, &info) {
+            Some(r) => r,
+            None => {
+                UNROUTED_CONS.inc();
+                tracing::debug!("No TCP-Rule for SNI {:?}", sni);
+                return;
+            }
+        };
+        let service = rule.service();
+
+        let (mut service_con, _endpoint) = match service
+            .connect_raw(&info, |address| TcpStream::connect(address.to_owned()))
+            .await
+        {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Connecting to Service: {:?}", e);
+                return;
+            }
+        };
+
+        if let Some(version) = service.proxy_protocol() {
+            let header =
+                proxy_protocol::encode(version, proxy_addresses(client_addr, local_addr).as_ref());
+            if let Err(e) = service_con.write_all(&header).await {
+                tracing::error!("Writing PROXY-Protocol Header: {:?}", e);
+                return;
+            }
+        }
+
+        let client = PrefixedStream::new(con, prefix);
+        match rule.tls() {
+            TcpRuleTLS::Terminate => {
+                // The Connection is not an HTTP-Connection, so no
+                // Protocol should be negotiated
+                let mut config = rustls::ServerConfig::clone(&tls_conf.get_config());
+                config.alpn_protocols.clear();
+
+                let acceptor = TlsAcceptor::from(Arc::new(config));
+                let client = match acceptor.accept(client).await {
+                    Ok(c) => c,
+                    Err(e) => {
+                        tracing::error!("Terminating TLS: {:?}", e);
+                        return;
+                    }
+                };
+
+                forward(client, service_con).await;
+            }
+            TcpRuleTLS::Passthrough | TcpRuleTLS::None => {
+                forward(client, service_con).await;
+            }
+        };
+    }
+
+    /// Actually starts the Acceptor and listens for Connections,
+    /// this function is never expected to actually return and therefore
+    /// run for the entire lifetime of the Program
+    pub async fn start(self) {
+        let listen_addr = format!("{}:{}", self.address, self.port);
+        let listener = match tokio::net::TcpListener::bind(&listen_addr).await {
+            Ok(l) => l,
+            Err(e) => {
+                tracing::error!("Binding TCP-Listener: {:?}", e);
+                return;
+            }
+        };
+
+        loop {
+            let con = match listener.accept().await {
+                Ok((s, _)) => s,
+                Err(e) => {
+                    tracing::error!("Accepting Connection: {}", e);
+                    continue;
+                }
+            };
+
+            tokio::task::spawn(Self::handle_con(
+                con,
+                self.rules.clone(),
+                self.tls_conf.clone(),
+                self.entrypoint.clone(),
+            ));
+        }
+    }
+}
+
+/// Combines the Addresses of the Connection for the PROXY-Protocol
+fn proxy_addresses(
+    client_addr: Option<SocketAddr>,
+    local_addr: Option<SocketAddr>,
+) -> Option<ProxyAddresses> {
+    match (client_addr, local_addr) {
+        (Some(source), Some(destination)) => Some(ProxyAddresses {
+            source,
+            destination,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Name, Shared};
+    use rules::{Service, TcpMatcher, TcpRule};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn client_hello_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (mut con, _) = listener.accept().await.unwrap();
+            con.write_all(b"SSH-2.0").await.unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+
+        let mut con = TcpStream::connect(address).await.unwrap();
+        let (prefix, hello) = read_client_hello(&mut con).await;
+
+        assert_eq!(ClientHello::NotTls, hello);
+        assert_eq!(b"SSH-2.0".to_vec(), prefix);
+    }
+
+    #[tokio::test]
+    async fn forward_plain_connection() {
+        let backend = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let backend_addr = backend.local_addr().unwrap().to_string();
+        tokio::task::spawn(async move {
+            let (mut con, _) = backend.accept().await.unwrap();
+            let mut buffer = [0; 4];
+            con.read_exact(&mut buffer).await.unwrap();
+            con.write_all(&buffer).await.unwrap();
+        });
+
+        let rules = TcpRuleList::new();
+        rules.set_tcp_rule(TcpRule::new(
+            Name::new("test", Group::Internal),
+            1,
+            TcpMatcher::Any,
+            Shared::new(Service::new(
+                Name::new("test", Group::Internal),
+                vec![backend_addr],
+            )),
+        ));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::task::spawn(async move {
+            let (con, _) = listener.accept().await.unwrap();
+            Server::handle_con(con, rules, tls::ConfigManager::new(), None).await;
+        });
+
+        let mut con = TcpStream::connect(address).await.unwrap();
+        con.write_all(b"ping").await.unwrap();
+        let mut buffer = [0; 4];
+        con.read_exact(&mut buffer).await.unwrap();
+
+        assert_eq!(b"ping", &buffer);
+    }
+}
@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+
+use tokio::task::JoinHandle;
+
+use super::Server;
+use crate::{cli::TcpOpts, configurator::TcpRuleList, tls};
+
+/// This handles all the Setup related stuff for the TCP-Acceptors, according
+/// to the provided Configuration
+pub fn setup(
+    rt: &tokio::runtime::Runtime,
+    config: &HashMap<String, TcpOpts>,
+    rules: TcpRuleList,
+    tls_config: tls::ConfigManager,
+    metrics_registry: &prometheus::Registry,
+) -> Vec<JoinHandle<()>> {
+    let mut result = Vec::new();
+
+    for (name, conf) in config {
+        tracing::info!("Starting TCP-{} ...", name);
+
+        let mut server = Server::new(
+            conf.port,
+            metrics_registry.clone(),
+            rules.clone(),
+            tls_config.clone(),
+        )
+        .with_entrypoint(name.clone());
+        if let Some(address) = conf.address.as_ref() {
+            server = server.with_address(address.clone());
+        }
+        result.push(rt.spawn(server.start()));
+    }
+
+    result
+}
@@ -55,12 +55,13 @@ impl<H> THandler for TunnelerHandler<H>
 where
     H: Handler + Send + Sync + 'static,
 {
-    async fn new_con(self: Arc<Self>, id: u32, _details: Details, con: UserCon) {
+    async fn new_con(self: Arc<Self>, id: u32, details: Details, con: UserCon) {
         OPEN_CONNECTIONS.inc();
 
         let (rx, tx) = con.into_split();
 
-        let receiver = Receiver::new(rx);
+        let mut receiver = Receiver::new(rx);
+        receiver.set_peer_ip(*details.ip());
         let sender = Sender::new(tx);
 
         let open_timer = OPEN_TIME.start_timer();
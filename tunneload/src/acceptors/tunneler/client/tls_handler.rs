@@ -62,12 +62,13 @@ impl<H> THandler for TLSHandler<H>
 where
     H: Handler + Send + Sync + 'static,
 {
-    async fn new_con(self: Arc<Self>, id: u32, _details: Details, con: UserCon) {
+    async fn new_con(self: Arc<Self>, id: u32, details: Details, con: UserCon) {
         OPEN_CONNECTIONS.inc();
 
         let (rx, tx) = con.into_split();
 
-        let raw_receiver = Receiver::new(rx);
+        let mut raw_receiver = Receiver::new(rx);
+        raw_receiver.set_peer_ip(*details.ip());
         let raw_sender = Sender::new(tx);
 
         let config = self.tls_config.get_config();
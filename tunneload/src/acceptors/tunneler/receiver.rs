@@ -1,4 +1,7 @@
-use std::fmt::{Debug, Formatter};
+use std::{
+    fmt::{Debug, Formatter},
+    net::IpAddr,
+};
 
 use general_traits::Receiver as ReceiverTrait;
 
@@ -9,6 +12,7 @@ use async_trait::async_trait;
 pub struct Receiver<R> {
     reader: R,
     buffer: Vec<u8>,
+    peer_ip: Option<IpAddr>,
 }
 
 impl<R> Debug for Receiver<R> {
@@ -27,8 +31,15 @@ where
         Self {
             reader,
             buffer: Vec::new(),
+            peer_ip: None,
         }
     }
+
+    /// Sets the IP-Address of the User on the other Side of the
+    /// Tunnel
+    pub fn set_peer_ip(&mut self, ip: IpAddr) {
+        self.peer_ip = Some(ip);
+    }
 }
 
 #[async_trait]
@@ -36,6 +47,10 @@ impl<R> ReceiverTrait for Receiver<R>
 where
     R: tunneler_core::client::Receiver + Send + Sync,
 {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_ip
+    }
+
     async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         if self.buffer.is_empty() {
             match self.reader.recv_msg().await {
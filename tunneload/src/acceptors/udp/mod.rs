@@ -0,0 +1,8 @@
+//! Accepts UDP-Datagrams and forwards them to Services, while keeping
+//! a Session for every Client so the Responses can be sent back
+
+mod server;
+pub use server::Server;
+
+mod setup;
+pub use setup::setup;
@@ -0,0 +1,334 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rules::{ConnectionInfo, Endpoint};
+use tokio::net::UdpSocket;
+
+use lazy_static::lazy_static;
+use prometheus::Registry;
+
+use crate::configurator::UdpRuleList;
+
+/// The Time after which a Session, that received no Datagrams in either
+/// Direction, is removed
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// The maximum Size of a single Datagram
+const MAX_DATAGRAM_SIZE: usize = 65535;
+
+lazy_static! {
+    static ref TOTAL_DATAGRAMS: prometheus::IntCounter = prometheus::IntCounter::new(
+        "udp_datagrams_total",
+        "The total Number of datagrams received by the UDP-Acceptor from Clients"
+    )
+    .expect("Creating a Metric should never fail");
+    static ref ACTIVE_SESSIONS: prometheus::IntGauge = prometheus::IntGauge::new(
+        "udp_sessions_active",
+        "The Number of currently active Sessions of the UDP-Acceptor"
+    )
+    .expect("Creating a Metric should never fail");
+}
+
+/// The Mapping of a single Client to the Socket used to forward its
+/// Datagrams to the Service
+struct Session {
+    upstream: Arc<UdpSocket>,
+    last_active: Arc<Mutex<Instant>>,
+}
+
+type Sessions = Arc<Mutex<HashMap<SocketAddr, Session>>>;
+
+/// Creates a new Socket that is connected to the given Address
+async fn connect_upstream(address: String) -> std::io::Result<UdpSocket> {
+    let target = tokio::net::lookup_host(&address)
+        .await?
+        .next()
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Could not resolve '{}'", address),
+            )
+        })?;
+
+    let bind_addr = if target.is_ipv6() {
+        "[::]:0"
+    } else {
+        "0.0.0.0:0"
+    };
+    let socket = UdpSocket::bind(bind_addr).await?;
+    socket.connect(target).await?;
+    Ok(socket)
+}
+
+/// Sends all the Datagrams received from the Service back to the Client,
+/// until the Session has been idle for the given Timeout
+async fn reply(
+    socket: Arc<UdpSocket>,
+    client: SocketAddr,
+    upstream: Arc<UdpSocket>,
+    last_active: Arc<Mutex<Instant>>,
+    idle_timeout: Duration,
+    sessions: Sessions,
+    _endpoint: Endpoint,
+) {
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    loop {
+        let idle = last_active.lock().expect("Locking Last-Active").elapsed();
+        let remaining = match idle_timeout.checked_sub(idle) {
+            Some(r) if !r.is_zero() => r,
+            _ => break,
+        };
+
+        match tokio::time::timeout(remaining, upstream.recv(&mut buffer)).await {
+            Ok(Ok(length)) => {
+                *last_active.lock().expect("Locking Last-Active") = Instant::now();
+                if let Err(e) = socket.send_to(&buffer[..length], client).await {
+                    tracing::error!("Sending Datagram to Client: {:?}", e);
+                }
+            }
+            Ok(Err(e)) => {
+                tracing::debug!("Receiving Datagram from Service: {:?}", e);
+                break;
+            }
+            // The Client may have sent Datagrams in the meantime
+            Err(_) => continue,
+        };
+    }
+
+    sessions.lock().expect("Locking Sessions").remove(&client);
+    ACTIVE_SESSIONS.dec();
+}
+
+/// The Acceptor for UDP-Datagrams on a single given Port
+pub struct Server {
+    address: String,
+    port: u32,
+    entrypoint: Option<String>,
+    rules: UdpRuleList,
+    idle_timeout: Duration,
+}
+
+impl Server {
+    /// Creates a new Server instance that is ready to start on
+    /// the given Port
+    pub fn new(port: u32, reg: Registry, rules: UdpRuleList) -> Self {
+        if let Err(e) = reg.register(Box::new(TOTAL_DATAGRAMS.clone())) {
+            tracing::error!("Registering Total-Datagrams UDP Metric: {:?}", e);
+        }
+        if let Err(e) = reg.register(Box::new(ACTIVE_SESSIONS.clone())) {
+            tracing::error!("Registering Active-Sessions UDP Metric: {:?}", e);
+        }
+
+        Self {
+            address: "0.0.0.0".to_owned(),
+            port,
+            entrypoint: None,
+            rules,
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Listens on the given Address instead of all the Interfaces
+    pub fn with_address(mut self, address: String) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Sets the Name of the Entrypoint this Server is for, which allows
+    /// Rules to be restricted to it
+    pub fn with_entrypoint(mut self, name: String) -> Self {
+        self.entrypoint = Some(name);
+        self
+    }
+
+    /// Sets the Time after which idle Sessions are removed
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// Creates a new Session for the Client, by selecting an Address of
+    /// the Service using its Load-Balancing Strategy
+    async fn create_session(
+        &self,
+        socket: &Arc<UdpSocket>,
+        sessions: &Sessions,
+        client: SocketAddr,
+    ) -> Option<Arc<UdpSocket>> {
+        let rule = match self.rules.find(self.entrypoint.as_deref()) {
+            Some(r) => r,
+            None => {
+                tracing::debug!("No UDP-Rule for Datagram from {}", client);
+                return None;
+            }
+        };
+        let service = rule.service();
+
+        let info = ConnectionInfo::new(Some(client.ip()))
+            .with_addresses(Some(client), socket.local_addr().ok())
+            .with_entrypoint(self.entrypoint.clone());
+        let (upstream, endpoint) = match service
+            .connect_raw(&info, |address| connect_upstream(address.to_owned()))
+            .await
+        {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::error!("Connecting to Service: {:?}", e);
+                return None;
+            }
+        };
+
+        let upstream = Arc::new(upstream);
+        let last_active = Arc::new(Mutex::new(Instant::now()));
+        sessions.lock().expect("Locking Sessions").insert(
+            client,
+            Session {
+                upstream: upstream.clone(),
+                last_active: last_active.clone(),
+            },
+        );
+        ACTIVE_SESSIONS.inc();
+
+        tokio::task::spawn(reply(
+            socket.clone(),
+            client,
+            upstream.clone(),
+            last_active,
+            self.idle_timeout,
+            sessions.clone(),
+            endpoint,
+        ));
+
+        Some(upstream)
+    }
+
+    /// Forwards all the Datagrams received on the given Socket
+    async fn serve(self, socket: UdpSocket) {
+        let socket = Arc::new(socket);
+        let sessions: Sessions = Arc::new(Mutex::new(HashMap::new()));
+
+        let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+        loop {
+            let (length, client) = match socket.recv_from(&mut buffer).await {
+                Ok(r) => r,
+                Err(e) => {
+                    tracing::error!("Receiving Datagram: {}", e);
+                    continue;
+                }
+            };
+            TOTAL_DATAGRAMS.inc();
+
+            let existing = sessions
+                .lock()
+                .expect("Locking Sessions")
+                .get(&client)
+                .map(|session| {
+                    *session.last_active.lock().expect("Locking Last-Active") = Instant::now();
+                    session.upstream.clone()
+                });
+            let upstream = match existing {
+                Some(u) => u,
+                None => match self.create_session(&socket, &sessions, client).await {
+                    Some(u) => u,
+                    None => continue,
+                },
+            };
+
+            if let Err(e) = upstream.send(&buffer[..length]).await {
+                tracing::error!("Sending Datagram to Service: {:?}", e);
+            }
+        }
+    }
+
+    /// Actually starts the Acceptor and listens for Datagrams,
+    /// this function is never expected to actually return and therefore
+    /// run for the entire lifetime of the Program
+    pub async fn start(self) {
+        let listen_addr = format!("{}:{}", self.address, self.port);
+        let socket = match UdpSocket::bind(&listen_addr).await {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::error!("Binding UDP-Socket: {:?}", e);
+                return;
+            }
+        };
+
+        self.serve(socket).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Name, Shared};
+    use rules::{Service, UdpRule};
+
+    use super::*;
+
+    /// Starts a Service that answers every Datagram with its own Address
+    async fn echo_service() -> String {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap().to_string();
+        tokio::task::spawn(async move {
+            let mut buffer = [0; 64];
+            loop {
+                let (_, peer) = socket.recv_from(&mut buffer).await.unwrap();
+                socket
+                    .send_to(peer.to_string().as_bytes(), peer)
+                    .await
+                    .unwrap();
+            }
+        });
+        address
+    }
+
+    async fn start_server(idle_timeout: Duration) -> SocketAddr {
+        let rules = UdpRuleList::new();
+        rules.set_udp_rule(UdpRule::new(
+            Name::new("test", Group::Internal),
+            1,
+            Shared::new(Service::new(
+                Name::new("test", Group::Internal),
+                vec![echo_service().await],
+            )),
+        ));
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        let server = Server::new(0, Registry::new(), rules).with_idle_timeout(idle_timeout);
+        tokio::task::spawn(server.serve(socket));
+        address
+    }
+
+    async fn request(client: &UdpSocket, server: SocketAddr) -> String {
+        client.send_to(b"ping", server).await.unwrap();
+        let mut buffer = [0; 64];
+        let length = client.recv(&mut buffer).await.unwrap();
+        String::from_utf8(buffer[..length].to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn keeps_session() {
+        let server = start_server(Duration::from_secs(30)).await;
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let first = request(&client, server).await;
+        let second = request(&client, server).await;
+
+        assert_eq!(first, second);
+    }
+
+    #[tokio::test]
+    async fn expires_idle_session() {
+        let server = start_server(Duration::from_millis(100)).await;
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+
+        let first = request(&client, server).await;
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let second = request(&client, server).await;
+
+        assert_ne!(first, second);
+    }
+}
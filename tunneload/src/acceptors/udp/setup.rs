@@ -0,0 +1,33 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::task::JoinHandle;
+
+use super::Server;
+use crate::{cli::UdpOpts, configurator::UdpRuleList};
+
+/// This handles all the Setup related stuff for the UDP-Acceptors, according
+/// to the provided Configuration
+pub fn setup(
+    rt: &tokio::runtime::Runtime,
+    config: &HashMap<String, UdpOpts>,
+    rules: UdpRuleList,
+    metrics_registry: &prometheus::Registry,
+) -> Vec<JoinHandle<()>> {
+    let mut result = Vec::new();
+
+    for (name, conf) in config {
+        tracing::info!("Starting UDP-{} ...", name);
+
+        let mut server = Server::new(conf.port, metrics_registry.clone(), rules.clone())
+            .with_entrypoint(name.clone());
+        if let Some(address) = conf.address.as_ref() {
+            server = server.with_address(address.clone());
+        }
+        if let Some(seconds) = conf.idle_timeout {
+            server = server.with_idle_timeout(Duration::from_secs(seconds));
+        }
+        result.push(rt.spawn(server.start()));
+    }
+
+    result
+}
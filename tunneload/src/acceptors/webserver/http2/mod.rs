@@ -0,0 +1,237 @@
+//! Serves HTTP/2 Connections, by mapping every Stream of the Connection
+//! onto its own Receiver and Sender that speak HTTP/1.1 with the Handler,
+//! so that all the Rules and Middlewares apply unchanged
+
+use general_traits::{Handler, Receiver as ReceiverTrait, Sender as SenderTrait};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, DuplexStream},
+    task::JoinHandle,
+};
+
+mod receiver;
+pub use receiver::Receiver;
+
+mod sender;
+pub use sender::Sender;
+
+/// The Size of the Buffers used to pass the Data between the
+/// Connection and the HTTP/2 Layer
+const BUFFER_SIZE: usize = 16 * 1024;
+
+/// Bridges the given Receiver and Sender into a single IO-Stream, which
+/// is needed because HTTP/2 reads and writes the Connection at the same
+/// time
+fn bridge<R, S>(mut rx: R, mut tx: S) -> (DuplexStream, JoinHandle<()>, JoinHandle<()>)
+where
+    R: ReceiverTrait + Send + 'static,
+    S: SenderTrait + Send + 'static,
+{
+    let (io, bridge) = tokio::io::duplex(BUFFER_SIZE);
+    let (mut bridge_read, mut bridge_write) = tokio::io::split(bridge);
+
+    let reading = tokio::spawn(async move {
+        let mut buffer = vec![0; BUFFER_SIZE];
+        loop {
+            let read = match rx.read(&mut buffer).await {
+                Ok(0) => return,
+                Ok(n) => n,
+                Err(e) => {
+                    tracing::debug!("Reading from HTTP/2 Connection: {:?}", e);
+                    return;
+                }
+            };
+            if bridge_write.write_all(&buffer[..read]).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    let writing = tokio::spawn(async move {
+        let mut buffer = vec![0; BUFFER_SIZE];
+        loop {
+            let read = match bridge_read.read(&mut buffer).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => n,
+            };
+            tx.send(&buffer[..read]).await;
+        }
+    });
+
+    (io, reading, writing)
+}
+
+/// Serves a single HTTP/2 Connection over the given Receiver and Sender,
+/// where every Stream is passed to the Handler as its own Connection
+pub async fn serve<R, S, H>(rx: R, tx: S, handler: H)
+where
+    R: ReceiverTrait + Send + 'static,
+    S: SenderTrait + Send + 'static,
+    H: Handler + Clone + Send + Sync + 'static,
+{
+    let peer_ip = rx.peer_ip();
+    let (peer_address, local_address) = (rx.peer_address(), rx.local_address());
+    let tls = rx.is_tls();
+    let entrypoint = rx.entrypoint().map(|name| name.to_owned());
+    let (io, reading, writing) = bridge(rx, tx);
+
+    let mut connection = match h2::server::handshake(io).await {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Starting HTTP/2 Connection: {:?}", e);
+            reading.abort();
+            return;
+        }
+    };
+
+    let mut id: u32 = 0;
+    while let Some(stream) = connection.accept().await {
+        let (request, respond) = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::debug!("Accepting HTTP/2 Stream: {:?}", e);
+                break;
+            }
+        };
+
+        id = id.wrapping_add(1);
+        let head_request = request.method() == http::Method::HEAD;
+
+        let receiver = Receiver::new(request, peer_ip)
+            .with_addresses(peer_address, local_address)
+            .with_tls(tls)
+            .with_entrypoint(entrypoint.clone());
+        let sender = Sender::new(respond, head_request);
+        let stream_handler = handler.clone();
+        tokio::spawn(async move {
+            stream_handler.handle(id, receiver, sender).await;
+        });
+    }
+
+    drop(connection);
+    reading.abort();
+    if let Err(e) = writing.await {
+        tracing::error!("Writing to HTTP/2 Connection: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Responds to every Request with the whole Request it received,
+    /// as a chunked Body
+    #[derive(Debug, Clone)]
+    struct EchoHandler;
+
+    #[async_trait]
+    impl Handler for EchoHandler {
+        async fn handle<R, S>(&self, _id: u32, mut receiver: R, mut sender: S)
+        where
+            R: ReceiverTrait + Send + 'static,
+            S: SenderTrait + Send + 'static,
+        {
+            let mut request = Vec::new();
+            let mut buffer = [0; 64];
+            loop {
+                match receiver.read(&mut buffer).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&buffer[..n]),
+                };
+            }
+
+            sender
+                .send(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n")
+                .await;
+            sender
+                .send(format!("{:x}\r\n", request.len()).as_bytes())
+                .await;
+            sender.send(&request).await;
+            sender.send(b"\r\n0\r\nx-echo: done\r\n\r\n").await;
+        }
+    }
+
+    async fn connect() -> h2::client::SendRequest<Bytes> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (con, _) = listener.accept().await.unwrap();
+            let (read, write) = con.into_split();
+            serve(read, write, EchoHandler).await;
+        });
+
+        let tcp = TcpStream::connect(address).await.unwrap();
+        let (client, connection) = h2::client::handshake(tcp).await.unwrap();
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+        client.ready().await.unwrap()
+    }
+
+    async fn read_body(response: http::Response<h2::RecvStream>) -> String {
+        let mut body = response.into_body();
+        let mut result = Vec::new();
+        while let Some(data) = body.data().await {
+            let data = data.unwrap();
+            body.flow_control().release_capacity(data.len()).unwrap();
+            result.extend_from_slice(&data);
+        }
+
+        let trailers = body.trailers().await.unwrap().unwrap();
+        assert_eq!("done", trailers.get("x-echo").unwrap());
+
+        String::from_utf8(result).unwrap()
+    }
+
+    #[tokio::test]
+    async fn serve_get() {
+        let mut client = connect().await;
+
+        let request = http::Request::builder()
+            .method("GET")
+            .uri("https://example.com/api/test")
+            .body(())
+            .unwrap();
+        let (response, _) = client.send_request(request, true).unwrap();
+        let response = response.await.unwrap();
+
+        assert_eq!(http::StatusCode::OK, response.status());
+        assert_eq!(
+            "GET /api/test HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            read_body(response).await
+        );
+    }
+
+    #[tokio::test]
+    async fn serve_post_streams() {
+        let mut client = connect().await;
+
+        let mut responses = Vec::new();
+        for index in 0..2 {
+            let request = http::Request::builder()
+                .method("POST")
+                .uri(format!("https://example.com/{}", index))
+                .body(())
+                .unwrap();
+            let (response, mut stream) = client.send_request(request, false).unwrap();
+            stream.send_data(Bytes::from_static(b"data"), true).unwrap();
+            responses.push(response);
+        }
+
+        for (index, response) in responses.into_iter().enumerate() {
+            let response = response.await.unwrap();
+            assert_eq!(
+                format!(
+                    "POST /{} HTTP/1.1\r\nHost: example.com\r\nTransfer-Encoding: chunked\r\n\r\n4\r\ndata\r\n0\r\n\r\n",
+                    index
+                ),
+                read_body(response).await
+            );
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    fmt::{Debug, Formatter},
+    net::{IpAddr, SocketAddr},
+};
+
+use general_traits::Receiver as ReceiverTrait;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use h2::RecvStream;
+
+use crate::util::http2::{
+    remove_connection_headers, serialize_chunk, serialize_headers, serialize_last_chunk,
+};
+
+/// Serializes the Head of the given HTTP/2 Request as an HTTP/1.1
+/// Request-Head, returns the Head and whether the Body has to be
+/// chunked, because its Length is unknown
+pub fn request_head<T>(request: &http::Request<T>, has_body: bool) -> (Vec<u8>, bool) {
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or("/");
+
+    let mut head = Vec::with_capacity(256);
+    head.extend_from_slice(request.method().as_str().as_bytes());
+    head.push(b' ');
+    head.extend_from_slice(path.as_bytes());
+    head.extend_from_slice(b" HTTP/1.1\r\n");
+
+    let mut headers = request.headers().clone();
+    remove_connection_headers(&mut headers);
+    if !headers.contains_key(http::header::HOST) {
+        if let Some(authority) = request.uri().authority() {
+            head.extend_from_slice(b"Host: ");
+            head.extend_from_slice(authority.as_str().as_bytes());
+            head.extend_from_slice(b"\r\n");
+        }
+    }
+
+    // HTTP/2 allows the Cookies to be split into multiple Headers, which
+    // have to be combined again for HTTP/1.1
+    let cookies: Vec<&[u8]> = headers
+        .get_all(http::header::COOKIE)
+        .iter()
+        .map(|v| v.as_bytes())
+        .collect();
+    if !cookies.is_empty() {
+        head.extend_from_slice(b"cookie: ");
+        head.extend_from_slice(&cookies.join(&b"; "[..]));
+        head.extend_from_slice(b"\r\n");
+    }
+    headers.remove(http::header::COOKIE);
+    serialize_headers(&headers, &mut head);
+
+    let chunked = has_body && !headers.contains_key(http::header::CONTENT_LENGTH);
+    if chunked {
+        head.extend_from_slice(b"Transfer-Encoding: chunked\r\n");
+    }
+    head.extend_from_slice(b"\r\n");
+
+    (head, chunked)
+}
+
+/// The Receiver for a single HTTP/2 Stream, which provides the Request
+/// of the Stream as an HTTP/1.1 Request, followed by the End of the
+/// Connection
+pub struct Receiver {
+    pending: Vec<u8>,
+    offset: usize,
+    body: Option<RecvStream>,
+    chunked: bool,
+    peer_ip: Option<IpAddr>,
+    peer_address: Option<SocketAddr>,
+    local_address: Option<SocketAddr>,
+    tls: bool,
+    entrypoint: Option<String>,
+}
+
+impl Debug for Receiver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP2-Receiver ()")
+    }
+}
+
+impl Receiver {
+    /// Creates a new Receiver for the given Request of a Stream
+    pub fn new(request: http::Request<RecvStream>, peer_ip: Option<IpAddr>) -> Self {
+        let body = request.body();
+        let has_body = !body.is_end_stream();
+        let (head, chunked) = request_head(&request, has_body);
+
+        let body = if has_body {
+            Some(request.into_body())
+        } else {
+            None
+        };
+
+        Self {
+            pending: head,
+            offset: 0,
+            body,
+            chunked,
+            peer_ip,
+            peer_address: None,
+            local_address: None,
+            tls: false,
+            entrypoint: None,
+        }
+    }
+
+    /// Marks the underlying Connection as using TLS
+    pub fn with_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Sets the Name of the Entrypoint that accepted the underlying
+    /// Connection
+    pub fn with_entrypoint(mut self, entrypoint: Option<String>) -> Self {
+        self.entrypoint = entrypoint;
+        self
+    }
+
+    /// Sets the Addresses of the underlying Connection
+    pub fn with_addresses(
+        mut self,
+        peer_address: Option<SocketAddr>,
+        local_address: Option<SocketAddr>,
+    ) -> Self {
+        self.peer_address = peer_address;
+        self.local_address = local_address;
+        self
+    }
+
+    /// Replaces the pending Data with the given Part of the Body,
+    /// framed as a Chunk if needed
+    fn set_pending(&mut self, data: Bytes) {
+        self.pending.clear();
+        self.offset = 0;
+
+        if self.chunked {
+            serialize_chunk(&data, &mut self.pending);
+        } else {
+            self.pending.extend_from_slice(&data);
+        }
+    }
+}
+
+#[async_trait]
+impl ReceiverTrait for Receiver {
+    async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.offset < self.pending.len() {
+                let left = &self.pending[self.offset..];
+                let size = std::cmp::min(left.len(), buf.len());
+                buf[..size].copy_from_slice(&left[..size]);
+                self.offset += size;
+                return Ok(size);
+            }
+
+            let body = match self.body.as_mut() {
+                Some(b) => b,
+                None => return Ok(0),
+            };
+
+            match body.data().await {
+                Some(Ok(data)) => {
+                    if let Err(e) = body.flow_control().release_capacity(data.len()) {
+                        return Err(std::io::Error::other(e));
+                    }
+                    // Empty Chunks would end the Body early
+                    if !data.is_empty() {
+                        self.set_pending(data);
+                    }
+                }
+                Some(Err(e)) => {
+                    return Err(std::io::Error::other(e));
+                }
+                None => {
+                    let trailers = if self.chunked {
+                        body.trailers().await.map_err(std::io::Error::other)?
+                    } else {
+                        None
+                    };
+
+                    self.body = None;
+                    self.pending.clear();
+                    self.offset = 0;
+                    if self.chunked {
+                        serialize_last_chunk(trailers.as_ref(), &mut self.pending);
+                    }
+                }
+            };
+        }
+    }
+
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_ip
+    }
+
+    fn peer_address(&self) -> Option<SocketAddr> {
+        self.peer_address
+    }
+
+    fn local_address(&self) -> Option<SocketAddr> {
+        self.local_address
+    }
+
+    fn is_tls(&self) -> bool {
+        self.tls
+    }
+
+    fn entrypoint(&self) -> Option<&str> {
+        self.entrypoint.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_without_body() {
+        let request = http::Request::builder()
+            .method("GET")
+            .uri("https://example.com/api/test?id=1")
+            .header("accept", "*/*")
+            .header("connection", "keep-alive")
+            .body(())
+            .unwrap();
+
+        let (head, chunked) = request_head(&request, false);
+        assert_eq!(false, chunked);
+        assert_eq!(
+            "GET /api/test?id=1 HTTP/1.1\r\nHost: example.com\r\naccept: */*\r\n\r\n",
+            String::from_utf8(head).unwrap()
+        );
+    }
+
+    #[test]
+    fn head_with_body() {
+        let request = http::Request::builder()
+            .method("POST")
+            .uri("https://example.com/")
+            .header("cookie", "a=1")
+            .header("cookie", "b=2")
+            .body(())
+            .unwrap();
+
+        let (head, chunked) = request_head(&request, true);
+        assert_eq!(true, chunked);
+        assert_eq!(
+            "POST / HTTP/1.1\r\nHost: example.com\r\ncookie: a=1; b=2\r\nTransfer-Encoding: chunked\r\n\r\n",
+            String::from_utf8(head).unwrap()
+        );
+    }
+
+    #[test]
+    fn head_with_length() {
+        let request = http::Request::builder()
+            .method("POST")
+            .uri("https://example.com/")
+            .header("content-length", "4")
+            .body(())
+            .unwrap();
+
+        let (head, chunked) = request_head(&request, true);
+        assert_eq!(false, chunked);
+        assert_eq!(
+            "POST / HTTP/1.1\r\nHost: example.com\r\ncontent-length: 4\r\n\r\n",
+            String::from_utf8(head).unwrap()
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt::{Debug, Formatter};
+
+use general_traits::Sender as SenderTrait;
+
+use crate::{
+    handler::{chunked::ChunkedFraming, header_end::HeaderEnd},
+    util::http2::{end_stream, parse_headers, remove_connection_headers, send_data},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use h2::{server::SendResponse, Reason, SendStream};
+
+/// The Framing of the Response-Body sent by the Handler
+#[derive(Debug)]
+pub enum Framing {
+    /// There is no Body left to send
+    Done,
+    /// The given Number of Bytes are still left
+    Fixed(usize),
+    /// The Body is chunked and its Framing needs to be removed
+    Chunked(ChunkedFraming),
+    /// The Body ends once the Handler is done
+    UntilEnd,
+}
+
+/// Parses the given HTTP/1.1 Response-Head into the Head of an HTTP/2
+/// Response and the Framing of the Body that follows it
+pub fn response_head(head: &[u8], head_request: bool) -> Option<(http::Response<()>, Framing)> {
+    let head = std::str::from_utf8(head).ok()?;
+    let (status_line, header_lines) = head.split_once("\r\n")?;
+
+    let status = status_line.split(' ').nth(1)?;
+    let status = http::StatusCode::from_bytes(status.as_bytes()).ok()?;
+
+    let mut headers = parse_headers(header_lines)?;
+    let chunked = headers
+        .get(http::header::TRANSFER_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_ascii_lowercase().ends_with("chunked"))
+        .unwrap_or(false);
+    let length = match headers.get(http::header::CONTENT_LENGTH) {
+        Some(v) => Some(v.to_str().ok()?.parse::<usize>().ok()?),
+        None => None,
+    };
+    remove_connection_headers(&mut headers);
+
+    let mut response = http::Response::new(());
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+
+    let framing = if head_request
+        || status == http::StatusCode::NO_CONTENT
+        || status == http::StatusCode::NOT_MODIFIED
+    {
+        Framing::Done
+    } else if chunked {
+        Framing::Chunked(ChunkedFraming::keeping_trailers())
+    } else {
+        match length {
+            Some(0) => Framing::Done,
+            Some(n) => Framing::Fixed(n),
+            None => Framing::UntilEnd,
+        }
+    };
+
+    Some((response, framing))
+}
+
+/// The Sender for a single HTTP/2 Stream, which accepts the HTTP/1.1
+/// Response of the Handler and sends it as an HTTP/2 Response
+pub struct Sender {
+    respond: Option<SendResponse<Bytes>>,
+    head: Vec<u8>,
+    head_end: HeaderEnd,
+    head_request: bool,
+    stream: Option<SendStream<Bytes>>,
+    framing: Framing,
+}
+
+impl Debug for Sender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP2-Sender ()")
+    }
+}
+
+impl Sender {
+    /// Creates a new Sender for the Response of a Stream, the Flag
+    /// indicates if the Request was a HEAD-Request
+    pub fn new(respond: SendResponse<Bytes>, head_request: bool) -> Self {
+        Self {
+            respond: Some(respond),
+            head: Vec::new(),
+            head_end: HeaderEnd::default(),
+            head_request,
+            stream: None,
+            framing: Framing::Done,
+        }
+    }
+
+    /// Sends the Head of the Response, informational Responses are
+    /// skipped as the final Response still follows them
+    fn start_response(&mut self, head: &[u8]) {
+        let mut respond = match self.respond.take() {
+            Some(r) => r,
+            None => return,
+        };
+
+        let (response, framing) = match response_head(head, self.head_request) {
+            Some(r) => r,
+            None => {
+                tracing::error!("Invalid Response-Head for HTTP/2");
+                respond.send_reset(Reason::INTERNAL_ERROR);
+                return;
+            }
+        };
+        if response.status().is_informational() {
+            self.respond = Some(respond);
+            return;
+        }
+
+        let end = matches!(framing, Framing::Done);
+        match respond.send_response(response, end) {
+            Ok(stream) if !end => {
+                self.stream = Some(stream);
+                self.framing = framing;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::error!("Sending HTTP/2 Response: {:?}", e);
+            }
+        };
+    }
+
+    /// Sends the Part of the Body on the Stream and ends the Stream,
+    /// including the Trailers of a chunked Body, if it was the last Part
+    async fn send_body(&mut self, data: Bytes, end: bool) -> Result<(), h2::Error> {
+        let stream = match self.stream.as_mut() {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+
+        send_data(stream, data).await?;
+        if end {
+            let trailers = match &self.framing {
+                Framing::Chunked(framing) => framing.trailers(),
+                _ => &[],
+            };
+            end_stream(stream, trailers)?;
+
+            self.stream = None;
+            self.framing = Framing::Done;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SenderTrait for Sender {
+    async fn send(&mut self, mut data: &[u8]) {
+        while self.respond.is_some() && !data.is_empty() {
+            match self.head_end.update(data) {
+                Some(end) => {
+                    self.head.extend_from_slice(&data[..end]);
+                    data = &data[end..];
+
+                    self.head_end = HeaderEnd::default();
+                    let head = std::mem::take(&mut self.head);
+                    self.start_response(&head);
+                }
+                None => {
+                    self.head.extend_from_slice(data);
+                    return;
+                }
+            };
+        }
+
+        if data.is_empty() {
+            return;
+        }
+
+        let (payload, end) = match &mut self.framing {
+            Framing::Done => return,
+            Framing::Fixed(remaining) => {
+                let size = std::cmp::min(*remaining, data.len());
+                *remaining -= size;
+                (Bytes::copy_from_slice(&data[..size]), *remaining == 0)
+            }
+            Framing::Chunked(framing) => {
+                let mut payload = Vec::with_capacity(data.len());
+                if framing
+                    .update_with(data, |d| payload.extend_from_slice(d))
+                    .is_err()
+                {
+                    tracing::error!("Invalid chunked Body for HTTP/2");
+                    if let Some(mut stream) = self.stream.take() {
+                        stream.send_reset(Reason::INTERNAL_ERROR);
+                    }
+                    self.framing = Framing::Done;
+                    return;
+                }
+                (Bytes::from(payload), framing.is_done())
+            }
+            Framing::UntilEnd => (Bytes::copy_from_slice(data), false),
+        };
+
+        if let Err(e) = self.send_body(payload, end).await {
+            tracing::error!("Sending HTTP/2 Data: {:?}", e);
+            self.stream = None;
+            self.framing = Framing::Done;
+        }
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        if let Some(mut respond) = self.respond.take() {
+            respond.send_reset(Reason::INTERNAL_ERROR);
+        }
+
+        if let Some(mut stream) = self.stream.take() {
+            match self.framing {
+                Framing::UntilEnd => {
+                    let _ = stream.send_data(Bytes::new(), true);
+                }
+                _ => stream.send_reset(Reason::CANCEL),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_with_length() {
+        let (response, framing) = response_head(
+            b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: keep-alive\r\n\r\n",
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(http::StatusCode::OK, response.status());
+        assert_eq!("10", response.headers().get("content-length").unwrap());
+        assert_eq!(false, response.headers().contains_key("connection"));
+        assert_eq!(true, matches!(framing, Framing::Fixed(10)));
+    }
+
+    #[test]
+    fn head_chunked() {
+        let (response, framing) = response_head(
+            b"HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n",
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(http::StatusCode::NOT_FOUND, response.status());
+        assert_eq!(false, response.headers().contains_key("transfer-encoding"));
+        assert_eq!(true, matches!(framing, Framing::Chunked(_)));
+    }
+
+    #[test]
+    fn head_without_body() {
+        let (_, framing) =
+            response_head(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n", true).unwrap();
+        assert_eq!(true, matches!(framing, Framing::Done));
+
+        let (_, framing) = response_head(b"HTTP/1.1 204 No Content\r\n\r\n", false).unwrap();
+        assert_eq!(true, matches!(framing, Framing::Done));
+
+        let (_, framing) = response_head(b"HTTP/1.1 200 OK\r\n\r\n", false).unwrap();
+        assert_eq!(true, matches!(framing, Framing::UntilEnd));
+    }
+
+    #[test]
+    fn head_invalid() {
+        assert_eq!(
+            true,
+            response_head(b"HTTP/1.1 abc OK\r\n\r\n", false).is_none()
+        );
+        assert_eq!(
+            true,
+            response_head(b"HTTP/1.1 200 OK\r\nInvalid\r\n\r\n", false).is_none()
+        );
+    }
+}
@@ -9,5 +9,7 @@ pub use sender::Sender;
 mod receiver;
 pub use receiver::Receiver;
 
+pub mod http2;
+
 mod setup;
 pub use setup::setup;
@@ -1,7 +1,12 @@
-use std::fmt::{Debug, Formatter};
+use std::{
+    fmt::{Debug, Formatter},
+    net::{IpAddr, SocketAddr},
+};
 
 use general_traits::Receiver as ReceiverTrait;
 
+use crate::util::proxy_protocol::{self, ProxyAddresses, ProxyHeaderError};
+
 use tokio::io::AsyncReadExt;
 
 use async_trait::async_trait;
@@ -10,6 +15,10 @@ use async_trait::async_trait;
 /// Webserver-Acceptor
 pub struct Receiver {
     rx: tokio::net::tcp::OwnedReadHalf,
+    pending: Vec<u8>,
+    offset: usize,
+    proxied: Option<ProxyAddresses>,
+    entrypoint: Option<String>,
 }
 
 impl Debug for Receiver {
@@ -22,13 +31,67 @@ impl Receiver {
     /// Creates a new Receiver with the given TCP-Reader as the underlying
     /// connection medium
     pub fn new(rx: tokio::net::tcp::OwnedReadHalf) -> Self {
-        Self { rx }
+        Self {
+            rx,
+            pending: Vec::new(),
+            offset: 0,
+            proxied: None,
+            entrypoint: None,
+        }
+    }
+
+    /// Sets the Name of the Entrypoint that accepted the Connection
+    pub fn with_entrypoint(mut self, entrypoint: Option<String>) -> Self {
+        self.entrypoint = entrypoint;
+        self
+    }
+
+    /// Reads the PROXY-Protocol Header from the Start of the Connection,
+    /// if there is one, and uses the Addresses from it as the Addresses
+    /// of the Connection from then on
+    pub async fn read_proxy_header(&mut self) -> Result<(), ProxyHeaderError> {
+        let (addresses, pending) = proxy_protocol::read_header(&mut self.rx).await?;
+        self.proxied = addresses;
+        self.pending = pending;
+        self.offset = 0;
+        Ok(())
     }
 }
 
 #[async_trait]
 impl ReceiverTrait for Receiver {
     async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // Data that was read together with the PROXY-Header comes first
+        if self.offset < self.pending.len() {
+            let remaining = &self.pending[self.offset..];
+            let length = std::cmp::min(remaining.len(), buf.len());
+            buf[..length].copy_from_slice(&remaining[..length]);
+            self.offset += length;
+            return Ok(length);
+        }
+
         AsyncReadExt::read(&mut self.rx, buf).await
     }
+
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.peer_address().map(|addr| addr.ip())
+    }
+
+    fn peer_address(&self) -> Option<SocketAddr> {
+        match self.proxied {
+            Some(addresses) => Some(addresses.source),
+            None => self.rx.peer_addr().ok(),
+        }
+    }
+
+    fn local_address(&self) -> Option<SocketAddr> {
+        match self.proxied {
+            Some(addresses) => Some(addresses.destination),
+            None => self.rx.local_addr().ok(),
+        }
+    }
+
+    fn entrypoint(&self) -> Option<&str> {
+        self.entrypoint.as_deref()
+    }
 }
@@ -1,14 +1,17 @@
+use std::sync::Arc;
+
 use crate::tls;
 use crate::{acceptors::webserver::Sender, internal_services::DashboardEntity};
 
-use general_traits::Handler;
+use general_traits::{Handler, Receiver as ReceiverTrait};
 
+use ipnetwork::IpNetwork;
 use lazy_static::lazy_static;
 use prometheus::Registry;
 
 use serde_json::json;
 
-use super::Receiver;
+use super::{http2, Receiver};
 
 lazy_static! {
     static ref TOTAL_REQS: prometheus::IntCounter = prometheus::IntCounter::new("web_req_total", "The total Number of requests received by the Webserver-Acceptor").expect("Creating a Metric should never fail");
@@ -18,8 +21,11 @@ lazy_static! {
 /// The actual Webserver that will accept Connections
 /// on a single given Port
 pub struct Server {
+    address: String,
     port: u32,
+    entrypoint: Option<String>,
     tls_conf: Option<tls::ConfigManager>,
+    proxy_trusted: Arc<Vec<IpNetwork>>,
 }
 
 impl Server {
@@ -33,26 +39,69 @@ impl Server {
             tracing::error!("Registering Parse-Time Webserver Metric: {:?}", e);
         }
 
-        Self { port, tls_conf }
+        Self {
+            address: "0.0.0.0".to_owned(),
+            port,
+            entrypoint: None,
+            tls_conf,
+            proxy_trusted: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Listens on the given Address instead of all the Interfaces
+    pub fn with_address(mut self, address: String) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Sets the Name of the Entrypoint this Server is for, which allows
+    /// Rules to be restricted to it
+    pub fn with_entrypoint(mut self, name: String) -> Self {
+        self.entrypoint = Some(name);
+        self
+    }
+
+    /// Accepts PROXY-Protocol Headers from Connections whose Source is
+    /// in one of the given Networks, the Addresses from the Header are
+    /// then used as the Addresses of the Connection
+    pub fn with_proxy_protocol(mut self, trusted: Vec<IpNetwork>) -> Self {
+        self.proxy_trusted = Arc::new(trusted);
+        self
     }
 
     /// Reads and parses the Request for a single connection, then
-    /// passes that request onto the given handler
+    /// passes that request onto the given handler.
+    ///
+    /// TLS-Connections that negotiated HTTP/2 are served by passing every
+    /// Stream to the Handler instead
     #[tracing::instrument]
     async fn handle_con<T>(
         con: tokio::net::TcpStream,
         handler: T,
         tls_conf: Option<tls::ConfigManager>,
+        proxy_trusted: Arc<Vec<IpNetwork>>,
+        entrypoint: Option<String>,
     ) where
-        T: Handler + Send + Sync + 'static,
+        T: Handler + Send + Sync + Clone + 'static,
     {
         TOTAL_REQS.inc();
 
         let (read, write) = con.into_split();
 
-        let receiver = Receiver::new(read);
+        let mut receiver = Receiver::new(read).with_entrypoint(entrypoint);
         let sender = Sender::new(write);
 
+        let trusted = receiver
+            .peer_ip()
+            .map(|ip| proxy_trusted.iter().any(|net| net.contains(ip)))
+            .unwrap_or(false);
+        if trusted {
+            if let Err(e) = receiver.read_proxy_header().await {
+                tracing::error!("Reading PROXY-Protocol Header: {:?}", e);
+                return;
+            }
+        }
+
         match tls_conf {
             Some(tls_config) => {
                 let config = tls_config.get_config();
@@ -68,6 +117,11 @@ impl Server {
                         }
                     };
 
+                if tls_receiver.alpn_protocol().as_deref() == Some(tls::ALPN_H2) {
+                    http2::serve(tls_receiver, tls_sender, handler).await;
+                    return;
+                }
+
                 handler.handle(0, tls_receiver, tls_sender).await;
             }
             None => {
@@ -83,7 +137,7 @@ impl Server {
     where
         T: Handler + Send + Sync + Clone + 'static,
     {
-        let listen_addr = format!("0.0.0.0:{}", self.port);
+        let listen_addr = format!("{}:{}", self.address, self.port);
         let listener = match tokio::net::TcpListener::bind(&listen_addr).await {
             Ok(l) => l,
             Err(e) => {
@@ -105,6 +159,8 @@ impl Server {
                 con,
                 handler.clone(),
                 self.tls_conf.clone(),
+                self.proxy_trusted.clone(),
+                self.entrypoint.clone(),
             ));
         }
     }
@@ -30,7 +30,20 @@ where
             None
         };
 
-        let web_server = Server::new(conf.port, metrics_registry.clone(), tls_conf);
+        let mut proxy_trusted = Vec::new();
+        for raw in conf.proxy_protocol.iter() {
+            match raw.parse() {
+                Ok(network) => proxy_trusted.push(network),
+                Err(e) => tracing::error!("Parsing trusted PROXY-Protocol CIDR '{}': {}", raw, e),
+            };
+        }
+
+        let mut web_server = Server::new(conf.port, metrics_registry.clone(), tls_conf)
+            .with_proxy_protocol(proxy_trusted)
+            .with_entrypoint(name.clone());
+        if let Some(address) = conf.address.as_ref() {
+            web_server = web_server.with_address(address.clone());
+        }
         result.push(rt.spawn(web_server.start(handler.clone())));
     }
 
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use argser::argser;
+
+use super::{TcpOpts, UdpOpts, WebserverOpts};
+
+/// All the options for a single named Entrypoint
+#[argser]
+#[derive(Debug)]
+pub struct EntrypointOpts {
+    /// The Address the Entrypoint listens on, defaults to all Interfaces
+    #[argser(default)]
+    pub address: Option<String>,
+    /// The Port the Entrypoint listens on
+    #[argser(default)]
+    pub port: u32,
+    /// Enables TLS for HTTP-Entrypoints
+    #[argser(default)]
+    pub tls: bool,
+    /// The Protocol of the Entrypoint, one of "http", "tcp" or "udp",
+    /// defaults to "http"
+    #[argser(default)]
+    pub protocol: Option<String>,
+    /// The Source-CIDRs that are trusted to send a PROXY-Protocol Header
+    /// in front of their Connections, only used by HTTP-Entrypoints
+    #[argser(default)]
+    pub proxy_protocol: Vec<String>,
+    /// The Number of Seconds after which an idle Session is removed,
+    /// only used by UDP-Entrypoints
+    #[argser(default)]
+    pub idle_timeout: Option<u64>,
+}
+
+/// The Entrypoints split up by their Protocol
+#[derive(Debug, Default)]
+pub struct Entrypoints {
+    /// The HTTP-Entrypoints
+    pub webserver: HashMap<String, WebserverOpts>,
+    /// The TCP-Entrypoints
+    pub tcp: HashMap<String, TcpOpts>,
+    /// The UDP-Entrypoints
+    pub udp: HashMap<String, UdpOpts>,
+}
+
+impl Entrypoints {
+    /// Splits the given Entrypoints up by their Protocol, Entrypoints with
+    /// an unknown Protocol are ignored
+    pub fn split(config: &HashMap<String, EntrypointOpts>) -> Self {
+        let mut result = Self::default();
+
+        for (name, conf) in config {
+            match conf.protocol.as_deref().unwrap_or("http") {
+                "http" => {
+                    result.webserver.insert(
+                        name.clone(),
+                        WebserverOpts {
+                            address: conf.address.clone(),
+                            port: conf.port,
+                            tls: conf.tls,
+                            proxy_protocol: conf.proxy_protocol.clone(),
+                        },
+                    );
+                }
+                "tcp" => {
+                    result.tcp.insert(
+                        name.clone(),
+                        TcpOpts {
+                            address: conf.address.clone(),
+                            port: conf.port,
+                        },
+                    );
+                }
+                "udp" => {
+                    result.udp.insert(
+                        name.clone(),
+                        UdpOpts {
+                            address: conf.address.clone(),
+                            port: conf.port,
+                            idle_timeout: conf.idle_timeout,
+                        },
+                    );
+                }
+                other => {
+                    tracing::error!("Unknown Protocol '{}' for Entrypoint '{}'", other, name);
+                }
+            };
+        }
+
+        result
+    }
+
+    /// Adds the given Acceptors, that were configured using their own
+    /// Options, as Entrypoints named after their Keys. Acceptors whose
+    /// Name is already used by another Entrypoint are ignored
+    pub fn extend(
+        &mut self,
+        webserver: &HashMap<String, WebserverOpts>,
+        tcp: &HashMap<String, TcpOpts>,
+        udp: &HashMap<String, UdpOpts>,
+    ) {
+        for (name, conf) in webserver {
+            if self.contains(name) {
+                tracing::warn!("Ignoring Webserver '{}', the Name is already used", name);
+                continue;
+            }
+            self.webserver.insert(name.clone(), conf.clone());
+        }
+        for (name, conf) in tcp {
+            if self.contains(name) {
+                tracing::warn!("Ignoring TCP-Acceptor '{}', the Name is already used", name);
+                continue;
+            }
+            self.tcp.insert(name.clone(), conf.clone());
+        }
+        for (name, conf) in udp {
+            if self.contains(name) {
+                tracing::warn!("Ignoring UDP-Acceptor '{}', the Name is already used", name);
+                continue;
+            }
+            self.udp.insert(name.clone(), conf.clone());
+        }
+    }
+
+    /// Returns the Names of all the Entrypoints
+    pub fn names(&self) -> Vec<String> {
+        self.webserver
+            .keys()
+            .chain(self.tcp.keys())
+            .chain(self.udp.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Checks if there already is an Entrypoint with the given Name
+    fn contains(&self, name: &str) -> bool {
+        self.webserver.contains_key(name)
+            || self.tcp.contains_key(name)
+            || self.udp.contains_key(name)
+    }
+}
@@ -9,8 +9,23 @@ pub use tunneler::TunnelerOpts;
 mod webserver;
 pub use webserver::WebserverOpts;
 
+mod tcp;
+pub use tcp::TcpOpts;
+
+mod udp;
+pub use udp::UdpOpts;
+
+mod entrypoint;
+pub use entrypoint::{EntrypointOpts, Entrypoints};
+
 mod kubernetes;
 pub use kubernetes::KubernetesOpts;
 
 mod auto_tls;
 pub use auto_tls::AutoTLSOpts;
+
+mod timeouts;
+pub use timeouts::TimeoutOpts;
+
+mod pool;
+pub use pool::PoolOpts;
@@ -1,6 +1,9 @@
 use argser::argser;
 
-use super::{AutoTLSOpts, KubernetesOpts, TunnelerOpts, WebserverOpts};
+use super::{
+    AutoTLSOpts, EntrypointOpts, KubernetesOpts, PoolOpts, TcpOpts, TimeoutOpts, TunnelerOpts,
+    UdpOpts, WebserverOpts,
+};
 
 /// The Command-Line options provided by the Load-Balancer
 #[argser]
@@ -15,12 +18,22 @@ pub struct Options {
     #[argser(rename("file-conf"), default)]
     pub file: Option<String>,
 
+    /// The named Entrypoints, which may use any of the Protocols
+    #[argser(map(subcategory))]
+    pub entrypoint: EntrypointOpts,
+
     /// The Webserver related options
     #[argser(map(subcategory))]
     pub webserver: WebserverOpts,
     /// The Tunneler related options
     #[argser(map(subcategory))]
     pub tunneler: TunnelerOpts,
+    /// The TCP-Acceptor related options
+    #[argser(map(subcategory))]
+    pub tcp: TcpOpts,
+    /// The UDP-Acceptor related options
+    #[argser(map(subcategory))]
+    pub udp: UdpOpts,
 
     /// Enables the Metrics endpoint
     #[argser(rename("metrics"), default)]
@@ -38,4 +51,21 @@ pub struct Options {
     /// The Auto-TLS related options
     #[argser(subcategory)]
     pub auto_tls: AutoTLSOpts,
+
+    /// Limits the Size of the Bodies of Requests, in Bytes
+    #[argser(rename("max-body-size"), default)]
+    pub max_body_size: Option<u64>,
+
+    /// The CIDRs of the Proxies whose X-Forwarded-* and Forwarded
+    /// Headers are trusted
+    #[argser(rename("trusted-proxies"), default)]
+    pub trusted_proxies: Vec<String>,
+
+    /// The Timeout related options
+    #[argser(subcategory)]
+    pub timeouts: TimeoutOpts,
+
+    /// The Connection-Pool related options
+    #[argser(subcategory)]
+    pub pool: PoolOpts,
 }
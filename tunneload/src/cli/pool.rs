@@ -0,0 +1,31 @@
+use argser::argser;
+
+use crate::forwarder::ConnectionPool;
+
+/// The Options for the Pool of idle Connections to the Services
+#[argser]
+#[derive(Debug)]
+pub struct PoolOpts {
+    /// The maximum Number of idle Connections kept for a single Address
+    #[argser(rename("max-idle"), default_func(default_max_idle))]
+    pub max_idle: u32,
+    /// The Time, in Milliseconds, after which an idle Connection is closed
+    #[argser(rename("idle-timeout"), default_func(default_idle_timeout))]
+    pub idle_timeout: u64,
+}
+
+fn default_max_idle() -> u32 {
+    ConnectionPool::default().max_idle() as u32
+}
+fn default_idle_timeout() -> u64 {
+    ConnectionPool::default().idle_timeout().as_millis() as u64
+}
+
+impl From<&PoolOpts> for ConnectionPool {
+    fn from(opts: &PoolOpts) -> Self {
+        Self::new(
+            opts.max_idle as usize,
+            std::time::Duration::from_millis(opts.idle_timeout),
+        )
+    }
+}
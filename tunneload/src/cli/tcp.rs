@@ -0,0 +1,13 @@
+use argser::argser;
+
+/// All the TCP-Acceptor specific options
+#[argser]
+#[derive(Debug, Clone)]
+pub struct TcpOpts {
+    /// The Address to listen on, defaults to all Interfaces
+    #[argser(default)]
+    pub address: Option<String>,
+    /// Accepts raw TCP-Connections on the given port
+    #[argser(default)]
+    pub port: u32,
+}
@@ -0,0 +1,60 @@
+use argser::argser;
+
+use crate::handler::Timeouts;
+
+/// The Timeouts, in Milliseconds, used for all Connections. All of them
+/// can be overwritten for every Service
+#[argser]
+#[derive(Debug)]
+pub struct TimeoutOpts {
+    /// The Time allowed for receiving the Headers of a Request
+    #[argser(rename("header-read"), default_func(default_header_read))]
+    pub header_read: u64,
+    /// The Time allowed between two Reads of the Body of a Request
+    #[argser(rename("body-read"), default_func(default_body_read))]
+    pub body_read: u64,
+    /// The Time a Keep-Alive Connection may be idle between Requests
+    #[argser(rename("idle"), default_func(default_keep_alive_idle))]
+    pub keep_alive_idle: u64,
+    /// The Time allowed for establishing a Connection to a Service
+    #[argser(rename("connect"), default_func(default_upstream_connect))]
+    pub upstream_connect: u64,
+    /// The Time allowed for a Service to send its Response
+    #[argser(rename("response"), default_func(default_upstream_response))]
+    pub upstream_response: u64,
+    /// The Time allowed between two Reads of the Body of a Response
+    #[argser(rename("response-read"), default_func(default_upstream_read))]
+    pub upstream_read: u64,
+}
+
+fn default_header_read() -> u64 {
+    Timeouts::default().header_read.as_millis() as u64
+}
+fn default_body_read() -> u64 {
+    Timeouts::default().body_read.as_millis() as u64
+}
+fn default_keep_alive_idle() -> u64 {
+    Timeouts::default().keep_alive_idle.as_millis() as u64
+}
+fn default_upstream_connect() -> u64 {
+    Timeouts::default().upstream_connect.as_millis() as u64
+}
+fn default_upstream_response() -> u64 {
+    Timeouts::default().upstream_response.as_millis() as u64
+}
+fn default_upstream_read() -> u64 {
+    Timeouts::default().upstream_read.as_millis() as u64
+}
+
+impl From<&TimeoutOpts> for Timeouts {
+    fn from(opts: &TimeoutOpts) -> Self {
+        Self {
+            header_read: std::time::Duration::from_millis(opts.header_read),
+            body_read: std::time::Duration::from_millis(opts.body_read),
+            keep_alive_idle: std::time::Duration::from_millis(opts.keep_alive_idle),
+            upstream_connect: std::time::Duration::from_millis(opts.upstream_connect),
+            upstream_response: std::time::Duration::from_millis(opts.upstream_response),
+            upstream_read: std::time::Duration::from_millis(opts.upstream_read),
+        }
+    }
+}
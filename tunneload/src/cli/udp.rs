@@ -0,0 +1,17 @@
+use argser::argser;
+
+/// All the UDP-Acceptor specific options
+#[argser]
+#[derive(Debug, Clone)]
+pub struct UdpOpts {
+    /// The Address to listen on, defaults to all Interfaces
+    #[argser(default)]
+    pub address: Option<String>,
+    /// Accepts UDP-Datagrams on the given port
+    #[argser(default)]
+    pub port: u32,
+    /// The Number of Seconds after which an idle Session
+    /// is removed
+    #[argser(default)]
+    pub idle_timeout: Option<u64>,
+}
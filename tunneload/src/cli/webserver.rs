@@ -2,8 +2,11 @@ use argser::argser;
 
 /// All the Webserver specific options
 #[argser]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WebserverOpts {
+    /// The Address to listen on, defaults to all Interfaces
+    #[argser(default)]
+    pub address: Option<String>,
     /// Enables the Non-TLS webserver on the given
     /// port
     #[argser(default)]
@@ -11,4 +14,8 @@ pub struct WebserverOpts {
     /// Enables the TLS webserver on the given port
     #[argser(default)]
     pub tls: bool,
+    /// The Source-CIDRs that are trusted to send a PROXY-Protocol Header
+    /// in front of their Connections
+    #[argser(default)]
+    pub proxy_protocol: Vec<String>,
 }
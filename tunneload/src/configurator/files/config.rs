@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use crate::configurator::files::ConfigRoute;
+use crate::configurator::files::{ConfigRoute, ConfigService, ConfigTcpRoute, ConfigUdpRoute};
 
 /// The underlying File Structure
 #[derive(Debug, Deserialize)]
@@ -10,4 +10,10 @@ pub struct Config {
     pub middleware: Option<Vec<serde_json::Value>>,
     /// The List of Routes defined in a Config File
     pub routes: Option<Vec<ConfigRoute>>,
+    /// The List of TCP-Routes defined in a Config File
+    pub tcp_routes: Option<Vec<ConfigTcpRoute>>,
+    /// The List of UDP-Routes defined in a Config File
+    pub udp_routes: Option<Vec<ConfigUdpRoute>>,
+    /// The List of Services defined in a Config File
+    pub services: Option<Vec<ConfigService>>,
 }
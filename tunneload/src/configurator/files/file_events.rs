@@ -5,7 +5,9 @@ use general::{Group, Name};
 use crate::{
     configurator::{
         files::Config,
-        parser::{self, EventEmitter, EventFuture, RawMiddlewareConfig, RawRuleConfig},
+        parser::{
+            self, EventEmitter, EventFuture, RawMiddlewareConfig, RawRuleConfig, RawServiceConfig,
+        },
     },
     util::files::events,
 };
@@ -104,6 +106,56 @@ impl FileEvents {
         }
     }
 
+    async fn service_events(
+        path: String,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawServiceConfig, Name>>,
+    ) {
+        let watcher = match events::CustomWatcher::new(path) {
+            Some(w) => w,
+            None => {
+                tracing::error!("Failed to create Service-File-Watcher");
+                return;
+            }
+        };
+
+        for path in watcher {
+            let content = match std::fs::read(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    tracing::error!("Reading File: {:?}", e);
+                    continue;
+                }
+            };
+
+            let deserialized: Config = match serde_yaml::from_slice(&content) {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::error!("Parsing Config: {:?}", e);
+                    continue;
+                }
+            };
+
+            let services = match deserialized.services {
+                Some(s) => s,
+                None => continue,
+            };
+
+            for tmp in services {
+                let value = match serde_json::to_value(tmp) {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+
+                if let Err(e) =
+                    sender.send(parser::Event::Update(RawServiceConfig { config: value }))
+                {
+                    tracing::error!("Sending Event: {:?}", e);
+                    return;
+                }
+            }
+        }
+    }
+
     async fn rule_events(
         path: String,
         sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
@@ -152,10 +204,124 @@ impl FileEvents {
             }
         }
     }
+
+    async fn tcp_rule_events(
+        path: String,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+    ) {
+        let watcher = match events::CustomWatcher::new(path) {
+            Some(w) => w,
+            None => {
+                tracing::error!("Failed to create TCP-Rule-File-Watcher");
+                return;
+            }
+        };
+
+        for path in watcher {
+            let content = match std::fs::read(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    tracing::error!("Reading File: {:?}", e);
+                    continue;
+                }
+            };
+
+            let deserialized: Config = match serde_yaml::from_slice(&content) {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::error!("Parsing Config: {:?}", e);
+                    continue;
+                }
+            };
+
+            let routes = match deserialized.tcp_routes {
+                Some(r) => r,
+                None => continue,
+            };
+
+            for tmp in routes {
+                let value = match serde_json::to_value(tmp) {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+
+                if let Err(e) = sender.send(parser::Event::Update(RawRuleConfig { config: value }))
+                {
+                    tracing::error!("Sending Event: {:?}", e);
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn udp_rule_events(
+        path: String,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+    ) {
+        let watcher = match events::CustomWatcher::new(path) {
+            Some(w) => w,
+            None => {
+                tracing::error!("Failed to create UDP-Rule-File-Watcher");
+                return;
+            }
+        };
+
+        for path in watcher {
+            let content = match std::fs::read(&path) {
+                Ok(c) => c,
+                Err(e) => {
+                    tracing::error!("Reading File: {:?}", e);
+                    continue;
+                }
+            };
+
+            let deserialized: Config = match serde_yaml::from_slice(&content) {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::error!("Parsing Config: {:?}", e);
+                    continue;
+                }
+            };
+
+            let routes = match deserialized.udp_routes {
+                Some(r) => r,
+                None => continue,
+            };
+
+            for tmp in routes {
+                let value = match serde_json::to_value(tmp) {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+
+                if let Err(e) = sender.send(parser::Event::Update(RawRuleConfig { config: value }))
+                {
+                    tracing::error!("Sending Event: {:?}", e);
+                    return;
+                }
+            }
+        }
+    }
 }
 
 #[async_trait]
 impl EventEmitter for FileEvents {
+    async fn service_listener(
+        &self,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawServiceConfig, Name>>,
+    ) -> Option<EventFuture> {
+        async fn run(
+            path: String,
+            sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawServiceConfig, Name>>,
+        ) {
+            tokio::task::spawn_blocking(move || {
+                futures::executor::block_on(FileEvents::service_events(path, sender));
+            });
+        }
+
+        Some(run(self.path.clone(), sender).boxed())
+    }
+
     async fn middleware_listener(
         &self,
         sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawMiddlewareConfig, Name>>,
@@ -187,4 +353,36 @@ impl EventEmitter for FileEvents {
 
         Some(run(self.path.clone(), sender).boxed())
     }
+
+    async fn tcp_rule_listener(
+        &self,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+    ) -> Option<EventFuture> {
+        async fn run(
+            path: String,
+            sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+        ) {
+            tokio::task::spawn_blocking(move || {
+                futures::executor::block_on(FileEvents::tcp_rule_events(path, sender));
+            });
+        }
+
+        Some(run(self.path.clone(), sender).boxed())
+    }
+
+    async fn udp_rule_listener(
+        &self,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+    ) -> Option<EventFuture> {
+        async fn run(
+            path: String,
+            sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+        ) {
+            tokio::task::spawn_blocking(move || {
+                futures::executor::block_on(FileEvents::udp_rule_events(path, sender));
+            });
+        }
+
+        Some(run(self.path.clone(), sender).boxed())
+    }
 }
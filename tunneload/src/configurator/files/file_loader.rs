@@ -1,9 +1,12 @@
 use async_trait::async_trait;
 
-use crate::configurator::parser::{Loader, RawMiddlewareConfig, RawRuleConfig};
+use crate::configurator::parser::{Loader, RawMiddlewareConfig, RawRuleConfig, RawServiceConfig};
 
 mod middlewares;
 mod rules;
+mod services;
+mod tcp_rules;
+mod udp_rules;
 
 /// The Loader for the File-Configuration
 pub struct FileLoader {
@@ -70,6 +73,12 @@ impl FileLoader {
 
 #[async_trait]
 impl Loader for FileLoader {
+    async fn services(&self) -> Vec<RawServiceConfig> {
+        Self::load(self.path.clone(), &|content: Vec<u8>| {
+            services::load_file(content)
+        })
+    }
+
     async fn middlewares(&self) -> Vec<RawMiddlewareConfig> {
         Self::load(self.path.clone(), &|content: Vec<u8>| {
             middlewares::load_file(content)
@@ -81,4 +90,16 @@ impl Loader for FileLoader {
             rules::load_file(content)
         })
     }
+
+    async fn tcp_rules(&self) -> Vec<RawRuleConfig> {
+        Self::load(self.path.clone(), &|content: Vec<u8>| {
+            tcp_rules::load_file(content)
+        })
+    }
+
+    async fn udp_rules(&self) -> Vec<RawRuleConfig> {
+        Self::load(self.path.clone(), &|content: Vec<u8>| {
+            udp_rules::load_file(content)
+        })
+    }
 }
@@ -0,0 +1,27 @@
+use crate::configurator::{files::Config, parser::RawServiceConfig};
+
+pub fn load_file(content: Vec<u8>) -> Option<Vec<RawServiceConfig>> {
+    let value: Config = match serde_yaml::from_slice(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            tracing::error!("Parsing YAML: {:?}", e);
+            return None;
+        }
+    };
+
+    let services = match value.services {
+        Some(s) => s,
+        None => return Some(Vec::new()),
+    };
+
+    let mut result = Vec::new();
+    for tmp in services {
+        let tmp_value = match serde_json::to_value(tmp) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        result.push(RawServiceConfig { config: tmp_value });
+    }
+
+    Some(result)
+}
@@ -3,13 +3,19 @@ use std::{error::Error, fmt::Display};
 use crate::configurator::parser::{ParseRuleContext, Parser};
 use general::{Group, Name};
 use rules::{
-    parser::{parse_matchers, ParseMatcherError},
-    Action, CorsOpts, Rule,
+    parser::{parse_matchers, parse_tcp_matchers, ParseMatcherError},
+    Action, AuthAddressError, CorsOpts, ForwardAuth, IpListOpts, Pattern, RateLimit, RateLimitKey,
+    RedirectRegexOpts, RedirectSchemeOpts, RedirectStatus, ReplacePathRegexOpts, Rule, Service,
+    ServiceTls, TcpRule, TcpRuleTLS, UdpRule,
 };
 
 use async_trait::async_trait;
 
-use super::route::ConfigRoute;
+use super::route::{
+    ConfigForwardAuth, ConfigIpList, ConfigRateLimit, ConfigRedirectRegex, ConfigRedirectScheme,
+    ConfigReplacePathRegex, ConfigRoute, ConfigService, ConfigServiceTls, ConfigTcpRoute,
+    ConfigUdpRoute,
+};
 
 /// This is the Parser for all the File-Configurator related stuff
 #[derive(Debug, Clone)]
@@ -32,11 +38,15 @@ impl Default for FileParser {
 pub enum ActionParseError {
     InvalidConfig,
     UnknownAction,
+    InvalidForwardAuth(AuthAddressError),
 }
 
 impl Display for ActionParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Action-Parse-Error")
+        match *self {
+            ActionParseError::InvalidForwardAuth(ref e) => write!(f, "Action-Parse-Error: {}", e),
+            _ => write!(f, "Action-Parse-Error"),
+        }
     }
 }
 impl Error for ActionParseError {}
@@ -54,8 +64,77 @@ impl Display for RuleParseError {
 }
 impl Error for RuleParseError {}
 
+#[derive(Debug)]
+pub enum ServiceParseError {
+    InvalidConfig(serde_json::Error),
+    ReadingFile(String, std::io::Error),
+}
+
+impl Display for ServiceParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidConfig(e) => write!(f, "Service-Parse-Error: {}", e),
+            Self::ReadingFile(path, e) => {
+                write!(f, "Service-Parse-Error: Reading '{}': {}", path, e)
+            }
+        }
+    }
+}
+impl Error for ServiceParseError {}
+
+/// Loads the TLS-Configuration for a Service, by reading all the
+/// configured PEM-Files
+fn load_tls(raw: ConfigServiceTls) -> Result<ServiceTls, ServiceParseError> {
+    let read = |path: Option<String>| match path {
+        Some(path) => match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(Some(content)),
+            Err(e) => Err(ServiceParseError::ReadingFile(path, e)),
+        },
+        None => Ok(None),
+    };
+
+    Ok(ServiceTls {
+        server_name: raw.server_name,
+        ca: read(raw.ca_file)?,
+        client_cert: read(raw.cert_file)?,
+        client_key: read(raw.key_file)?,
+        insecure_skip_verify: raw.insecure_skip_verify,
+    })
+}
+
 #[async_trait]
 impl Parser for FileParser {
+    async fn service(&self, config: &serde_json::Value) -> Result<Service, Box<dyn Error>> {
+        let raw: ConfigService = serde_json::from_value(config.to_owned())
+            .map_err(|e| Box::new(ServiceParseError::InvalidConfig(e)))?;
+
+        let name = Name::new(raw.name, Group::File {});
+        let mut service = Service::new(name, raw.addresses.unwrap_or_default());
+        if let Some(strategy) = raw.strategy {
+            service.set_strategy(strategy);
+        }
+        if let Some(health_check) = raw.health_check {
+            service.set_health_check(health_check);
+        }
+        if let Some(timeouts) = raw.timeouts {
+            service.set_timeouts(timeouts);
+        }
+        if let Some(outlier_detection) = raw.outlier_detection {
+            service.set_outlier_detection(outlier_detection);
+        }
+        if let Some(protocol) = raw.protocol {
+            service.set_protocol(protocol);
+        }
+        if let Some(tls) = raw.tls {
+            service.set_tls(load_tls(tls)?);
+        }
+        if let Some(version) = raw.proxy_protocol {
+            service.set_proxy_protocol(version);
+        }
+
+        Ok(service)
+    }
+
     async fn parse_action(
         &self,
         name: &str,
@@ -63,10 +142,48 @@ impl Parser for FileParser {
     ) -> Result<Action, Box<dyn Error>> {
         match name {
             "RemovePrefix" => {
+                // A single Prefix is still accepted as a plain String
+                let prefixes = match config.as_str() {
+                    Some(prefix) => vec![prefix.to_owned()],
+                    None => serde_json::from_value(config.to_owned())
+                        .map_err(|_| Box::new(ActionParseError::InvalidConfig))?,
+                };
+                Ok(Action::RemovePrefix(prefixes))
+            }
+            "AddPrefix" => {
                 let prefix = config
                     .as_str()
                     .ok_or_else(|| Box::new(ActionParseError::InvalidConfig))?;
-                Ok(Action::RemovePrefix(prefix.to_owned()))
+                Ok(Action::AddPrefix(prefix.to_owned()))
+            }
+            "StripPrefixRegex" => {
+                let raw: Vec<String> = serde_json::from_value(config.to_owned())
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+
+                let mut patterns = Vec::with_capacity(raw.len());
+                for tmp in raw.iter() {
+                    let pattern =
+                        Pattern::new(tmp).map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+                    patterns.push(pattern);
+                }
+                Ok(Action::StripPrefixRegex(patterns))
+            }
+            "ReplacePath" => {
+                let path = config
+                    .as_str()
+                    .ok_or_else(|| Box::new(ActionParseError::InvalidConfig))?;
+                Ok(Action::ReplacePath(path.to_owned()))
+            }
+            "ReplacePathRegex" => {
+                let replace: ConfigReplacePathRegex = serde_json::from_value(config.to_owned())
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+                let regex = Pattern::new(&replace.regex)
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+
+                Ok(Action::ReplacePathRegex(ReplacePathRegexOpts {
+                    regex,
+                    replacement: replace.replacement,
+                }))
             }
             "AddHeader" => {
                 let headers = config
@@ -185,6 +302,76 @@ impl Parser for FileParser {
                     .ok_or_else(|| Box::new(ActionParseError::InvalidConfig))?;
                 Ok(Action::new_basic_auth_hashed(auth))
             }
+            "IpAllowList" | "IpDenyList" => {
+                let list: ConfigIpList = serde_json::from_value(config.to_owned())
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+
+                let mut networks = Vec::with_capacity(list.networks.len());
+                for raw in list.networks.iter() {
+                    let network = raw
+                        .parse()
+                        .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+                    networks.push(network);
+                }
+
+                let opts = IpListOpts {
+                    networks,
+                    depth: list.depth,
+                };
+                if name == "IpAllowList" {
+                    Ok(Action::IpAllowList(opts))
+                } else {
+                    Ok(Action::IpDenyList(opts))
+                }
+            }
+            "ForwardAuth" => {
+                let auth: ConfigForwardAuth = serde_json::from_value(config.to_owned())
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+                let timeout = auth.timeout_ms.map(std::time::Duration::from_millis);
+                let mut auth =
+                    ForwardAuth::new(&auth.address, auth.request_headers, auth.response_headers)
+                        .map_err(|e| Box::new(ActionParseError::InvalidForwardAuth(e)))?;
+                if let Some(timeout) = timeout {
+                    auth = auth.with_timeout(timeout);
+                }
+                Ok(Action::ForwardAuth(auth))
+            }
+            "RateLimit" => {
+                let limit: ConfigRateLimit = serde_json::from_value(config.to_owned())
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+                Ok(Action::RateLimit(RateLimit::new(
+                    limit.average,
+                    std::time::Duration::from_secs(limit.period),
+                    limit.burst,
+                    limit.key.unwrap_or(RateLimitKey::ClientIP),
+                )))
+            }
+            "RedirectScheme" => {
+                let redirect: ConfigRedirectScheme = serde_json::from_value(config.to_owned())
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+                let status = RedirectStatus::from_code(redirect.status)
+                    .ok_or_else(|| Box::new(ActionParseError::InvalidConfig))?;
+
+                Ok(Action::RedirectScheme(RedirectSchemeOpts {
+                    scheme: redirect.scheme,
+                    port: redirect.port,
+                    status,
+                }))
+            }
+            "RedirectRegex" => {
+                let redirect: ConfigRedirectRegex = serde_json::from_value(config.to_owned())
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+                let status = RedirectStatus::from_code(redirect.status)
+                    .ok_or_else(|| Box::new(ActionParseError::InvalidConfig))?;
+                let regex = Pattern::new(&redirect.regex)
+                    .map_err(|_| Box::new(ActionParseError::InvalidConfig))?;
+
+                Ok(Action::RedirectRegex(RedirectRegexOpts {
+                    regex,
+                    replacement: redirect.replacement,
+                    status,
+                }))
+            }
             _ => Err(Box::new(ActionParseError::UnknownAction)),
         }
     }
@@ -223,13 +410,62 @@ impl Parser for FileParser {
         };
 
         let rule_name = Name::new(name, Group::File {});
-        Ok(Rule::new(
-            rule_name,
-            priority,
-            matcher,
-            middlewares,
-            service,
-        ))
+        let mut rule = Rule::new(rule_name, priority, matcher, middlewares, service);
+        rule.set_entry_points(route.entrypoints.unwrap_or_default());
+
+        Ok(rule)
+    }
+
+    async fn tcp_rule<'a>(
+        &self,
+        config: &serde_json::Value,
+        context: ParseRuleContext<'a>,
+    ) -> Result<TcpRule, Box<dyn Error>> {
+        let route: ConfigTcpRoute = match serde_json::from_value(config.to_owned()) {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(Box::new(RuleParseError::InvalidConfig(e)));
+            }
+        };
+
+        let matcher = parse_tcp_matchers(&route.rule)
+            .map_err(|e| Box::new(RuleParseError::InvalidMatchers(e)))?;
+
+        let service_name = Name::parse(&route.service, || Group::File {});
+        let service = context.services.get_with_default(service_name);
+
+        let rule_name = Name::new(route.name, Group::File {});
+        let mut rule = TcpRule::new(rule_name, route.priority, matcher, service);
+        rule.set_entry_points(route.entrypoints.unwrap_or_default());
+        match route.tls {
+            Some(tls) if tls.passthrough => rule.set_tls(TcpRuleTLS::Passthrough),
+            Some(_) => rule.set_tls(TcpRuleTLS::Terminate),
+            None => {}
+        };
+
+        Ok(rule)
+    }
+
+    async fn udp_rule<'a>(
+        &self,
+        config: &serde_json::Value,
+        context: ParseRuleContext<'a>,
+    ) -> Result<UdpRule, Box<dyn Error>> {
+        let route: ConfigUdpRoute = match serde_json::from_value(config.to_owned()) {
+            Ok(d) => d,
+            Err(e) => {
+                return Err(Box::new(RuleParseError::InvalidConfig(e)));
+            }
+        };
+
+        let service_name = Name::parse(&route.service, || Group::File {});
+        let service = context.services.get_with_default(service_name);
+
+        let rule_name = Name::new(route.name, Group::File {});
+        let mut rule = UdpRule::new(rule_name, route.priority, service);
+        rule.set_entry_points(route.entrypoints.unwrap_or_default());
+
+        Ok(rule)
     }
 }
 
@@ -238,7 +474,8 @@ mod tests {
     use serde_json::json;
 
     use general::Shared;
-    use rules::{Matcher, Middleware, Service};
+    use general_traits::ConfigItem;
+    use rules::{Matcher, Middleware, Service, TcpMatcher};
 
     use crate::configurator::{MiddlewareList, ServiceList};
 
@@ -312,4 +549,510 @@ mod tests {
         assert_eq!(true, result.is_ok());
         assert_eq!(expected, result.unwrap());
     }
+
+    #[tokio::test]
+    async fn rule_extended_matchers() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-name",
+            "rule": "PathPrefix(`/admin/`) && !ClientIP(`10.0.0.0/8`) && Method(`POST`)",
+            "service": "test-service",
+        });
+        let context = ParseRuleContext {
+            middlewares: &MiddlewareList::new(),
+            services: &ServiceList::new(),
+            cert_queue: None,
+        };
+
+        let result = parser.rule(&config, context).await;
+        let expected = Rule::new(
+            Name::new("test-name", Group::File {}),
+            1,
+            Matcher::And(vec![
+                Matcher::PathPrefix("/admin/".to_owned()),
+                Matcher::Not(Box::new(Matcher::ClientIP("10.0.0.0/8".parse().unwrap()))),
+                Matcher::Method("POST".to_owned()),
+            ]),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        );
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn tcp_rule_passthrough() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-name",
+            "priority": 3,
+            "rule": "HostSNI(`db.example.com`)",
+            "service": "test-service",
+            "tls": {
+                "passthrough": true,
+            },
+        });
+        let context = ParseRuleContext {
+            middlewares: &MiddlewareList::new(),
+            services: &ServiceList::new(),
+            cert_queue: None,
+        };
+
+        let result = parser.tcp_rule(&config, context).await;
+        let mut expected = TcpRule::new(
+            Name::new("test-name", Group::File {}),
+            3,
+            TcpMatcher::HostSNI("db.example.com".to_owned()),
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        );
+        expected.set_tls(TcpRuleTLS::Passthrough);
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn tcp_rule_catch_all() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-name",
+            "rule": "HostSNI(`*`)",
+            "service": "test-service",
+        });
+        let context = ParseRuleContext {
+            middlewares: &MiddlewareList::new(),
+            services: &ServiceList::new(),
+            cert_queue: None,
+        };
+
+        let result = parser.tcp_rule(&config, context).await.unwrap();
+
+        assert_eq!(&TcpMatcher::Any, result.matcher());
+        assert_eq!(&TcpRuleTLS::None, result.tls());
+    }
+
+    #[tokio::test]
+    async fn udp_rule() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-name",
+            "service": "dns",
+        });
+        let context = ParseRuleContext {
+            middlewares: &MiddlewareList::new(),
+            services: &ServiceList::new(),
+            cert_queue: None,
+        };
+
+        let result = parser.udp_rule(&config, context).await;
+        let expected = UdpRule::new(
+            Name::new("test-name", Group::File {}),
+            1,
+            Shared::new(Service::new(Name::new("dns", Group::File {}), vec![])),
+        );
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rule_with_entrypoints() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-name",
+            "rule": "PathPrefix(`/`)",
+            "service": "test-service",
+            "entrypoints": ["internal"],
+        });
+        let context = ParseRuleContext {
+            middlewares: &MiddlewareList::new(),
+            services: &ServiceList::new(),
+            cert_queue: None,
+        };
+
+        let result = parser.rule(&config, context).await.unwrap();
+
+        assert_eq!(&["internal".to_owned()], result.entry_points().names());
+    }
+
+    #[tokio::test]
+    async fn action_ip_allow_list() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "networks": ["10.0.0.0/8", "2001:db8::/32"],
+            "depth": 1,
+        });
+
+        let result = parser.parse_action("IpAllowList", &config).await;
+        let expected = Action::IpAllowList(IpListOpts {
+            networks: vec![
+                "10.0.0.0/8".parse().unwrap(),
+                "2001:db8::/32".parse().unwrap(),
+            ],
+            depth: Some(1),
+        });
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+
+        let config = json!({
+            "networks": ["not-a-network"],
+        });
+        assert_eq!(
+            true,
+            parser.parse_action("IpDenyList", &config).await.is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn action_remove_prefix() {
+        let parser = FileParser::default();
+
+        let result = parser.parse_action("RemovePrefix", &json!("/api")).await;
+        assert_eq!(
+            Action::RemovePrefix(vec!["/api".to_owned()]),
+            result.unwrap()
+        );
+
+        let result = parser
+            .parse_action("RemovePrefix", &json!(["/v1", "/v2"]))
+            .await;
+        assert_eq!(
+            Action::RemovePrefix(vec!["/v1".to_owned(), "/v2".to_owned()]),
+            result.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn action_replace_path_regex() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "regex": "^/docs/(.*)",
+            "replacement": "/static/${1}",
+        });
+
+        let result = parser.parse_action("ReplacePathRegex", &config).await;
+        let expected = Action::ReplacePathRegex(ReplacePathRegexOpts {
+            regex: Pattern::new("^/docs/(.*)").unwrap(),
+            replacement: "/static/${1}".to_owned(),
+        });
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn action_redirect_scheme() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "scheme": "https",
+            "status": 308,
+        });
+
+        let result = parser.parse_action("RedirectScheme", &config).await;
+        let expected = Action::RedirectScheme(RedirectSchemeOpts {
+            scheme: "https".to_owned(),
+            port: None,
+            status: RedirectStatus::PermanentRedirect,
+        });
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+
+        let config = json!({
+            "scheme": "https",
+            "status": 200,
+        });
+        assert_eq!(
+            true,
+            parser
+                .parse_action("RedirectScheme", &config)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn action_forward_auth() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "address": "http://auth:8080/verify",
+            "response_headers": ["X-User"],
+        });
+
+        let result = parser.parse_action("ForwardAuth", &config).await;
+        let expected = Action::ForwardAuth(
+            ForwardAuth::new("http://auth:8080/verify", vec![], vec!["X-User".to_owned()]).unwrap(),
+        );
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+
+        let config = json!({
+            "address": "http://auth:8080/verify",
+            "timeout_ms": 500,
+        });
+        let expected = Action::ForwardAuth(
+            ForwardAuth::new("http://auth:8080/verify", vec![], vec![])
+                .unwrap()
+                .with_timeout(std::time::Duration::from_millis(500)),
+        );
+        assert_eq!(
+            expected,
+            parser.parse_action("ForwardAuth", &config).await.unwrap()
+        );
+
+        let config = json!({
+            "address": "https://auth:8443",
+        });
+        assert_eq!(
+            true,
+            parser.parse_action("ForwardAuth", &config).await.is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn action_rate_limit() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "average": 100,
+            "burst": 50,
+            "key": {
+                "type": "Header",
+                "name": "X-User",
+            },
+        });
+
+        let result = parser.parse_action("RateLimit", &config).await;
+        let expected = Action::RateLimit(RateLimit::new(
+            100,
+            std::time::Duration::from_secs(1),
+            50,
+            RateLimitKey::Header {
+                name: "X-User".to_owned(),
+            },
+        ));
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn service_with_strategy() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "addresses": ["10.0.0.1:80", "10.0.0.2:80"],
+            "strategy": {
+                "type": "ConsistentHash",
+                "key": {
+                    "type": "Header",
+                    "name": "X-User",
+                },
+            },
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(
+            &Name::new("test-service", Group::File {}),
+            ConfigItem::name(&result)
+        );
+        assert_eq!(
+            &["10.0.0.1:80".to_owned(), "10.0.0.2:80".to_owned()],
+            result.addresses()
+        );
+        assert_eq!(
+            Some(&rules::Strategy::ConsistentHash {
+                key: rules::HashKey::Header {
+                    name: "X-User".to_owned()
+                }
+            }),
+            result.strategy()
+        );
+    }
+
+    #[tokio::test]
+    async fn service_without_strategy() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(0, result.address_count());
+        assert_eq!(None, result.strategy());
+        assert_eq!(None, result.health_check());
+        assert_eq!(None, result.timeouts());
+    }
+
+    #[tokio::test]
+    async fn service_with_timeouts() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "timeouts": {
+                "response_ms": 1500,
+                "response_read_ms": 3000,
+            },
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(
+            Some(&rules::ServiceTimeouts {
+                response_ms: Some(1500),
+                response_read_ms: Some(3000),
+                ..Default::default()
+            }),
+            result.timeouts()
+        );
+    }
+
+    #[tokio::test]
+    async fn service_with_outlier_detection() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "outlier_detection": {
+                "consecutive_failures": 3,
+                "base_ejection_ms": 2000,
+            },
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(
+            Some(&rules::OutlierDetection {
+                consecutive_failures: 3,
+                base_ejection_ms: 2000,
+                ..Default::default()
+            }),
+            result.outlier_detection()
+        );
+    }
+
+    #[tokio::test]
+    async fn service_with_health_check() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "addresses": ["10.0.0.1:80"],
+            "health_check": {
+                "path": "/health",
+                "interval_ms": 5000,
+            },
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(
+            Some(&rules::HealthCheck {
+                path: Some("/health".to_owned()),
+                interval_ms: 5000,
+                ..Default::default()
+            }),
+            result.health_check()
+        );
+    }
+
+    #[tokio::test]
+    async fn service_with_protocol() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "addresses": ["10.0.0.1:50051"],
+            "protocol": "h2c",
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(rules::ServiceProtocol::H2c, result.protocol());
+    }
+
+    #[tokio::test]
+    async fn service_with_tls() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "addresses": ["10.0.0.1:443"],
+            "protocol": "https",
+            "tls": {
+                "server_name": "example.com",
+                "insecure_skip_verify": true,
+            },
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(rules::ServiceProtocol::Https, result.protocol());
+        assert_eq!(
+            Some(&ServiceTls {
+                server_name: Some("example.com".to_owned()),
+                insecure_skip_verify: true,
+                ..Default::default()
+            }),
+            result.tls()
+        );
+    }
+
+    #[tokio::test]
+    async fn service_with_proxy_protocol() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "addresses": ["10.0.0.1:80"],
+            "proxy_protocol": "v2",
+        });
+
+        let result = parser.service(&config).await.unwrap();
+        assert_eq!(Some(rules::ProxyProtocol::V2), result.proxy_protocol());
+    }
+
+    #[tokio::test]
+    async fn service_with_missing_ca_file() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "tls": {
+                "ca_file": "/does/not/exist.pem",
+            },
+        });
+
+        assert_eq!(true, parser.service(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn service_unknown_strategy() {
+        let parser = FileParser::default();
+
+        let config = json!({
+            "name": "test-service",
+            "strategy": {
+                "type": "Unknown",
+            },
+        });
+
+        assert_eq!(true, parser.service(&config).await.is_err());
+    }
 }
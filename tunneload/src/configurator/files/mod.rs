@@ -5,7 +5,7 @@ mod loader;
 pub use loader::FileConfigurator;
 
 mod route;
-pub use route::{ConfigRoute, ConfigService};
+pub use route::{ConfigRoute, ConfigService, ConfigTcpRoute, ConfigTcpTls, ConfigUdpRoute};
 
 mod config;
 pub use config::*;
@@ -1,3 +1,7 @@
+use rules::{
+    HealthCheck, OutlierDetection, ProxyProtocol, RateLimitKey, ServiceProtocol, ServiceTimeouts,
+    Strategy,
+};
 use serde::{Deserialize, Serialize};
 
 /// The Service Configuration for a given Route
@@ -8,6 +12,46 @@ pub struct ConfigService {
     /// An optional List of addresses that should be used for
     /// this service
     pub addresses: Option<Vec<String>>,
+    /// The Load-Balancing Strategy that should be used for
+    /// this service
+    pub strategy: Option<Strategy>,
+    /// The Health-Check that should be used for the addresses
+    /// of this service
+    pub health_check: Option<HealthCheck>,
+    /// The Timeouts that should overwrite the global Timeouts
+    /// for this service
+    pub timeouts: Option<ServiceTimeouts>,
+    /// The Settings for ejecting the addresses of this service that
+    /// keep failing
+    pub outlier_detection: Option<OutlierDetection>,
+    /// The Protocol that should be used to forward Requests to
+    /// this service
+    pub protocol: Option<ServiceProtocol>,
+    /// The TLS-Configuration that should be used for the Connections
+    /// to this service, if its protocol uses TLS
+    pub tls: Option<ConfigServiceTls>,
+    /// The Version of the PROXY-Protocol that should be sent when
+    /// connecting to the addresses of this service
+    pub proxy_protocol: Option<ProxyProtocol>,
+}
+
+/// The TLS Configuration for the Connections to a Service
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ConfigServiceTls {
+    /// The Name that should be used for SNI and the Verification of
+    /// the Certificate of the service
+    pub server_name: Option<String>,
+    /// The Path to the PEM-File containing the CAs that should be
+    /// trusted for this service
+    pub ca_file: Option<String>,
+    /// The Path to the PEM-File containing the Client-Certificate
+    pub cert_file: Option<String>,
+    /// The Path to the PEM-File containing the Key of the
+    /// Client-Certificate
+    pub key_file: Option<String>,
+    /// Disables the Verification of the Certificate of the service
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
 }
 
 /// The Rule Configuration for a single Rule
@@ -24,6 +68,138 @@ pub struct ConfigRoute {
     pub service: String,
     /// An opitonal List of all Middlewares for this Rule
     pub middleware: Option<Vec<String>>,
+    /// The Names of the Entrypoints this Rule is restricted to, the Rule
+    /// applies to all Entrypoints if this is not set
+    pub entrypoints: Option<Vec<String>>,
+}
+
+/// The Configuration for a single Rule that routes TCP-Connections
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigTcpRoute {
+    /// The Name of the Rule itself
+    pub name: String,
+    /// The Priority of the Rule
+    #[serde(default = "default_priority")]
+    pub priority: u32,
+    /// The actual Matcher-Rule to use, like "HostSNI(`example.com`)"
+    pub rule: String,
+    /// The target Service for all matching Connections
+    pub service: String,
+    /// The TLS-Configuration for the Connections, if this is not set
+    /// the Connections are forwarded as they are
+    pub tls: Option<ConfigTcpTls>,
+    /// The Names of the Entrypoints this Rule is restricted to, the Rule
+    /// applies to all Entrypoints if this is not set
+    pub entrypoints: Option<Vec<String>>,
+}
+
+/// The TLS Configuration for a TCP-Rule
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ConfigTcpTls {
+    /// Forwards the TLS-Connection to the service without terminating
+    /// it, otherwise it is terminated by the load-balancer
+    #[serde(default)]
+    pub passthrough: bool,
+}
+
+/// The Configuration for a single Rule that forwards UDP-Datagrams
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigUdpRoute {
+    /// The Name of the Rule itself
+    pub name: String,
+    /// The Priority of the Rule
+    #[serde(default = "default_priority")]
+    pub priority: u32,
+    /// The target Service for all the Datagrams
+    pub service: String,
+    /// The Names of the Entrypoints this Rule is restricted to, the Rule
+    /// applies to all Entrypoints if this is not set
+    pub entrypoints: Option<Vec<String>>,
+}
+
+/// The Configuration for an IP-Allow- or IP-Deny-List Middleware
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigIpList {
+    /// The CIDRs of the Networks in the List
+    pub networks: Vec<String>,
+    /// Uses the Address at this Depth in the X-Forwarded-For Header,
+    /// counted from the right, instead of the Address of the Client
+    pub depth: Option<usize>,
+}
+
+/// The Configuration for a Rate-Limit Middleware
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigRateLimit {
+    /// The average Number of Requests allowed per Period
+    pub average: u64,
+    /// The Length of the Period in Seconds
+    #[serde(default = "default_period")]
+    pub period: u64,
+    /// The Number of Requests allowed at once
+    #[serde(default)]
+    pub burst: u64,
+    /// What the Requests are grouped by, defaults to the Client-IP
+    pub key: Option<RateLimitKey>,
+}
+
+/// The Configuration for a Redirect-Scheme Middleware
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigRedirectScheme {
+    /// The Scheme to redirect to
+    pub scheme: String,
+    /// The Port to redirect to, defaults to the Port of the Scheme
+    pub port: Option<u16>,
+    /// The Status-Code used for the Redirect
+    #[serde(default = "default_redirect_status")]
+    pub status: u16,
+}
+
+/// The Configuration for a Redirect-Regex Middleware
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigRedirectRegex {
+    /// The Regex the full URL of a Request is matched against
+    pub regex: String,
+    /// The URL to redirect to, which may reference captured Groups
+    pub replacement: String,
+    /// The Status-Code used for the Redirect
+    #[serde(default = "default_redirect_status")]
+    pub status: u16,
+}
+
+/// The Configuration for a Forward-Auth Middleware
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigForwardAuth {
+    /// The "http://" Address of the Auth-Service, "https://" is not
+    /// supported
+    pub address: String,
+    /// The Headers forwarded to the Auth-Service, all Headers are
+    /// forwarded if this is empty
+    #[serde(default)]
+    pub request_headers: Vec<String>,
+    /// The Headers copied from the Response of the Auth-Service onto
+    /// the Request
+    #[serde(default)]
+    pub response_headers: Vec<String>,
+    /// The Time allowed for the Auth-Service to respond, in Milliseconds
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+/// The Configuration for a Replace-Path-Regex Middleware
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConfigReplacePathRegex {
+    /// The Regex the Path of a Request is matched against
+    pub regex: String,
+    /// The new Path, which may reference captured Groups
+    pub replacement: String,
+}
+
+fn default_redirect_status() -> u16 {
+    302
+}
+
+fn default_period() -> u64 {
+    1
 }
 
 fn default_priority() -> u32 {
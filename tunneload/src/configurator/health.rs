@@ -0,0 +1,381 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use general::Name;
+use general_traits::ConfigItem;
+use rules::{HealthCheck, ProxyProtocol, ServiceTls};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use lazy_static::lazy_static;
+use prometheus::Registry;
+
+use super::ServiceList;
+use crate::{forwarder::UpstreamTls, util::proxy_protocol};
+
+/// The Interval in which the Services are checked for Addresses that
+/// are due for their next Health-Check
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+lazy_static! {
+    static ref SERVICE_ENDPOINT_HEALTHY: prometheus::IntGaugeVec = prometheus::IntGaugeVec::new(
+        prometheus::Opts::new(
+            "service_endpoint_healthy",
+            "Whether or not an Address of a Service is considered healthy",
+        ),
+        &["service", "address"]
+    )
+    .expect("Creating a Metric should never Fail");
+}
+
+/// Registers all the Prometheus Metrics related to the Health-Checks
+pub fn register_metrics(reg: &mut Registry) {
+    if let Err(e) = reg.register(Box::new(SERVICE_ENDPOINT_HEALTHY.clone())) {
+        tracing::error!("Registering Metric: {:?}", e);
+    }
+}
+
+/// The Result of the previous Checks for a single Address
+#[derive(Debug)]
+struct AddressState {
+    healthy: bool,
+    successes: u32,
+    failures: u32,
+    last_check: Instant,
+}
+
+impl AddressState {
+    fn new(healthy: bool, now: Instant) -> Self {
+        Self {
+            healthy,
+            successes: 0,
+            failures: 0,
+            last_check: now,
+        }
+    }
+
+    /// Records the Result of a Check and returns the resulting Health,
+    /// which only changes once the configured Threshold is reached
+    fn record(&mut self, success: bool, config: &HealthCheck) -> bool {
+        if success {
+            self.successes += 1;
+            self.failures = 0;
+            if !self.healthy && self.successes >= config.healthy_threshold {
+                self.healthy = true;
+            }
+        } else {
+            self.failures += 1;
+            self.successes = 0;
+            if self.healthy && self.failures >= config.unhealthy_threshold {
+                self.healthy = false;
+            }
+        }
+
+        self.healthy
+    }
+}
+
+/// Requests the Path over the given Connection and checks the Status
+/// of the Response
+async fn check_path<S>(mut connection: S, address: &str, path: &str) -> Option<bool>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: tunneload-health\r\nConnection: close\r\n\r\n",
+        path, address
+    );
+    connection.write_all(request.as_bytes()).await.ok()?;
+
+    let mut buffer = [0; 64];
+    let mut read = 0;
+    while read < buffer.len() {
+        let n_read = connection.read(&mut buffer[read..]).await.ok()?;
+        if n_read == 0 {
+            break;
+        }
+        read += n_read;
+
+        if buffer[..read].contains(&b'\n') {
+            break;
+        }
+    }
+
+    Some(is_success_status(&buffer[..read]))
+}
+
+/// Runs a single Check against the given Address, the Request of an
+/// HTTP-Check is sent using TLS if a TLS-Configuration is given
+async fn check_address(
+    address: &str,
+    config: &HealthCheck,
+    tls: Option<(&UpstreamTls, Option<&ServiceTls>)>,
+    proxy: Option<ProxyProtocol>,
+) -> bool {
+    let check = async {
+        let mut connection = TcpStream::connect(address).await.ok()?;
+
+        let path = match config.path.as_ref() {
+            Some(p) => p,
+            None => return Some(true),
+        };
+
+        // The Check does not belong to any Client, so no Addresses are sent
+        if let Some(version) = proxy {
+            let header = proxy_protocol::encode(version, None);
+            connection.write_all(&header).await.ok()?;
+        }
+
+        match tls {
+            Some((upstream, service_tls)) => {
+                let connection = upstream
+                    .connect(connection, address, service_tls, false)
+                    .await
+                    .ok()?;
+                check_path(connection, address, path).await
+            }
+            None => check_path(connection, address, path).await,
+        }
+    };
+
+    matches!(
+        tokio::time::timeout(config.timeout(), check).await,
+        Ok(Some(true))
+    )
+}
+
+/// Checks if the Response starts with a Status-Line that has a 2xx or
+/// 3xx Status-Code
+fn is_success_status(response: &[u8]) -> bool {
+    let line = match std::str::from_utf8(response) {
+        Ok(raw) => raw.lines().next().unwrap_or(""),
+        Err(e) => std::str::from_utf8(&response[..e.valid_up_to()])
+            .unwrap_or("")
+            .lines()
+            .next()
+            .unwrap_or(""),
+    };
+
+    let mut parts = line.split(' ');
+    if !parts.next().unwrap_or("").starts_with("HTTP/") {
+        return false;
+    }
+
+    matches!(
+        parts.next().and_then(|code| code.parse::<u16>().ok()),
+        Some(200..=399)
+    )
+}
+
+/// Actively checks the Addresses of all the Services that have a
+/// Health-Check configured and takes failing Addresses out of rotation
+pub struct HealthChecker {
+    services: ServiceList,
+    states: HashMap<(Name, String), AddressState>,
+    tls: UpstreamTls,
+}
+
+impl HealthChecker {
+    /// Creates a new Checker for the Services in the given List
+    pub fn new(services: ServiceList) -> Self {
+        Self {
+            services,
+            states: HashMap::new(),
+            tls: UpstreamTls::new(),
+        }
+    }
+
+    /// Runs all the Checks that are currently due
+    async fn tick(&mut self) {
+        let now = Instant::now();
+
+        let mut due = Vec::new();
+        for service in self.services.get_all() {
+            let config = match service.health_check() {
+                Some(c) => c.clone(),
+                None => continue,
+            };
+            let tls = if service.protocol().is_tls() {
+                Some(service.tls().cloned())
+            } else {
+                None
+            };
+
+            for (index, address) in service.addresses().iter().enumerate() {
+                let key = (service.name().clone(), address.clone());
+                let is_new = !self.states.contains_key(&key);
+                let state = self
+                    .states
+                    .entry(key.clone())
+                    .or_insert_with(|| AddressState::new(service.is_healthy(index), now));
+
+                if is_new || now.duration_since(state.last_check) >= config.interval() {
+                    state.last_check = now;
+                    due.push((key, config.clone(), tls.clone(), service.proxy_protocol()));
+                }
+            }
+        }
+
+        let upstream_tls = &self.tls;
+        let results = futures::future::join_all(due.into_iter().map(
+            |(key, config, tls, proxy)| async move {
+                let tls = tls.as_ref().map(|t| (upstream_tls, t.as_ref()));
+                let success = check_address(&key.1, &config, tls, proxy).await;
+                (key, config, success)
+            },
+        ))
+        .await;
+
+        for ((name, address), config, success) in results {
+            let state = match self.states.get_mut(&(name.clone(), address.clone())) {
+                Some(s) => s,
+                None => continue,
+            };
+            let previous = state.healthy;
+            let healthy = state.record(success, &config);
+
+            if previous != healthy {
+                if healthy {
+                    tracing::info!("Address '{}' of Service '{}' is healthy", address, name);
+                } else {
+                    tracing::warn!("Address '{}' of Service '{}' is unhealthy", address, name);
+                }
+            }
+
+            // The Service may have been updated while the Checks were
+            // running, so the current Version is loaded again
+            if let Some(service) = self.services.get(&name) {
+                let service = service.get();
+                if let Some(index) = service.addresses().iter().position(|a| a == &address) {
+                    service.set_healthy(index, healthy);
+                }
+            }
+
+            SERVICE_ENDPOINT_HEALTHY
+                .with_label_values(&[&name.to_string(), &address])
+                .set(healthy as i64);
+        }
+
+        self.remove_stale();
+    }
+
+    /// Removes the State for all Addresses that are no longer checked
+    fn remove_stale(&mut self) {
+        let services = self.services.clone();
+        self.states.retain(|(name, address), _| {
+            let keep = match services.get(name) {
+                Some(service) => {
+                    let service = service.get();
+                    service.health_check().is_some() && service.addresses().contains(address)
+                }
+                None => false,
+            };
+
+            if !keep {
+                let _ = SERVICE_ENDPOINT_HEALTHY
+                    .remove_label_values(&[&name.to_string(), address.as_str()]);
+            }
+            keep
+        });
+    }
+
+    /// Runs the Checks forever
+    pub async fn run(mut self) {
+        loop {
+            self.tick().await;
+
+            tokio::time::sleep(TICK_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::Group;
+    use rules::Service;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn success_status() {
+        assert_eq!(true, is_success_status(b"HTTP/1.1 200 OK\r\n"));
+        assert_eq!(true, is_success_status(b"HTTP/1.1 301 Moved"));
+        assert_eq!(false, is_success_status(b"HTTP/1.1 503 Unavailable\r\n"));
+        assert_eq!(false, is_success_status(b"garbage"));
+        assert_eq!(false, is_success_status(b""));
+    }
+
+    #[test]
+    fn record_thresholds() {
+        let config = HealthCheck {
+            healthy_threshold: 2,
+            unhealthy_threshold: 2,
+            ..Default::default()
+        };
+        let mut state = AddressState::new(true, Instant::now());
+
+        assert_eq!(true, state.record(false, &config));
+        assert_eq!(false, state.record(false, &config));
+        assert_eq!(false, state.record(true, &config));
+        assert_eq!(true, state.record(true, &config));
+    }
+
+    #[tokio::test]
+    async fn tcp_check() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        assert_eq!(
+            true,
+            check_address(&address, &HealthCheck::default(), None, None).await
+        );
+
+        drop(listener);
+        assert_eq!(
+            false,
+            check_address(&address, &HealthCheck::default(), None, None).await
+        );
+    }
+
+    #[tokio::test]
+    async fn http_check() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::task::spawn(async move {
+            let (mut con, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 256];
+            let _ = con.read(&mut buffer).await;
+            con.write_all(b"HTTP/1.1 503 Service Unavailable\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let config = HealthCheck {
+            path: Some("/health".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(false, check_address(&address, &config, None, None).await);
+    }
+
+    #[tokio::test]
+    async fn tick_marks_unhealthy() {
+        let services = ServiceList::new();
+        let name = Name::new("test", Group::Internal);
+        let mut service = Service::new(name.clone(), vec!["127.0.0.1:1".to_owned()]);
+        service.set_health_check(HealthCheck {
+            unhealthy_threshold: 1,
+            ..Default::default()
+        });
+        services.set_service(service);
+
+        let mut checker = HealthChecker::new(services.clone());
+        checker.tick().await;
+
+        assert_eq!(false, services.get(&name).unwrap().get().is_healthy(0));
+    }
+}
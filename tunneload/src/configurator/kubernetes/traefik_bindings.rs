@@ -4,14 +4,27 @@
 //! Loads Rules based on Traefik's HTTP-Route CRDs and basically just loads the entire
 //! Configuration just like Traefik would
 //!
+//! # TCP-Rules
+//! Loads TCP-Rules based on Traefik's TCP-Route CRDs, which are routed using the SNI
+//! of the Connections
+//!
+//! # UDP-Rules
+//! Loads UDP-Rules based on Traefik's UDP-Route CRDs
+//!
 //! # Middlewares
 //! Loads Middlewares based on Traefik's Middleware CRDs and mostly just loads them the same
 //! way that Traefik would
 
 /// Ingressroute support for kubernetes traefik
 pub mod ingressroute;
+/// IngressrouteTCP support for kubernetes traefik
+pub mod ingressroutetcp;
+/// IngressrouteUDP support for kubernetes traefik
+pub mod ingressrouteudp;
 /// Middlware support for kubernetes traefik
 pub mod middleware;
+/// ServersTransport support for kubernetes traefik
+pub mod servers_transport;
 
 mod traefik_parser;
 pub use traefik_parser::TraefikParser;
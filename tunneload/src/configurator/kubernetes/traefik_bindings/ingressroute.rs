@@ -2,6 +2,8 @@
 #![allow(clippy::disallowed_methods)]
 #![allow(missing_docs)]
 
+use std::collections::BTreeMap;
+
 use kube_derive::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -66,4 +68,72 @@ pub struct Service {
     /// The Port to which the requests should be
     /// forwarded to
     pub port: Option<u32>,
+    /// The Load-Balancing Strategy, which is one of "RoundRobin",
+    /// "WeightedRoundRobin", "LeastOutstanding", "PowerOfTwoChoices",
+    /// "Random" or "ConsistentHash"
+    pub strategy: Option<String>,
+    /// The Weights of the Addresses, used by "WeightedRoundRobin"
+    pub weights: Option<BTreeMap<String, u32>>,
+    /// The Header that is hashed by "ConsistentHash", if neither this
+    /// nor a Sticky-Cookie is set, the IP of the Client is used
+    #[serde(rename = "hashHeader")]
+    pub hash_header: Option<String>,
+    /// The Sticky-Session configuration
+    pub sticky: Option<Sticky>,
+    /// The Health-Check configuration
+    #[serde(rename = "healthCheck")]
+    pub health_check: Option<ServiceHealthCheck>,
+    /// The Timeouts for the Connections to the Service
+    #[serde(rename = "forwardingTimeouts")]
+    pub forwarding_timeouts: Option<ForwardingTimeouts>,
+    /// The Scheme used to reach the Service, which is one of "http",
+    /// "https" or "h2c"
+    pub scheme: Option<String>,
+    /// The Name of the ServersTransport that configures the TLS for
+    /// the Connections to the Service
+    #[serde(rename = "serversTransport")]
+    pub servers_transport: Option<String>,
+}
+
+/// The Traefik Forwarding-Timeouts for a Service
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct ForwardingTimeouts {
+    /// The Time allowed for establishing a Connection, like "5s"
+    #[serde(rename = "dialTimeout")]
+    pub dial_timeout: Option<String>,
+    /// The Time allowed for receiving the Response Headers, like "30s"
+    #[serde(rename = "responseHeaderTimeout")]
+    pub response_header_timeout: Option<String>,
+}
+
+/// The Traefik Health-Check configuration for a Service
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct ServiceHealthCheck {
+    /// The Path to request, if not set only a TCP-Connection
+    /// is established
+    pub path: Option<String>,
+    /// The Interval between Checks, like "10s" or "500ms"
+    pub interval: Option<String>,
+    /// The Timeout for a single Check, like "3s"
+    pub timeout: Option<String>,
+    /// The Number of successful Checks needed to be healthy again
+    #[serde(rename = "healthyThreshold")]
+    pub healthy_threshold: Option<u32>,
+    /// The Number of failed Checks needed to be unhealthy
+    #[serde(rename = "unhealthyThreshold")]
+    pub unhealthy_threshold: Option<u32>,
+}
+
+/// The Traefik Sticky-Session configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Sticky {
+    /// The Cookie that identifies a Session
+    pub cookie: Option<StickyCookie>,
+}
+
+/// The Cookie used for Sticky-Sessions
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct StickyCookie {
+    /// The Name of the Cookie
+    pub name: Option<String>,
 }
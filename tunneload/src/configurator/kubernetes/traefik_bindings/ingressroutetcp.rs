@@ -0,0 +1,71 @@
+// These are only allowed here because the Macros otherwise cause warnings that can not be fixed
+#![allow(clippy::disallowed_methods)]
+#![allow(missing_docs)]
+
+use kube_derive::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The actual Spec
+#[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[kube(
+    group = "traefik.containo.us",
+    version = "v1alpha1",
+    kind = "IngressRouteTCP",
+    plural = "ingressroutetcps",
+    namespaced
+)]
+pub struct Spec {
+    /// All the Entrypoints that should lead to this Route
+    #[serde(rename = "entryPoints")]
+    pub entry_points: Option<Vec<String>>,
+    /// All the Routes assosicated with the given Rule
+    pub routes: Vec<Route>,
+    /// The TLS-Config for the Routes
+    pub tls: Option<Tls>,
+}
+
+/// The Traefik TLS configuration for TCP-Routes
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Tls {
+    /// Whether or not the TLS-Connection should be forwarded to the
+    /// Service as is, instead of being terminated
+    #[serde(default)]
+    pub passthrough: bool,
+    /// The Name of the Kubernetes Secret for the TLS-Certs
+    #[serde(rename = "secretName")]
+    pub secret_name: Option<String>,
+}
+
+/// The actual Traefik TCP-Route
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Route {
+    #[serde(rename = "match")]
+    /// The Rules used to determine if a Connection matches
+    /// this Route
+    pub rule: String,
+    /// The Priority of the Route
+    pub priority: Option<u32>,
+    /// The Target service of this Route
+    pub services: Vec<Service>,
+}
+
+/// The Traefik target service configuration for TCP-Routes
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Service {
+    /// The name of the Service
+    pub name: String,
+    /// The Port to which the Connections should be
+    /// forwarded to
+    pub port: Option<u32>,
+    /// The PROXY-Protocol configuration
+    #[serde(rename = "proxyProtocol")]
+    pub proxy_protocol: Option<ProxyProtocol>,
+}
+
+/// The Traefik PROXY-Protocol configuration for a TCP-Service
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct ProxyProtocol {
+    /// The Version of the Protocol, either 1 or 2
+    pub version: Option<u8>,
+}
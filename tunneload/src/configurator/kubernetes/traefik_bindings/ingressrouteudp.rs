@@ -0,0 +1,41 @@
+// These are only allowed here because the Macros otherwise cause warnings that can not be fixed
+#![allow(clippy::disallowed_methods)]
+#![allow(missing_docs)]
+
+use kube_derive::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The actual Spec
+#[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[kube(
+    group = "traefik.containo.us",
+    version = "v1alpha1",
+    kind = "IngressRouteUDP",
+    plural = "ingressrouteudps",
+    namespaced
+)]
+pub struct Spec {
+    /// All the Entrypoints that should lead to this Route
+    #[serde(rename = "entryPoints")]
+    pub entry_points: Option<Vec<String>>,
+    /// All the Routes assosicated with the given Rule
+    pub routes: Vec<Route>,
+}
+
+/// The actual Traefik UDP-Route
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Route {
+    /// The Target service of this Route
+    pub services: Vec<Service>,
+}
+
+/// The Traefik target service configuration for UDP-Routes
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct Service {
+    /// The name of the Service
+    pub name: String,
+    /// The Port to which the Datagrams should be
+    /// forwarded to
+    pub port: Option<u32>,
+}
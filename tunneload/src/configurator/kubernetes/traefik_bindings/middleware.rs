@@ -21,6 +21,18 @@ pub struct MiddlewareSpec {
     /// The Strip-Prefix config options
     #[serde(rename = "stripPrefix", skip_serializing_if = "Option::is_none")]
     pub strip_prefix: Option<StripPrefix>,
+    /// The Strip-Prefix-Regex config options
+    #[serde(rename = "stripPrefixRegex", skip_serializing_if = "Option::is_none")]
+    pub strip_prefix_regex: Option<StripPrefixRegex>,
+    /// The Add-Prefix config options
+    #[serde(rename = "addPrefix", skip_serializing_if = "Option::is_none")]
+    pub add_prefix: Option<AddPrefix>,
+    /// The Replace-Path config options
+    #[serde(rename = "replacePath", skip_serializing_if = "Option::is_none")]
+    pub replace_path: Option<ReplacePath>,
+    /// The Replace-Path-Regex config options
+    #[serde(rename = "replacePathRegex", skip_serializing_if = "Option::is_none")]
+    pub replace_path_regex: Option<ReplacePathRegex>,
     /// The Headers/CORS config options
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<BTreeMap<String, Vec<String>>>,
@@ -30,6 +42,24 @@ pub struct MiddlewareSpec {
     /// The Basic-Auth config options
     #[serde(rename = "basicAuth", skip_serializing_if = "Option::is_none")]
     pub basic_auth: Option<BasicAuth>,
+    /// The Forward-Auth config options
+    #[serde(rename = "forwardAuth", skip_serializing_if = "Option::is_none")]
+    pub forward_auth: Option<ForwardAuth>,
+    /// The Rate-Limit config options
+    #[serde(rename = "rateLimit", skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+    /// The IP-Allow-List config options, under their old Name
+    #[serde(rename = "ipWhiteList", skip_serializing_if = "Option::is_none")]
+    pub ip_white_list: Option<IpAllowList>,
+    /// The IP-Allow-List config options
+    #[serde(rename = "ipAllowList", skip_serializing_if = "Option::is_none")]
+    pub ip_allow_list: Option<IpAllowList>,
+    /// The Redirect-Scheme config options
+    #[serde(rename = "redirectScheme", skip_serializing_if = "Option::is_none")]
+    pub redirect_scheme: Option<RedirectScheme>,
+    /// The Redirect-Regex config options
+    #[serde(rename = "redirectRegex", skip_serializing_if = "Option::is_none")]
+    pub redirect_regex: Option<RedirectRegex>,
 }
 
 /// The Strip-Prefix Configuration
@@ -39,6 +69,36 @@ pub struct StripPrefix {
     pub prefixes: Vec<String>,
 }
 
+/// The Strip-Prefix-Regex Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct StripPrefixRegex {
+    /// The Regexes matching the Prefixes to remove from the Requests
+    pub regex: Vec<String>,
+}
+
+/// The Add-Prefix Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct AddPrefix {
+    /// The Prefix to add to the Requests
+    pub prefix: String,
+}
+
+/// The Replace-Path Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct ReplacePath {
+    /// The new Path of the Requests
+    pub path: String,
+}
+
+/// The Replace-Path-Regex Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct ReplacePathRegex {
+    /// The Regex the Path of the Requests is matched against
+    pub regex: String,
+    /// The new Path of the Requests
+    pub replacement: String,
+}
+
 /// The Compress Configuration
 #[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
 pub struct Compress {}
@@ -48,3 +108,84 @@ pub struct Compress {}
 pub struct BasicAuth {
     secret: String,
 }
+
+/// The Forward-Auth Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct ForwardAuth {
+    /// The Address of the Auth-Service
+    pub address: String,
+    /// The Headers forwarded to the Auth-Service
+    #[serde(rename = "authRequestHeaders", default)]
+    pub auth_request_headers: Vec<String>,
+    /// The Headers copied from the Auth-Service onto the Requests
+    #[serde(rename = "authResponseHeaders", default)]
+    pub auth_response_headers: Vec<String>,
+}
+
+/// The Rate-Limit Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct RateLimit {
+    /// The average Number of Requests allowed per Period
+    pub average: Option<u64>,
+    /// The Period, either in Seconds or as a Duration like "1m"
+    pub period: Option<serde_json::Value>,
+    /// The Number of Requests allowed at once
+    pub burst: Option<u64>,
+    /// What the Requests are grouped by
+    #[serde(rename = "sourceCriterion")]
+    pub source_criterion: Option<SourceCriterion>,
+}
+
+/// Determines what the Requests of a Rate-Limit are grouped by
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct SourceCriterion {
+    /// Groups the Requests by the Value of this Header
+    #[serde(rename = "requestHeaderName")]
+    pub request_header_name: Option<String>,
+    /// Groups the Requests by their Host
+    #[serde(rename = "requestHost", default)]
+    pub request_host: bool,
+}
+
+/// The IP-Allow-List Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct IpAllowList {
+    /// The allowed IPs or CIDRs
+    #[serde(rename = "sourceRange")]
+    pub source_range: Vec<String>,
+    /// Determines which IP of the Request is checked
+    #[serde(rename = "ipStrategy")]
+    pub ip_strategy: Option<IpStrategy>,
+}
+
+/// Determines which IP of a Request is used
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct IpStrategy {
+    /// Uses the IP at this Depth in the X-Forwarded-For Header, counted
+    /// from the right
+    pub depth: Option<usize>,
+}
+
+/// The Redirect-Scheme Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct RedirectScheme {
+    /// The Scheme to redirect to
+    pub scheme: String,
+    /// The Port to redirect to
+    pub port: Option<String>,
+    /// Whether the Redirect is permanent
+    #[serde(default)]
+    pub permanent: bool,
+}
+
+/// The Redirect-Regex Configuration
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct RedirectRegex {
+    /// The Regex the URL of the Requests is matched against
+    pub regex: String,
+    /// The URL to redirect to
+    pub replacement: String,
+    /// Whether the Redirect is permanent
+    #[serde(default)]
+    pub permanent: bool,
+}
@@ -0,0 +1,32 @@
+// These are only allowed here because the Macros otherwise cause warnings that can not be fixed
+#![allow(clippy::disallowed_methods)]
+#![allow(missing_docs)]
+
+use kube_derive::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The Spec for Traefik based ServersTransport ressources, which configure
+/// the Connections to Services
+#[derive(CustomResource, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[kube(
+    group = "traefik.containo.us",
+    version = "v1alpha1",
+    kind = "ServersTransport",
+    plural = "serverstransports",
+    namespaced
+)]
+pub struct ServersTransportSpec {
+    /// The Name used for SNI and the Verification of the Certificates
+    #[serde(rename = "serverName")]
+    pub server_name: Option<String>,
+    /// Disables the Verification of the Certificates of the Services
+    #[serde(rename = "insecureSkipVerify")]
+    pub insecure_skip_verify: Option<bool>,
+    /// The Names of the Secrets containing the trusted CAs
+    #[serde(rename = "rootCAsSecrets")]
+    pub root_cas_secrets: Option<Vec<String>>,
+    /// The Names of the Secrets containing the Client-Certificates
+    #[serde(rename = "certificatesSecrets")]
+    pub certificates_secrets: Option<Vec<String>>,
+}
@@ -5,7 +5,10 @@ use kube::{api::ResourceExt, Api};
 
 use crate::{
     configurator::{
-        kubernetes::traefik_bindings::{ingressroute::IngressRoute, middleware::Middleware},
+        kubernetes::traefik_bindings::{
+            ingressroute::IngressRoute, ingressroutetcp::IngressRouteTCP,
+            ingressrouteudp::IngressRouteUDP, middleware::Middleware,
+        },
         parser::{self, EventEmitter, EventFuture, RawMiddlewareConfig, RawRuleConfig},
     },
     util::kubernetes::watcher::{Event, Watcher},
@@ -104,12 +107,20 @@ impl TraefikEvents {
         }
     }
 
-    async fn rule_events(
+    async fn rule_events<K>(
         client: kube::Client,
         namespace: String,
         sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
-    ) {
-        let api: Api<IngressRoute> = Api::namespaced(client, &namespace);
+    ) where
+        K: kube::Resource<DynamicType = ()>
+            + Clone
+            + std::fmt::Debug
+            + serde::de::DeserializeOwned
+            + serde::Serialize
+            + Send
+            + 'static,
+    {
+        let api: Api<K> = Api::namespaced(client, &namespace);
 
         let mut watcher = match Watcher::from_api(api, None).await {
             Ok(w) => w,
@@ -172,6 +183,37 @@ impl EventEmitter for TraefikEvents {
         &self,
         sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
     ) -> Option<EventFuture> {
-        Some(Self::rule_events(self.client.clone(), self.namespace.clone(), sender).boxed())
+        Some(
+            Self::rule_events::<IngressRoute>(self.client.clone(), self.namespace.clone(), sender)
+                .boxed(),
+        )
+    }
+
+    async fn tcp_rule_listener(
+        &self,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+    ) -> Option<EventFuture> {
+        Some(
+            Self::rule_events::<IngressRouteTCP>(
+                self.client.clone(),
+                self.namespace.clone(),
+                sender,
+            )
+            .boxed(),
+        )
+    }
+
+    async fn udp_rule_listener(
+        &self,
+        sender: tokio::sync::mpsc::UnboundedSender<parser::Event<RawRuleConfig, Name>>,
+    ) -> Option<EventFuture> {
+        Some(
+            Self::rule_events::<IngressRouteUDP>(
+                self.client.clone(),
+                self.namespace.clone(),
+                sender,
+            )
+            .boxed(),
+        )
     }
 }
@@ -91,4 +91,56 @@ impl Loader for TraefikLoader {
 
         result
     }
+
+    async fn tcp_rules(&self) -> Vec<RawRuleConfig> {
+        let mut result = Vec::new();
+
+        let ingressroutes: Api<traefik_bindings::ingressroutetcp::IngressRouteTCP> =
+            Api::namespaced(self.client.clone(), &self.namespace);
+        let lp = ListParams::default();
+
+        let route_list = match ingressroutes.list(&lp).await {
+            Ok(l) => l,
+            Err(e) => {
+                tracing::error!("Listing Ingress-Routes-TCP: {:?}", e);
+                return Vec::new();
+            }
+        };
+
+        for route in route_list {
+            let spec_value = match serde_json::to_value(route) {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            result.push(RawRuleConfig { config: spec_value });
+        }
+
+        result
+    }
+
+    async fn udp_rules(&self) -> Vec<RawRuleConfig> {
+        let mut result = Vec::new();
+
+        let ingressroutes: Api<traefik_bindings::ingressrouteudp::IngressRouteUDP> =
+            Api::namespaced(self.client.clone(), &self.namespace);
+        let lp = ListParams::default();
+
+        let route_list = match ingressroutes.list(&lp).await {
+            Ok(l) => l,
+            Err(e) => {
+                tracing::error!("Listing Ingress-Routes-UDP: {:?}", e);
+                return Vec::new();
+            }
+        };
+
+        for route in route_list {
+            let spec_value = match serde_json::to_value(route) {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            result.push(RawRuleConfig { config: spec_value });
+        }
+
+        result
+    }
 }
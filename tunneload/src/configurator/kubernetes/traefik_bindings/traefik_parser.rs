@@ -7,15 +7,25 @@ use crate::configurator::{
     MiddlewareList,
 };
 use rules::{
-    parser::{parse_matchers, ParseMatcherError},
-    Action, Middleware, Rule, RuleTLS,
+    parser::{parse_matchers, parse_tcp_matchers, ParseMatcherError},
+    Action, Middleware, ProxyProtocol, Rule, RuleTLS, TcpRule, TcpRuleTLS, UdpRule,
 };
 
 use general::{Group, Name, Shared};
 
-use super::ingressroute::{self, IngressRoute};
+use super::{
+    ingressroute::{self, IngressRoute},
+    ingressroutetcp::IngressRouteTCP,
+    ingressrouteudp::IngressRouteUDP,
+};
 
 mod action;
+mod duration;
+mod health_check;
+mod protocol;
+mod servers_transport;
+mod strategy;
+mod timeouts;
 
 /// This is the Parser for all the Traefik related Parts
 #[derive(Clone, Default)]
@@ -52,7 +62,13 @@ impl TraefikParser {
 pub enum ActionParseError {
     InvalidConfig,
     InvalidStripPrefix(action::StripPrefixError),
+    InvalidPathRegex(action::PathRegexError),
     InvalidBasicAuth(action::BasicAuthError),
+    InvalidRateLimit(action::RateLimitError),
+    InvalidForwardAuth(action::ForwardAuthError),
+    InvalidIpAllowList(action::IpAllowListError),
+    InvalidRedirectScheme(action::RedirectSchemeError),
+    InvalidRedirectRegex(action::RedirectRegexError),
     UnknownAction(String),
 }
 
@@ -89,9 +105,28 @@ impl Parser for TraefikParser {
         match name {
             "stripPrefix" => action::strip_prefix(config)
                 .map_err(|e| Box::new(ActionParseError::InvalidStripPrefix(e)) as Box<dyn Error>),
+            "stripPrefixRegex" => action::strip_prefix_regex(config)
+                .map_err(|e| Box::new(ActionParseError::InvalidPathRegex(e)) as Box<dyn Error>),
+            "addPrefix" => action::add_prefix(config)
+                .ok_or_else(|| Box::new(ActionParseError::InvalidConfig) as Box<dyn Error>),
+            "replacePath" => action::replace_path(config)
+                .ok_or_else(|| Box::new(ActionParseError::InvalidConfig) as Box<dyn Error>),
+            "replacePathRegex" => action::replace_path_regex(config)
+                .map_err(|e| Box::new(ActionParseError::InvalidPathRegex(e)) as Box<dyn Error>),
             "headers" => action::headers(config)
                 .ok_or_else(|| Box::new(ActionParseError::InvalidConfig) as Box<dyn Error>),
             "compress" => Ok(Action::Compress),
+            "ipWhiteList" | "ipAllowList" => action::ip_allow_list(config)
+                .map_err(|e| Box::new(ActionParseError::InvalidIpAllowList(e)) as Box<dyn Error>),
+            "redirectScheme" => action::redirect_scheme(config).map_err(|e| {
+                Box::new(ActionParseError::InvalidRedirectScheme(e)) as Box<dyn Error>
+            }),
+            "redirectRegex" => action::redirect_regex(config)
+                .map_err(|e| Box::new(ActionParseError::InvalidRedirectRegex(e)) as Box<dyn Error>),
+            "forwardAuth" => action::forward_auth(config)
+                .map_err(|e| Box::new(ActionParseError::InvalidForwardAuth(e)) as Box<dyn Error>),
+            "rateLimit" => action::rate_limit(config)
+                .map_err(|e| Box::new(ActionParseError::InvalidRateLimit(e)) as Box<dyn Error>),
             "basicAuth" => action::basic_auth(
                 config,
                 self.client
@@ -146,6 +181,63 @@ impl Parser for TraefikParser {
             namespace: namespace.clone(),
         });
 
+        match strategy::service_strategy(route_service) {
+            Ok(Some(strategy)) => context
+                .services
+                .set_strategy(service_name.clone(), strategy),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Parsing Strategy for Service({}): {:?}", service_name, e);
+            }
+        };
+        match health_check::service_health_check(route_service) {
+            Ok(Some(health_check)) => context
+                .services
+                .set_health_check(service_name.clone(), health_check),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!(
+                    "Parsing Health-Check for Service({}): {:?}",
+                    service_name,
+                    e
+                );
+            }
+        };
+        match timeouts::service_timeouts(route_service) {
+            Ok(Some(timeouts)) => context
+                .services
+                .set_timeouts(service_name.clone(), timeouts),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Parsing Timeouts for Service({}): {:?}", service_name, e);
+            }
+        };
+
+        match protocol::service_protocol(route_service) {
+            Ok(Some(protocol)) => context
+                .services
+                .set_protocol(service_name.clone(), protocol),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Parsing Protocol for Service({}): {:?}", service_name, e);
+            }
+        };
+        if let (Some(transport), Some(client)) = (
+            route_service.servers_transport.as_ref(),
+            self.client.clone(),
+        ) {
+            match servers_transport::load_service_tls(client, &namespace, transport).await {
+                Ok(tls) => context.services.set_tls(service_name.clone(), tls),
+                Err(e) => {
+                    tracing::error!(
+                        "Loading ServersTransport for Service({}): {}",
+                        service_name,
+                        e
+                    );
+                }
+            };
+        }
+
         let service = context.services.get_with_default(service_name);
 
         let rule_name = Name::new(name, Group::Kubernetes { namespace });
@@ -157,6 +249,7 @@ impl Parser for TraefikParser {
             rule_middleware,
             service,
         );
+        rule.set_entry_points(ingress.spec.entry_points.clone().unwrap_or_default());
 
         // If the Route has a TLS-Secret set, use that one and exit early
         if let Some(tls) = ingress.spec.tls {
@@ -175,6 +268,17 @@ impl Parser for TraefikParser {
                     return Ok(rule);
                 }
             };
+
+            // Wildcard-Certificates can only be obtained using the DNS-Challenge,
+            // which is not supported by the Auto-TLS
+            if domain.starts_with("*.") {
+                tracing::warn!(
+                    "Skipping Certificate for Wildcard-Domain {:?}, because only HTTP-Challenges are supported",
+                    domain
+                );
+                return Ok(rule);
+            }
+
             tx.request(domain.clone());
 
             rule.set_tls(RuleTLS::Generate(domain));
@@ -183,14 +287,120 @@ impl Parser for TraefikParser {
 
         Ok(rule)
     }
+
+    async fn tcp_rule<'a>(
+        &self,
+        raw_config: &serde_json::Value,
+        context: ParseRuleContext<'a>,
+    ) -> Result<TcpRule, Box<dyn Error>> {
+        let ingress: IngressRouteTCP = serde_json::from_value(raw_config.to_owned())
+            .map_err(|e| Box::new(RuleParseError::InvalidConfig(e)))?;
+        let name = ingress
+            .metadata
+            .name
+            .ok_or_else(|| Box::new(RuleParseError::MissingName))?;
+        let namespace = ingress
+            .metadata
+            .namespace
+            .unwrap_or_else(|| "default".to_owned());
+
+        let route = ingress
+            .spec
+            .routes
+            .first()
+            .ok_or_else(|| Box::new(RuleParseError::MissingRoute))?;
+        let priority = route.priority.unwrap_or(1);
+
+        let matcher = parse_tcp_matchers(&route.rule)
+            .map_err(|e| Box::new(RuleParseError::MissingMatcher(e)))?;
+
+        let route_service = route
+            .services
+            .first()
+            .ok_or_else(|| Box::new(RuleParseError::MissingService))?;
+
+        let service_name = Name::parse(&route_service.name, || Group::Kubernetes {
+            namespace: namespace.clone(),
+        });
+
+        if let Some(raw) = route_service.proxy_protocol.as_ref() {
+            match ProxyProtocol::from_version(raw.version.unwrap_or(2)) {
+                Some(version) => context
+                    .services
+                    .set_proxy_protocol(service_name.clone(), version),
+                None => {
+                    tracing::error!(
+                        "Unknown PROXY-Protocol Version for Service({}): {:?}",
+                        service_name,
+                        raw.version
+                    );
+                }
+            };
+        }
+
+        let service = context.services.get_with_default(service_name);
+
+        let rule_name = Name::new(name, Group::Kubernetes { namespace });
+        let mut rule = TcpRule::new(rule_name, priority, matcher, service);
+        rule.set_entry_points(ingress.spec.entry_points.clone().unwrap_or_default());
+
+        // The Certificates for terminated Connections are looked up by
+        // their SNI, so the Secret only needs to be loaded as usual
+        if let Some(tls) = ingress.spec.tls {
+            if tls.passthrough {
+                rule.set_tls(TcpRuleTLS::Passthrough);
+            } else {
+                rule.set_tls(TcpRuleTLS::Terminate);
+            }
+        }
+
+        Ok(rule)
+    }
+
+    async fn udp_rule<'a>(
+        &self,
+        raw_config: &serde_json::Value,
+        context: ParseRuleContext<'a>,
+    ) -> Result<UdpRule, Box<dyn Error>> {
+        let ingress: IngressRouteUDP = serde_json::from_value(raw_config.to_owned())
+            .map_err(|e| Box::new(RuleParseError::InvalidConfig(e)))?;
+        let name = ingress
+            .metadata
+            .name
+            .ok_or_else(|| Box::new(RuleParseError::MissingName))?;
+        let namespace = ingress
+            .metadata
+            .namespace
+            .unwrap_or_else(|| "default".to_owned());
+
+        let route_service = ingress
+            .spec
+            .routes
+            .first()
+            .ok_or_else(|| Box::new(RuleParseError::MissingRoute))?
+            .services
+            .first()
+            .ok_or_else(|| Box::new(RuleParseError::MissingService))?;
+
+        let service_name = Name::parse(&route_service.name, || Group::Kubernetes {
+            namespace: namespace.clone(),
+        });
+        let service = context.services.get_with_default(service_name);
+
+        let rule_name = Name::new(name, Group::Kubernetes { namespace });
+        let mut rule = UdpRule::new(rule_name, 1, service);
+        rule.set_entry_points(ingress.spec.entry_points.clone().unwrap_or_default());
+
+        Ok(rule)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use serde_json::json;
 
-    use crate::configurator::ServiceList;
-    use rules::{Matcher, Service};
+    use crate::{configurator::ServiceList, tls::auto::CertificateQueue};
+    use rules::{Matcher, Service, TcpMatcher};
 
     use super::*;
 
@@ -208,7 +418,7 @@ mod tests {
                 }),
             )
             .await;
-        let expected = Action::RemovePrefix("/api".to_owned());
+        let expected = Action::RemovePrefix(vec!["/api".to_owned()]);
 
         assert_eq!(true, result.is_ok());
         assert_eq!(expected, result.unwrap());
@@ -424,4 +634,218 @@ mod tests {
         assert_eq!(true, result.is_ok());
         assert_eq!(expected_rule, result.unwrap());
     }
+
+    #[tokio::test]
+    async fn parse_rule_wildcard_domain_skips_certificate() {
+        let ingress = json!({
+            "apiVersion": "",
+            "kind": "IngressRoute",
+            "metadata": {
+                "name": "test-route",
+                "namespace": "default",
+            },
+            "spec":  {
+                "entryPoints": [],
+                "routes": [ {
+                    "kind": "IngressRoute",
+                    "middlewares": [],
+                    "match": "Host(`*.lol3r.net`)",
+                    "services": [ {
+                        "name": "personal",
+                        "port": 8080,
+                    }],
+                }],
+            },
+        });
+
+        let (queue, mut queue_rx) = CertificateQueue::new();
+        let context = ParseRuleContext {
+            services: &ServiceList::new(),
+            middlewares: &MiddlewareList::new(),
+            cert_queue: Some(queue),
+        };
+
+        let parser = TraefikParser::new(None, None);
+
+        let result = parser.rule(&ingress, context).await;
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(&RuleTLS::None, result.unwrap().tls());
+        assert_eq!(true, queue_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn parse_rule_sets_service_strategy() {
+        let ingress = json!({
+            "apiVersion": "",
+            "kind": "IngressRoute",
+            "metadata": {
+                "name": "test-route",
+                "namespace": "default",
+            },
+            "spec":  {
+                "entryPoints": [],
+                "routes": [ {
+                    "kind": "IngressRoute",
+                    "middlewares": [],
+                    "match": "Host(`lol3r.net`)",
+                    "services": [ {
+                        "name": "personal",
+                        "port": 8080,
+                        "strategy": "PowerOfTwoChoices",
+                    }],
+                }],
+            },
+        });
+
+        let services = ServiceList::new();
+        let context = ParseRuleContext {
+            services: &services,
+            middlewares: &MiddlewareList::new(),
+            cert_queue: None,
+        };
+
+        let parser = TraefikParser::new(None, None);
+
+        let result = parser.rule(&ingress, context).await;
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(
+            Some(&rules::Strategy::PowerOfTwoChoices),
+            result.unwrap().service().strategy()
+        );
+    }
+
+    #[tokio::test]
+    async fn parse_tcp_rule_passthrough() {
+        let ingress = json!({
+            "apiVersion": "",
+            "kind": "IngressRouteTCP",
+            "metadata": {
+                "name": "test-route",
+                "namespace": "default",
+            },
+            "spec":  {
+                "entryPoints": [],
+                "routes": [ {
+                    "match": "HostSNI(`db.example.com`)",
+                    "priority": 2,
+                    "services": [ {
+                        "name": "database",
+                        "port": 5432,
+                        "proxyProtocol": {
+                            "version": 1,
+                        },
+                    }],
+                }],
+                "tls": {
+                    "passthrough": true,
+                },
+            },
+        });
+
+        let services = ServiceList::new();
+        let context = ParseRuleContext {
+            services: &services,
+            middlewares: &MiddlewareList::new(),
+            cert_queue: None,
+        };
+
+        let parser = TraefikParser::new(None, None);
+        let result = parser.tcp_rule(&ingress, context).await.unwrap();
+
+        assert_eq!(2, result.priority());
+        assert_eq!(
+            &TcpMatcher::HostSNI("db.example.com".to_owned()),
+            result.matcher()
+        );
+        assert_eq!(&TcpRuleTLS::Passthrough, result.tls());
+        assert_eq!(Some(ProxyProtocol::V1), result.service().proxy_protocol());
+    }
+
+    #[tokio::test]
+    async fn parse_udp_rule() {
+        let ingress = json!({
+            "apiVersion": "",
+            "kind": "IngressRouteUDP",
+            "metadata": {
+                "name": "test-route",
+                "namespace": "default",
+            },
+            "spec":  {
+                "entryPoints": [],
+                "routes": [ {
+                    "services": [ {
+                        "name": "dns",
+                        "port": 53,
+                    }],
+                }],
+            },
+        });
+
+        let context = ParseRuleContext {
+            services: &ServiceList::new(),
+            middlewares: &MiddlewareList::new(),
+            cert_queue: None,
+        };
+
+        let parser = TraefikParser::new(None, None);
+        let result = parser.udp_rule(&ingress, context).await;
+
+        let expected = UdpRule::new(
+            Name::new(
+                "test-route",
+                Group::Kubernetes {
+                    namespace: "default".to_owned(),
+                },
+            ),
+            1,
+            Shared::new(Service::new(
+                Name::new(
+                    "dns",
+                    Group::Kubernetes {
+                        namespace: "default".to_owned(),
+                    },
+                ),
+                vec![],
+            )),
+        );
+
+        assert_eq!(true, result.is_ok());
+        assert_eq!(expected, result.unwrap());
+    }
+
+    #[tokio::test]
+    async fn parse_udp_rule_entry_points() {
+        let ingress = json!({
+            "apiVersion": "",
+            "kind": "IngressRouteUDP",
+            "metadata": {
+                "name": "test-route",
+                "namespace": "default",
+            },
+            "spec":  {
+                "entryPoints": ["dns"],
+                "routes": [ {
+                    "services": [ {
+                        "name": "dns",
+                        "port": 53,
+                    }],
+                }],
+            },
+        });
+
+        let context = ParseRuleContext {
+            services: &ServiceList::new(),
+            middlewares: &MiddlewareList::new(),
+            cert_queue: None,
+        };
+
+        let parser = TraefikParser::new(None, None);
+        let result = parser.udp_rule(&ingress, context).await.unwrap();
+
+        assert_eq!(&["dns".to_owned()], result.entry_points().names());
+        assert_eq!(true, result.matches(Some("dns")));
+        assert_eq!(false, result.matches(Some("other")));
+    }
 }
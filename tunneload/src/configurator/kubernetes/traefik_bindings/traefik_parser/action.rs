@@ -1,8 +1,15 @@
+use std::time::Duration;
+
 use crate::{
     configurator::kubernetes::traefik_bindings::middleware,
     util::kubernetes::secret::{load_secret, LoadSecretError},
 };
-use rules::{Action, CorsOpts};
+use rules::{
+    Action, AuthAddressError, CorsOpts, ForwardAuth, IpListOpts, Pattern, RateLimit, RateLimitKey,
+    RedirectRegexOpts, RedirectSchemeOpts, RedirectStatus, ReplacePathRegexOpts,
+};
+
+use super::duration::{parse_duration, InvalidDuration};
 
 #[derive(Debug, PartialEq)]
 pub enum StripPrefixError {
@@ -19,16 +26,77 @@ pub fn strip_prefix(value: &serde_json::Value) -> Result<Action, StripPrefixErro
         )
     })?;
 
-    let mut prefix: &str = match parsed.prefixes.get(0) {
-        Some(r) => r.as_ref(),
-        None => return Err(StripPrefixError::MissingPrefix),
-    };
+    if parsed.prefixes.is_empty() {
+        return Err(StripPrefixError::MissingPrefix);
+    }
+
+    let prefixes = parsed
+        .prefixes
+        .iter()
+        .map(|prefix| prefix.strip_suffix('/').unwrap_or(prefix).to_owned())
+        .collect();
 
-    if prefix.ends_with('/') {
-        prefix = &prefix[..prefix.len() - 1];
+    Ok(Action::RemovePrefix(prefixes))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PathRegexError {
+    InvalidConfig(String),
+    InvalidRegex(String),
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// Strip-Prefix-Regex Action
+pub fn strip_prefix_regex(value: &serde_json::Value) -> Result<Action, PathRegexError> {
+    let parsed: middleware::StripPrefixRegex =
+        serde_json::from_value(value.clone()).map_err(|_| {
+            PathRegexError::InvalidConfig(
+                serde_json::to_string(&value).expect("Should be able to serialize"),
+            )
+        })?;
+
+    let mut patterns = Vec::with_capacity(parsed.regex.len());
+    for raw in parsed.regex {
+        match Pattern::new(&raw) {
+            Ok(pattern) => patterns.push(pattern),
+            Err(_) => return Err(PathRegexError::InvalidRegex(raw)),
+        };
     }
 
-    Ok(Action::RemovePrefix(prefix.to_owned()))
+    Ok(Action::StripPrefixRegex(patterns))
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// Add-Prefix Action
+pub fn add_prefix(value: &serde_json::Value) -> Option<Action> {
+    let parsed: middleware::AddPrefix = serde_json::from_value(value.clone()).ok()?;
+    Some(Action::AddPrefix(parsed.prefix))
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// Replace-Path Action
+pub fn replace_path(value: &serde_json::Value) -> Option<Action> {
+    let parsed: middleware::ReplacePath = serde_json::from_value(value.clone()).ok()?;
+    Some(Action::ReplacePath(parsed.path))
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// Replace-Path-Regex Action
+pub fn replace_path_regex(value: &serde_json::Value) -> Result<Action, PathRegexError> {
+    let parsed: middleware::ReplacePathRegex =
+        serde_json::from_value(value.clone()).map_err(|_| {
+            PathRegexError::InvalidConfig(
+                serde_json::to_string(&value).expect("Should be able to serialize"),
+            )
+        })?;
+
+    let regex = Pattern::new(&parsed.regex)
+        .map_err(|_| PathRegexError::InvalidRegex(parsed.regex.clone()))?;
+
+    Ok(Action::ReplacePathRegex(ReplacePathRegexOpts {
+        regex,
+        replacement: parsed.replacement,
+    }))
 }
 
 pub fn headers(value: &serde_json::Value) -> Option<Action> {
@@ -133,6 +201,182 @@ pub async fn basic_auth(
     Ok(Action::new_basic_auth_hashed(users_data))
 }
 
+#[derive(Debug, PartialEq)]
+pub enum ForwardAuthError {
+    InvalidConfig(String),
+    InvalidAddress(AuthAddressError),
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// Forward-Auth Action
+pub fn forward_auth(value: &serde_json::Value) -> Result<Action, ForwardAuthError> {
+    let parsed: middleware::ForwardAuth = serde_json::from_value(value.clone()).map_err(|_| {
+        ForwardAuthError::InvalidConfig(
+            serde_json::to_string(&value).expect("Should be able to serialize"),
+        )
+    })?;
+
+    let auth = ForwardAuth::new(
+        &parsed.address,
+        parsed.auth_request_headers,
+        parsed.auth_response_headers,
+    )
+    .map_err(ForwardAuthError::InvalidAddress)?;
+
+    Ok(Action::ForwardAuth(auth))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum IpAllowListError {
+    InvalidConfig(String),
+    InvalidRange(String),
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// IP-Allow-List Action
+pub fn ip_allow_list(value: &serde_json::Value) -> Result<Action, IpAllowListError> {
+    let parsed: middleware::IpAllowList = serde_json::from_value(value.clone()).map_err(|_| {
+        IpAllowListError::InvalidConfig(
+            serde_json::to_string(&value).expect("Should be able to serialize"),
+        )
+    })?;
+
+    let mut networks = Vec::with_capacity(parsed.source_range.len());
+    for raw in parsed.source_range {
+        match raw.parse() {
+            Ok(network) => networks.push(network),
+            Err(_) => return Err(IpAllowListError::InvalidRange(raw)),
+        };
+    }
+
+    Ok(Action::IpAllowList(IpListOpts {
+        networks,
+        depth: parsed.ip_strategy.and_then(|strategy| strategy.depth),
+    }))
+}
+
+fn redirect_status(permanent: bool) -> RedirectStatus {
+    if permanent {
+        RedirectStatus::MovedPermanently
+    } else {
+        RedirectStatus::Found
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RedirectSchemeError {
+    InvalidConfig(String),
+    InvalidPort(String),
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// Redirect-Scheme Action
+pub fn redirect_scheme(value: &serde_json::Value) -> Result<Action, RedirectSchemeError> {
+    let parsed: middleware::RedirectScheme =
+        serde_json::from_value(value.clone()).map_err(|_| {
+            RedirectSchemeError::InvalidConfig(
+                serde_json::to_string(&value).expect("Should be able to serialize"),
+            )
+        })?;
+
+    let port = match parsed.port {
+        Some(raw) => match raw.parse() {
+            Ok(p) => Some(p),
+            Err(_) => return Err(RedirectSchemeError::InvalidPort(raw)),
+        },
+        None => None,
+    };
+
+    Ok(Action::RedirectScheme(RedirectSchemeOpts {
+        scheme: parsed.scheme,
+        port,
+        status: redirect_status(parsed.permanent),
+    }))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RedirectRegexError {
+    InvalidConfig(String),
+    InvalidRegex(String),
+}
+
+/// Attempts to parse the given Value as the configuration for the
+/// Redirect-Regex Action
+pub fn redirect_regex(value: &serde_json::Value) -> Result<Action, RedirectRegexError> {
+    let parsed: middleware::RedirectRegex =
+        serde_json::from_value(value.clone()).map_err(|_| {
+            RedirectRegexError::InvalidConfig(
+                serde_json::to_string(&value).expect("Should be able to serialize"),
+            )
+        })?;
+
+    let regex = Pattern::new(&parsed.regex)
+        .map_err(|_| RedirectRegexError::InvalidRegex(parsed.regex.clone()))?;
+
+    Ok(Action::RedirectRegex(RedirectRegexOpts {
+        regex,
+        replacement: parsed.replacement,
+        status: redirect_status(parsed.permanent),
+    }))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RateLimitError {
+    InvalidConfig(String),
+    InvalidPeriod(InvalidDuration),
+}
+
+/// Attempts to parse the given Value as the configuration for the Rate-Limit
+/// Action, the Requests are grouped by the Client-IP unless a Header or the
+/// Host is configured as the Source-Criterion
+pub fn rate_limit(value: &serde_json::Value) -> Result<Action, RateLimitError> {
+    let parsed: middleware::RateLimit = serde_json::from_value(value.clone()).map_err(|_| {
+        RateLimitError::InvalidConfig(
+            serde_json::to_string(&value).expect("Should be able to serialize"),
+        )
+    })?;
+
+    let period = match parsed.period {
+        None => Duration::from_secs(1),
+        Some(serde_json::Value::Number(n)) => match n.as_u64() {
+            Some(seconds) => Duration::from_secs(seconds),
+            None => {
+                return Err(RateLimitError::InvalidPeriod(InvalidDuration(
+                    n.to_string(),
+                )))
+            }
+        },
+        Some(serde_json::Value::String(raw)) => {
+            parse_duration(&raw).map_err(RateLimitError::InvalidPeriod)?
+        }
+        Some(other) => {
+            return Err(RateLimitError::InvalidPeriod(InvalidDuration(
+                other.to_string(),
+            )))
+        }
+    };
+
+    let key = match parsed.source_criterion {
+        Some(middleware::SourceCriterion {
+            request_header_name: Some(name),
+            ..
+        }) => RateLimitKey::Header { name },
+        Some(middleware::SourceCriterion {
+            request_host: true, ..
+        }) => RateLimitKey::Header {
+            name: "Host".to_owned(),
+        },
+        _ => RateLimitKey::ClientIP,
+    };
+
+    Ok(Action::RateLimit(RateLimit::new(
+        parsed.average.unwrap_or(0),
+        period,
+        parsed.burst.unwrap_or(1),
+        key,
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,7 +464,7 @@ mod tests {
         });
 
         let result = strip_prefix(&value);
-        assert_eq!(Ok(Action::RemovePrefix("/test".to_owned())), result);
+        assert_eq!(Ok(Action::RemovePrefix(vec!["/test".to_owned()])), result);
     }
 
     #[test]
@@ -232,7 +476,56 @@ mod tests {
         });
 
         let result = strip_prefix(&value);
-        assert_eq!(Ok(Action::RemovePrefix("/test".to_owned())), result);
+        assert_eq!(Ok(Action::RemovePrefix(vec!["/test".to_owned()])), result);
+    }
+
+    #[test]
+    fn multiple_prefixes() {
+        let value = json!({
+            "prefixes": [
+                "/v1/",
+                "/v2",
+            ],
+        });
+
+        let result = strip_prefix(&value);
+        assert_eq!(
+            Ok(Action::RemovePrefix(vec![
+                "/v1".to_owned(),
+                "/v2".to_owned()
+            ])),
+            result
+        );
+    }
+
+    #[test]
+    fn strip_prefix_regex_invalid() {
+        let value = json!({
+            "regex": ["/v[0-9]+", "/users/[a-z"],
+        });
+
+        let result = strip_prefix_regex(&value);
+        assert_eq!(
+            Err(PathRegexError::InvalidRegex("/users/[a-z".to_owned())),
+            result
+        );
+    }
+
+    #[test]
+    fn replace_path_regex_valid() {
+        let value = json!({
+            "regex": "^/foo/(.*)",
+            "replacement": "/bar/${1}",
+        });
+
+        let result = replace_path_regex(&value);
+        assert_eq!(
+            Ok(Action::ReplacePathRegex(ReplacePathRegexOpts {
+                regex: Pattern::new("^/foo/(.*)").unwrap(),
+                replacement: "/bar/${1}".to_owned(),
+            })),
+            result
+        );
     }
 
     #[test]
@@ -261,4 +554,175 @@ mod tests {
         let result = strip_prefix(&value);
         assert_eq!(Err(StripPrefixError::MissingPrefix), result);
     }
+
+    #[test]
+    fn rate_limit_header() {
+        let value = json!({
+            "average": 100,
+            "period": "1m",
+            "burst": 50,
+            "sourceCriterion": {
+                "requestHeaderName": "X-User",
+            },
+        });
+
+        let result = rate_limit(&value);
+        assert_eq!(
+            Ok(Action::RateLimit(RateLimit::new(
+                100,
+                Duration::from_secs(60),
+                50,
+                RateLimitKey::Header {
+                    name: "X-User".to_owned()
+                },
+            ))),
+            result
+        );
+    }
+
+    #[test]
+    fn rate_limit_defaults() {
+        let value = json!({
+            "average": 10,
+        });
+
+        let result = rate_limit(&value);
+        assert_eq!(
+            Ok(Action::RateLimit(RateLimit::new(
+                10,
+                Duration::from_secs(1),
+                1,
+                RateLimitKey::ClientIP,
+            ))),
+            result
+        );
+    }
+
+    #[test]
+    fn ip_allow_list_with_depth() {
+        let value = json!({
+            "sourceRange": ["127.0.0.1/32", "192.168.1.7"],
+            "ipStrategy": {
+                "depth": 2,
+            },
+        });
+
+        let result = ip_allow_list(&value);
+        assert_eq!(
+            Ok(Action::IpAllowList(IpListOpts {
+                networks: vec![
+                    "127.0.0.1/32".parse().unwrap(),
+                    "192.168.1.7".parse().unwrap()
+                ],
+                depth: Some(2),
+            })),
+            result
+        );
+    }
+
+    #[test]
+    fn ip_allow_list_invalid_range() {
+        let value = json!({
+            "sourceRange": ["300.0.0.1/8"],
+        });
+
+        let result = ip_allow_list(&value);
+        assert_eq!(
+            Err(IpAllowListError::InvalidRange("300.0.0.1/8".to_owned())),
+            result
+        );
+    }
+
+    #[test]
+    fn redirect_scheme_permanent() {
+        let value = json!({
+            "scheme": "https",
+            "port": "8443",
+            "permanent": true,
+        });
+
+        let result = redirect_scheme(&value);
+        assert_eq!(
+            Ok(Action::RedirectScheme(RedirectSchemeOpts {
+                scheme: "https".to_owned(),
+                port: Some(8443),
+                status: RedirectStatus::MovedPermanently,
+            })),
+            result
+        );
+    }
+
+    #[test]
+    fn redirect_regex_temporary() {
+        let value = json!({
+            "regex": "^http://localhost/(.*)",
+            "replacement": "http://example.com/${1}",
+        });
+
+        let result = redirect_regex(&value);
+        assert_eq!(
+            Ok(Action::RedirectRegex(RedirectRegexOpts {
+                regex: Pattern::new("^http://localhost/(.*)").unwrap(),
+                replacement: "http://example.com/${1}".to_owned(),
+                status: RedirectStatus::Found,
+            })),
+            result
+        );
+
+        let value = json!({
+            "regex": "(",
+            "replacement": "",
+        });
+        assert_eq!(
+            Err(RedirectRegexError::InvalidRegex("(".to_owned())),
+            redirect_regex(&value)
+        );
+    }
+
+    #[test]
+    fn forward_auth_headers() {
+        let value = json!({
+            "address": "http://auth.default.svc/verify",
+            "authRequestHeaders": ["Authorization"],
+            "authResponseHeaders": ["X-User"],
+        });
+
+        let result = forward_auth(&value);
+        assert_eq!(
+            Ok(Action::ForwardAuth(
+                ForwardAuth::new(
+                    "http://auth.default.svc/verify",
+                    vec!["Authorization".to_owned()],
+                    vec!["X-User".to_owned()],
+                )
+                .unwrap()
+            )),
+            result
+        );
+    }
+
+    #[test]
+    fn forward_auth_invalid_address() {
+        let value = json!({
+            "address": "auth.default.svc",
+        });
+
+        let result = forward_auth(&value);
+        assert_eq!(
+            Err(ForwardAuthError::InvalidAddress(
+                AuthAddressError::UnsupportedScheme("auth.default.svc".to_owned())
+            )),
+            result
+        );
+
+        let value = json!({
+            "address": "https://auth.default.svc",
+        });
+        assert_eq!(
+            Err(ForwardAuthError::InvalidAddress(
+                AuthAddressError::UnsupportedScheme("https://auth.default.svc".to_owned())
+            )),
+            forward_auth(&value)
+        );
+    }
 }
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+/// The given Duration could not be parsed
+#[derive(Debug, PartialEq)]
+pub struct InvalidDuration(pub String);
+
+/// Parses a Duration like "10s", "500ms", "1m" or "1h", a Duration
+/// without a Unit is in Seconds
+pub fn parse_duration(raw: &str) -> Result<Duration, InvalidDuration> {
+    let raw = raw.trim();
+    let split = raw.find(|c: char| !c.is_ascii_digit()).unwrap_or(raw.len());
+    let (value, unit) = raw.split_at(split);
+
+    let value: u64 = value.parse().map_err(|_| InvalidDuration(raw.to_owned()))?;
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "" | "s" => Ok(Duration::from_secs(value)),
+        "m" => Ok(Duration::from_secs(value * 60)),
+        "h" => Ok(Duration::from_secs(value * 60 * 60)),
+        _ => Err(InvalidDuration(raw.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations() {
+        assert_eq!(Ok(Duration::from_millis(500)), parse_duration("500ms"));
+        assert_eq!(Ok(Duration::from_secs(10)), parse_duration("10s"));
+        assert_eq!(Ok(Duration::from_secs(10)), parse_duration("10"));
+        assert_eq!(Ok(Duration::from_secs(120)), parse_duration("2m"));
+        assert_eq!(
+            Err(InvalidDuration("10x".to_owned())),
+            parse_duration("10x")
+        );
+        assert_eq!(Err(InvalidDuration("s".to_owned())), parse_duration("s"));
+    }
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use rules::HealthCheck;
+
+use crate::configurator::kubernetes::traefik_bindings::ingressroute;
+
+use super::duration;
+
+#[derive(Debug, PartialEq)]
+pub enum HealthCheckError {
+    InvalidDuration(String),
+}
+
+fn parse_duration(raw: &str) -> Result<Duration, HealthCheckError> {
+    duration::parse_duration(raw).map_err(|e| HealthCheckError::InvalidDuration(e.0))
+}
+
+/// Attempts to load the Health-Check configured for the given Service,
+/// returns None if no Health-Check was configured
+pub fn service_health_check(
+    service: &ingressroute::Service,
+) -> Result<Option<HealthCheck>, HealthCheckError> {
+    let raw = match service.health_check.as_ref() {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+
+    let mut result = HealthCheck {
+        path: raw.path.clone(),
+        ..Default::default()
+    };
+    if let Some(interval) = raw.interval.as_ref() {
+        result.interval_ms = parse_duration(interval)?.as_millis() as u64;
+    }
+    if let Some(timeout) = raw.timeout.as_ref() {
+        result.timeout_ms = parse_duration(timeout)?.as_millis() as u64;
+    }
+    if let Some(threshold) = raw.healthy_threshold {
+        result.healthy_threshold = threshold;
+    }
+    if let Some(threshold) = raw.unhealthy_threshold {
+        result.unhealthy_threshold = threshold;
+    }
+
+    Ok(Some(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(raw: serde_json::Value) -> ingressroute::Service {
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn no_health_check() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+        }));
+
+        assert_eq!(Ok(None), service_health_check(&tmp));
+    }
+
+    #[test]
+    fn full_health_check() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "healthCheck": {
+                "path": "/health",
+                "interval": "5s",
+                "timeout": "500ms",
+                "healthyThreshold": 1,
+                "unhealthyThreshold": 4,
+            },
+        }));
+
+        assert_eq!(
+            Ok(Some(HealthCheck {
+                path: Some("/health".to_owned()),
+                interval_ms: 5000,
+                timeout_ms: 500,
+                healthy_threshold: 1,
+                unhealthy_threshold: 4,
+            })),
+            service_health_check(&tmp)
+        );
+    }
+
+    #[test]
+    fn invalid_interval() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "healthCheck": {
+                "interval": "often",
+            },
+        }));
+
+        assert_eq!(
+            Err(HealthCheckError::InvalidDuration("often".to_owned())),
+            service_health_check(&tmp)
+        );
+    }
+}
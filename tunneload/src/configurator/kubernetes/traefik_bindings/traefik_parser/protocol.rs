@@ -0,0 +1,67 @@
+use rules::ServiceProtocol;
+
+use crate::configurator::kubernetes::traefik_bindings::ingressroute;
+
+#[derive(Debug, PartialEq)]
+pub enum ProtocolError {
+    UnknownScheme(String),
+}
+
+/// Attempts to load the Protocol configured for the given Service,
+/// using its Scheme, returns None if no Scheme was configured
+pub fn service_protocol(
+    service: &ingressroute::Service,
+) -> Result<Option<ServiceProtocol>, ProtocolError> {
+    let raw = match service.scheme.as_deref() {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+
+    match raw {
+        "http" => Ok(Some(ServiceProtocol::Http1)),
+        "https" => Ok(Some(ServiceProtocol::Https)),
+        "h2c" => Ok(Some(ServiceProtocol::H2c)),
+        other => Err(ProtocolError::UnknownScheme(other.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(raw: serde_json::Value) -> ingressroute::Service {
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn no_scheme() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+        }));
+
+        assert_eq!(Ok(None), service_protocol(&tmp));
+    }
+
+    #[test]
+    fn h2c_scheme() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "scheme": "h2c",
+        }));
+
+        assert_eq!(Ok(Some(ServiceProtocol::H2c)), service_protocol(&tmp));
+    }
+
+    #[test]
+    fn unknown_scheme() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "scheme": "ftp",
+        }));
+
+        assert_eq!(
+            Err(ProtocolError::UnknownScheme("ftp".to_owned())),
+            service_protocol(&tmp)
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use k8s_openapi::ByteString;
+use kube::Api;
+use rules::ServiceTls;
+
+use crate::{
+    configurator::kubernetes::traefik_bindings::servers_transport::{
+        ServersTransport, ServersTransportSpec,
+    },
+    util::kubernetes::secret::{load_secret, LoadSecretError},
+};
+
+/// The Keys in a Secret that may contain the PEM-encoded CAs
+const CA_KEYS: &[&str] = &["tls.ca", "ca.crt"];
+/// The Key in a Secret containing the PEM-encoded Certificate
+const CERT_KEY: &str = "tls.crt";
+/// The Key in a Secret containing the PEM-encoded Private-Key
+const KEY_KEY: &str = "tls.key";
+
+type SecretData = BTreeMap<String, ByteString>;
+
+#[derive(Debug)]
+pub enum ServersTransportError {
+    Loading(kube::Error),
+    LoadingSecret(LoadSecretError),
+    MissingCa(String),
+    MissingCertificate(String),
+    InvalidData(std::string::FromUtf8Error),
+}
+
+impl Display for ServersTransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Loading(e) => write!(f, "Loading ServersTransport: {}", e),
+            Self::LoadingSecret(e) => write!(f, "Loading Secret: {:?}", e),
+            Self::MissingCa(name) => write!(f, "Secret '{}' contains no CA", name),
+            Self::MissingCertificate(name) => {
+                write!(f, "Secret '{}' contains no Certificate and Key", name)
+            }
+            Self::InvalidData(e) => write!(f, "Invalid PEM-Data: {}", e),
+        }
+    }
+}
+
+fn pem(data: &SecretData, key: &str) -> Option<Result<String, ServersTransportError>> {
+    data.get(key)
+        .map(|v| String::from_utf8(v.0.clone()).map_err(ServersTransportError::InvalidData))
+}
+
+/// Creates the TLS-Configuration from the Spec of a ServersTransport and
+/// the Data of the Secrets it references, which are given as pairs of
+/// the Name and the Data of every Secret
+pub fn service_tls(
+    spec: &ServersTransportSpec,
+    root_cas: &[(String, SecretData)],
+    certificate: Option<&(String, SecretData)>,
+) -> Result<ServiceTls, ServersTransportError> {
+    let mut ca = None;
+    for (name, data) in root_cas.iter() {
+        let raw = CA_KEYS
+            .iter()
+            .find_map(|k| pem(data, k))
+            .ok_or_else(|| ServersTransportError::MissingCa(name.clone()))??;
+
+        let cas: &mut String = ca.get_or_insert_with(String::new);
+        cas.push_str(&raw);
+        cas.push('\n');
+    }
+
+    let (client_cert, client_key) = match certificate {
+        Some((name, data)) => {
+            let missing = || ServersTransportError::MissingCertificate(name.clone());
+            let cert = pem(data, CERT_KEY).ok_or_else(missing)??;
+            let key = pem(data, KEY_KEY).ok_or_else(missing)??;
+            (Some(cert), Some(key))
+        }
+        None => (None, None),
+    };
+
+    Ok(ServiceTls {
+        server_name: spec.server_name.clone(),
+        ca,
+        client_cert,
+        client_key,
+        insecure_skip_verify: spec.insecure_skip_verify.unwrap_or(false),
+    })
+}
+
+/// Loads the ServersTransport with the given Name and all the Secrets
+/// it references, to create the TLS-Configuration for a Service.
+///
+/// The ServersTransport is only loaded when the Rule using it is parsed,
+/// so changes to it only apply once the Rule is updated
+pub async fn load_service_tls(
+    client: kube::Client,
+    namespace: &str,
+    name: &str,
+) -> Result<ServiceTls, ServersTransportError> {
+    let api: Api<ServersTransport> = Api::namespaced(client.clone(), namespace);
+    let transport = api
+        .get(name)
+        .await
+        .map_err(ServersTransportError::Loading)?;
+    let spec = transport.spec;
+
+    let mut root_cas = Vec::new();
+    for secret in spec.root_cas_secrets.iter().flatten() {
+        let data = load_secret(client.clone(), namespace, secret)
+            .await
+            .map_err(ServersTransportError::LoadingSecret)?;
+        root_cas.push((secret.clone(), data));
+    }
+
+    // Only a single Client-Certificate can be presented to the Service
+    let certificate = match spec.certificates_secrets.iter().flatten().next() {
+        Some(secret) => {
+            let data = load_secret(client.clone(), namespace, secret)
+                .await
+                .map_err(ServersTransportError::LoadingSecret)?;
+            Some((secret.clone(), data))
+        }
+        None => None,
+    };
+
+    service_tls(&spec, &root_cas, certificate.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(raw: serde_json::Value) -> ServersTransportSpec {
+        serde_json::from_value(raw).unwrap()
+    }
+
+    fn secret(entries: &[(&str, &str)]) -> SecretData {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), ByteString(v.as_bytes().to_vec())))
+            .collect()
+    }
+
+    #[test]
+    fn without_secrets() {
+        let tmp = spec(serde_json::json!({
+            "serverName": "example.com",
+            "insecureSkipVerify": true,
+        }));
+
+        assert_eq!(
+            ServiceTls {
+                server_name: Some("example.com".to_owned()),
+                insecure_skip_verify: true,
+                ..Default::default()
+            },
+            service_tls(&tmp, &[], None).unwrap()
+        );
+    }
+
+    #[test]
+    fn with_secrets() {
+        let tmp = spec(serde_json::json!({}));
+        let root_cas = vec![
+            ("first".to_owned(), secret(&[("tls.ca", "first-ca")])),
+            ("second".to_owned(), secret(&[("ca.crt", "second-ca")])),
+        ];
+        let certificate = (
+            "client".to_owned(),
+            secret(&[("tls.crt", "cert"), ("tls.key", "key")]),
+        );
+
+        let result = service_tls(&tmp, &root_cas, Some(&certificate)).unwrap();
+        assert_eq!(Some("first-ca\nsecond-ca\n".to_owned()), result.ca);
+        assert_eq!(Some("cert".to_owned()), result.client_cert);
+        assert_eq!(Some("key".to_owned()), result.client_key);
+        assert_eq!(false, result.insecure_skip_verify);
+    }
+
+    #[test]
+    fn missing_ca() {
+        let tmp = spec(serde_json::json!({}));
+        let root_cas = vec![("first".to_owned(), secret(&[("other", "data")]))];
+
+        assert_eq!(
+            true,
+            matches!(
+                service_tls(&tmp, &root_cas, None),
+                Err(ServersTransportError::MissingCa(_))
+            )
+        );
+    }
+
+    #[test]
+    fn missing_key() {
+        let tmp = spec(serde_json::json!({}));
+        let certificate = ("client".to_owned(), secret(&[("tls.crt", "cert")]));
+
+        assert_eq!(
+            true,
+            matches!(
+                service_tls(&tmp, &[], Some(&certificate)),
+                Err(ServersTransportError::MissingCertificate(_))
+            )
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use rules::{HashKey, Strategy};
+
+use crate::configurator::kubernetes::traefik_bindings::ingressroute;
+
+#[derive(Debug, PartialEq)]
+pub enum StrategyError {
+    UnknownStrategy(String),
+}
+
+/// Attempts to load the Load-Balancing Strategy configured for the given
+/// Service, returns None if no Strategy was configured.
+///
+/// A Sticky-Cookie, without an explicit Strategy, results in Consistent-Hashing
+/// on that Cookie
+pub fn service_strategy(
+    service: &ingressroute::Service,
+) -> Result<Option<Strategy>, StrategyError> {
+    let sticky_cookie = service
+        .sticky
+        .as_ref()
+        .and_then(|s| s.cookie.as_ref())
+        .and_then(|c| c.name.clone());
+
+    let raw = match (service.strategy.as_deref(), sticky_cookie.as_ref()) {
+        (Some(r), _) => r,
+        (None, Some(_)) => "ConsistentHash",
+        (None, None) => return Ok(None),
+    };
+
+    let strategy = match raw {
+        "RoundRobin" => Strategy::RoundRobin,
+        "WeightedRoundRobin" => Strategy::WeightedRoundRobin {
+            weights: service.weights.clone().unwrap_or_default(),
+        },
+        "LeastOutstanding" => Strategy::LeastOutstanding,
+        "PowerOfTwoChoices" => Strategy::PowerOfTwoChoices,
+        "Random" => Strategy::Random,
+        "ConsistentHash" => {
+            let key = match (sticky_cookie, service.hash_header.clone()) {
+                (Some(name), _) => HashKey::Cookie { name },
+                (None, Some(name)) => HashKey::Header { name },
+                (None, None) => HashKey::ClientIP,
+            };
+            Strategy::ConsistentHash { key }
+        }
+        _ => return Err(StrategyError::UnknownStrategy(raw.to_owned())),
+    };
+
+    Ok(Some(strategy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(raw: serde_json::Value) -> ingressroute::Service {
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn no_strategy() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+        }));
+
+        assert_eq!(Ok(None), service_strategy(&tmp));
+    }
+
+    #[test]
+    fn simple_strategy() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "strategy": "LeastOutstanding",
+        }));
+
+        assert_eq!(Ok(Some(Strategy::LeastOutstanding)), service_strategy(&tmp));
+    }
+
+    #[test]
+    fn consistent_hash_header() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "strategy": "ConsistentHash",
+            "hashHeader": "X-User",
+        }));
+
+        assert_eq!(
+            Ok(Some(Strategy::ConsistentHash {
+                key: HashKey::Header {
+                    name: "X-User".to_owned()
+                }
+            })),
+            service_strategy(&tmp)
+        );
+    }
+
+    #[test]
+    fn sticky_cookie() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "sticky": {
+                "cookie": {
+                    "name": "session",
+                },
+            },
+        }));
+
+        assert_eq!(
+            Ok(Some(Strategy::ConsistentHash {
+                key: HashKey::Cookie {
+                    name: "session".to_owned()
+                }
+            })),
+            service_strategy(&tmp)
+        );
+    }
+
+    #[test]
+    fn unknown_strategy() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "strategy": "Other",
+        }));
+
+        assert_eq!(
+            Err(StrategyError::UnknownStrategy("Other".to_owned())),
+            service_strategy(&tmp)
+        );
+    }
+}
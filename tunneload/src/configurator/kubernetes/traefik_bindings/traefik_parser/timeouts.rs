@@ -0,0 +1,85 @@
+use rules::ServiceTimeouts;
+
+use crate::configurator::kubernetes::traefik_bindings::ingressroute;
+
+use super::duration::parse_duration;
+
+#[derive(Debug, PartialEq)]
+pub enum TimeoutsError {
+    InvalidDuration(String),
+}
+
+/// Attempts to load the Forwarding-Timeouts configured for the given
+/// Service, returns None if no Timeouts were configured
+pub fn service_timeouts(
+    service: &ingressroute::Service,
+) -> Result<Option<ServiceTimeouts>, TimeoutsError> {
+    let raw = match service.forwarding_timeouts.as_ref() {
+        Some(r) => r,
+        None => return Ok(None),
+    };
+
+    let parse = |value: &Option<String>| match value.as_deref() {
+        Some(v) => parse_duration(v)
+            .map(|d| Some(d.as_millis() as u64))
+            .map_err(|e| TimeoutsError::InvalidDuration(e.0)),
+        None => Ok(None),
+    };
+
+    Ok(Some(ServiceTimeouts {
+        connect_ms: parse(&raw.dial_timeout)?,
+        response_ms: parse(&raw.response_header_timeout)?,
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service(raw: serde_json::Value) -> ingressroute::Service {
+        serde_json::from_value(raw).unwrap()
+    }
+
+    #[test]
+    fn no_timeouts() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+        }));
+
+        assert_eq!(Ok(None), service_timeouts(&tmp));
+    }
+
+    #[test]
+    fn timeouts() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "forwardingTimeouts": {
+                "dialTimeout": "2s",
+            },
+        }));
+
+        assert_eq!(
+            Ok(Some(ServiceTimeouts {
+                connect_ms: Some(2000),
+                ..Default::default()
+            })),
+            service_timeouts(&tmp)
+        );
+    }
+
+    #[test]
+    fn invalid_timeout() {
+        let tmp = service(serde_json::json!({
+            "name": "test",
+            "forwardingTimeouts": {
+                "responseHeaderTimeout": "forever",
+            },
+        }));
+
+        assert_eq!(
+            Err(TimeoutsError::InvalidDuration("forever".to_owned())),
+            service_timeouts(&tmp)
+        );
+    }
+}
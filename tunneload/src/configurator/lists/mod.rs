@@ -13,3 +13,9 @@ pub use rule_list::RuleList;
 
 mod plugin_list;
 pub use plugin_list::PluginList;
+
+mod tcp_rule_list;
+pub use tcp_rule_list::TcpRuleList;
+
+mod udp_rule_list;
+pub use udp_rule_list::UdpRuleList;
@@ -1,10 +1,11 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use general::Name;
-use rules::{rule_list::RuleListWriteHandle, Rule};
+use rules::{rule_list::RuleListWriteHandle, Rule, RuleConflict};
 
 use lazy_static::lazy_static;
 use prometheus::Registry;
+use tokio::sync::Notify;
 
 lazy_static! {
     static ref CONFIG_RULES_COUNT: prometheus::IntGauge =
@@ -17,6 +18,11 @@ lazy_static! {
 #[derive(Clone)]
 pub struct RuleList {
     writer: std::sync::Arc<std::sync::Mutex<RuleListWriteHandle>>,
+    /// Notified every time the Rules change, so the Conflicts can be
+    /// updated
+    changed: Arc<Notify>,
+    /// The Conflicts found in the Rules, when they were last analyzed
+    conflicts: Arc<std::sync::Mutex<Vec<RuleConflict>>>,
 }
 
 impl RuleList {
@@ -25,6 +31,8 @@ impl RuleList {
     pub fn new(write_handle: RuleListWriteHandle) -> Self {
         Self {
             writer: std::sync::Arc::new(std::sync::Mutex::new(write_handle)),
+            changed: Arc::new(Notify::new()),
+            conflicts: Arc::new(std::sync::Mutex::new(Vec::new())),
         }
     }
 
@@ -41,6 +49,7 @@ impl RuleList {
         let mut writer = self.writer.lock().expect("Locking Writer");
 
         CONFIG_RULES_COUNT.set(writer.set_single(n_srv) as i64);
+        self.changed.notify_one();
     }
 
     /// Removes the Rules matching the given Name
@@ -48,6 +57,35 @@ impl RuleList {
         let mut writer = self.writer.lock().expect("Locking Writer");
 
         CONFIG_RULES_COUNT.set(writer.remove(name) as i64);
+        self.changed.notify_one();
+    }
+
+    /// Returns all the currently active Rules, sorted by their
+    /// Priority
+    pub fn get_all(&self) -> Vec<Arc<Rule>> {
+        let writer = self.writer.lock().expect("Locking Writer");
+
+        writer.clone_all_rules()
+    }
+
+    /// Waits until the Rules changed since the last call to this
+    /// Function, multiple Changes in the meantime are only reported once
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+
+    /// Replaces the stored Conflicts with the given ones
+    pub fn set_conflicts(&self, conflicts: Vec<RuleConflict>) {
+        let mut inner = self.conflicts.lock().expect("Locking Conflicts");
+
+        *inner = conflicts;
+    }
+
+    /// Returns the Conflicts found, when the Rules were last analyzed
+    pub fn conflicts(&self) -> Vec<RuleConflict> {
+        let inner = self.conflicts.lock().expect("Locking Conflicts");
+
+        inner.clone()
     }
 }
 
@@ -62,7 +100,7 @@ mod tests {
     use stream_httparse::{Headers, Request};
 
     use general::{Group, Shared};
-    use rules::{self, Action, Matcher, Middleware, Service};
+    use rules::{self, Action, ConnectionInfo, Matcher, Middleware, Service};
 
     use super::*;
 
@@ -93,7 +131,41 @@ mod tests {
             Headers::new(),
             &[],
         );
-        let matched_res = read.match_req(&tmp_req);
+        let matched_res = read.match_req(&tmp_req, &ConnectionInfo::default());
         assert_eq!(true, matched_res.is_some());
     }
+
+    /// Checks if a Change of the Rules was reported, without waiting for
+    /// a new one
+    async fn was_changed(rules: &RuleList) -> bool {
+        tokio::time::timeout(std::time::Duration::from_millis(10), rules.changed())
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn set_and_remove_mark_changed() {
+        let (_, write) = rules::new();
+
+        let tmp_rule_list = RuleList::new(write);
+        assert_eq!(false, was_changed(&tmp_rule_list).await);
+
+        tmp_rule_list.set_rule(Rule::new(
+            Name::new("test-name", Group::Internal),
+            1,
+            Matcher::PathPrefix("/".to_owned()),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::Internal),
+                vec![],
+            )),
+        ));
+        assert_eq!(true, was_changed(&tmp_rule_list).await);
+        assert_eq!(false, was_changed(&tmp_rule_list).await);
+        assert_eq!(1, tmp_rule_list.get_all().len());
+
+        tmp_rule_list.remove_rule(Name::new("test-name", Group::Internal));
+        assert_eq!(true, was_changed(&tmp_rule_list).await);
+        assert_eq!(0, tmp_rule_list.get_all().len());
+    }
 }
@@ -1,4 +1,8 @@
-use rules::Service;
+use general::Name;
+use general_traits::DefaultConfig;
+use rules::{
+    HealthCheck, ProxyProtocol, Service, ServiceProtocol, ServiceTimeouts, ServiceTls, Strategy,
+};
 
 use lazy_static::lazy_static;
 use prometheus::Registry;
@@ -39,10 +43,156 @@ impl ServiceList {
 
     /// Inserts or Updates the given Service in the
     /// List of Services
-    pub fn set_service(&self, n_srv: Service) {
+    ///
+    /// The new Service inherits the Configuration and Health that it
+    /// does not set itself from the previous Version of the Service,
+    /// see `Service::inherit`
+    pub fn set_service(&self, mut n_srv: Service) {
+        if let Some(previous) = self.get(n_srv.name()) {
+            n_srv.inherit(&previous.get());
+        }
+
         CONFIG_SERVICE_ENTRIES_COUNT
             .with_label_values(&[&n_srv.name().to_string()])
             .set(n_srv.address_count() as i64);
         CONFIG_SERVICE_COUNT.set(self.set(n_srv) as i64);
     }
+
+    /// Sets the Strategy for the Service with the given Name, if
+    /// the Service does not exist yet, it is created without any
+    /// Addresses
+    pub fn set_strategy(&self, name: Name, strategy: Strategy) {
+        let mut n_srv = self.load_or_default(name);
+        if n_srv.strategy() == Some(&strategy) {
+            return;
+        }
+
+        n_srv.set_strategy(strategy);
+        self.set_service(n_srv);
+    }
+
+    /// Sets the Health-Check for the Service with the given Name, if
+    /// the Service does not exist yet, it is created without any
+    /// Addresses
+    pub fn set_health_check(&self, name: Name, health_check: HealthCheck) {
+        let mut n_srv = self.load_or_default(name);
+        if n_srv.health_check() == Some(&health_check) {
+            return;
+        }
+
+        n_srv.set_health_check(health_check);
+        self.set_service(n_srv);
+    }
+
+    /// Sets the Timeouts for the Service with the given Name, if
+    /// the Service does not exist yet, it is created without any
+    /// Addresses
+    pub fn set_timeouts(&self, name: Name, timeouts: ServiceTimeouts) {
+        let mut n_srv = self.load_or_default(name);
+        if n_srv.timeouts() == Some(&timeouts) {
+            return;
+        }
+
+        n_srv.set_timeouts(timeouts);
+        self.set_service(n_srv);
+    }
+
+    /// Sets the Protocol for the Service with the given Name, if
+    /// the Service does not exist yet, it is created without any
+    /// Addresses
+    pub fn set_protocol(&self, name: Name, protocol: ServiceProtocol) {
+        let mut n_srv = self.load_or_default(name);
+        if n_srv.protocol() == protocol {
+            return;
+        }
+
+        n_srv.set_protocol(protocol);
+        self.set_service(n_srv);
+    }
+
+    /// Sets the TLS-Configuration for the Service with the given Name,
+    /// if the Service does not exist yet, it is created without any
+    /// Addresses
+    pub fn set_tls(&self, name: Name, tls: ServiceTls) {
+        let mut n_srv = self.load_or_default(name);
+        if n_srv.tls() == Some(&tls) {
+            return;
+        }
+
+        n_srv.set_tls(tls);
+        self.set_service(n_srv);
+    }
+
+    /// Sets the Version of the PROXY-Protocol for the Service with the
+    /// given Name, if the Service does not exist yet, it is created
+    /// without any Addresses
+    pub fn set_proxy_protocol(&self, name: Name, version: ProxyProtocol) {
+        let mut n_srv = self.load_or_default(name);
+        if n_srv.proxy_protocol() == Some(version) {
+            return;
+        }
+
+        n_srv.set_proxy_protocol(version);
+        self.set_service(n_srv);
+    }
+
+    fn load_or_default(&self, name: Name) -> Service {
+        match self.get(&name) {
+            Some(previous) => Service::clone(&previous.get()),
+            None => Service::default_name(name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::Group;
+
+    use super::*;
+
+    #[test]
+    fn set_service_keeps_strategy() {
+        let tmp_list = ServiceList::new();
+        let name = Name::new("test-service", Group::Internal);
+
+        tmp_list.set_strategy(name.clone(), Strategy::LeastOutstanding);
+        tmp_list.set_service(Service::new(name.clone(), vec!["addr1".to_owned()]));
+
+        let result = tmp_list.get(&name).unwrap().get();
+        assert_eq!(Some(&Strategy::LeastOutstanding), result.strategy());
+        assert_eq!(&["addr1".to_owned()], result.addresses());
+    }
+
+    #[test]
+    fn set_service_overwrites_strategy() {
+        let tmp_list = ServiceList::new();
+        let name = Name::new("test-service", Group::Internal);
+
+        tmp_list.set_strategy(name.clone(), Strategy::LeastOutstanding);
+        let mut n_srv = Service::new(name.clone(), vec![]);
+        n_srv.set_strategy(Strategy::Random);
+        tmp_list.set_service(n_srv);
+
+        let result = tmp_list.get(&name).unwrap().get();
+        assert_eq!(Some(&Strategy::Random), result.strategy());
+    }
+
+    #[test]
+    fn set_service_keeps_health() {
+        let tmp_list = ServiceList::new();
+        let name = Name::new("test-service", Group::Internal);
+
+        tmp_list.set_health_check(name.clone(), HealthCheck::default());
+        tmp_list.set_service(Service::new(name.clone(), vec!["addr1".to_owned()]));
+        tmp_list.get(&name).unwrap().get().set_healthy(0, false);
+        tmp_list.set_service(Service::new(
+            name.clone(),
+            vec!["addr1".to_owned(), "addr2".to_owned()],
+        ));
+
+        let result = tmp_list.get(&name).unwrap().get();
+        assert_eq!(Some(&HealthCheck::default()), result.health_check());
+        assert_eq!(false, result.is_healthy(0));
+        assert_eq!(true, result.is_healthy(1));
+    }
 }
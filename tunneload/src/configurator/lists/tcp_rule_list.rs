@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use general::Name;
+use rules::{ConnectionInfo, TcpRule};
+
+use lazy_static::lazy_static;
+use prometheus::Registry;
+
+use super::ConfigList;
+
+lazy_static! {
+    static ref CONFIG_TCP_RULES_COUNT: prometheus::IntGauge = prometheus::IntGauge::new(
+        "config_tcp_rules",
+        "The Number of tcp-rules currently registered",
+    )
+    .expect("Creating a Metric should never fail");
+}
+
+/// Holds a list of all TcpRules and provides a more
+/// ergonomic interface to interact with it
+pub type TcpRuleList = ConfigList<TcpRule>;
+
+impl TcpRuleList {
+    /// This registers all the Prometheus Metrics related to
+    /// tcp-rule configuration
+    pub fn register_metrics(reg: &mut Registry) {
+        if let Err(e) = reg.register(Box::new(CONFIG_TCP_RULES_COUNT.clone())) {
+            tracing::error!("Registering Metric: {:?}", e);
+        }
+    }
+
+    /// Adds the given TcpRule to the List or replaces the
+    /// previous one
+    pub fn set_tcp_rule(&self, n_rule: TcpRule) {
+        CONFIG_TCP_RULES_COUNT.set(self.set(n_rule) as i64);
+    }
+
+    /// Removes any TcpRule with the given Name from the List
+    pub fn remove_tcp_rule(&self, name: &Name) {
+        CONFIG_TCP_RULES_COUNT.set(self.remove(name) as i64);
+    }
+
+    /// Finds the TcpRule with the highest Priority that matches a
+    /// Connection, which requested the given Server-Name
+    pub fn find(&self, sni: Option<&str>, info: &ConnectionInfo) -> Option<Arc<TcpRule>> {
+        self.get_all()
+            .into_iter()
+            .filter(|rule| rule.matches(sni, info))
+            .max_by_key(|rule| rule.priority())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Shared};
+    use general_traits::ConfigItem;
+    use rules::{Service, TcpMatcher};
+
+    use super::*;
+
+    fn rule(name: &str, priority: u32, matcher: TcpMatcher) -> TcpRule {
+        TcpRule::new(
+            Name::new(name, Group::Internal),
+            priority,
+            matcher,
+            Shared::new(Service::new(Name::new("test", Group::Internal), vec![])),
+        )
+    }
+
+    #[test]
+    fn find_highest_priority() {
+        let list = TcpRuleList::new();
+        list.set_tcp_rule(rule("catch-all", 1, TcpMatcher::Any));
+        list.set_tcp_rule(rule(
+            "db",
+            10,
+            TcpMatcher::HostSNI("db.example.com".to_owned()),
+        ));
+
+        let info = ConnectionInfo::default();
+        assert_eq!(
+            Some(Name::new("db", Group::Internal)),
+            list.find(Some("db.example.com"), &info)
+                .map(|r| r.name().clone())
+        );
+        assert_eq!(
+            Some(Name::new("catch-all", Group::Internal)),
+            list.find(None, &info).map(|r| r.name().clone())
+        );
+    }
+
+    #[test]
+    fn find_nothing() {
+        let list = TcpRuleList::new();
+        list.set_tcp_rule(rule(
+            "db",
+            1,
+            TcpMatcher::HostSNI("db.example.com".to_owned()),
+        ));
+
+        assert_eq!(true, list.find(None, &ConnectionInfo::default()).is_none());
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+
+use general::Name;
+use rules::UdpRule;
+
+use lazy_static::lazy_static;
+use prometheus::Registry;
+
+use super::ConfigList;
+
+lazy_static! {
+    static ref CONFIG_UDP_RULES_COUNT: prometheus::IntGauge = prometheus::IntGauge::new(
+        "config_udp_rules",
+        "The Number of udp-rules currently registered",
+    )
+    .expect("Creating a Metric should never fail");
+}
+
+/// Holds a list of all UdpRules and provides a more
+/// ergonomic interface to interact with it
+pub type UdpRuleList = ConfigList<UdpRule>;
+
+impl UdpRuleList {
+    /// This registers all the Prometheus Metrics related to
+    /// udp-rule configuration
+    pub fn register_metrics(reg: &mut Registry) {
+        if let Err(e) = reg.register(Box::new(CONFIG_UDP_RULES_COUNT.clone())) {
+            tracing::error!("Registering Metric: {:?}", e);
+        }
+    }
+
+    /// Adds the given UdpRule to the List or replaces the
+    /// previous one
+    pub fn set_udp_rule(&self, n_rule: UdpRule) {
+        CONFIG_UDP_RULES_COUNT.set(self.set(n_rule) as i64);
+    }
+
+    /// Removes any UdpRule with the given Name from the List
+    pub fn remove_udp_rule(&self, name: &Name) {
+        CONFIG_UDP_RULES_COUNT.set(self.remove(name) as i64);
+    }
+
+    /// Finds the UdpRule with the highest Priority, that applies to the
+    /// given Entrypoint
+    pub fn find(&self, entrypoint: Option<&str>) -> Option<Arc<UdpRule>> {
+        self.get_all()
+            .into_iter()
+            .filter(|rule| rule.matches(entrypoint))
+            .max_by_key(|rule| rule.priority())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Shared};
+    use general_traits::ConfigItem;
+    use rules::Service;
+
+    use super::*;
+
+    fn rule(name: &str, priority: u32, entry_points: &[&str]) -> UdpRule {
+        let mut tmp = UdpRule::new(
+            Name::new(name, Group::Internal),
+            priority,
+            Shared::new(Service::new(Name::new("test", Group::Internal), vec![])),
+        );
+        tmp.set_entry_points(entry_points.iter().map(|n| n.to_string()).collect());
+        tmp
+    }
+
+    #[test]
+    fn find_for_entrypoint() {
+        let list = UdpRuleList::new();
+        list.set_udp_rule(rule("any", 1, &[]));
+        list.set_udp_rule(rule("dns", 10, &["dns"]));
+
+        assert_eq!(
+            Some(Name::new("dns", Group::Internal)),
+            list.find(Some("dns")).map(|r| r.name().clone())
+        );
+        assert_eq!(
+            Some(Name::new("any", Group::Internal)),
+            list.find(Some("other")).map(|r| r.name().clone())
+        );
+        // Acceptors without a Name are not restricted by the Entrypoints
+        assert_eq!(
+            Some(Name::new("dns", Group::Internal)),
+            list.find(None).map(|r| r.name().clone())
+        );
+    }
+}
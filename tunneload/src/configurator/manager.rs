@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
 use crate::{internal_services::traits::InternalService, tls, tls::auto::CertificateQueue};
-use rules::rule_list::RuleListWriteHandle;
+use general_traits::ConfigItem;
+use rules::{find_conflicts, rule_list::RuleListWriteHandle};
 
 use super::{
-    manager_builder::ManagerBuilder, parser::GeneralConfigurator, MiddlewareList, PluginList,
-    RuleList, ServiceList,
+    health::{self, HealthChecker},
+    manager_builder::ManagerBuilder,
+    parser::GeneralConfigurator,
+    MiddlewareList, PluginList, RuleList, ServiceList, TcpRuleList, UdpRuleList,
 };
 
 use prometheus::Registry;
@@ -21,12 +24,18 @@ pub struct Manager {
     action_plugins: PluginList,
     /// All currently active Rules
     rules: RuleList,
+    /// All currently active TcpRules
+    tcp_rules: TcpRuleList,
+    /// All currently active UdpRules
+    udp_rules: UdpRuleList,
     /// All currently active Services
     services: ServiceList,
     /// All the registered Middlewares
     middlewares: MiddlewareList,
     /// The Queue of Domains to generate
     auto_tls_queue: Option<CertificateQueue>,
+    /// The Names of all the configured Entrypoints
+    entrypoints: Vec<String>,
 }
 
 impl Manager {
@@ -35,6 +44,7 @@ impl Manager {
         tls: tls::ConfigManager,
         writer: RuleListWriteHandle,
         plugin_loader: Option<plugins::Loader>,
+        entrypoints: Vec<String>,
     ) -> Self {
         Self {
             general_configurators,
@@ -44,7 +54,10 @@ impl Manager {
             services: ServiceList::new(),
             middlewares: MiddlewareList::new(),
             rules: RuleList::new(writer),
+            tcp_rules: TcpRuleList::new(),
+            udp_rules: UdpRuleList::new(),
             auto_tls_queue: None,
+            entrypoints,
         }
     }
 
@@ -64,6 +77,9 @@ impl Manager {
         ServiceList::register_metrics(&mut reg);
         MiddlewareList::register_metrics(&mut reg);
         RuleList::register_metrics(&mut reg);
+        TcpRuleList::register_metrics(&mut reg);
+        UdpRuleList::register_metrics(&mut reg);
+        health::register_metrics(&mut reg);
     }
 
     /// Returns cloned versions of all the internal
@@ -77,6 +93,18 @@ impl Manager {
         )
     }
 
+    /// Returns a cloned version of the List containing
+    /// all the TcpRules
+    pub fn get_tcp_rules(&self) -> TcpRuleList {
+        self.tcp_rules.clone()
+    }
+
+    /// Returns a cloned version of the List containing
+    /// all the UdpRules
+    pub fn get_udp_rules(&self) -> UdpRuleList {
+        self.udp_rules.clone()
+    }
+
     /// This function is used to register all internal Services as they
     /// can not be "found"/"discovered" using the Configurators
     pub fn register_internal_service<I>(&mut self, service: &I)
@@ -129,6 +157,34 @@ impl Manager {
         }
     }
 
+    async fn update_tcp_rules(&mut self) {
+        let mut result = Vec::new();
+        for gconf in self.general_configurators.iter() {
+            let tmp = gconf
+                .load_tcp_rules(&self.middlewares, &self.services)
+                .await;
+            result.extend(tmp);
+        }
+
+        for tmp_rule in result.drain(..) {
+            self.tcp_rules.set_tcp_rule(tmp_rule);
+        }
+    }
+
+    async fn update_udp_rules(&mut self) {
+        let mut result = Vec::new();
+        for gconf in self.general_configurators.iter() {
+            let tmp = gconf
+                .load_udp_rules(&self.middlewares, &self.services)
+                .await;
+            result.extend(tmp);
+        }
+
+        for tmp_rule in result.drain(..) {
+            self.udp_rules.set_udp_rule(tmp_rule);
+        }
+    }
+
     async fn update_tls(&mut self) {
         let mut result = Vec::new();
         for gconf in self.general_configurators.iter() {
@@ -151,6 +207,8 @@ impl Manager {
         self.update_services().await;
         self.update_middlewares().await;
         self.update_rules().await;
+        self.update_tcp_rules().await;
+        self.update_udp_rules().await;
         self.update_tls().await;
         self.update_plugins();
     }
@@ -169,8 +227,24 @@ impl Manager {
                 self.rules.clone(),
                 self.auto_tls_queue.clone(),
             ));
+            tokio::task::spawn(gconf.clone().tcp_rule_events(
+                self.services.clone(),
+                self.middlewares.clone(),
+                self.tcp_rules.clone(),
+            ));
+            tokio::task::spawn(gconf.clone().udp_rule_events(
+                self.services.clone(),
+                self.middlewares.clone(),
+                self.udp_rules.clone(),
+            ));
             tokio::task::spawn(gconf.clone().tls_events(self.tls.clone()));
         }
+
+        tokio::task::spawn(watch_rule_conflicts(
+            self.rules.clone(),
+            self.entrypoints.clone(),
+        ));
+        tokio::task::spawn(HealthChecker::new(self.services.clone()).run());
     }
 
     /// Starts the Manager itself and all the Tasks
@@ -187,3 +261,53 @@ impl Manager {
         self.start_event_listeners();
     }
 }
+
+/// Analyzes the currently active Rules for Conflicts, logs the new
+/// Conflicts and stores all of them in the Rule-List, so they can be
+/// displayed in the Dashboard.
+///
+/// The Analysis compares every pair of Rules and therefore runs on a
+/// blocking Thread, to not stall the Runtime for large Rule-Sets
+async fn check_rule_conflicts(rules: &RuleList) {
+    let all_rules = rules.get_all();
+    let conflicts = match tokio::task::spawn_blocking(move || find_conflicts(&all_rules)).await {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("Analyzing Rule-Conflicts: {}", e);
+            return;
+        }
+    };
+
+    let previous = rules.conflicts();
+    for conflict in conflicts.iter().filter(|c| !previous.contains(c)) {
+        tracing::warn!("Rule-Conflict: {}", conflict);
+    }
+
+    rules.set_conflicts(conflicts);
+}
+
+/// Warns about the Rules that are restricted to Entrypoints which are
+/// not configured, as they only match Connections of unnamed Acceptors
+fn check_entrypoints(rules: &RuleList, entrypoints: &[String]) {
+    for rule in rules.get_all() {
+        for name in rule.entry_points().names() {
+            if !entrypoints.contains(name) {
+                tracing::warn!(
+                    "Rule({}) uses the unknown Entrypoint '{}'",
+                    rule.name(),
+                    name
+                );
+            }
+        }
+    }
+}
+
+/// Reanalyzes the Rules for Conflicts and unknown Entrypoints every
+/// time they changed
+async fn watch_rule_conflicts(rules: RuleList, entrypoints: Vec<String>) {
+    loop {
+        rules.changed().await;
+        check_rule_conflicts(&rules).await;
+        check_entrypoints(&rules, &entrypoints);
+    }
+}
@@ -11,6 +11,7 @@ pub struct ManagerBuilder {
     tls_config: Option<tls::ConfigManager>,
     writer: Option<RuleListWriteHandle>,
     plugin_loader: Option<plugins::Loader>,
+    entrypoints: Vec<String>,
 }
 
 impl ManagerBuilder {
@@ -21,6 +22,7 @@ impl ManagerBuilder {
             tls_config: None,
             writer: None,
             plugin_loader: None,
+            entrypoints: Vec::new(),
         }
     }
 
@@ -50,13 +52,27 @@ impl ManagerBuilder {
         self
     }
 
+    /// Sets the Names of all the configured Entrypoints, which the
+    /// Entrypoints of the Rules are checked against
+    pub fn entrypoints(mut self, names: Vec<String>) -> Self {
+        self.entrypoints = names;
+
+        self
+    }
+
     /// Builds the final Manager from the configured
     /// Settings in the Builder
     pub fn build(self) -> Manager {
         let tls = self.tls_config.expect("Missing TLS Configuration");
         let writer = self.writer.expect("Missing Writer Configuration");
 
-        Manager::new(self.general_configurators, tls, writer, self.plugin_loader)
+        Manager::new(
+            self.general_configurators,
+            tls,
+            writer,
+            self.plugin_loader,
+            self.entrypoints,
+        )
     }
 }
 
@@ -10,5 +10,7 @@ pub use manager_builder::ManagerBuilder;
 mod manager;
 pub use manager::Manager;
 
+mod health;
+
 mod lists;
 pub use lists::*;
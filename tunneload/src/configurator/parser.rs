@@ -8,12 +8,12 @@ use std::{
 
 use crate::tls::{self, auto::CertificateQueue};
 use general::{Group, Name};
-use rules::{Action, Middleware, Rule, Service};
+use rules::{Action, Middleware, Rule, Service, TcpRule, UdpRule};
 
 use futures::Future;
 use rustls::sign::CertifiedKey;
 
-use super::{MiddlewareList, PluginList, RuleList, ServiceList};
+use super::{MiddlewareList, PluginList, RuleList, ServiceList, TcpRuleList, UdpRuleList};
 
 #[cfg(test)]
 pub mod mocks;
@@ -217,6 +217,70 @@ impl GeneralConfigurator {
         result
     }
 
+    /// Attempts to load and parse the TcpRules using the provided Loader and Parser
+    #[tracing::instrument(skip(middlewares, services))]
+    pub async fn load_tcp_rules(
+        &self,
+        middlewares: &MiddlewareList,
+        services: &ServiceList,
+    ) -> Vec<TcpRule> {
+        let mut result = Vec::new();
+        let raw_rules = self.loader.tcp_rules().await;
+
+        tracing::debug!("Raw-TCP-Rules-Count: {}", raw_rules.len());
+
+        for raw_rule in raw_rules.iter() {
+            let context = ParseRuleContext {
+                middlewares,
+                services,
+                cert_queue: None,
+            };
+
+            match self.parser.tcp_rule(&raw_rule.config, context).await {
+                Ok(rule) => {
+                    result.push(rule);
+                }
+                Err(e) => {
+                    tracing::error!("Parsing TCP-Rule: {:?}", e);
+                }
+            };
+        }
+
+        result
+    }
+
+    /// Attempts to load and parse the UdpRules using the provided Loader and Parser
+    #[tracing::instrument(skip(middlewares, services))]
+    pub async fn load_udp_rules(
+        &self,
+        middlewares: &MiddlewareList,
+        services: &ServiceList,
+    ) -> Vec<UdpRule> {
+        let mut result = Vec::new();
+        let raw_rules = self.loader.udp_rules().await;
+
+        tracing::debug!("Raw-UDP-Rules-Count: {}", raw_rules.len());
+
+        for raw_rule in raw_rules.iter() {
+            let context = ParseRuleContext {
+                middlewares,
+                services,
+                cert_queue: None,
+            };
+
+            match self.parser.udp_rule(&raw_rule.config, context).await {
+                Ok(rule) => {
+                    result.push(rule);
+                }
+                Err(e) => {
+                    tracing::error!("Parsing UDP-Rule: {:?}", e);
+                }
+            };
+        }
+
+        result
+    }
+
     /// Attempts to load all the TLS-Settings using the configured Loader and Parser
     #[tracing::instrument]
     pub async fn load_tls(&self) -> Vec<(String, CertifiedKey)> {
@@ -262,7 +326,7 @@ impl GeneralConfigurator {
                 Event::Update(updated) => {
                     match self.parser.service(&updated.config).await {
                         Ok(updated_service) => {
-                            services.set(updated_service);
+                            services.set_service(updated_service);
                         }
                         Err(e) => {
                             tracing::error!("Parsing Service \n{:?}", e);
@@ -378,6 +442,106 @@ impl GeneralConfigurator {
         }
     }
 
+    /// This starts the configured Event-Emitter for TcpRules and then listens for incoming
+    /// events as well as handling them accordingly
+    pub async fn tcp_rule_events(
+        self: Arc<Self>,
+        services: ServiceList,
+        middlewares: MiddlewareList,
+        tcp_rules: TcpRuleList,
+    ) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let rule_future = match self.events.tcp_rule_listener(tx).await {
+            Some(r) => r,
+            None => return,
+        };
+
+        // Actually run the event emitter
+        tokio::spawn(rule_future);
+
+        loop {
+            let event = match rx.recv().await {
+                Some(e) => e,
+                None => {
+                    tracing::error!("Could not receive Event");
+                    return;
+                }
+            };
+
+            match event {
+                Event::Update(updated) => {
+                    let context = ParseRuleContext {
+                        services: &services,
+                        middlewares: &middlewares,
+                        cert_queue: None,
+                    };
+                    match self.parser.tcp_rule(&updated.config, context).await {
+                        Ok(rule) => {
+                            tcp_rules.set_tcp_rule(rule);
+                        }
+                        Err(e) => {
+                            tracing::error!("Parsing TCP-Rule: {:?}", e);
+                        }
+                    }
+                }
+                Event::Remove(name) => {
+                    tracing::info!("Removing TCP-Rule: {:?}", name);
+                    tcp_rules.remove_tcp_rule(&name);
+                }
+            };
+        }
+    }
+
+    /// This starts the configured Event-Emitter for UdpRules and then listens for incoming
+    /// events as well as handling them accordingly
+    pub async fn udp_rule_events(
+        self: Arc<Self>,
+        services: ServiceList,
+        middlewares: MiddlewareList,
+        udp_rules: UdpRuleList,
+    ) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let rule_future = match self.events.udp_rule_listener(tx).await {
+            Some(r) => r,
+            None => return,
+        };
+
+        // Actually run the event emitter
+        tokio::spawn(rule_future);
+
+        loop {
+            let event = match rx.recv().await {
+                Some(e) => e,
+                None => {
+                    tracing::error!("Could not receive Event");
+                    return;
+                }
+            };
+
+            match event {
+                Event::Update(updated) => {
+                    let context = ParseRuleContext {
+                        services: &services,
+                        middlewares: &middlewares,
+                        cert_queue: None,
+                    };
+                    match self.parser.udp_rule(&updated.config, context).await {
+                        Ok(rule) => {
+                            udp_rules.set_udp_rule(rule);
+                        }
+                        Err(e) => {
+                            tracing::error!("Parsing UDP-Rule: {:?}", e);
+                        }
+                    }
+                }
+                Event::Remove(name) => {
+                    tracing::info!("Removing UDP-Rule: {:?}", name);
+                    udp_rules.remove_udp_rule(&name);
+                }
+            };
+        }
+    }
+
     /// This starts the configured Event-Emitter for TLS and then listens for incoming
     /// events as well as handling them accordingly
     pub async fn tls_events(self: Arc<Self>, tls_config: tls::ConfigManager) {
@@ -2,7 +2,7 @@
 
 use async_trait::async_trait;
 use general::Name;
-use rules::{Action, Rule, Service};
+use rules::{Action, Rule, Service, TcpRule, UdpRule};
 
 use std::error::Error;
 
@@ -41,6 +41,24 @@ pub trait Parser: Send + Sync + 'static {
         Err(Box::new(UnimplementedParserError {}))
     }
 
+    /// Parses the given TcpRule
+    async fn tcp_rule<'a>(
+        &self,
+        _config: &serde_json::Value,
+        _context: ParseRuleContext<'a>,
+    ) -> Result<TcpRule, Box<dyn Error>> {
+        Err(Box::new(UnimplementedParserError {}))
+    }
+
+    /// Parses the given UdpRule
+    async fn udp_rule<'a>(
+        &self,
+        _config: &serde_json::Value,
+        _context: ParseRuleContext<'a>,
+    ) -> Result<UdpRule, Box<dyn Error>> {
+        Err(Box::new(UnimplementedParserError {}))
+    }
+
     ///  Parses the given Config into a useable TLS-Config
     async fn tls(
         &self,
@@ -71,6 +89,18 @@ pub trait Loader: Send + Sync + 'static {
         Vec::new()
     }
 
+    /// Loads all the raw tcp-rule configurations which will then be passed
+    /// onto the Parser
+    async fn tcp_rules(&self) -> Vec<RawRuleConfig> {
+        Vec::new()
+    }
+
+    /// Loads all the raw udp-rule configurations which will then be passed
+    /// onto the Parser
+    async fn udp_rules(&self) -> Vec<RawRuleConfig> {
+        Vec::new()
+    }
+
     /// Loads all the raw tls configurations which will then be passed
     /// onto the Parser
     async fn tls(&self) -> Vec<RawTLSConfig> {
@@ -108,6 +138,24 @@ pub trait EventEmitter: Send + Sync + 'static {
         None
     }
 
+    /// Listens for TcpRule-Events in the Background and sends all the received Events over the
+    /// provided Channel
+    async fn tcp_rule_listener(
+        &self,
+        _sender: tokio::sync::mpsc::UnboundedSender<Event<RawRuleConfig, Name>>,
+    ) -> Option<EventFuture> {
+        None
+    }
+
+    /// Listens for UdpRule-Events in the Background and sends all the received Events over the
+    /// provided Channel
+    async fn udp_rule_listener(
+        &self,
+        _sender: tokio::sync::mpsc::UnboundedSender<Event<RawRuleConfig, Name>>,
+    ) -> Option<EventFuture> {
+        None
+    }
+
     /// Listens for TLS-Events in the Background and sends all the received Events over the
     /// provided Channel
     async fn tls_listener(
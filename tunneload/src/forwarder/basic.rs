@@ -1,21 +1,74 @@
+use std::{future::Future, sync::Arc};
+
 use async_trait::async_trait;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpStream,
+    },
+};
+
+use prometheus::Registry;
+use rules::{self, ConnectionInfo, Endpoint, Rule, Service};
+use stream_httparse::Request;
+use tokio_rustls::client::TlsStream;
 
-use rules::{self, Rule};
+use crate::util::proxy_protocol::{self, ProxyAddresses};
 
-use super::Forwarder;
+use super::{
+    pool, ConnectionPool, Forwarder, Http2Connection, Http2Forwarder, Http2Reader, Http2Writer,
+    ServiceConnection, ServiceReader, ServiceWriter, UpstreamTls,
+};
 
 /// This is a simple Forwarder
 ///
 /// # Behaviour
 /// This Forwader establishes a new Connection to a Rules-Service
-/// and then forwards the requests without any further processing
+/// and then forwards the requests without any further processing.
+/// If connecting to an Address of the Service fails, the next Address
+/// is tried, see `Service::connect_with`.
+///
+/// Connections are kept in a Pool after a Response was completely
+/// received, so they can be reused for later Requests. This does not
+/// apply to Connections using TLS or Connections to Services that use
+/// the PROXY-Protocol, as their Header only applies to a single Client,
+/// which are closed instead.
+///
+/// Requests for Services using HTTP/2 are forwarded using the
+/// `Http2Forwarder` instead
 #[derive(Debug, Clone)]
-pub struct BasicForwarder {}
+pub struct BasicForwarder {
+    pool: Arc<ConnectionPool>,
+    tls: Arc<UpstreamTls>,
+    http2: Http2Forwarder,
+}
 
 impl BasicForwarder {
-    /// Creates a new empty BasicForwarder Instance
+    /// Creates a new BasicForwarder Instance with an empty Pool
     pub fn new() -> Self {
-        Self {}
+        Self::with_pool(ConnectionPool::default())
+    }
+
+    /// Creates a new BasicForwarder Instance that uses the given Pool
+    pub fn with_pool(pool: ConnectionPool) -> Self {
+        let tls = Arc::new(UpstreamTls::new());
+        Self {
+            pool: Arc::new(pool),
+            tls: tls.clone(),
+            http2: Http2Forwarder::with_tls(tls),
+        }
+    }
+
+    /// Returns the Future that periodically closes the expired idle
+    /// Connections of the Pool, which should be spawned once
+    pub fn sweep_pool(&self) -> impl Future<Output = ()> {
+        pool::sweep(Arc::downgrade(&self.pool))
+    }
+
+    /// Registers all the Prometheus Metrics related to the Forwarder
+    pub fn register_metrics(reg: &Registry) {
+        pool::register_metrics(reg);
     }
 }
 
@@ -25,14 +78,271 @@ impl Default for BasicForwarder {
     }
 }
 
+/// A Connection to a single Address of a Service, which counts as an
+/// outstanding Request for that Address as long as it exists
+#[derive(Debug)]
+pub struct BasicConnection {
+    inner: Inner,
+}
+
+#[derive(Debug)]
+enum Inner {
+    Http1 {
+        stream: TcpStream,
+        service: Arc<Service>,
+        endpoint: Endpoint,
+        pool: Option<Arc<ConnectionPool>>,
+    },
+    Https {
+        stream: Box<TlsStream<TcpStream>>,
+        service: Arc<Service>,
+        endpoint: Endpoint,
+    },
+    Http2(Box<Http2Connection>),
+}
+
+/// The Read-Half of a BasicConnection
+#[derive(Debug)]
+pub enum BasicReadHalf {
+    /// The Read-Half of a plain HTTP/1.1 Connection
+    Http1(OwnedReadHalf),
+    /// The Read-Half of an HTTP/1.1 Connection using TLS
+    Https(ReadHalf<Box<TlsStream<TcpStream>>>),
+    /// The Read-Half of a Request over a shared HTTP/2 Connection
+    Http2(Http2Reader),
+}
+
+/// The Write-Half of a BasicConnection, which keeps the Endpoint
+/// of the Connection
+#[derive(Debug)]
+pub struct BasicWriteHalf {
+    inner: WriteInner,
+}
+
+#[derive(Debug)]
+enum WriteInner {
+    Http1 {
+        write: OwnedWriteHalf,
+        _endpoint: Endpoint,
+    },
+    Https {
+        write: WriteHalf<Box<TlsStream<TcpStream>>>,
+        _endpoint: Endpoint,
+    },
+    Http2(Http2Writer),
+}
+
+#[async_trait]
+impl ServiceConnection for BasicConnection {
+    async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            Inner::Http1 { stream, .. } => AsyncReadExt::read(stream, buf).await,
+            Inner::Https { stream, .. } => AsyncReadExt::read(stream, buf).await,
+            Inner::Http2(con) => con.read(buf).await,
+        }
+    }
+
+    async fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            Inner::Http1 { stream, .. } => AsyncWriteExt::write(stream, buf).await,
+            Inner::Https { stream, .. } => AsyncWriteExt::write(stream, buf).await,
+            Inner::Http2(con) => con.write(buf).await,
+        }
+    }
+
+    fn report_success(&self) {
+        match &self.inner {
+            Inner::Http1 { endpoint, .. } | Inner::Https { endpoint, .. } => {
+                endpoint.report_success()
+            }
+            Inner::Http2(con) => con.report_success(),
+        };
+    }
+
+    fn report_failure(&self) {
+        match &self.inner {
+            Inner::Http1 {
+                service, endpoint, ..
+            }
+            | Inner::Https {
+                service, endpoint, ..
+            } => service.report_failure(endpoint),
+            Inner::Http2(con) => con.report_failure(),
+        };
+    }
+
+    fn release(self) {
+        // Requests over HTTP/2 share their Connection anyway
+        if let Inner::Http1 {
+            stream,
+            service,
+            endpoint,
+            pool: Some(pool),
+        } = self.inner
+        {
+            let address = service.addresses()[endpoint.index()].clone();
+            pool.put(address, stream);
+        }
+    }
+
+    type ReadHalf = BasicReadHalf;
+    type WriteHalf = BasicWriteHalf;
+
+    fn halves_owned(self) -> (Self::ReadHalf, Self::WriteHalf) {
+        match self.inner {
+            Inner::Http1 {
+                stream, endpoint, ..
+            } => {
+                let (read, write) = stream.into_split();
+                (
+                    BasicReadHalf::Http1(read),
+                    BasicWriteHalf {
+                        inner: WriteInner::Http1 {
+                            write,
+                            _endpoint: endpoint,
+                        },
+                    },
+                )
+            }
+            Inner::Https {
+                stream, endpoint, ..
+            } => {
+                let (read, write) = tokio::io::split(stream);
+                (
+                    BasicReadHalf::Https(read),
+                    BasicWriteHalf {
+                        inner: WriteInner::Https {
+                            write,
+                            _endpoint: endpoint,
+                        },
+                    },
+                )
+            }
+            Inner::Http2(con) => {
+                let (read, write) = (*con).halves_owned();
+                (
+                    BasicReadHalf::Http2(read),
+                    BasicWriteHalf {
+                        inner: WriteInner::Http2(write),
+                    },
+                )
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ServiceReader for BasicReadHalf {
+    async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Http1(read) => AsyncReadExt::read(read, buf).await,
+            Self::Https(read) => AsyncReadExt::read(read, buf).await,
+            Self::Http2(read) => read.read(buf).await,
+        }
+    }
+}
+
+#[async_trait]
+impl ServiceWriter for BasicWriteHalf {
+    async fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.inner {
+            WriteInner::Http1 { write, .. } => AsyncWriteExt::write(write, buf).await,
+            WriteInner::Https { write, .. } => AsyncWriteExt::write(write, buf).await,
+            WriteInner::Http2(write) => write.write(buf).await,
+        }
+    }
+}
+
 #[async_trait]
 impl Forwarder for BasicForwarder {
-    type Connection = tokio::net::TcpStream;
+    type Connection = BasicConnection;
     type ConnectError = rules::ConnectError;
 
-    async fn create_con(&self, rule: &Rule) -> Result<Self::Connection, Self::ConnectError> {
+    async fn create_con(
+        &self,
+        rule: &Rule,
+        req: &Request<'_>,
+        info: &ConnectionInfo,
+    ) -> Result<Self::Connection, Self::ConnectError> {
         let service = rule.service();
+        if service.protocol().is_http2() {
+            let con = self.http2.create_con(rule, req, info).await?;
+            return Ok(BasicConnection {
+                inner: Inner::Http2(Box::new(con)),
+            });
+        }
+
+        let proxy_header = service.proxy_protocol().map(|version| {
+            let addresses = match (info.client_addr(), info.local_addr()) {
+                (Some(source), Some(destination)) => Some(ProxyAddresses {
+                    source: *source,
+                    destination: *destination,
+                }),
+                _ => None,
+            };
+            proxy_protocol::encode(version, addresses.as_ref())
+        });
+
+        if service.protocol().is_tls() {
+            let tls = service.tls();
+            let proxy_header = proxy_header.as_deref();
+            let (stream, endpoint) = service
+                .connect_using(req, info, |address| {
+                    let address = address.to_owned();
+                    async move {
+                        let mut stream = TcpStream::connect(&address).await?;
+                        if let Some(header) = proxy_header {
+                            AsyncWriteExt::write_all(&mut stream, header).await?;
+                        }
+                        self.tls.connect(stream, &address, tls, false).await
+                    }
+                })
+                .await?;
+
+            return Ok(BasicConnection {
+                inner: Inner::Https {
+                    stream: Box::new(stream),
+                    service,
+                    endpoint,
+                },
+            });
+        }
+
+        if let Some(header) = proxy_header {
+            let header = &header;
+            let (stream, endpoint) = service
+                .connect_using(req, info, |address| {
+                    let address = address.to_owned();
+                    async move {
+                        let mut stream = TcpStream::connect(&address).await?;
+                        AsyncWriteExt::write_all(&mut stream, header).await?;
+                        Ok(stream)
+                    }
+                })
+                .await?;
+
+            return Ok(BasicConnection {
+                inner: Inner::Http1 {
+                    stream,
+                    service,
+                    endpoint,
+                    pool: None,
+                },
+            });
+        }
+
+        let pool = self.pool.clone();
+        let (stream, endpoint) = service
+            .connect_using(req, info, |address| pool.connect(address.to_owned()))
+            .await?;
 
-        service.connect().await
+        Ok(BasicConnection {
+            inner: Inner::Http1 {
+                stream,
+                service,
+                endpoint,
+                pool: Some(self.pool.clone()),
+            },
+        })
     }
 }
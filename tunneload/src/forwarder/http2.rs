@@ -0,0 +1,703 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use h2::{
+    client::{ResponseFuture, SendRequest},
+    RecvStream, SendStream,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+    sync::oneshot,
+};
+
+use rules::{ConnectionInfo, Endpoint, Rule, Service, ServiceProtocol, ServiceTls};
+use stream_httparse::Request;
+
+use crate::{
+    handler::{chunked::ChunkedFraming, header_end::HeaderEnd},
+    util::http2::{
+        end_stream, parse_headers, remove_connection_headers, send_data, serialize_chunk,
+        serialize_headers, serialize_last_chunk,
+    },
+};
+
+use super::{Forwarder, ServiceConnection, ServiceReader, ServiceWriter, UpstreamTls};
+
+fn io_error(e: h2::Error) -> std::io::Error {
+    std::io::Error::other(e)
+}
+
+/// Performs the HTTP/2 Handshake over the given Connection and drives
+/// the Connection in the Background
+async fn handshake<T>(io: T) -> std::io::Result<SendRequest<Bytes>>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (sender, connection) = h2::client::handshake(io).await.map_err(io_error)?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            tracing::debug!("HTTP/2 Connection to Service: {:?}", e);
+        }
+    });
+    Ok(sender)
+}
+
+/// Establishes a new HTTP/2 Connection to the given Address
+async fn establish(
+    address: &str,
+    protocol: ServiceProtocol,
+    upstream_tls: &UpstreamTls,
+    tls: Option<&ServiceTls>,
+) -> std::io::Result<SendRequest<Bytes>> {
+    let stream = TcpStream::connect(address).await?;
+    if !protocol.is_tls() {
+        return handshake(stream).await;
+    }
+
+    let stream = upstream_tls.connect(stream, address, tls, true).await?;
+    handshake(stream).await
+}
+
+/// The shared HTTP/2 Connections to the Addresses of Services, where
+/// a single Connection is used for all the Requests to its Address
+#[derive(Debug, Default)]
+pub struct Http2Pool {
+    connections: Mutex<HashMap<String, SendRequest<Bytes>>>,
+    tls: Arc<UpstreamTls>,
+}
+
+impl Http2Pool {
+    /// Creates a new empty Pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new empty Pool, that uses the given TLS-Configurations
+    /// for Services using `h2`
+    pub fn with_tls(tls: Arc<UpstreamTls>) -> Self {
+        Self {
+            connections: Mutex::new(HashMap::new()),
+            tls,
+        }
+    }
+
+    /// Returns the shared Connection to the given Address, which is
+    /// established first if there is none yet or the previous one was
+    /// closed, the TLS-Configuration is only used for `h2`
+    pub async fn connect(
+        &self,
+        address: String,
+        protocol: ServiceProtocol,
+        tls: Option<&ServiceTls>,
+    ) -> std::io::Result<SendRequest<Bytes>> {
+        let key = format!("{}://{}", protocol.scheme(), address);
+
+        let existing = self
+            .connections
+            .lock()
+            .expect("The Lock should always be available")
+            .get(&key)
+            .cloned();
+        if let Some(sender) = existing {
+            match sender.ready().await {
+                Ok(s) => return Ok(s),
+                Err(e) => {
+                    tracing::debug!("Shared HTTP/2 Connection to '{}' closed: {:?}", address, e);
+                }
+            };
+        }
+
+        let sender = establish(&address, protocol, &self.tls, tls).await?;
+        self.connections
+            .lock()
+            .expect("The Lock should always be available")
+            .insert(key, sender.clone());
+
+        sender.ready().await.map_err(io_error)
+    }
+
+    /// The Number of shared Connections
+    pub fn len(&self) -> usize {
+        self.connections
+            .lock()
+            .expect("The Lock should always be available")
+            .len()
+    }
+
+    /// Checks if there are no shared Connections
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The Framing of the Body of the Request that is being sent
+#[derive(Debug)]
+enum RequestFraming {
+    /// There is no Body left to send
+    Done,
+    /// The given Number of Bytes are still left
+    Fixed(usize),
+    /// The Body is chunked and its Framing needs to be removed
+    Chunked(ChunkedFraming),
+}
+
+/// Parses the given HTTP/1.1 Request-Head into the Head of an HTTP/2
+/// Request and the Framing of the Body that follows it
+fn request_head(
+    head: &[u8],
+    scheme: &str,
+    authority: &str,
+) -> Option<(http::Request<()>, RequestFraming)> {
+    let head = std::str::from_utf8(head).ok()?;
+    let (request_line, header_lines) = head.split_once("\r\n")?;
+
+    let mut parts = request_line.split(' ');
+    let method = http::Method::from_bytes(parts.next()?.as_bytes()).ok()?;
+    let path = parts.next()?;
+
+    let mut headers = parse_headers(header_lines)?;
+    let chunked = headers
+        .get(http::header::TRANSFER_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_ascii_lowercase().ends_with("chunked"))
+        .unwrap_or(false);
+    let length = match headers.get(http::header::CONTENT_LENGTH) {
+        Some(v) => v.to_str().ok()?.parse::<usize>().ok()?,
+        None => 0,
+    };
+    remove_connection_headers(&mut headers);
+
+    let host = headers.remove(http::header::HOST);
+    let authority = match host.as_ref().and_then(|h| h.to_str().ok()) {
+        Some(h) => h,
+        None => authority,
+    };
+    let uri = format!("{}://{}{}", scheme, authority, path)
+        .parse::<http::Uri>()
+        .ok()?;
+
+    let mut request = http::Request::new(());
+    *request.method_mut() = method;
+    *request.uri_mut() = uri;
+    *request.headers_mut() = headers;
+
+    let framing = if chunked {
+        RequestFraming::Chunked(ChunkedFraming::keeping_trailers())
+    } else if length > 0 {
+        RequestFraming::Fixed(length)
+    } else {
+        RequestFraming::Done
+    };
+
+    Some((request, framing))
+}
+
+/// Serializes the Head of the given HTTP/2 Response as an HTTP/1.1
+/// Response-Head, returns the Head and whether the Body is chunked.
+///
+/// Bodies of unknown Length are chunked, which also allows the Trailers
+/// of the Response to be passed on
+fn response_head(parts: &http::response::Parts, end_of_stream: bool) -> (Vec<u8>, bool) {
+    let status = parts.status;
+
+    let mut head = Vec::with_capacity(256);
+    head.extend_from_slice(
+        format!(
+            "HTTP/1.1 {} {}\r\n",
+            status.as_u16(),
+            status.canonical_reason().unwrap_or("")
+        )
+        .as_bytes(),
+    );
+    serialize_headers(&parts.headers, &mut head);
+
+    let has_length = parts.headers.contains_key(http::header::CONTENT_LENGTH);
+    let without_body = status.is_informational()
+        || status == http::StatusCode::NO_CONTENT
+        || status == http::StatusCode::NOT_MODIFIED;
+
+    let chunked = !end_of_stream && !has_length && !without_body;
+    if chunked {
+        head.extend_from_slice(b"transfer-encoding: chunked\r\n");
+    } else if end_of_stream && !has_length && !without_body {
+        head.extend_from_slice(b"content-length: 0\r\n");
+    }
+    head.extend_from_slice(b"\r\n");
+
+    (head, chunked)
+}
+
+/// The Write-Half of a Request over a shared HTTP/2 Connection, which
+/// accepts the HTTP/1.1 Request and sends it as an HTTP/2 Request
+#[derive(Debug)]
+pub struct Http2Writer {
+    sender: Option<SendRequest<Bytes>>,
+    scheme: &'static str,
+    authority: String,
+    head: Vec<u8>,
+    head_end: HeaderEnd,
+    stream: Option<SendStream<Bytes>>,
+    framing: RequestFraming,
+    response: Option<oneshot::Sender<ResponseFuture>>,
+}
+
+impl Http2Writer {
+    /// Sends the Head of the Request and passes the Response on to the
+    /// Reader
+    fn start_request(&mut self, head: &[u8]) -> std::io::Result<()> {
+        let mut sender = match self.sender.take() {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+
+        let (request, framing) =
+            request_head(head, self.scheme, &self.authority).ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Invalid Request-Head for HTTP/2",
+                )
+            })?;
+
+        let end = matches!(framing, RequestFraming::Done);
+        let (response, stream) = sender.send_request(request, end).map_err(io_error)?;
+        if let Some(tx) = self.response.take() {
+            let _ = tx.send(response);
+        }
+        if !end {
+            self.stream = Some(stream);
+            self.framing = framing;
+        }
+        Ok(())
+    }
+
+    /// Sends the next Part of the Body of the Request
+    async fn send_body(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let (payload, end) = match &mut self.framing {
+            RequestFraming::Done => return Ok(()),
+            RequestFraming::Fixed(remaining) => {
+                let size = std::cmp::min(*remaining, data.len());
+                *remaining -= size;
+                (Bytes::copy_from_slice(&data[..size]), *remaining == 0)
+            }
+            RequestFraming::Chunked(framing) => {
+                let mut payload = Vec::with_capacity(data.len());
+                framing
+                    .update_with(data, |d| payload.extend_from_slice(d))
+                    .map_err(|_| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid Chunk")
+                    })?;
+                (Bytes::from(payload), framing.is_done())
+            }
+        };
+
+        let stream = match self.stream.as_mut() {
+            Some(s) => s,
+            None => return Ok(()),
+        };
+        send_data(stream, payload).await.map_err(io_error)?;
+
+        if end {
+            let trailers = match &self.framing {
+                RequestFraming::Chunked(framing) => framing.trailers(),
+                _ => &[],
+            };
+            end_stream(stream, trailers).map_err(io_error)?;
+
+            self.stream = None;
+            self.framing = RequestFraming::Done;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ServiceWriter for Http2Writer {
+    async fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut data = buf;
+        if self.sender.is_some() {
+            match self.head_end.update(data) {
+                Some(end) => {
+                    self.head.extend_from_slice(&data[..end]);
+                    data = &data[end..];
+
+                    let head = std::mem::take(&mut self.head);
+                    self.start_request(&head)?;
+                }
+                None => {
+                    self.head.extend_from_slice(data);
+                    return Ok(buf.len());
+                }
+            };
+        }
+
+        if !data.is_empty() {
+            self.send_body(data).await?;
+        }
+        Ok(buf.len())
+    }
+}
+
+/// The Read-Half of a Request over a shared HTTP/2 Connection, which
+/// returns the HTTP/2 Response as an HTTP/1.1 Response
+#[derive(Debug)]
+pub struct Http2Reader {
+    response: Option<oneshot::Receiver<ResponseFuture>>,
+    body: Option<RecvStream>,
+    chunked: bool,
+    pending: Vec<u8>,
+    offset: usize,
+}
+
+#[async_trait]
+impl ServiceReader for Http2Reader {
+    async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.offset < self.pending.len() {
+                let left = &self.pending[self.offset..];
+                let size = std::cmp::min(left.len(), buf.len());
+                buf[..size].copy_from_slice(&left[..size]);
+                self.offset += size;
+                return Ok(size);
+            }
+            self.pending.clear();
+            self.offset = 0;
+
+            if let Some(rx) = self.response.take() {
+                let response = rx.await.map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::BrokenPipe,
+                        "The Request was never sent",
+                    )
+                })?;
+                let response = response.await.map_err(io_error)?;
+
+                let (parts, body) = response.into_parts();
+                let (head, chunked) = response_head(&parts, body.is_end_stream());
+                self.pending = head;
+                if !body.is_end_stream() {
+                    self.body = Some(body);
+                    self.chunked = chunked;
+                }
+                continue;
+            }
+
+            let body = match self.body.as_mut() {
+                Some(b) => b,
+                None => return Ok(0),
+            };
+
+            match body.data().await {
+                Some(Ok(data)) => {
+                    body.flow_control()
+                        .release_capacity(data.len())
+                        .map_err(io_error)?;
+                    if data.is_empty() {
+                        continue;
+                    }
+
+                    if self.chunked {
+                        serialize_chunk(&data, &mut self.pending);
+                    } else {
+                        self.pending.extend_from_slice(&data);
+                    }
+                }
+                Some(Err(e)) => return Err(io_error(e)),
+                None => {
+                    if self.chunked {
+                        let trailers = body.trailers().await.map_err(io_error)?;
+                        serialize_last_chunk(trailers.as_ref(), &mut self.pending);
+                    }
+                    self.body = None;
+                }
+            };
+        }
+    }
+}
+
+/// A single Request to an Address of a Service over a shared HTTP/2
+/// Connection, which is sent and received as HTTP/1.1 by the Handler
+#[derive(Debug)]
+pub struct Http2Connection {
+    writer: Http2Writer,
+    reader: Http2Reader,
+    service: Arc<Service>,
+    endpoint: Endpoint,
+}
+
+impl Http2Connection {
+    /// Creates a new Connection for a single Request using the given
+    /// shared Connection
+    pub fn new(
+        sender: SendRequest<Bytes>,
+        protocol: ServiceProtocol,
+        service: Arc<Service>,
+        endpoint: Endpoint,
+    ) -> Self {
+        let (tx, rx) = oneshot::channel();
+        let authority = service.addresses()[endpoint.index()].clone();
+
+        Self {
+            writer: Http2Writer {
+                sender: Some(sender),
+                scheme: protocol.scheme(),
+                authority,
+                head: Vec::new(),
+                head_end: HeaderEnd::default(),
+                stream: None,
+                framing: RequestFraming::Done,
+                response: Some(tx),
+            },
+            reader: Http2Reader {
+                response: Some(rx),
+                body: None,
+                chunked: false,
+                pending: Vec::new(),
+                offset: 0,
+            },
+            service,
+            endpoint,
+        }
+    }
+}
+
+#[async_trait]
+impl ServiceConnection for Http2Connection {
+    async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf).await
+    }
+
+    async fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf).await
+    }
+
+    fn report_success(&self) {
+        self.endpoint.report_success();
+    }
+
+    fn report_failure(&self) {
+        self.service.report_failure(&self.endpoint);
+    }
+
+    type ReadHalf = Http2Reader;
+    type WriteHalf = Http2Writer;
+
+    fn halves_owned(self) -> (Self::ReadHalf, Self::WriteHalf) {
+        (self.reader, self.writer)
+    }
+}
+
+/// A Forwarder that sends all the Requests to an Address of a Service
+/// over a single shared HTTP/2 Connection, using TLS if the Protocol
+/// of the Service is `h2` and HTTP/2 with prior Knowledge otherwise
+#[derive(Debug, Clone, Default)]
+pub struct Http2Forwarder {
+    pool: Arc<Http2Pool>,
+}
+
+impl Http2Forwarder {
+    /// Creates a new Forwarder without any Connections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new Forwarder without any Connections, that uses the
+    /// given TLS-Configurations for Services using `h2`
+    pub fn with_tls(tls: Arc<UpstreamTls>) -> Self {
+        Self {
+            pool: Arc::new(Http2Pool::with_tls(tls)),
+        }
+    }
+
+    /// The Pool of shared Connections used by the Forwarder
+    pub fn pool(&self) -> &Http2Pool {
+        &self.pool
+    }
+}
+
+#[async_trait]
+impl Forwarder for Http2Forwarder {
+    type Connection = Http2Connection;
+    type ConnectError = rules::ConnectError;
+
+    async fn create_con(
+        &self,
+        rule: &Rule,
+        req: &Request<'_>,
+        info: &ConnectionInfo,
+    ) -> Result<Self::Connection, Self::ConnectError> {
+        let service = rule.service();
+        let protocol = match service.protocol() {
+            ServiceProtocol::H2 => ServiceProtocol::H2,
+            _ => ServiceProtocol::H2c,
+        };
+
+        let pool = self.pool.clone();
+        let tls = service.tls();
+        let (sender, endpoint) = service
+            .connect_using(req, info, |address| {
+                pool.connect(address.to_owned(), protocol, tls)
+            })
+            .await?;
+
+        Ok(Http2Connection::new(sender, protocol, service, endpoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Name, Shared};
+    use rules::Matcher;
+    use stream_httparse::{Headers, Method};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn parse_request_head() {
+        let (request, framing) = request_head(
+            b"POST /api?id=1 HTTP/1.1\r\nHost: example.com\r\nConnection: keep-alive\r\nContent-Length: 4\r\n\r\n",
+            "http",
+            "10.0.0.1:80",
+        )
+        .unwrap();
+
+        assert_eq!(http::Method::POST, request.method());
+        assert_eq!("http://example.com/api?id=1", request.uri().to_string());
+        assert_eq!(false, request.headers().contains_key("host"));
+        assert_eq!(false, request.headers().contains_key("connection"));
+        assert_eq!(true, matches!(framing, RequestFraming::Fixed(4)));
+    }
+
+    #[test]
+    fn parse_request_head_without_host() {
+        let (request, framing) = request_head(
+            b"GET / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nTE: trailers\r\n\r\n",
+            "https",
+            "10.0.0.1:443",
+        )
+        .unwrap();
+
+        assert_eq!("https://10.0.0.1:443/", request.uri().to_string());
+        assert_eq!("trailers", request.headers().get("te").unwrap());
+        assert_eq!(true, matches!(framing, RequestFraming::Chunked(_)));
+    }
+
+    #[test]
+    fn serialize_response_head() {
+        let (parts, _) = http::Response::builder()
+            .status(200)
+            .header("content-type", "application/grpc")
+            .body(())
+            .unwrap()
+            .into_parts();
+
+        let (head, chunked) = response_head(&parts, false);
+        assert_eq!(true, chunked);
+        assert_eq!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/grpc\r\ntransfer-encoding: chunked\r\n\r\n",
+            String::from_utf8(head).unwrap()
+        );
+
+        let (head, chunked) = response_head(&parts, true);
+        assert_eq!(false, chunked);
+        assert_eq!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/grpc\r\ncontent-length: 0\r\n\r\n",
+            String::from_utf8(head).unwrap()
+        );
+    }
+
+    /// Starts an HTTP/2 Server, that accepts a single Connection and
+    /// responds to every Request with its Body, followed by a Trailer
+    async fn echo_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        tokio::spawn(async move {
+            let (con, _) = listener.accept().await.unwrap();
+            let mut connection = h2::server::handshake(con).await.unwrap();
+
+            while let Some(Ok((request, mut respond))) = connection.accept().await {
+                tokio::spawn(async move {
+                    let path = request.uri().path().to_owned();
+                    let mut body = request.into_body();
+                    let mut data = Vec::new();
+                    while let Some(chunk) = body.data().await {
+                        let chunk = chunk.unwrap();
+                        body.flow_control().release_capacity(chunk.len()).unwrap();
+                        data.extend_from_slice(&chunk);
+                    }
+
+                    let response = http::Response::builder().status(200).body(()).unwrap();
+                    let mut stream = respond.send_response(response, false).unwrap();
+                    stream.send_data(Bytes::from(data), false).unwrap();
+
+                    let mut trailers = http::HeaderMap::new();
+                    trailers.insert("x-path", path.parse().unwrap());
+                    stream.send_trailers(trailers).unwrap();
+                });
+            }
+        });
+
+        address
+    }
+
+    async fn read_response(con: &mut Http2Connection) -> String {
+        let mut result = Vec::new();
+        let mut buffer = [0; 64];
+        loop {
+            let read = con.read(&mut buffer).await.unwrap();
+            if read == 0 {
+                break;
+            }
+            result.extend_from_slice(&buffer[..read]);
+        }
+        String::from_utf8(result).unwrap()
+    }
+
+    #[tokio::test]
+    async fn forward_requests() {
+        let address = echo_server().await;
+
+        let mut service = Service::new(Name::new("test", Group::Internal), vec![address]);
+        service.set_protocol(ServiceProtocol::H2c);
+        let rule = Rule::new(
+            Name::new("test-rule", Group::Internal),
+            1,
+            Matcher::PathPrefix("/".to_owned()),
+            vec![],
+            Shared::new(service),
+        );
+        let req = Request::new("HTTP/1.1", Method::POST, "/", Headers::new(), &[]);
+        let info = ConnectionInfo::default();
+
+        let forwarder = Http2Forwarder::new();
+        let mut first = forwarder.create_con(&rule, &req, &info).await.unwrap();
+        let mut second = forwarder.create_con(&rule, &req, &info).await.unwrap();
+        assert_eq!(1, forwarder.pool().len());
+
+        first
+            .write_all(b"POST /first HTTP/1.1\r\nHost: example.com\r\nContent-Length: 4\r\n\r\n")
+            .await
+            .unwrap();
+        second
+            .write_all(b"POST /second HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n")
+            .await
+            .unwrap();
+        first.write_all(b"data").await.unwrap();
+        second.write_all(b"5\r\nother\r\n0\r\n\r\n").await.unwrap();
+
+        assert_eq!(
+            "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n4\r\ndata\r\n0\r\nx-path: /first\r\n\r\n",
+            read_response(&mut first).await
+        );
+        assert_eq!(
+            "HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n5\r\nother\r\n0\r\nx-path: /second\r\n\r\n",
+            read_response(&mut second).await
+        );
+    }
+}
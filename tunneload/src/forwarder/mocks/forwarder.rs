@@ -1,15 +1,26 @@
+use std::sync::Mutex;
+
 use async_trait::async_trait;
 
 use crate::forwarder::{mocks::ServiceConnection, Forwarder as ForwarderTrait};
-use rules::Rule;
+use rules::{ConnectionInfo, Rule};
+use stream_httparse::Request;
 
 pub struct Forwarder {
-    con: ServiceConnection,
+    cons: Mutex<Vec<ServiceConnection>>,
 }
 
 impl Forwarder {
     pub fn new(con: ServiceConnection) -> Self {
-        Self { con }
+        Self::with_connections(vec![con])
+    }
+
+    /// Returns the given Connections in order, the last Connection
+    /// is returned for all the following Calls
+    pub fn with_connections(cons: Vec<ServiceConnection>) -> Self {
+        Self {
+            cons: Mutex::new(cons),
+        }
     }
 }
 
@@ -20,7 +31,16 @@ impl ForwarderTrait for Forwarder {
     type Connection = ServiceConnection;
     type ConnectError = MockError;
 
-    async fn create_con(&self, _rule: &Rule) -> Result<Self::Connection, Self::ConnectError> {
-        Ok(self.con.clone())
+    async fn create_con(
+        &self,
+        _rule: &Rule,
+        _req: &Request<'_>,
+        _info: &ConnectionInfo,
+    ) -> Result<Self::Connection, Self::ConnectError> {
+        let mut cons = self.cons.lock().unwrap();
+        if cons.len() > 1 {
+            return Ok(cons.remove(0));
+        }
+        cons.first().cloned().ok_or(())
     }
 }
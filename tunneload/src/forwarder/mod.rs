@@ -5,7 +5,16 @@ mod traits;
 pub use traits::*;
 
 mod basic;
-pub use basic::BasicForwarder;
+pub use basic::{BasicConnection, BasicForwarder, BasicReadHalf, BasicWriteHalf};
+
+mod http2;
+pub use http2::{Http2Connection, Http2Forwarder, Http2Pool, Http2Reader, Http2Writer};
+
+mod pool;
+pub use pool::ConnectionPool;
+
+mod tls;
+pub use tls::UpstreamTls;
 
 #[cfg(test)]
 pub mod mocks;
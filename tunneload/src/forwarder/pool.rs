@@ -0,0 +1,297 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, Weak},
+    time::{Duration, Instant},
+};
+
+use tokio::net::TcpStream;
+
+use lazy_static::lazy_static;
+use prometheus::Registry;
+
+lazy_static! {
+    static ref POOL_IDLE_CONNECTIONS: prometheus::IntGauge = prometheus::IntGauge::new(
+        "upstream_pool_idle_connections",
+        "The Number of idle Connections to Services that are kept for reuse",
+    )
+    .expect("Creating a Metric should never Fail");
+    static ref POOL_REQUESTS: prometheus::IntCounterVec = prometheus::IntCounterVec::new(
+        prometheus::Opts::new(
+            "upstream_pool_requests",
+            "The Number of Connections requested from the Pool, by whether an idle Connection could be reused",
+        ),
+        &["result"]
+    )
+    .expect("Creating a Metric should never Fail");
+}
+
+/// Registers all the Prometheus Metrics related to the Connection-Pool
+pub fn register_metrics(reg: &Registry) {
+    if let Err(e) = reg.register(Box::new(POOL_IDLE_CONNECTIONS.clone())) {
+        tracing::error!("Registering Metric: {:?}", e);
+    }
+    if let Err(e) = reg.register(Box::new(POOL_REQUESTS.clone())) {
+        tracing::error!("Registering Metric: {:?}", e);
+    }
+}
+
+/// The default Number of idle Connections kept for a single Address
+pub const DEFAULT_MAX_IDLE: usize = 32;
+/// The default Duration after which an idle Connection is closed
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// The shortest Interval in which the Pool is swept for expired Connections
+const MIN_SWEEP_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+struct IdleConnection {
+    stream: TcpStream,
+    since: Instant,
+}
+
+impl IdleConnection {
+    /// Checks if the Connection can still be used, which is not the
+    /// case if the Service closed it or sent unexpected Data
+    fn is_usable(&self) -> bool {
+        let mut buf = [0; 1];
+        matches!(
+            self.stream.try_read(&mut buf),
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock
+        )
+    }
+}
+
+/// A Pool of idle Connections to the Addresses of Services, which can
+/// be reused for later Requests.
+///
+/// Connections that were idle for longer than the Idle-Timeout are
+/// evicted whenever the Pool is used and periodically by `sweep`
+#[derive(Debug)]
+pub struct ConnectionPool {
+    idle: Mutex<HashMap<String, Vec<IdleConnection>>>,
+    max_idle: usize,
+    idle_timeout: Duration,
+}
+
+impl Default for ConnectionPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_IDLE, DEFAULT_IDLE_TIMEOUT)
+    }
+}
+
+impl ConnectionPool {
+    /// Creates a new empty Pool that keeps at most `max_idle`
+    /// Connections per Address, for at most `idle_timeout`
+    pub fn new(max_idle: usize, idle_timeout: Duration) -> Self {
+        Self {
+            idle: Mutex::new(HashMap::new()),
+            max_idle,
+            idle_timeout,
+        }
+    }
+
+    /// The maximum Number of idle Connections kept for a single Address
+    pub fn max_idle(&self) -> usize {
+        self.max_idle
+    }
+
+    /// The Duration after which an idle Connection is closed
+    pub fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    /// Removes all the Connections that exceeded the Idle-Timeout
+    fn evict(&self, idle: &mut HashMap<String, Vec<IdleConnection>>, now: Instant) {
+        let timeout = self.idle_timeout;
+        idle.retain(|_, cons| {
+            cons.retain(|c| now.duration_since(c.since) < timeout);
+            !cons.is_empty()
+        });
+    }
+
+    /// Removes all the Connections that exceeded the Idle-Timeout, without
+    /// taking or returning a Connection
+    pub fn evict_expired(&self) {
+        let mut idle = self
+            .idle
+            .lock()
+            .expect("The Lock should always be available");
+        self.evict(&mut idle, Instant::now());
+        Self::update_size(&idle);
+    }
+
+    fn update_size(idle: &HashMap<String, Vec<IdleConnection>>) {
+        let size: usize = idle.values().map(|cons| cons.len()).sum();
+        POOL_IDLE_CONNECTIONS.set(size as i64);
+    }
+
+    /// Takes an idle Connection to the given Address out of the Pool,
+    /// if there is one that can still be used
+    pub fn take(&self, address: &str) -> Option<TcpStream> {
+        let mut idle = self
+            .idle
+            .lock()
+            .expect("The Lock should always be available");
+        self.evict(&mut idle, Instant::now());
+
+        let mut result = None;
+        if let Some(cons) = idle.get_mut(address) {
+            while let Some(con) = cons.pop() {
+                if con.is_usable() {
+                    result = Some(con.stream);
+                    break;
+                }
+            }
+        }
+        Self::update_size(&idle);
+        drop(idle);
+
+        let label = if result.is_some() { "hit" } else { "miss" };
+        POOL_REQUESTS.with_label_values(&[label]).inc();
+
+        result
+    }
+
+    /// Returns the Connection to the given Address to the Pool, if the
+    /// Pool is already full for that Address, the Connection is closed
+    pub fn put(&self, address: String, stream: TcpStream) {
+        let now = Instant::now();
+
+        let mut idle = self
+            .idle
+            .lock()
+            .expect("The Lock should always be available");
+        self.evict(&mut idle, now);
+
+        let cons = idle.entry(address).or_default();
+        if cons.len() < self.max_idle {
+            cons.push(IdleConnection { stream, since: now });
+        }
+        Self::update_size(&idle);
+    }
+
+    /// Reuses an idle Connection to the given Address or establishes
+    /// a new one, if there is none
+    pub async fn connect(&self, address: String) -> std::io::Result<TcpStream> {
+        match self.take(&address) {
+            Some(stream) => Ok(stream),
+            None => TcpStream::connect(address).await,
+        }
+    }
+
+    /// The Number of idle Connections to the given Address
+    pub fn idle_count(&self, address: &str) -> usize {
+        let idle = self
+            .idle
+            .lock()
+            .expect("The Lock should always be available");
+        idle.get(address).map(|cons| cons.len()).unwrap_or(0)
+    }
+}
+
+/// Periodically evicts the expired Connections from the Pool, so they
+/// are closed even if the Pool is not used anymore. This stops once the
+/// Pool itself was dropped
+pub async fn sweep(pool: Weak<ConnectionPool>) {
+    let period = match pool.upgrade() {
+        Some(p) => (p.idle_timeout / 2).max(MIN_SWEEP_INTERVAL),
+        None => return,
+    };
+
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+
+        match pool.upgrade() {
+            Some(p) => p.evict_expired(),
+            None => return,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+    use super::*;
+
+    async fn connected() -> (String, TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let client = TcpStream::connect(&address).await.unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        (address, client, server)
+    }
+
+    #[tokio::test]
+    async fn reuse_connection() {
+        let pool = ConnectionPool::default();
+        let (address, client, _server) = connected().await;
+        let local = client.local_addr().unwrap();
+
+        pool.put(address.clone(), client);
+        assert_eq!(1, pool.idle_count(&address));
+
+        let reused = pool.take(&address).unwrap();
+        assert_eq!(local, reused.local_addr().unwrap());
+        assert_eq!(0, pool.idle_count(&address));
+        assert_eq!(true, pool.take(&address).is_none());
+    }
+
+    #[tokio::test]
+    async fn closed_connection() {
+        let pool = ConnectionPool::default();
+        let (address, client, server) = connected().await;
+
+        pool.put(address.clone(), client);
+        drop(server);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(true, pool.take(&address).is_none());
+    }
+
+    #[tokio::test]
+    async fn unexpected_data() {
+        let pool = ConnectionPool::default();
+        let (address, client, mut server) = connected().await;
+
+        pool.put(address.clone(), client);
+        server.write_all(b"HTTP/1.1 408").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(true, pool.take(&address).is_none());
+    }
+
+    #[tokio::test]
+    async fn idle_timeout() {
+        let pool = ConnectionPool::new(4, Duration::from_millis(0));
+        let (address, client, _server) = connected().await;
+
+        pool.put(address.clone(), client);
+        assert_eq!(true, pool.take(&address).is_none());
+    }
+
+    #[tokio::test]
+    async fn sweep_evicts_without_use() {
+        let pool = std::sync::Arc::new(ConnectionPool::new(4, Duration::from_millis(20)));
+        tokio::spawn(sweep(std::sync::Arc::downgrade(&pool)));
+        let (address, client, _server) = connected().await;
+
+        pool.put(address.clone(), client);
+        assert_eq!(1, pool.idle_count(&address));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(0, pool.idle_count(&address));
+    }
+
+    #[tokio::test]
+    async fn max_idle() {
+        let pool = ConnectionPool::new(1, DEFAULT_IDLE_TIMEOUT);
+        let (address, first, _first_server) = connected().await;
+        let (_, second, _second_server) = connected().await;
+
+        pool.put(address.clone(), first);
+        pool.put(address.clone(), second);
+        assert_eq!(1, pool.idle_count(&address));
+    }
+}
@@ -0,0 +1,390 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    io::{BufReader, Cursor},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use rules::ServiceTls;
+use rustls::{
+    client::{ServerCertVerified, ServerCertVerifier},
+    Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore, ServerName,
+};
+use tokio::net::TcpStream;
+use tokio_rustls::{client::TlsStream, TlsConnector};
+
+/// The Name used for SNI if the Certificate of the Service is not
+/// verified and its Address does not contain a valid Domain
+const UNVERIFIED_SERVER_NAME: &str = "localhost";
+
+fn invalid_data<E>(e: E) -> std::io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+}
+
+/// Accepts every Certificate presented by a Service
+struct NoVerification;
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn parse_certs(raw: &str) -> std::io::Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(Cursor::new(raw.as_bytes()));
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    if certs.is_empty() {
+        return Err(invalid_data("No Certificate found"));
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn parse_key(raw: &str) -> std::io::Result<PrivateKey> {
+    let mut reader = BufReader::new(Cursor::new(raw.as_bytes()));
+    loop {
+        match rustls_pemfile::read_one(&mut reader)? {
+            Some(rustls_pemfile::Item::RSAKey(key))
+            | Some(rustls_pemfile::Item::PKCS8Key(key))
+            | Some(rustls_pemfile::Item::ECKey(key)) => return Ok(PrivateKey(key)),
+            Some(_) => {}
+            None => return Err(invalid_data("No Private-Key found")),
+        };
+    }
+}
+
+fn root_store(ca: Option<&str>) -> std::io::Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    match ca {
+        Some(ca) => {
+            for cert in parse_certs(ca)? {
+                roots.add(&cert).map_err(|e| invalid_data(e.to_string()))?;
+            }
+        }
+        None => {
+            roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|ta| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject,
+                    ta.spki,
+                    ta.name_constraints,
+                )
+            }));
+        }
+    };
+    Ok(roots)
+}
+
+/// Creates the Client-Configuration for the given TLS-Configuration of a
+/// Service, which negotiates HTTP/2 if `http2` is set and HTTP/1.1
+/// otherwise
+pub fn create_config(tls: &ServiceTls, http2: bool) -> std::io::Result<ClientConfig> {
+    let builder = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_store(tls.ca.as_deref())?);
+
+    let mut config = match tls.client_auth() {
+        Some((cert, key)) => builder
+            .with_single_cert(parse_certs(cert)?, parse_key(key)?)
+            .map_err(|e| invalid_data(e.to_string()))?,
+        None => builder.with_no_client_auth(),
+    };
+
+    if tls.insecure_skip_verify {
+        config
+            .dangerous()
+            .set_certificate_verifier(Arc::new(NoVerification));
+    }
+
+    config.alpn_protocols = if http2 {
+        vec![b"h2".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+    Ok(config)
+}
+
+/// Determines the Name of the Service, used for SNI and the Verification
+/// of its Certificate, from the Configuration or the Host of the Address
+pub fn server_name(address: &str, tls: &ServiceTls) -> std::io::Result<ServerName> {
+    let host = match tls.server_name.as_deref() {
+        Some(name) => name,
+        None => address
+            .rsplit_once(':')
+            .map(|(host, _)| host)
+            .unwrap_or(address),
+    };
+
+    match ServerName::try_from(host) {
+        Ok(name) => Ok(name),
+        Err(_) if tls.insecure_skip_verify => Ok(ServerName::try_from(UNVERIFIED_SERVER_NAME)
+            .expect("The Fallback should always be a valid Name")),
+        Err(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid Server-Name '{}', a Server-Name needs to be configured for the Service",
+                host
+            ),
+        )),
+    }
+}
+
+/// Establishes the TLS-Connections to Services, the Client-Configuration
+/// is only created once for every distinct TLS-Configuration and then
+/// reused for all later Connections
+#[derive(Default)]
+pub struct UpstreamTls {
+    configs: Mutex<HashMap<(ServiceTls, bool), Arc<ClientConfig>>>,
+}
+
+impl Debug for UpstreamTls {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UpstreamTls ()")
+    }
+}
+
+impl UpstreamTls {
+    /// Creates a new Instance without any Configurations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the Client-Configuration for the given TLS-Configuration
+    pub fn config(&self, tls: &ServiceTls, http2: bool) -> std::io::Result<Arc<ClientConfig>> {
+        let mut configs = self
+            .configs
+            .lock()
+            .expect("The Lock should always be available");
+
+        let key = (tls.clone(), http2);
+        if let Some(config) = configs.get(&key) {
+            return Ok(config.clone());
+        }
+
+        let config = Arc::new(create_config(tls, http2)?);
+        configs.insert(key, config.clone());
+        Ok(config)
+    }
+
+    /// Performs the TLS-Handshake with the Service over the given
+    /// Connection to the Address, if no TLS-Configuration is given, the
+    /// default one is used
+    pub async fn connect(
+        &self,
+        stream: TcpStream,
+        address: &str,
+        tls: Option<&ServiceTls>,
+        http2: bool,
+    ) -> std::io::Result<TlsStream<TcpStream>> {
+        let default = ServiceTls::default();
+        let tls = tls.unwrap_or(&default);
+
+        let config = self.config(tls, http2)?;
+        let name = server_name(address, tls)?;
+        TlsConnector::from(config).connect(name, stream).await
+    }
+}
+
+#[cfg(test)]
+pub mod testing {
+    use acme2::openssl::{
+        asn1::Asn1Time,
+        bn::BigNum,
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::PKey,
+        x509::{extension::SubjectAlternativeName, X509Name, X509},
+    };
+
+    /// Generates a self-signed Certificate for the given Domain, returns
+    /// the PEM-encoded Certificate and Private-Key
+    pub fn self_signed(domain: &str) -> (String, String) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, domain).unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        let san = SubjectAlternativeName::new()
+            .dns(domain)
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(san).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+
+        let cert = builder.build().to_pem().unwrap();
+        let key = key.private_key_to_pem_pkcs8().unwrap();
+        (
+            String::from_utf8(cert).unwrap(),
+            String::from_utf8(key).unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn server_name_from_address() {
+        let tls = ServiceTls::default();
+        assert_eq!(
+            ServerName::try_from("example.com").unwrap(),
+            server_name("example.com:443", &tls).unwrap()
+        );
+        assert_eq!(true, server_name("10.0.0.1:443", &tls).is_err());
+    }
+
+    #[test]
+    fn server_name_configured() {
+        let mut tls = ServiceTls {
+            server_name: Some("internal.example.com".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ServerName::try_from("internal.example.com").unwrap(),
+            server_name("10.0.0.1:443", &tls).unwrap()
+        );
+
+        tls.server_name = None;
+        tls.insecure_skip_verify = true;
+        assert_eq!(true, server_name("10.0.0.1:443", &tls).is_ok());
+    }
+
+    #[test]
+    fn invalid_config() {
+        let tls = ServiceTls {
+            ca: Some("invalid".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(true, create_config(&tls, false).is_err());
+
+        let tls = ServiceTls {
+            client_cert: Some("invalid".to_owned()),
+            client_key: Some("invalid".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(true, create_config(&tls, false).is_err());
+    }
+
+    /// Starts a TLS-Server with the given Certificate, that requires a
+    /// Client-Certificate if a CA for it is given, and answers with "ok"
+    async fn tls_server(cert: &str, key: &str, client_ca: Option<&str>) -> String {
+        let builder = rustls::ServerConfig::builder().with_safe_defaults();
+        let builder = match client_ca {
+            Some(ca) => builder.with_client_cert_verifier(
+                rustls::server::AllowAnyAuthenticatedClient::new(root_store(Some(ca)).unwrap()),
+            ),
+            None => builder.with_no_client_auth(),
+        };
+        let config = builder
+            .with_single_cert(parse_certs(cert).unwrap(), parse_key(key).unwrap())
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (con, _) = listener.accept().await.unwrap();
+            if let Ok(mut stream) = acceptor.accept(con).await {
+                let _ = stream.write_all(b"ok").await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        address
+    }
+
+    async fn connect(address: &str, tls: &ServiceTls) -> std::io::Result<Vec<u8>> {
+        let stream = TcpStream::connect(address).await?;
+        let mut stream = UpstreamTls::new()
+            .connect(stream, address, Some(tls), false)
+            .await?;
+
+        let mut result = Vec::new();
+        stream.read_to_end(&mut result).await?;
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn custom_ca() {
+        let (cert, key) = testing::self_signed("service.local");
+        let address = tls_server(&cert, &key, None).await;
+
+        let tls = ServiceTls {
+            server_name: Some("service.local".to_owned()),
+            ca: Some(cert),
+            ..Default::default()
+        };
+        assert_eq!(b"ok".to_vec(), connect(&address, &tls).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn untrusted_certificate() {
+        let (cert, key) = testing::self_signed("service.local");
+        let address = tls_server(&cert, &key, None).await;
+
+        let tls = ServiceTls {
+            server_name: Some("service.local".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(true, connect(&address, &tls).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn insecure_skip_verify() {
+        let (cert, key) = testing::self_signed("service.local");
+        let address = tls_server(&cert, &key, None).await;
+
+        let tls = ServiceTls {
+            insecure_skip_verify: true,
+            ..Default::default()
+        };
+        assert_eq!(b"ok".to_vec(), connect(&address, &tls).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn client_certificate() {
+        let (cert, key) = testing::self_signed("service.local");
+        let (client_cert, client_key) = testing::self_signed("client.local");
+        let address = tls_server(&cert, &key, Some(&client_cert)).await;
+
+        let tls = ServiceTls {
+            server_name: Some("service.local".to_owned()),
+            ca: Some(cert),
+            client_cert: Some(client_cert),
+            client_key: Some(client_key),
+            ..Default::default()
+        };
+        assert_eq!(b"ok".to_vec(), connect(&address, &tls).await.unwrap());
+    }
+}
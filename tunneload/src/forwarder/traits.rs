@@ -4,7 +4,7 @@ use tokio::{
     net::tcp::{OwnedReadHalf, OwnedWriteHalf},
 };
 
-use rules::Rule;
+use rules::{ConnectionInfo, Rule};
 
 use stream_httparse::Request;
 
@@ -59,6 +59,23 @@ pub trait ServiceConnection: Send + Sync + 'static {
         Ok(())
     }
 
+    /// Reports that the Request sent over this Connection received
+    /// a Response, which is used for passive Outlier-Detection
+    fn report_success(&self) {}
+
+    /// Reports that the Request sent over this Connection failed,
+    /// because the Connection was reset or closed before a Response
+    /// was received
+    fn report_failure(&self) {}
+
+    /// Releases the Connection after a Response was completely received,
+    /// so that it can be reused for later Requests
+    fn release(self)
+    where
+        Self: Sized,
+    {
+    }
+
     /// The Writer Half of the Service-Connection
     type WriteHalf: ServiceWriter;
     /// The Reader Half of the Service-Connection
@@ -98,8 +115,14 @@ pub trait Forwarder {
     type ConnectError: std::fmt::Debug + Send;
 
     /// Attempts to create a new Connection based on the Data provided
-    /// by the Rule
-    async fn create_con(&self, rule: &Rule) -> Result<Self::Connection, Self::ConnectError>;
+    /// by the Rule, for the given Request that was received on a
+    /// Connection described by the given Info
+    async fn create_con(
+        &self,
+        rule: &Rule,
+        req: &Request<'_>,
+        info: &ConnectionInfo,
+    ) -> Result<Self::Connection, Self::ConnectError>;
 }
 
 #[async_trait]
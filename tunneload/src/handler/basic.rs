@@ -3,9 +3,14 @@ use std::{
     sync::Arc,
 };
 
-use crate::{forwarder::Forwarder, internal_services::Internals, websockets};
+use crate::{
+    forwarder::Forwarder,
+    handler::{ForwardedHeaders, Timeouts},
+    internal_services::Internals,
+    websockets,
+};
 use general_traits::{Handler, Receiver, Sender};
-use rules::ReadManager;
+use rules::{ConnectionInfo, ReadManager};
 
 use stream_httparse::streaming_parser::{ReqParser, RespParser};
 
@@ -16,9 +21,12 @@ use prometheus::Registry;
 
 use tracing::Level;
 
-use self::http_handler::Context;
+use self::{body::Body, http_handler::Context};
 
+mod body;
+pub(crate) mod chunked;
 mod error_messages;
+pub(crate) mod header_end;
 mod request;
 
 mod http_handler;
@@ -47,12 +55,18 @@ lazy_static! {
 
 /// A Basic Handler that parses the Requests, matches them against
 /// all the known Rules, applies all the matching middlewares accordingly
-/// and forwards the Request using the provided Forwarder
+/// and forwards the Request using the provided Forwarder.
+///
+/// The Bodies of the Requests are streamed to the Services, so
+/// Middlewares only get to see the Head of a Request
 #[derive(Clone)]
 pub struct BasicHandler<F> {
     rules: ReadManager,
     forwarder: F,
     internals: Arc<Internals>,
+    timeouts: Timeouts,
+    max_body_size: Option<usize>,
+    forwarded: ForwardedHeaders,
 }
 
 impl<F> Debug for BasicHandler<F> {
@@ -88,8 +102,32 @@ where
             rules: rules_manager,
             forwarder,
             internals: Arc::new(internals),
+            timeouts: Timeouts::default(),
+            max_body_size: None,
+            forwarded: ForwardedHeaders::default(),
         }
     }
+
+    /// Replaces the default Timeouts with the given ones
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Limits the Size of the Bodies of Requests to the given Number
+    /// of Bytes, larger Requests are rejected.
+    /// By default the Size is not limited
+    pub fn with_max_body_size(mut self, size: Option<usize>) -> Self {
+        self.max_body_size = size;
+        self
+    }
+
+    /// Replaces the default Forwarded-Headers, which trust no Proxies,
+    /// with the given ones
+    pub fn with_forwarded_headers(mut self, forwarded: ForwardedHeaders) -> Self {
+        self.forwarded = forwarded;
+        self
+    }
 }
 
 #[async_trait]
@@ -105,6 +143,11 @@ where
     {
         let mut keep_alive = true;
 
+        let conn_info = ConnectionInfo::new(receiver.peer_ip())
+            .with_addresses(receiver.peer_address(), receiver.local_address())
+            .with_tls(receiver.is_tls())
+            .with_entrypoint(receiver.entrypoint().map(|name| name.to_owned()));
+
         let mut req_buf = [0; 2048];
         let mut req_offset = 0;
         let mut req_parser = ReqParser::new_capacity(2048);
@@ -112,31 +155,55 @@ where
         let mut resp_buf = [0; 2048];
         let mut resp_parser = RespParser::new_capacity(2048);
 
+        // The Timeouts of the Service of the previous Request also apply
+        // to receiving the next Request on the same Connection
+        let mut timeouts = self.timeouts.clone();
+        let mut idle = false;
         while keep_alive {
-            let request =
-                match request::receive(&mut req_parser, &mut receiver, &mut req_buf, req_offset)
-                    .await
-                {
-                    Ok((r, n_offset)) => {
-                        req_offset = n_offset;
-                        r
-                    }
-                    Err(e) => {
-                        match e {
-                            request::RecvReqError::EOF => {
-                                tracing::event!(Level::DEBUG, "Received EOF");
-                            }
-                            _ => {
-                                tracing::event!(Level::ERROR, "Received Invalid Request: {:?}", e);
-                            }
-                        };
-                        error_messages::bad_request(&mut sender).await;
-                        return;
-                    }
-                };
+            let mut request = match request::receive(
+                &mut req_parser,
+                &mut receiver,
+                &mut req_buf,
+                req_offset,
+                &timeouts,
+                idle,
+            )
+            .await
+            {
+                Ok((r, n_offset)) => {
+                    req_offset = n_offset;
+                    r
+                }
+                Err(request::RecvReqError::Timeout(request::ReadPhase::Idle)) => {
+                    // A Client could already be sending its next Request,
+                    // which would receive an unsolicited Response as its
+                    // Answer, so the Connection is closed silently
+                    tracing::event!(Level::DEBUG, "Closing idle Keep-Alive Connection");
+                    return;
+                }
+                Err(request::RecvReqError::Timeout(phase)) => {
+                    tracing::event!(Level::DEBUG, "Timed out receiving Request: {:?}", phase);
+                    error_messages::request_timeout(&mut sender).await;
+                    return;
+                }
+                Err(e) => {
+                    match e {
+                        request::RecvReqError::EOF => {
+                            tracing::event!(Level::DEBUG, "Received EOF");
+                        }
+                        _ => {
+                            tracing::event!(Level::ERROR, "Received Invalid Request: {:?}", e);
+                        }
+                    };
+                    error_messages::bad_request(&mut sender).await;
+                    return;
+                }
+            };
+            idle = true;
             keep_alive = request.is_keep_alive();
+            let req_info = self.forwarded.apply(&mut request, &conn_info);
 
-            let matched = match self.rules.match_req(&request) {
+            let matched = match self.rules.match_req(&request, &req_info) {
                 Some(m) => m,
                 None => {
                     tracing::event!(Level::ERROR, "No Rule matched the Request: {:?}", request);
@@ -144,18 +211,45 @@ where
                     return;
                 }
             };
+            timeouts = self.timeouts.for_service(&matched.service());
 
             // Check if the received Request is the starting Handshake of a Websocket connection
             if websockets::is_websocket(&request) {
-                ws_handler::handle(id, request, receiver, sender, matched, &mut resp_parser).await;
+                ws_handler::handle(
+                    id,
+                    request,
+                    receiver,
+                    sender,
+                    matched,
+                    &req_info,
+                    &mut resp_parser,
+                )
+                .await;
 
                 return;
             }
 
+            let mut body = match Body::new(
+                &request,
+                &mut receiver,
+                &mut req_buf,
+                req_offset,
+                self.max_body_size,
+                timeouts.body_read,
+            ) {
+                Ok(b) => b,
+                Err(e) => {
+                    tracing::event!(Level::ERROR, "Received Invalid Request-Body: {:?}", e);
+                    e.send_response(&mut sender).await;
+                    return;
+                }
+            };
+
             let internals = self.internals.clone();
             if http_handler::handle(
                 id,
                 request,
+                &mut body,
                 matched,
                 &mut resp_parser,
                 &mut resp_buf,
@@ -163,6 +257,8 @@ where
                     sender: &mut sender,
                     forwarder: &self.forwarder,
                     internals,
+                    conn_info: &req_info,
+                    timeouts: &timeouts,
                 },
             )
             .await
@@ -170,6 +266,7 @@ where
             {
                 return;
             }
+            req_offset = body.left_over();
 
             // Clearing the Parser and therefore making it ready
             // parse a new Request without needing to allocate
@@ -185,7 +282,7 @@ mod tests {
     use general::Group;
     use general::Name;
     use general::Shared;
-    use rules::{Matcher, Rule, Service};
+    use rules::{Action, Matcher, Middleware, Rule, Service};
 
     use super::*;
 
@@ -259,4 +356,271 @@ mod tests {
             String::from_utf8(sender.get_combined_data())
         );
     }
+
+    #[tokio::test]
+    async fn basic_handle_client_ip_match() {
+        let mut tmp_service_con = MockServiceConnection::new();
+        tmp_service_con.add_chunk("HTTP/1.1 200 OK\r\n\r\n".as_bytes().to_vec());
+        let tmp_forwarder = MockForwarder::new(tmp_service_con);
+
+        let mut receiver = MockReceiver::new();
+        receiver.add_chunk("GET /api/test/ HTTP/1.1\r\n\r\n".as_bytes().to_vec());
+        receiver.set_peer_ip("10.0.0.12".parse().unwrap());
+        let sender = MockSender::new();
+
+        let (read, mut write) = rules::new();
+        write.set_single(Rule::new(
+            Name::new("test-rule", Group::Internal),
+            12,
+            Matcher::ClientIP("10.0.0.0/8".parse().unwrap()),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        ));
+
+        let handler: BasicHandler<MockForwarder> =
+            BasicHandler::new(read.clone(), tmp_forwarder, Internals::new(), None);
+
+        handler.handle(12, receiver, sender.clone()).await;
+
+        assert_eq!(
+            Ok("HTTP/1.1 200 OK\r\n\r\n".to_owned()),
+            String::from_utf8(sender.get_combined_data())
+        );
+    }
+
+    #[tokio::test]
+    async fn basic_handle_client_ip_no_match() {
+        let mut tmp_service_con = MockServiceConnection::new();
+        tmp_service_con.add_chunk("HTTP/1.1 200 OK\r\n\r\n".as_bytes().to_vec());
+        let tmp_forwarder = MockForwarder::new(tmp_service_con);
+
+        let mut receiver = MockReceiver::new();
+        receiver.add_chunk("GET /api/test/ HTTP/1.1\r\n\r\n".as_bytes().to_vec());
+        receiver.set_peer_ip("192.168.0.12".parse().unwrap());
+        let sender = MockSender::new();
+
+        let (read, mut write) = rules::new();
+        write.set_single(Rule::new(
+            Name::new("test-rule", Group::Internal),
+            12,
+            Matcher::ClientIP("10.0.0.0/8".parse().unwrap()),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        ));
+
+        let handler: BasicHandler<MockForwarder> =
+            BasicHandler::new(read.clone(), tmp_forwarder, Internals::new(), None);
+
+        handler.handle(12, receiver, sender.clone()).await;
+
+        assert_eq!(
+            Ok("HTTP/1.1 404 Not Found\r\n\r\nNot Found".to_owned()),
+            String::from_utf8(sender.get_combined_data())
+        );
+    }
+
+    #[tokio::test]
+    async fn basic_handle_retries_idempotent() {
+        let mut tmp_service_con = MockServiceConnection::new();
+        tmp_service_con.add_chunk("HTTP/1.1 200 OK\r\n\r\n".as_bytes().to_vec());
+        let tmp_forwarder =
+            MockForwarder::with_connections(vec![MockServiceConnection::new(), tmp_service_con]);
+
+        let mut receiver = MockReceiver::new();
+        receiver.add_chunk("GET /api/test/ HTTP/1.1\r\n\r\n".as_bytes().to_vec());
+        let sender = MockSender::new();
+
+        let (read, mut write) = rules::new();
+        write.set_single(Rule::new(
+            Name::new("test-rule", Group::Internal),
+            12,
+            Matcher::PathPrefix("/api".to_owned()),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        ));
+
+        let handler: BasicHandler<MockForwarder> =
+            BasicHandler::new(read.clone(), tmp_forwarder, Internals::new(), None);
+
+        handler.handle(12, receiver, sender.clone()).await;
+
+        assert_eq!(
+            Ok("HTTP/1.1 200 OK\r\n\r\n".to_owned()),
+            String::from_utf8(sender.get_combined_data())
+        );
+    }
+
+    #[tokio::test]
+    async fn basic_handle_no_retry_non_idempotent() {
+        let mut tmp_service_con = MockServiceConnection::new();
+        tmp_service_con.add_chunk("HTTP/1.1 200 OK\r\n\r\n".as_bytes().to_vec());
+        let tmp_forwarder =
+            MockForwarder::with_connections(vec![MockServiceConnection::new(), tmp_service_con]);
+
+        let mut receiver = MockReceiver::new();
+        receiver.add_chunk("POST /api/test/ HTTP/1.1\r\n\r\n".as_bytes().to_vec());
+        let sender = MockSender::new();
+
+        let (read, mut write) = rules::new();
+        write.set_single(Rule::new(
+            Name::new("test-rule", Group::Internal),
+            12,
+            Matcher::PathPrefix("/api".to_owned()),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        ));
+
+        let handler: BasicHandler<MockForwarder> =
+            BasicHandler::new(read.clone(), tmp_forwarder, Internals::new(), None);
+
+        handler.handle(12, receiver, sender.clone()).await;
+
+        assert_eq!(
+            Ok("HTTP/1.1 500 Internal Server Error\r\n\r\nInternal Server Error".to_owned()),
+            String::from_utf8(sender.get_combined_data())
+        );
+    }
+
+    #[tokio::test]
+    async fn basic_handle_expect_continue() {
+        let mut tmp_service_con = MockServiceConnection::new();
+        tmp_service_con.add_chunk("HTTP/1.1 200 OK\r\n\r\n".as_bytes().to_vec());
+        let tmp_forwarder = MockForwarder::new(tmp_service_con);
+
+        let mut receiver = MockReceiver::new();
+        receiver.add_chunk(
+            "PUT /api/test/ HTTP/1.1\r\nContent-Length: 4\r\nExpect: 100-continue\r\n\r\n"
+                .as_bytes()
+                .to_vec(),
+        );
+        receiver.add_chunk("data".as_bytes().to_vec());
+        let sender = MockSender::new();
+
+        let (read, mut write) = rules::new();
+        write.set_single(Rule::new(
+            Name::new("test-rule", Group::Internal),
+            12,
+            Matcher::PathPrefix("/api".to_owned()),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        ));
+
+        let handler: BasicHandler<MockForwarder> =
+            BasicHandler::new(read.clone(), tmp_forwarder, Internals::new(), None);
+
+        handler.handle(12, receiver, sender.clone()).await;
+
+        assert_eq!(
+            Ok("HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\n\r\n".to_owned()),
+            String::from_utf8(sender.get_combined_data())
+        );
+    }
+
+    #[tokio::test]
+    async fn basic_handle_body_too_large() {
+        let tmp_forwarder = MockForwarder::new(MockServiceConnection::new());
+
+        let mut receiver = MockReceiver::new();
+        receiver.add_chunk(
+            "POST /api/test/ HTTP/1.1\r\nContent-Length: 10\r\n\r\n"
+                .as_bytes()
+                .to_vec(),
+        );
+        let sender = MockSender::new();
+
+        let (read, mut write) = rules::new();
+        write.set_single(Rule::new(
+            Name::new("test-rule", Group::Internal),
+            12,
+            Matcher::PathPrefix("/api".to_owned()),
+            vec![],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        ));
+
+        let handler: BasicHandler<MockForwarder> =
+            BasicHandler::new(read.clone(), tmp_forwarder, Internals::new(), None)
+                .with_max_body_size(Some(8));
+
+        handler.handle(12, receiver, sender.clone()).await;
+
+        assert_eq!(
+            Ok(
+                "HTTP/1.1 413 Request Entity Too Large\r\nConnection: close\r\n\r\nPayload Too Large"
+                    .to_owned()
+            ),
+            String::from_utf8(sender.get_combined_data())
+        );
+    }
+
+    #[tokio::test]
+    async fn basic_handle_compress_chunked() {
+        let mut tmp_service_con = MockServiceConnection::new();
+        tmp_service_con.add_chunk(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nTest\r\n"
+                .as_bytes()
+                .to_vec(),
+        );
+        tmp_service_con.add_chunk("5\r\n Data\r\n0\r\n\r\n".as_bytes().to_vec());
+        let tmp_forwarder = MockForwarder::new(tmp_service_con);
+
+        let mut receiver = MockReceiver::new();
+        receiver.add_chunk(
+            "GET /api/test/ HTTP/1.1\r\nAccept-Encoding: gzip\r\n\r\n"
+                .as_bytes()
+                .to_vec(),
+        );
+        let sender = MockSender::new();
+
+        let (read, mut write) = rules::new();
+        write.set_single(Rule::new(
+            Name::new("test-rule", Group::Internal),
+            12,
+            Matcher::PathPrefix("/api".to_owned()),
+            vec![Shared::new(Middleware::new(
+                Name::new("compress", Group::Internal),
+                Action::Compress,
+            ))],
+            Shared::new(Service::new(
+                Name::new("test-service", Group::File {}),
+                vec![],
+            )),
+        ));
+
+        let handler: BasicHandler<MockForwarder> =
+            BasicHandler::new(read.clone(), tmp_forwarder, Internals::new(), None);
+
+        handler.handle(12, receiver, sender.clone()).await;
+
+        let data = sender.get_combined_data();
+        let head_end = data.windows(4).position(|w| w == b"\r\n\r\n").unwrap() + 4;
+        let head = String::from_utf8(data[..head_end].to_vec()).unwrap();
+        assert_eq!(true, head.contains("Transfer-Encoding: chunked"));
+        assert_eq!(true, head.contains("content-encoding: gzip"));
+
+        let mut framing = chunked::ChunkedFraming::new();
+        let mut body = Vec::new();
+        framing
+            .update_with(&data[head_end..], |d| body.extend_from_slice(d))
+            .unwrap();
+        assert_eq!(true, framing.is_done());
+        assert_eq!(&[0x1f, 0x8b], &body[..2]);
+    }
 }
@@ -0,0 +1,466 @@
+use std::time::Duration;
+
+use general_traits::{Receiver, Sender};
+use stream_httparse::Request;
+
+use crate::forwarder::ServiceConnection;
+
+use super::{chunked::ChunkedFraming, error_messages};
+
+/// How the Body of a Request is delimited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyLength {
+    /// The Request has no Body
+    Empty,
+    /// The Body has the given Number of Bytes
+    Fixed(usize),
+    /// The Body is sent using the Chunked Transfer-Encoding
+    Chunked,
+}
+
+impl BodyLength {
+    /// Determines the Length of the Body from the Headers of the Request
+    pub fn from_request(req: &Request<'_>) -> Result<Self, BodyError> {
+        let headers = req.headers();
+
+        if let Some(encoding) = headers.get("Transfer-Encoding") {
+            let encoding = encoding.to_string().to_ascii_lowercase();
+            // Chunked has to be the last Encoding, otherwise the End
+            // of the Body can not be determined
+            return match encoding.rsplit(',').next().map(|e| e.trim()) {
+                Some("chunked") => Ok(Self::Chunked),
+                _ => Err(BodyError::InvalidLength),
+            };
+        }
+
+        match headers.get("Content-Length") {
+            Some(raw) => match raw.to_string().trim().parse::<usize>() {
+                Ok(0) => Ok(Self::Empty),
+                Ok(length) => Ok(Self::Fixed(length)),
+                Err(_) => Err(BodyError::InvalidLength),
+            },
+            None => Ok(Self::Empty),
+        }
+    }
+}
+
+/// Removes the Content-Length of a Request whose Body is chunked, as the
+/// Chunks take precedence and a Service that reads the Content-Length
+/// instead would treat the Rest of the Body as another Request
+pub fn remove_conflicting_length(req: &mut Request<'_>, length: BodyLength) {
+    if length == BodyLength::Chunked {
+        req.header_mut().remove("Content-Length");
+    }
+}
+
+/// Checks if the Client waits for a `100 Continue` before it sends
+/// the Body of the Request
+pub fn expects_continue(req: &Request<'_>) -> bool {
+    matches!(
+        req.headers().get("Expect"),
+        Some(value) if value.to_string().eq_ignore_ascii_case("100-continue")
+    )
+}
+
+/// Tells the Client, that is waiting for a `100 Continue`, to start
+/// sending the Body
+pub async fn send_continue<S>(sender: &mut S)
+where
+    S: Sender,
+{
+    sender.send(b"HTTP/1.1 100 Continue\r\n\r\n").await;
+}
+
+#[derive(Debug)]
+pub enum BodyError {
+    /// The Content-Length or Transfer-Encoding of the Request is invalid
+    InvalidLength,
+    /// The Framing of a Chunk is invalid
+    InvalidChunk,
+    /// The Body exceeds the configured maximum Size
+    TooLarge,
+    /// The Client closed the Connection before the Body was received
+    ConnectionClosed,
+    ReadingCon(std::io::Error),
+    WritingCon(std::io::Error),
+    /// No Data of the Body was received within the Body-Read Timeout
+    Timeout,
+}
+
+impl BodyError {
+    /// Sends the matching Error-Response to the Client, if the Client
+    /// is still there to receive it
+    pub async fn send_response<S>(&self, sender: &mut S)
+    where
+        S: Sender,
+    {
+        match self {
+            Self::InvalidLength | Self::InvalidChunk => error_messages::bad_request(sender).await,
+            Self::TooLarge => error_messages::payload_too_large(sender).await,
+            Self::Timeout => error_messages::request_timeout(sender).await,
+            Self::WritingCon(e) => {
+                tracing::error!("Writing Body to Service: {}", e);
+                error_messages::internal_server_error(sender).await;
+            }
+            Self::ReadingCon(e) => tracing::debug!("Reading Body from Client: {}", e),
+            Self::ConnectionClosed => {}
+        };
+    }
+}
+
+/// The Body of a Request, which is received from the Client while it
+/// is being forwarded, instead of buffering it in Memory.
+///
+/// The Buffer is shared with the Parsing of the Requests, so any Data
+/// after the Body is left in it for the next Request
+pub struct Body<'a, R> {
+    rx: &'a mut R,
+    buffer: &'a mut [u8],
+    /// The Number of Bytes at the Start of the Buffer that were already
+    /// received but not processed yet
+    buffered: usize,
+    length: BodyLength,
+    /// The Number of Bytes left for a Body with a fixed Length
+    remaining: usize,
+    framing: ChunkedFraming,
+    started: bool,
+    max_size: Option<usize>,
+    read_timeout: Duration,
+}
+
+impl<'a, R> Body<'a, R>
+where
+    R: Receiver + Send,
+{
+    /// Creates the Body for the given Request, starting with the Data
+    /// that is already in the Buffer
+    pub fn new(
+        req: &Request<'_>,
+        rx: &'a mut R,
+        buffer: &'a mut [u8],
+        buffered: usize,
+        max_size: Option<usize>,
+        read_timeout: Duration,
+    ) -> Result<Self, BodyError> {
+        let length = BodyLength::from_request(req)?;
+        let remaining = match length {
+            BodyLength::Fixed(size) if max_size.map(|max| size > max).unwrap_or(false) => {
+                return Err(BodyError::TooLarge);
+            }
+            BodyLength::Fixed(size) => size,
+            _ => 0,
+        };
+
+        Ok(Self {
+            rx,
+            buffer,
+            buffered,
+            length,
+            remaining,
+            framing: ChunkedFraming::new(),
+            started: false,
+            max_size,
+            read_timeout,
+        })
+    }
+
+    /// The way the Body is delimited
+    pub fn length(&self) -> BodyLength {
+        self.length
+    }
+
+    /// Checks if the whole Body was received
+    pub fn is_done(&self) -> bool {
+        match self.length {
+            BodyLength::Empty => true,
+            BodyLength::Fixed(_) => self.remaining == 0,
+            BodyLength::Chunked => self.framing.is_done(),
+        }
+    }
+
+    /// Checks if any Part of the Body was already processed, after
+    /// which the Body can no longer be sent again
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    /// Checks if the Client already started sending the Body
+    pub fn is_buffered(&self) -> bool {
+        self.buffered > 0
+    }
+
+    /// The Number of Bytes after the Body that were already received
+    /// and belong to the next Request
+    pub fn left_over(&self) -> usize {
+        self.buffered
+    }
+
+    /// Makes sure there is Data for the Body in the Buffer and returns
+    /// how many Bytes at its Start belong to the Body, None once the
+    /// whole Body was received
+    async fn next_part(&mut self) -> Result<Option<usize>, BodyError> {
+        if self.is_done() {
+            return Ok(None);
+        }
+
+        if self.buffered == 0 {
+            let read =
+                match tokio::time::timeout(self.read_timeout, self.rx.read(self.buffer)).await {
+                    Ok(r) => r,
+                    Err(_) => return Err(BodyError::Timeout),
+                };
+
+            self.buffered = match read {
+                Ok(0) => return Err(BodyError::ConnectionClosed),
+                Ok(n) => n,
+                Err(e) => return Err(BodyError::ReadingCon(e)),
+            };
+        }
+
+        let part = match self.length {
+            BodyLength::Empty => 0,
+            BodyLength::Fixed(_) => {
+                let part = self.remaining.min(self.buffered);
+                self.remaining -= part;
+                part
+            }
+            BodyLength::Chunked => {
+                let part = self
+                    .framing
+                    .update(&self.buffer[..self.buffered])
+                    .map_err(|_| BodyError::InvalidChunk)?;
+                if let Some(max) = self.max_size {
+                    if self.framing.size() > max {
+                        return Err(BodyError::TooLarge);
+                    }
+                }
+                part
+            }
+        };
+        self.started = true;
+
+        Ok(Some(part))
+    }
+
+    /// Removes the given Number of processed Bytes from the Buffer
+    fn consume(&mut self, size: usize) {
+        self.buffer.copy_within(size..self.buffered, 0);
+        self.buffered -= size;
+    }
+
+    /// Forwards the Body to the Connection, as it is received, and
+    /// returns the Number of Bytes that were forwarded
+    pub async fn forward<C>(&mut self, con: &mut C) -> Result<usize, BodyError>
+    where
+        C: ServiceConnection,
+    {
+        let mut forwarded = 0;
+        while let Some(part) = self.next_part().await? {
+            con.write_all(&self.buffer[..part])
+                .await
+                .map_err(BodyError::WritingCon)?;
+            forwarded += part;
+            self.consume(part);
+        }
+
+        Ok(forwarded)
+    }
+
+    /// Receives the whole Body into Memory, this should only be used
+    /// for Requests that are not forwarded to a Service
+    pub async fn read_all(&mut self) -> Result<Vec<u8>, BodyError> {
+        let mut result = Vec::new();
+        while let Some(part) = self.next_part().await? {
+            result.extend_from_slice(&self.buffer[..part]);
+            self.consume(part);
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::{Headers, Method};
+
+    use super::*;
+
+    use crate::acceptors::mocks::Receiver as MockReceiver;
+    use crate::forwarder::mocks::ServiceConnection as MockServiceConnection;
+
+    fn request(headers: &[(&'static str, &'static str)]) -> Request<'static> {
+        let mut tmp = Headers::new();
+        for (key, value) in headers {
+            tmp.set(*key, *value);
+        }
+        Request::new("HTTP/1.1", Method::POST, "/upload", tmp, &[])
+    }
+
+    #[test]
+    fn length_from_request() {
+        assert_eq!(
+            BodyLength::Empty,
+            BodyLength::from_request(&request(&[])).unwrap()
+        );
+        assert_eq!(
+            BodyLength::Fixed(12),
+            BodyLength::from_request(&request(&[("Content-Length", "12")])).unwrap()
+        );
+        assert_eq!(
+            BodyLength::Chunked,
+            BodyLength::from_request(&request(&[
+                ("Content-Length", "12"),
+                ("Transfer-Encoding", "gzip, chunked")
+            ]))
+            .unwrap()
+        );
+        assert_eq!(
+            true,
+            BodyLength::from_request(&request(&[("Content-Length", "abc")])).is_err()
+        );
+        assert_eq!(
+            true,
+            BodyLength::from_request(&request(&[("Transfer-Encoding", "gzip")])).is_err()
+        );
+    }
+
+    #[test]
+    fn conflicting_length_removed() {
+        let mut req = request(&[("Content-Length", "4"), ("Transfer-Encoding", "chunked")]);
+        let length = BodyLength::from_request(&req).unwrap();
+
+        remove_conflicting_length(&mut req, length);
+        assert_eq!(None, req.headers().get("Content-Length"));
+        assert_eq!(
+            Some("chunked".to_owned()),
+            req.headers()
+                .get("Transfer-Encoding")
+                .map(|v| v.to_string())
+        );
+
+        let mut req = request(&[("Content-Length", "4")]);
+        remove_conflicting_length(&mut req, BodyLength::Fixed(4));
+        assert_eq!(
+            Some("4".to_owned()),
+            req.headers().get("Content-Length").map(|v| v.to_string())
+        );
+    }
+
+    #[test]
+    fn continue_expected() {
+        assert_eq!(
+            true,
+            expects_continue(&request(&[("Expect", "100-Continue")]))
+        );
+        assert_eq!(false, expects_continue(&request(&[])));
+    }
+
+    #[tokio::test]
+    async fn forward_fixed() {
+        let req = request(&[("Content-Length", "10")]);
+        let mut rx = MockReceiver::new();
+        rx.add_chunk(b"data.GET / HTTP/1.1".to_vec());
+
+        let mut buffer = [0; 32];
+        buffer[..5].copy_from_slice(b"Just ");
+        let mut body =
+            Body::new(&req, &mut rx, &mut buffer, 5, None, Duration::from_secs(1)).unwrap();
+
+        let mut con = MockServiceConnection::new();
+        assert_eq!(10, body.forward(&mut con).await.unwrap());
+        assert_eq!(true, body.is_done());
+        assert_eq!(14, body.left_over());
+        assert_eq!(b"Just data.".to_vec(), con.get_write_chunks().concat());
+        assert_eq!(b"GET / HTTP/1.1", &buffer[..14]);
+    }
+
+    #[tokio::test]
+    async fn forward_chunked() {
+        let req = request(&[("Transfer-Encoding", "chunked")]);
+        let mut rx = MockReceiver::new();
+        rx.add_chunk(b"4\r\nWi".to_vec());
+        rx.add_chunk(b"ki\r\n0\r\n\r\n".to_vec());
+
+        let mut buffer = [0; 32];
+        let mut body =
+            Body::new(&req, &mut rx, &mut buffer, 0, None, Duration::from_secs(1)).unwrap();
+
+        let mut con = MockServiceConnection::new();
+        assert_eq!(14, body.forward(&mut con).await.unwrap());
+        assert_eq!(0, body.left_over());
+        assert_eq!(
+            b"4\r\nWiki\r\n0\r\n\r\n".to_vec(),
+            con.get_write_chunks().concat()
+        );
+    }
+
+    #[tokio::test]
+    async fn max_size() {
+        let mut rx = MockReceiver::new();
+        let mut buffer = [0; 32];
+
+        let req = request(&[("Content-Length", "10")]);
+        assert_eq!(
+            true,
+            matches!(
+                Body::new(
+                    &req,
+                    &mut rx,
+                    &mut buffer,
+                    0,
+                    Some(8),
+                    Duration::from_secs(1)
+                ),
+                Err(BodyError::TooLarge)
+            )
+        );
+
+        let req = request(&[("Transfer-Encoding", "chunked")]);
+        rx.add_chunk(b"a\r\n0123456789\r\n0\r\n\r\n".to_vec());
+        let mut body = Body::new(
+            &req,
+            &mut rx,
+            &mut buffer,
+            0,
+            Some(8),
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert_eq!(
+            true,
+            matches!(body.read_all().await, Err(BodyError::TooLarge))
+        );
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let req = request(&[("Content-Length", "10")]);
+        let mut rx = PendingReceiver;
+
+        let mut buffer = [0; 32];
+        let mut body = Body::new(
+            &req,
+            &mut rx,
+            &mut buffer,
+            0,
+            None,
+            Duration::from_millis(10),
+        )
+        .unwrap();
+        assert_eq!(
+            true,
+            matches!(body.read_all().await, Err(BodyError::Timeout))
+        );
+    }
+
+    /// A Receiver that never returns any Data
+    #[derive(Debug)]
+    struct PendingReceiver;
+
+    #[async_trait::async_trait]
+    impl Receiver for PendingReceiver {
+        async fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            std::future::pending().await
+        }
+    }
+}
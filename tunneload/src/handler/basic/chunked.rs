@@ -0,0 +1,226 @@
+/// The maximum Size of the Trailers that are kept
+const MAX_TRAILERS: usize = 8 * 1024;
+
+/// The Framing of a chunked Body is invalid
+#[derive(Debug, PartialEq)]
+pub struct InvalidChunk;
+
+/// The States of the Framing of a chunked Body
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChunkState {
+    /// Parsing the hex Size of the next Chunk
+    Size { size: usize, digits: usize },
+    /// Skipping the Extensions of the Chunk until the Size-Line ends
+    Extension { size: usize },
+    /// Expecting the '\n' that ends the Size-Line
+    SizeEnd { size: usize },
+    /// Skipping the given Number of Data-Bytes of the Chunk
+    Data(usize),
+    /// Expecting the '\r\n' after the Data of the Chunk, the Flag
+    /// is set once the '\r' was received
+    DataEnd(bool),
+    /// Skipping the Trailers after the last Chunk, tracking the Length
+    /// of the current Line and whether it already received its '\r'
+    Trailer { line: usize, cr: bool },
+    /// The whole Body was received
+    Done,
+}
+
+/// Tracks the Framing of a chunked Body, so that its End can be found
+/// while the Body is forwarded unchanged
+#[derive(Debug)]
+pub struct ChunkedFraming {
+    state: ChunkState,
+    size: usize,
+    trailers: Option<Vec<u8>>,
+}
+
+impl ChunkedFraming {
+    pub fn new() -> Self {
+        Self {
+            state: ChunkState::Size { size: 0, digits: 0 },
+            size: 0,
+            trailers: None,
+        }
+    }
+
+    /// Creates a new Framing that also keeps the Trailers of the Body,
+    /// instead of only skipping them
+    pub fn keeping_trailers() -> Self {
+        Self {
+            trailers: Some(Vec::new()),
+            ..Self::new()
+        }
+    }
+
+    /// The Trailer-Lines received so far, each ending with '\r\n', which
+    /// are only kept if the Framing was created using `keeping_trailers`
+    pub fn trailers(&self) -> &[u8] {
+        self.trailers.as_deref().unwrap_or(&[])
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.state == ChunkState::Done
+    }
+
+    /// The Number of Data-Bytes of all the Chunks received so far
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Processes the given Data and returns how many Bytes of it belong
+    /// to the Body, which is less than the Length of the Data once the
+    /// End of the Body was found
+    pub fn update(&mut self, data: &[u8]) -> Result<usize, InvalidChunk> {
+        self.update_with(data, |_| {})
+    }
+
+    /// Works like `update`, but also passes the Data of the Chunks,
+    /// without their Framing, to the given Function
+    pub fn update_with<F>(&mut self, data: &[u8], mut on_data: F) -> Result<usize, InvalidChunk>
+    where
+        F: FnMut(&[u8]),
+    {
+        let mut index = 0;
+        while index < data.len() && !self.is_done() {
+            let byte = data[index];
+            let in_trailers = matches!(self.state, ChunkState::Trailer { .. });
+            self.state = match self.state {
+                ChunkState::Size { size, digits } => match (byte as char).to_digit(16) {
+                    Some(value) => {
+                        let size = size
+                            .checked_mul(16)
+                            .and_then(|s| s.checked_add(value as usize))
+                            .ok_or(InvalidChunk)?;
+                        ChunkState::Size {
+                            size,
+                            digits: digits + 1,
+                        }
+                    }
+                    None if digits > 0 && byte == b';' => ChunkState::Extension { size },
+                    None if digits > 0 && byte == b'\r' => ChunkState::SizeEnd { size },
+                    None => return Err(InvalidChunk),
+                },
+                ChunkState::Extension { size } if byte == b'\r' => ChunkState::SizeEnd { size },
+                ChunkState::Extension { size } => ChunkState::Extension { size },
+                ChunkState::SizeEnd { size } if byte == b'\n' => {
+                    self.size = self.size.saturating_add(size);
+                    if size == 0 {
+                        ChunkState::Trailer { line: 0, cr: false }
+                    } else {
+                        ChunkState::Data(size)
+                    }
+                }
+                ChunkState::Data(left) => {
+                    let skipped = left.min(data.len() - index);
+                    on_data(&data[index..index + skipped]);
+                    index += skipped;
+                    self.state = if skipped == left {
+                        ChunkState::DataEnd(false)
+                    } else {
+                        ChunkState::Data(left - skipped)
+                    };
+                    continue;
+                }
+                ChunkState::DataEnd(false) if byte == b'\r' => ChunkState::DataEnd(true),
+                ChunkState::DataEnd(true) if byte == b'\n' => {
+                    ChunkState::Size { size: 0, digits: 0 }
+                }
+                ChunkState::Trailer { line, cr: false } if byte == b'\r' => {
+                    ChunkState::Trailer { line, cr: true }
+                }
+                ChunkState::Trailer { line: 0, cr: true } if byte == b'\n' => ChunkState::Done,
+                ChunkState::Trailer { cr: true, .. } if byte == b'\n' => {
+                    ChunkState::Trailer { line: 0, cr: false }
+                }
+                ChunkState::Trailer { line, cr: false } => ChunkState::Trailer {
+                    line: line + 1,
+                    cr: false,
+                },
+                _ => return Err(InvalidChunk),
+            };
+            if let (Some(trailers), ChunkState::Trailer { line, cr }, true) =
+                (self.trailers.as_mut(), self.state, in_trailers)
+            {
+                // The '\r' of the empty Line, that ends the Trailers, is
+                // not part of them
+                if line > 0 || !cr {
+                    trailers.push(byte);
+                }
+                if trailers.len() > MAX_TRAILERS {
+                    return Err(InvalidChunk);
+                }
+            }
+            index += 1;
+        }
+
+        Ok(index)
+    }
+}
+
+impl Default for ChunkedFraming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_framing() {
+        let mut framing = ChunkedFraming::new();
+        assert_eq!(Ok(7), framing.update(b"4;ext=1"));
+        assert_eq!(Ok(8), framing.update(b"\r\nWiki\r\n"));
+        assert_eq!(false, framing.is_done());
+        assert_eq!(Ok(5), framing.update(b"0\r\n\r\nGET"));
+        assert_eq!(true, framing.is_done());
+        assert_eq!(4, framing.size());
+    }
+
+    #[test]
+    fn chunked_framing_trailers() {
+        let mut framing = ChunkedFraming::new();
+        let data = b"0\r\nExpires: never\r\n\r\n";
+        assert_eq!(Ok(data.len()), framing.update(data));
+        assert_eq!(true, framing.is_done());
+    }
+
+    #[test]
+    fn chunked_framing_keeping_trailers() {
+        let mut framing = ChunkedFraming::keeping_trailers();
+        let data = b"4\r\ndata\r\n0\r\ngrpc-status: 0\r\nExpires: never\r\n\r\n";
+        assert_eq!(Ok(data.len()), framing.update(data));
+        assert_eq!(true, framing.is_done());
+        assert_eq!(
+            b"grpc-status: 0\r\nExpires: never\r\n".to_vec(),
+            framing.trailers()
+        );
+
+        let mut framing = ChunkedFraming::keeping_trailers();
+        assert_eq!(Ok(5), framing.update(b"0\r\n\r\n"));
+        assert_eq!(true, framing.trailers().is_empty());
+    }
+
+    #[test]
+    fn chunked_framing_invalid() {
+        let mut framing = ChunkedFraming::new();
+        assert_eq!(true, framing.update(b"x\r\n").is_err());
+
+        let mut framing = ChunkedFraming::new();
+        assert_eq!(true, framing.update(b"2\r\nabc\r\n").is_err());
+    }
+
+    #[test]
+    fn chunked_framing_data() {
+        let mut framing = ChunkedFraming::new();
+        let mut data = Vec::new();
+        let raw = b"4\r\nWiki\r\n5;ext\r\npedia\r\n0\r\n\r\n";
+        assert_eq!(
+            Ok(raw.len()),
+            framing.update_with(raw, |d| data.extend_from_slice(d))
+        );
+        assert_eq!(b"Wikipedia".to_vec(), data);
+    }
+}
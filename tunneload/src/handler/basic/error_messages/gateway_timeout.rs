@@ -0,0 +1,17 @@
+use general_traits::Sender;
+
+use stream_httparse::{Headers, Response, StatusCode};
+
+pub async fn gateway_timeout<T>(sender: &mut T)
+where
+    T: Sender,
+{
+    let response = Response::new(
+        "HTTP/1.1",
+        StatusCode::GatewayTimeout,
+        Headers::new(),
+        "Gateway Timeout".as_bytes().to_vec(),
+    );
+
+    sender.send_response(&response).await;
+}
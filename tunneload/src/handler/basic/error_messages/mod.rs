@@ -1,11 +1,23 @@
 mod bad_request;
 pub use bad_request::bad_request;
 
+mod expectation_failed;
+pub use expectation_failed::expectation_failed;
+
+mod gateway_timeout;
+pub use gateway_timeout::gateway_timeout;
+
 mod internal_server_error;
 pub use internal_server_error::internal_server_error;
 
 mod not_found;
 pub use not_found::not_found;
 
+mod payload_too_large;
+pub use payload_too_large::payload_too_large;
+
+mod request_timeout;
+pub use request_timeout::request_timeout;
+
 mod service_unavailable;
 pub use service_unavailable::service_unavailable;
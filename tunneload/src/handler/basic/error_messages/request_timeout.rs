@@ -0,0 +1,19 @@
+use general_traits::Sender;
+
+use stream_httparse::{Headers, Response, StatusCode};
+
+pub async fn request_timeout<T>(sender: &mut T)
+where
+    T: Sender,
+{
+    let mut headers = Headers::new();
+    headers.set("Connection", "close");
+    let response = Response::new(
+        "HTTP/1.1",
+        StatusCode::RequestTimeOut,
+        headers,
+        "Request Timeout".as_bytes().to_vec(),
+    );
+
+    sender.send_response(&response).await;
+}
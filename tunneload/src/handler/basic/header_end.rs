@@ -0,0 +1,48 @@
+/// Finds the End of the Headers, which may be split over multiple
+/// Reads
+#[derive(Debug, Default)]
+pub struct HeaderEnd {
+    matched: usize,
+}
+
+impl HeaderEnd {
+    /// Checks if the given Data contains the End of the Headers and
+    /// returns the Index right after it
+    pub fn update(&mut self, data: &[u8]) -> Option<usize> {
+        const END: &[u8] = b"\r\n\r\n";
+
+        for (index, byte) in data.iter().enumerate() {
+            if *byte == END[self.matched] {
+                self.matched += 1;
+                if self.matched == END.len() {
+                    return Some(index + 1);
+                }
+            } else if *byte == END[0] {
+                self.matched = 1;
+            } else {
+                self.matched = 0;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_end_split() {
+        let mut end = HeaderEnd::default();
+        assert_eq!(None, end.update(b"GET / HTTP/1.1\r\nKey: value\r"));
+        assert_eq!(None, end.update(b"\n\r"));
+        assert_eq!(Some(1), end.update(b"\nbody"));
+    }
+
+    #[test]
+    fn header_end_restart() {
+        let mut end = HeaderEnd::default();
+        assert_eq!(None, end.update(b"a\r\r\n\r"));
+        assert_eq!(Some(1), end.update(b"\n"));
+    }
+}
@@ -1,35 +1,59 @@
 use std::sync::Arc;
 
-use stream_httparse::{streaming_parser::RespParser, Request};
+use stream_httparse::{streaming_parser::RespParser, Method, Request, Response};
 
 use crate::{
     configurator::ConfigItem,
     forwarder::{Forwarder, ServiceConnection},
+    handler::Timeouts,
     internal_services::Internals,
 };
-use general_traits::Sender;
-use rules::Rule;
+use general_traits::{Receiver, Sender};
+use rules::{ConnectionInfo, Rule};
 
-use super::{error_messages, HANDLE_TIME_VEC, SERVICE_REQ_VEC, STATUS_CODES_VEC};
+use super::{
+    body::{self, Body, BodyError, BodyLength},
+    error_messages, HANDLE_TIME_VEC, SERVICE_REQ_VEC, STATUS_CODES_VEC,
+};
+
+use self::stream::{ResponseBody, ResponseLength};
 
-mod chunks;
 mod response;
+mod stream;
+
+/// The maximum Number of times an idempotent Request is sent, if the
+/// Connection to the Service is reset before receiving a Response and
+/// before any Part of its Body was sent
+const MAX_REQUEST_ATTEMPTS: usize = 3;
+
+/// Checks if a Request with the given Method can safely be sent more
+/// than once
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS | Method::TRACE
+    )
+}
 
 pub struct Context<'send, 'forward, S, F> {
     pub sender: &'send mut S,
     pub forwarder: &'forward F,
     pub internals: Arc<Internals>,
+    pub conn_info: &'forward ConnectionInfo,
+    pub timeouts: &'forward Timeouts,
 }
 
-pub async fn handle<S, F>(
+pub async fn handle<R, S, F>(
     id: u32,
     request: Request<'_>,
+    body: &mut Body<'_, R>,
     matched: Arc<Rule>,
     resp_parser: &mut RespParser,
     resp_buf: &mut [u8],
     ctx: Context<'_, '_, S, F>,
 ) -> Result<(), ()>
 where
+    R: Receiver + Send,
     S: Sender + Send,
     F: Forwarder + Send,
 {
@@ -47,60 +71,212 @@ where
         .inc();
 
     let mut out_req = request;
+
+    // The Continue is sent by the Load-Balancer itself, once the Body
+    // is actually going to be received, so the Service should not
+    // send its own one
+    let expect_continue = body::expects_continue(&out_req);
+    if out_req.headers().get("Expect").is_some() {
+        if !expect_continue {
+            error_messages::expectation_failed(ctx.sender).await;
+            return Err(());
+        }
+        out_req.header_mut().remove("Expect");
+    }
+
     // If a middleware decided that this request should not be processed
     // anymore and instead a certain Response needs to be send to the
     // Client first, sends the given Response to the client and moves
     // on from this request
-    if let Err(mid_resp) = middlewares.apply_middlewares_req(&mut out_req) {
-        ctx.sender.send_response(&mid_resp).await;
+    if let Err(mid_resp) = middlewares
+        .apply_middlewares_req(&mut out_req, ctx.conn_info, matched.name())
+        .await
+    {
+        let (mid_head, mid_body) = mid_resp.serialize();
+        ctx.sender.send(&mid_head).await;
+        ctx.sender.send(mid_body).await;
 
         handle_timer.observe_duration();
 
+        // The Body was never received, so the Connection can not be
+        // used for any further Requests
+        if !body.is_done() {
+            return Err(());
+        }
         return Ok(());
     }
 
+    // Done after the Middlewares, as they could have set the Headers
+    body::remove_conflicting_length(&mut out_req, body.length());
+
     let service = matched.service();
     if service.is_internal() {
+        // Internal Services only handle small Requests, so the Body
+        // is simply received into Memory
+        if body.length() != BodyLength::Empty {
+            if expect_continue && !body.is_buffered() {
+                body::send_continue(ctx.sender).await;
+            }
+            match body.read_all().await {
+                Ok(data) => out_req.set_body(data),
+                Err(e) => {
+                    tracing::error!("Receiving Body: {:?}", e);
+                    e.send_response(ctx.sender).await;
+                    return Err(());
+                }
+            };
+        }
+
         let result = ctx.internals.handle(&out_req, matched, ctx.sender);
         return result.await;
     }
 
-    let mut connection = match ctx.forwarder.create_con(&matched).await {
-        Ok(c) => c,
-        Err(e) => {
-            tracing::error!("Connecting to Service({:?}): {:?}", service.name(), e);
-            error_messages::service_unavailable(ctx.sender).await;
+    // Idempotent Requests can safely be sent again, if the Connection
+    // was reset before a Response was received
+    let attempts = if is_idempotent(out_req.method()) {
+        MAX_REQUEST_ATTEMPTS
+    } else {
+        1
+    };
+
+    let connect_timeout = ctx.timeouts.upstream_connect;
+    let response_timeout = ctx.timeouts.upstream_response;
+
+    let mut continued = false;
+    let mut attempt = 0;
+    let (mut connection, mut response, left_over_buffer) = loop {
+        attempt += 1;
+        resp_parser.clear();
+
+        let connect = ctx.forwarder.create_con(&matched, &out_req, ctx.conn_info);
+        let mut connection = match tokio::time::timeout(connect_timeout, connect).await {
+            Ok(Ok(c)) => c,
+            Ok(Err(e)) => {
+                tracing::error!("Connecting to Service({:?}): {:?}", service.name(), e);
+                error_messages::service_unavailable(ctx.sender).await;
+                return Err(());
+            }
+            Err(_) => {
+                tracing::error!("Connecting to Service({:?}): Timed out", service.name());
+                error_messages::gateway_timeout(ctx.sender).await;
+                return Err(());
+            }
+        };
+
+        if let Err(e) = connection.write_req(&out_req).await {
+            tracing::error!("Sending Request to Service({:?}): {}", service.name(), e);
+            connection.report_failure();
+            if attempt < attempts && !body.is_started() {
+                continue;
+            }
+
+            error_messages::internal_server_error(ctx.sender).await;
             return Err(());
         }
-    };
 
-    if let Err(e) = connection.write_req(&out_req).await {
-        tracing::error!("Sending Request to Service({:?}): {}", service.name(), e);
-        error_messages::internal_server_error(ctx.sender).await;
-        return Err(());
-    }
+        if expect_continue && !continued && !body.is_done() && !body.is_buffered() {
+            body::send_continue(ctx.sender).await;
+            continued = true;
+        }
+
+        // The Body is forwarded as it is received from the Client,
+        // after which the Request can no longer be retried
+        if let Err(e) = body.forward(&mut connection).await {
+            tracing::error!("Forwarding Body to Service({:?}): {:?}", service.name(), e);
+            if let BodyError::WritingCon(_) = e {
+                connection.report_failure();
+            }
+            e.send_response(ctx.sender).await;
+            return Err(());
+        }
+
+        let receive = response::receive(id, resp_parser, &mut connection, resp_buf);
+        let received = match tokio::time::timeout(response_timeout, receive).await {
+            Ok(r) => r,
+            Err(_) => {
+                tracing::error!("Receiving from Service({:?}): Timed out", service.name());
+                connection.report_failure();
+                error_messages::gateway_timeout(ctx.sender).await;
+                return Err(());
+            }
+        };
 
-    let (mut response, left_over_buffer) =
-        match response::receive(id, resp_parser, &mut connection, resp_buf).await {
-            Some(resp) => resp,
+        match received {
+            Some((resp, left_over)) => {
+                connection.report_success();
+                break (connection, resp, left_over);
+            }
             None => {
+                connection.report_failure();
+                if attempt < attempts && !body.is_started() {
+                    tracing::warn!("Retrying Request for Service({:?})", service.name());
+                    continue;
+                }
+
                 error_messages::internal_server_error(ctx.sender).await;
                 return Err(());
             }
         };
+    };
 
-    middlewares.apply_middlewares_resp(&out_req, &mut response);
+    let length = ResponseLength::from_response(out_req.method(), &response);
+    let mut body = ResponseBody::new(
+        resp_buf,
+        left_over_buffer,
+        length,
+        ctx.timeouts.upstream_read,
+    );
 
-    let (resp_header, resp_body) = response.serialize();
-    ctx.sender.send(&resp_header).await;
+    // Without a Length, the Body only ends once the Connection is
+    // closed, which then also has to be done for the Client
+    let mut client_delimited = true;
+    let result = if middlewares.streams_resp() {
+        let mut pipeline = middlewares.apply_middlewares_resp_stream(&out_req, &mut response);
+        if pipeline.is_empty() || length == ResponseLength::Empty {
+            let (resp_header, _) = response.serialize();
+            ctx.sender.send(&resp_header).await;
 
-    // First assumes that it is NOT chunked and should
-    // just send the data normally
-    if !response.is_chunked() {
-        ctx.sender.send(resp_body).await;
+            client_delimited = length != ResponseLength::UntilClose;
+            stream::forward(id, &mut body, &mut connection, ctx.sender).await
+        } else {
+            ctx.sender.send(&stream::chunked_head(&response)).await;
+
+            stream::forward_transformed(id, &mut body, &mut connection, ctx.sender, &mut pipeline)
+                .await
+        }
     } else {
-        chunks::forward(id, &mut connection, ctx.sender, resp_buf, left_over_buffer).await;
-    }
+        // Some Middlewares need the whole Response at once, so the Body
+        // is received completely before anything is sent to the Client
+        let data = match stream::collect(&mut body, &mut connection).await {
+            Ok(d) => d,
+            Err(e) => {
+                tracing::error!("Receiving Body from Service({:?}): {}", service.name(), e);
+                error_messages::internal_server_error(ctx.sender).await;
+                return Err(());
+            }
+        };
+
+        let protocol = response.protocol().to_owned();
+        let mut headers = response.headers().clone();
+        headers.remove("Transfer-Encoding");
+        let mut full_response = Response::new(
+            &protocol,
+            response.status_code().clone(),
+            headers,
+            Vec::new(),
+        );
+        if length != ResponseLength::Empty {
+            full_response.set_body(data);
+        }
+
+        middlewares.apply_middlewares_resp(&out_req, &mut full_response);
+
+        let (resp_header, resp_body) = full_response.serialize();
+        ctx.sender.send(&resp_header).await;
+        ctx.sender.send(resp_body).await;
+
+        Ok(body.is_complete())
+    };
 
     handle_timer.observe_duration();
 
@@ -109,5 +285,66 @@ where
         .expect("The Metric should always be registered")
         .inc();
 
+    let complete = match result {
+        Ok(c) => c,
+        Err(_) => return Err(()),
+    };
+    if complete && keeps_alive(&response) {
+        connection.release();
+    }
+
+    if !client_delimited {
+        return Err(());
+    }
     Ok(())
 }
+
+/// Checks if the Service wants to keep the Connection open after
+/// the given Response
+fn keeps_alive(response: &Response) -> bool {
+    let connection = response
+        .headers()
+        .get("Connection")
+        .map(|v| v.to_string().to_ascii_lowercase());
+
+    match connection.as_deref() {
+        Some(value) if value.contains("close") => false,
+        Some(value) if value.contains("keep-alive") => true,
+        _ => response.protocol() != "HTTP/1.0",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::{Headers, StatusCode};
+
+    use super::*;
+
+    fn response(protocol: &'static str, connection: Option<&'static str>) -> Response<'static> {
+        let mut headers = Headers::new();
+        if let Some(value) = connection {
+            headers.set("Connection", value);
+        }
+        Response::new(protocol, StatusCode::OK, headers, Vec::new())
+    }
+
+    #[test]
+    fn keeps_alive_default() {
+        assert_eq!(true, keeps_alive(&response("HTTP/1.1", None)));
+        assert_eq!(false, keeps_alive(&response("HTTP/1.0", None)));
+    }
+
+    #[test]
+    fn keeps_alive_connection_header() {
+        assert_eq!(false, keeps_alive(&response("HTTP/1.1", Some("close"))));
+        assert_eq!(true, keeps_alive(&response("HTTP/1.0", Some("Keep-Alive"))));
+    }
+
+    #[test]
+    fn idempotent_methods() {
+        assert_eq!(true, is_idempotent(&Method::GET));
+        assert_eq!(true, is_idempotent(&Method::PUT));
+        assert_eq!(false, is_idempotent(&Method::POST));
+        assert_eq!(false, is_idempotent(&Method::CONNECT));
+    }
+}
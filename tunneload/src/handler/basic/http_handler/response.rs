@@ -2,8 +2,13 @@ use crate::forwarder::ServiceConnection;
 
 use stream_httparse::{streaming_parser::RespParser, Response};
 
-/// Returns the finished response and the amount of
-/// data still left in the Buffer
+use super::super::header_end::HeaderEnd;
+
+/// Receives the Head of the Response and returns it together with the
+/// amount of data still left in the Buffer, which contains the start
+/// of the Body.
+///
+/// The Body itself is not received, see `ResponseBody`
 #[tracing::instrument(skip(parser, con, read_buf))]
 pub async fn receive<'a, 'b, R>(
     id: u32,
@@ -15,25 +20,23 @@ where
     'a: 'b,
     R: ServiceConnection + Send,
 {
-    let mut left_in_buffer: usize = 0;
+    let mut header_end = HeaderEnd::default();
 
-    loop {
+    let left_in_buffer = loop {
         match con.read(read_buf).await {
             Ok(n) if n == 0 => {
                 return None;
             }
-            Ok(n) => {
-                let (done, left_over) = parser.block_parse(&read_buf[0..n]);
-                if done {
-                    if left_over > 0 {
-                        let start = n - left_over;
-                        read_buf.copy_within(start.., 0);
-                        left_in_buffer = left_over;
-                    }
-
-                    break;
+            Ok(n) => match header_end.update(&read_buf[..n]) {
+                Some(end) => {
+                    parser.block_parse(&read_buf[..end]);
+                    read_buf.copy_within(end..n, 0);
+                    break n - end;
                 }
-            }
+                None => {
+                    parser.block_parse(&read_buf[..n]);
+                }
+            },
             Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                 continue;
             }
@@ -42,7 +45,7 @@ where
                 return None;
             }
         };
-    }
+    };
 
     let result = match parser.finish() {
         Ok(r) => r,
@@ -108,15 +111,13 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("Test-Key", "test-value");
         headers.set("Content-Length", 10);
-        let expected_response = Response::new(
-            "HTTP/1.1",
-            StatusCode::OK,
-            headers,
-            "Test Data.".as_bytes().to_vec(),
-        );
+        let expected_response =
+            Response::new("HTTP/1.1", StatusCode::OK, headers, "".as_bytes().to_vec());
         assert_eq!(expected_response, response);
 
-        assert_eq!(0, left_over_buffer);
+        // The Body is left in the Buffer
+        assert_eq!(10, left_over_buffer);
+        assert_eq!("Test Data.".as_bytes(), &buf[0..10]);
     }
 
     #[tokio::test]
@@ -139,16 +140,12 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("Test-Key", "test-value");
         headers.set("Content-Length", 10);
-        let expected_response = Response::new(
-            "HTTP/1.1",
-            StatusCode::OK,
-            headers,
-            "Test Data.".as_bytes().to_vec(),
-        );
+        let expected_response =
+            Response::new("HTTP/1.1", StatusCode::OK, headers, "".as_bytes().to_vec());
         assert_eq!(expected_response, response);
 
-        assert_eq!(15, left_over_buffer);
-        assert_eq!("Some extra data".as_bytes(), &buf[0..15]);
+        assert_eq!(25, left_over_buffer);
+        assert_eq!("Test Data.Some extra data".as_bytes(), &buf[0..25]);
     }
 
     #[tokio::test]
@@ -0,0 +1,518 @@
+use std::time::Duration;
+
+use crate::forwarder::ServiceConnection;
+use general_traits::Sender;
+use rules::BodyPipeline;
+
+use stream_httparse::{Chunk, Method, Response, StatusCode};
+
+use super::super::chunked::ChunkedFraming;
+
+/// How the Body of a Response is delimited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseLength {
+    /// The Response has no Body
+    Empty,
+    /// The Body has the given Number of Bytes
+    Fixed(usize),
+    /// The Body is sent using the Chunked Transfer-Encoding
+    Chunked,
+    /// The Body only ends once the Service closes the Connection
+    UntilClose,
+}
+
+impl ResponseLength {
+    /// Determines the Length of the Body of the Response to a Request
+    /// with the given Method
+    pub fn from_response(method: &Method, resp: &Response<'_>) -> Self {
+        if method == &Method::HEAD {
+            return Self::Empty;
+        }
+        if matches!(
+            resp.status_code(),
+            StatusCode::Continue
+                | StatusCode::SwitchingProtocols
+                | StatusCode::NoContent
+                | StatusCode::NotModified
+        ) {
+            return Self::Empty;
+        }
+        if resp.is_chunked() {
+            return Self::Chunked;
+        }
+
+        match resp.headers().get("Content-Length") {
+            Some(raw) => match raw.to_string().trim().parse::<usize>() {
+                Ok(0) => Self::Empty,
+                Ok(length) => Self::Fixed(length),
+                Err(_) => Self::UntilClose,
+            },
+            None => Self::UntilClose,
+        }
+    }
+}
+
+/// The Body of a Response, which is received from the Service while it
+/// is being forwarded to the Client
+pub struct ResponseBody<'a> {
+    buffer: &'a mut [u8],
+    /// The Number of Bytes at the Start of the Buffer that were already
+    /// received but not processed yet
+    buffered: usize,
+    length: ResponseLength,
+    /// The Number of Bytes left for a Body with a fixed Length
+    remaining: usize,
+    framing: ChunkedFraming,
+    closed: bool,
+    /// The Time allowed for a single Read from the Service
+    read_timeout: Duration,
+}
+
+impl<'a> ResponseBody<'a> {
+    /// Creates the Body with the given Length, starting with the Data
+    /// that is already in the Buffer. Every Read from the Service has to
+    /// complete within the Read-Timeout
+    pub fn new(
+        buffer: &'a mut [u8],
+        buffered: usize,
+        length: ResponseLength,
+        read_timeout: Duration,
+    ) -> Self {
+        let remaining = match length {
+            ResponseLength::Fixed(size) => size,
+            _ => 0,
+        };
+
+        Self {
+            buffer,
+            buffered,
+            length,
+            remaining,
+            framing: ChunkedFraming::new(),
+            closed: false,
+            read_timeout,
+        }
+    }
+
+    /// Checks if the whole Body was received
+    pub fn is_done(&self) -> bool {
+        match self.length {
+            ResponseLength::Empty => true,
+            ResponseLength::Fixed(_) => self.remaining == 0,
+            ResponseLength::Chunked => self.framing.is_done(),
+            ResponseLength::UntilClose => self.closed,
+        }
+    }
+
+    /// Checks if the whole Body was received without any Data after it,
+    /// meaning that the Connection could be used for another Request
+    pub fn is_complete(&self) -> bool {
+        self.is_done() && self.buffered == 0 && self.length != ResponseLength::UntilClose
+    }
+
+    /// Returns the next Part of the Body, None once the whole Body was
+    /// received.
+    ///
+    /// If `decode` is set, the Framing of a chunked Body is removed and
+    /// only the Data of the Chunks is returned
+    pub async fn next<C>(&mut self, con: &mut C, decode: bool) -> std::io::Result<Option<Vec<u8>>>
+    where
+        C: ServiceConnection + Send,
+    {
+        if self.is_done() {
+            return Ok(None);
+        }
+
+        while self.buffered == 0 {
+            let read = match tokio::time::timeout(self.read_timeout, con.read(self.buffer)).await {
+                Ok(r) => r,
+                Err(_) => return Err(std::io::ErrorKind::TimedOut.into()),
+            };
+
+            match read {
+                Ok(0) => {
+                    if self.length == ResponseLength::UntilClose {
+                        self.closed = true;
+                        return Ok(None);
+                    }
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
+                Ok(n) => {
+                    self.buffered = n;
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        let (part, data) = match self.length {
+            ResponseLength::Fixed(_) => {
+                let part = self.remaining.min(self.buffered);
+                self.remaining -= part;
+                (part, self.buffer[..part].to_vec())
+            }
+            ResponseLength::Chunked => {
+                let mut decoded = Vec::new();
+                let part = self
+                    .framing
+                    .update_with(&self.buffer[..self.buffered], |data| {
+                        if decode {
+                            decoded.extend_from_slice(data);
+                        }
+                    })
+                    .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidData))?;
+
+                if decode {
+                    (part, decoded)
+                } else {
+                    (part, self.buffer[..part].to_vec())
+                }
+            }
+            _ => (self.buffered, self.buffer[..self.buffered].to_vec()),
+        };
+
+        self.buffer.copy_within(part..self.buffered, 0);
+        self.buffered -= part;
+
+        Ok(Some(data))
+    }
+}
+
+/// Serializes the Head of the Response for a Body that is sent using
+/// chunks, because its Length is not known in advance
+pub fn chunked_head(resp: &Response<'_>) -> Vec<u8> {
+    let mut headers = resp.headers().clone();
+    headers.remove("Content-Length");
+    headers.set("Transfer-Encoding", "chunked");
+
+    let head = Response::new(
+        resp.protocol(),
+        resp.status_code().clone(),
+        headers,
+        Vec::new(),
+    );
+    head.serialize().0
+}
+
+/// Forwards the Body to the Client, without changing it, and returns
+/// whether it was completely received.
+///
+/// An Error is returned if the Body could not be received completely,
+/// in which case the Client did not get the whole Response
+#[tracing::instrument(skip(body, con, sender))]
+pub async fn forward<C, S>(
+    id: u32,
+    body: &mut ResponseBody<'_>,
+    con: &mut C,
+    sender: &mut S,
+) -> Result<bool, ()>
+where
+    C: ServiceConnection + Send,
+    S: Sender + Send,
+{
+    loop {
+        match body.next(con, false).await {
+            Ok(Some(part)) => sender.send(&part).await,
+            Ok(None) => return Ok(body.is_complete()),
+            Err(e) => {
+                tracing::error!("Reading from Connection: {}", e);
+                return Err(());
+            }
+        };
+    }
+}
+
+/// Passes the Body through the Pipeline and forwards the result to the
+/// Client using chunks, see `chunked_head`, returns whether the Body was
+/// completely received
+#[tracing::instrument(skip(body, con, sender, pipeline))]
+pub async fn forward_transformed<C, S>(
+    id: u32,
+    body: &mut ResponseBody<'_>,
+    con: &mut C,
+    sender: &mut S,
+    pipeline: &mut BodyPipeline,
+) -> Result<bool, ()>
+where
+    C: ServiceConnection + Send,
+    S: Sender + Send,
+{
+    loop {
+        match body.next(con, true).await {
+            Ok(Some(part)) => send_chunk(sender, pipeline.transform(&part)).await,
+            Ok(None) => break,
+            Err(e) => {
+                // The final Chunk is never sent, so the Client knows
+                // that the Body is incomplete
+                tracing::error!("Reading from Connection: {}", e);
+                return Err(());
+            }
+        };
+    }
+
+    send_chunk(sender, pipeline.finish()).await;
+    sender.send(b"0\r\n\r\n").await;
+
+    Ok(body.is_complete())
+}
+
+/// Receives the whole Body, without the Framing of a chunked Body
+pub async fn collect<C>(body: &mut ResponseBody<'_>, con: &mut C) -> std::io::Result<Vec<u8>>
+where
+    C: ServiceConnection + Send,
+{
+    let mut result = Vec::new();
+    while let Some(part) = body.next(con, true).await? {
+        result.extend(part);
+    }
+    Ok(result)
+}
+
+/// Sends the Data as a single Chunk, empty Data is skipped as it would
+/// otherwise mark the End of the Body
+async fn send_chunk<S>(sender: &mut S, data: Vec<u8>)
+where
+    S: Sender + Send,
+{
+    if data.is_empty() {
+        return;
+    }
+
+    let chunk = Chunk::new(data.len(), data);
+    let mut out = Vec::with_capacity(chunk.size() + 16);
+    chunk.serialize(&mut out);
+    sender.send(&out).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rules::BodyTransform;
+    use stream_httparse::Headers;
+
+    use crate::acceptors::mocks::Sender as MockSender;
+    use crate::forwarder::mocks::ServiceConnection as MockServiceConnection;
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// A Connection to a Service that never sends any Data
+    struct StalledConnection;
+    #[async_trait::async_trait]
+    impl ServiceConnection for StalledConnection {
+        async fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            std::future::pending().await
+        }
+
+        async fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        type ReadHalf = <MockServiceConnection as ServiceConnection>::ReadHalf;
+        type WriteHalf = <MockServiceConnection as ServiceConnection>::WriteHalf;
+
+        fn halves_owned(self) -> (Self::ReadHalf, Self::WriteHalf) {
+            MockServiceConnection::new().halves_owned()
+        }
+    }
+
+    fn response(headers: &[(&'static str, &'static str)]) -> Response<'static> {
+        let mut tmp = Headers::new();
+        for (key, value) in headers {
+            tmp.set(*key, *value);
+        }
+        Response::new("HTTP/1.1", StatusCode::OK, tmp, Vec::new())
+    }
+
+    #[test]
+    fn length_from_response() {
+        assert_eq!(
+            ResponseLength::Fixed(10),
+            ResponseLength::from_response(&Method::GET, &response(&[("Content-Length", "10")]))
+        );
+        assert_eq!(
+            ResponseLength::Empty,
+            ResponseLength::from_response(&Method::HEAD, &response(&[("Content-Length", "10")]))
+        );
+        assert_eq!(
+            ResponseLength::Chunked,
+            ResponseLength::from_response(
+                &Method::GET,
+                &response(&[("Transfer-Encoding", "chunked")])
+            )
+        );
+        assert_eq!(
+            ResponseLength::UntilClose,
+            ResponseLength::from_response(&Method::GET, &response(&[]))
+        );
+    }
+
+    #[test]
+    fn head_for_chunks() {
+        let head = chunked_head(&response(&[("Content-Length", "10")]));
+        assert_eq!(
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".as_bytes(),
+            head.as_slice()
+        );
+    }
+
+    #[tokio::test]
+    async fn forward_fixed_with_left_over() {
+        let mut con = MockServiceConnection::new();
+        con.add_chunk("Data.Extra".as_bytes().to_vec());
+
+        let mut sender = MockSender::new();
+        let mut buffer = [0; 2048];
+        buffer[..5].clone_from_slice("Test ".as_bytes());
+
+        let mut body = ResponseBody::new(&mut buffer, 5, ResponseLength::Fixed(10), TIMEOUT);
+        let complete = forward(0, &mut body, &mut con, &mut sender).await;
+        assert_eq!(Ok(false), complete);
+
+        assert_eq!("Test Data.".as_bytes().to_vec(), sender.get_combined_data());
+    }
+
+    #[tokio::test]
+    async fn valid_no_inital_data_one_chunk_without_final_empty_chunk() {
+        let mut con = MockServiceConnection::new();
+        con.add_chunk("9\r\nTest Data\r\n".as_bytes().to_vec());
+
+        let mut sender = MockSender::new();
+        let mut buffer = [0; 2048];
+
+        let mut body = ResponseBody::new(&mut buffer, 0, ResponseLength::Chunked, TIMEOUT);
+        let complete = forward(0, &mut body, &mut con, &mut sender).await;
+        assert_eq!(Err(()), complete);
+
+        assert_eq!(
+            vec!["9\r\nTest Data\r\n".as_bytes().to_vec()],
+            sender.get_chunks()
+        );
+    }
+
+    #[tokio::test]
+    async fn valid_no_inital_data_one_chunk_final_empty_chunk() {
+        let mut con = MockServiceConnection::new();
+        con.add_chunk("9\r\nTest Data\r\n".as_bytes().to_vec());
+        con.add_chunk("0\r\n\r\n".as_bytes().to_vec());
+
+        let mut sender = MockSender::new();
+        let mut buffer = [0; 2048];
+
+        let mut body = ResponseBody::new(&mut buffer, 0, ResponseLength::Chunked, TIMEOUT);
+        let complete = forward(0, &mut body, &mut con, &mut sender).await;
+        assert_eq!(Ok(true), complete);
+
+        assert_eq!(
+            vec![
+                "9\r\nTest Data\r\n".as_bytes().to_vec(),
+                "0\r\n\r\n".as_bytes().to_vec()
+            ],
+            sender.get_chunks()
+        );
+    }
+
+    #[tokio::test]
+    async fn valid_with_inital_data_one_chunk() {
+        let mut con = MockServiceConnection::new();
+        con.add_chunk("9\r\nTest Data\r\n0\r\n\r\n".as_bytes().to_vec());
+
+        let mut sender = MockSender::new();
+        let mut buffer = [0; 2048];
+        buffer[..10].clone_from_slice("5\r\nOther\r\n".as_bytes());
+
+        let mut body = ResponseBody::new(&mut buffer, 10, ResponseLength::Chunked, TIMEOUT);
+        let complete = forward(0, &mut body, &mut con, &mut sender).await;
+        assert_eq!(Ok(true), complete);
+
+        assert_eq!(
+            "5\r\nOther\r\n9\r\nTest Data\r\n0\r\n\r\n"
+                .as_bytes()
+                .to_vec(),
+            sender.get_combined_data()
+        );
+    }
+
+    #[tokio::test]
+    async fn until_close() {
+        let mut con = MockServiceConnection::new();
+        con.add_chunk("Some Data".as_bytes().to_vec());
+
+        let mut sender = MockSender::new();
+        let mut buffer = [0; 2048];
+
+        let mut body = ResponseBody::new(&mut buffer, 0, ResponseLength::UntilClose, TIMEOUT);
+        let complete = forward(0, &mut body, &mut con, &mut sender).await;
+        assert_eq!(Ok(false), complete);
+        assert_eq!("Some Data".as_bytes().to_vec(), sender.get_combined_data());
+    }
+
+    #[tokio::test]
+    async fn stalled_body_times_out() {
+        let mut con = StalledConnection;
+        let mut sender = MockSender::new();
+        let mut buffer = [0; 2048];
+        buffer[..5].clone_from_slice("Test ".as_bytes());
+
+        let timeout = Duration::from_millis(10);
+        let mut body = ResponseBody::new(&mut buffer, 5, ResponseLength::Fixed(10), timeout);
+        let complete = forward(0, &mut body, &mut con, &mut sender).await;
+        assert_eq!(Err(()), complete);
+        assert_eq!("Test ".as_bytes().to_vec(), sender.get_combined_data());
+
+        let mut buffer = [0; 2048];
+        let mut body = ResponseBody::new(&mut buffer, 0, ResponseLength::Chunked, timeout);
+        assert_eq!(
+            std::io::ErrorKind::TimedOut,
+            collect(&mut body, &mut con).await.unwrap_err().kind()
+        );
+    }
+
+    /// Uppercases all the Data
+    struct Upper;
+    impl BodyTransform for Upper {
+        fn transform(&mut self, data: &[u8]) -> Vec<u8> {
+            data.to_ascii_uppercase()
+        }
+        fn finish(&mut self) -> Vec<u8> {
+            Vec::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn transformed_chunks() {
+        let mut con = MockServiceConnection::new();
+        con.add_chunk("4\r\nTest\r\n5\r\n Data\r\n0\r\n\r\n".as_bytes().to_vec());
+
+        let mut sender = MockSender::new();
+        let mut buffer = [0; 2048];
+        let mut pipeline = BodyPipeline::new();
+        pipeline.push(Box::new(Upper));
+
+        let mut body = ResponseBody::new(&mut buffer, 0, ResponseLength::Chunked, TIMEOUT);
+        let complete =
+            forward_transformed(0, &mut body, &mut con, &mut sender, &mut pipeline).await;
+        assert_eq!(Ok(true), complete);
+
+        assert_eq!(
+            "9\r\nTEST DATA\r\n0\r\n\r\n".as_bytes().to_vec(),
+            sender.get_combined_data()
+        );
+    }
+
+    #[tokio::test]
+    async fn collect_chunks() {
+        let mut con = MockServiceConnection::new();
+        con.add_chunk("4\r\nTest\r\n5\r\n Data\r\n0\r\n\r\n".as_bytes().to_vec());
+
+        let mut buffer = [0; 2048];
+        let mut body = ResponseBody::new(&mut buffer, 0, ResponseLength::Chunked, TIMEOUT);
+        assert_eq!(
+            "Test Data".as_bytes().to_vec(),
+            collect(&mut body, &mut con).await.unwrap()
+        );
+        assert_eq!(true, body.is_complete());
+    }
+}
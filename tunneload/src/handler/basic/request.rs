@@ -1,58 +1,105 @@
 use general_traits::Receiver;
 
 use stream_httparse::{streaming_parser::ReqParser, Request};
+use tokio::time::Instant;
+
+use crate::handler::Timeouts;
+
+use super::header_end::HeaderEnd;
 
 #[derive(Debug)]
 pub enum RecvReqError {
     EOF,
     ReadingCon(std::io::Error),
     ParseError(stream_httparse::streaming_parser::ParseError),
+    /// The Request was not received within the Timeout of the
+    /// current Phase
+    Timeout(ReadPhase),
+}
+
+/// The Phases of receiving the Head of a Request, each with its own
+/// Timeout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadPhase {
+    /// Waiting for the next Request on a Keep-Alive Connection
+    Idle,
+    /// Receiving the Headers of the Request
+    Headers,
+}
+
+/// Parses the first `size` Bytes of the Buffer and returns the Number
+/// of Bytes left in the Buffer, once the whole Head was parsed.
+///
+/// Only the Head is passed to the Parser, everything after it is moved
+/// to the Start of the Buffer
+fn parse_head(
+    parser: &mut ReqParser,
+    header_end: &mut HeaderEnd,
+    buffer: &mut [u8],
+    size: usize,
+) -> Option<usize> {
+    match header_end.update(&buffer[..size]) {
+        Some(end) => {
+            parser.block_parse(&buffer[..end]);
+            buffer.copy_within(end..size, 0);
+            Some(size - end)
+        }
+        None => {
+            parser.block_parse(&buffer[..size]);
+            None
+        }
+    }
 }
 
-/// Returns the finished response and the amount of data
-/// still left in the buffer
+/// Receives the Head of the next Request and returns it together with
+/// the amount of data still left in the buffer, which contains the
+/// start of the Body.
+///
+/// The Body itself is not received, see `Body`.
+///
+/// If `idle` is set, the Connection is waiting for another Request and
+/// the Keep-Alive Timeout applies until its first Data is received
 pub async fn receive<'a, 'b, R>(
     parser: &'a mut ReqParser,
     rx: &mut R,
     buffer: &mut [u8],
     inital_offset: usize,
+    timeouts: &Timeouts,
+    idle: bool,
 ) -> Result<(Request<'b>, usize), RecvReqError>
 where
     R: Receiver + Send,
     'a: 'b,
 {
-    let mut continue_parsing = true;
-    let mut left_in_buffer: usize = 0;
+    let mut header_end = HeaderEnd::default();
+    let (mut phase, mut deadline) = if idle && inital_offset == 0 {
+        (ReadPhase::Idle, Instant::now() + timeouts.keep_alive_idle)
+    } else {
+        (ReadPhase::Headers, Instant::now() + timeouts.header_read)
+    };
 
+    let mut left_in_buffer = None;
     if inital_offset > 0 {
-        let (done, raw_left_over) = parser.block_parse(&buffer[..inital_offset]);
-        if done {
-            continue_parsing = false;
-
-            if let Some(left_over) = raw_left_over {
-                let start = buffer.len() - left_over;
-                buffer.copy_within(start.., 0);
-                left_in_buffer = left_over;
-            }
-        }
+        left_in_buffer = parse_head(parser, &mut header_end, buffer, inital_offset);
     }
 
-    while continue_parsing {
-        match rx.read(buffer).await {
+    while left_in_buffer.is_none() {
+        let read = match tokio::time::timeout_at(deadline, rx.read(buffer)).await {
+            Ok(r) => r,
+            Err(_) => return Err(RecvReqError::Timeout(phase)),
+        };
+
+        match read {
             Ok(n) if n == 0 => {
                 return Err(RecvReqError::EOF);
             }
             Ok(n) => {
-                let (done, raw_left_over) = parser.block_parse(&buffer[..n]);
-                if done {
-                    continue_parsing = false;
-
-                    if let Some(left_over) = raw_left_over {
-                        let start = n - left_over;
-                        buffer.copy_within(start..n, 0);
-                        left_in_buffer = left_over;
-                    }
+                if phase == ReadPhase::Idle {
+                    phase = ReadPhase::Headers;
+                    deadline = Instant::now() + timeouts.header_read;
                 }
+
+                left_in_buffer = parse_head(parser, &mut header_end, buffer, n);
             }
             Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
                 continue;
@@ -64,7 +111,7 @@ where
     }
 
     match parser.finish() {
-        Ok(req) => Ok((req, left_in_buffer)),
+        Ok(req) => Ok((req, left_in_buffer.unwrap_or(0))),
         Err(e) => Err(RecvReqError::ParseError(e)),
     }
 }
@@ -92,7 +139,15 @@ mod tests {
         let mut parser = ReqParser::new_capacity(2048);
 
         // Actually run the function to test
-        let result = receive(&mut parser, &mut tmp_recv, &mut read_buf, initial_offset).await;
+        let result = receive(
+            &mut parser,
+            &mut tmp_recv,
+            &mut read_buf,
+            initial_offset,
+            &Timeouts::default(),
+            false,
+        )
+        .await;
 
         assert_eq!(true, result.is_ok());
         let (request, left_over) = result.unwrap();
@@ -100,16 +155,12 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("Content-Length", 10);
         headers.set("Other-Header", "other-value");
-        let expected_req = Request::new(
-            "HTTP/1.1",
-            Method::GET,
-            "/test/",
-            headers,
-            "Just data.".as_bytes(),
-        );
+        let expected_req = Request::new("HTTP/1.1", Method::GET, "/test/", headers, "".as_bytes());
         assert_eq!(expected_req, request);
 
-        assert_eq!(0, left_over);
+        // The Body is left in the Buffer
+        assert_eq!(10, left_over);
+        assert_eq!("Just data.".as_bytes(), &read_buf[..10]);
     }
 
     #[tokio::test]
@@ -127,7 +178,15 @@ mod tests {
         let mut parser = ReqParser::new_capacity(2048);
 
         // Actually run the function to test
-        let result = receive(&mut parser, &mut tmp_recv, &mut read_buf, initial_offset).await;
+        let result = receive(
+            &mut parser,
+            &mut tmp_recv,
+            &mut read_buf,
+            initial_offset,
+            &Timeouts::default(),
+            false,
+        )
+        .await;
 
         assert_eq!(true, result.is_ok());
         let (request, left_over) = result.unwrap();
@@ -135,17 +194,11 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("Content-Length", 10);
         headers.set("Other-Header", "other-value");
-        let expected_req = Request::new(
-            "HTTP/1.1",
-            Method::GET,
-            "/test/",
-            headers,
-            "Just data.".as_bytes(),
-        );
+        let expected_req = Request::new("HTTP/1.1", Method::GET, "/test/", headers, "".as_bytes());
         assert_eq!(expected_req, request);
 
-        assert_eq!(13, left_over);
-        assert_eq!("And some more".as_bytes(), &read_buf[..13]);
+        assert_eq!(23, left_over);
+        assert_eq!("Just data.And some more".as_bytes(), &read_buf[..23]);
     }
 
     #[tokio::test]
@@ -164,7 +217,15 @@ mod tests {
         let mut parser = ReqParser::new_capacity(2048);
 
         // Actually run the function to test
-        let result = receive(&mut parser, &mut tmp_recv, &mut read_buf, initial_offset).await;
+        let result = receive(
+            &mut parser,
+            &mut tmp_recv,
+            &mut read_buf,
+            initial_offset,
+            &Timeouts::default(),
+            false,
+        )
+        .await;
 
         assert_eq!(true, result.is_ok());
         let (request, left_over) = result.unwrap();
@@ -172,15 +233,93 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("Content-Length", 10);
         headers.set("Other-Header", "other-value");
-        let expected_req = Request::new(
-            "HTTP/1.1",
-            Method::GET,
-            "/test/",
-            headers,
-            "Just data.".as_bytes(),
-        );
+        let expected_req = Request::new("HTTP/1.1", Method::GET, "/test/", headers, "".as_bytes());
         assert_eq!(expected_req, request);
 
-        assert_eq!(0, left_over);
+        // The Body is left in the Buffer
+        assert_eq!(10, left_over);
+        assert_eq!("Just data.".as_bytes(), &read_buf[..10]);
+    }
+
+    #[tokio::test]
+    async fn timeout_headers() {
+        let mut tmp_recv = PendingReceiver::new(b"GET /test/ HTTP/1.1\r\n");
+
+        let mut read_buf = [0; 2048];
+        let mut parser = ReqParser::new_capacity(2048);
+        let timeouts = Timeouts {
+            header_read: std::time::Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        let result = receive(
+            &mut parser,
+            &mut tmp_recv,
+            &mut read_buf,
+            0,
+            &timeouts,
+            false,
+        )
+        .await;
+        assert_eq!(
+            true,
+            matches!(result, Err(RecvReqError::Timeout(ReadPhase::Headers)))
+        );
+    }
+
+    #[tokio::test]
+    async fn timeout_idle() {
+        let mut tmp_recv = PendingReceiver::new(b"");
+
+        let mut read_buf = [0; 2048];
+        let mut parser = ReqParser::new_capacity(2048);
+        let timeouts = Timeouts {
+            keep_alive_idle: std::time::Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        let result = receive(
+            &mut parser,
+            &mut tmp_recv,
+            &mut read_buf,
+            0,
+            &timeouts,
+            true,
+        )
+        .await;
+        assert_eq!(
+            true,
+            matches!(result, Err(RecvReqError::Timeout(ReadPhase::Idle)))
+        );
+    }
+
+    /// A Receiver that returns the given Data and then never returns
+    /// anything else
+    #[derive(Debug)]
+    struct PendingReceiver {
+        data: Option<Vec<u8>>,
+    }
+
+    impl PendingReceiver {
+        fn new(data: &[u8]) -> Self {
+            let data = if data.is_empty() {
+                None
+            } else {
+                Some(data.to_vec())
+            };
+            Self { data }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Receiver for PendingReceiver {
+        async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if let Some(data) = self.data.take() {
+                buf[..data.len()].copy_from_slice(&data);
+                return Ok(data.len());
+            }
+
+            std::future::pending().await
+        }
     }
 }
@@ -4,33 +4,39 @@ use stream_httparse::{streaming_parser::RespParser, Request};
 
 use crate::websockets;
 use general_traits::{Receiver, Sender};
-use rules::Rule;
+use rules::{ConnectionInfo, Rule};
 
 mod websocket_con;
 
-#[tracing::instrument(skip(resp_parser, request))]
+#[tracing::instrument(skip(resp_parser, request, conn_info))]
 pub async fn handle<R, S>(
     id: u32,
     request: Request<'_>,
     receiver: R,
     mut sender: S,
     matched: Arc<Rule>,
+    conn_info: &ConnectionInfo,
     resp_parser: &mut RespParser,
 ) where
     R: Receiver + Send + 'static,
     S: Sender + Send + 'static,
 {
-    let (read, write) =
-        match websockets::handshake::handle(&request, &mut sender, &matched, resp_parser).await {
-            Some(c) => c,
-            None => {
-                tracing::error!("[{}] Performing Websocket Handshake", id);
-                return;
-            }
-        };
+    let (read, write) = match websockets::handshake::handle(
+        &request,
+        &mut sender,
+        &matched,
+        conn_info,
+        resp_parser,
+    )
+    .await
+    {
+        Some(c) => c,
+        None => {
+            tracing::error!("[{}] Performing Websocket Handshake", id);
+            return;
+        }
+    };
 
     tokio::task::spawn(websocket_con::run_receiver(receiver, write));
     tokio::task::spawn(websocket_con::run_sender(sender, read));
-
-    
 }
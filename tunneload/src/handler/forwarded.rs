@@ -0,0 +1,284 @@
+use std::net::IpAddr;
+
+use ipnetwork::IpNetwork;
+use rules::ConnectionInfo;
+use stream_httparse::Request;
+
+/// Adds the X-Forwarded-*, X-Real-IP and Forwarded Headers to Requests,
+/// so the Services know about the Client and how it connected.
+///
+/// The Values of these Headers that were sent by the Client are only
+/// kept, if the Client is one of the trusted Proxies, otherwise they
+/// are replaced. The same applies to the X-Forwarded-Prefix and
+/// X-Replaced-Path Headers, which are set by the path rewriting Actions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ForwardedHeaders {
+    trusted: Vec<IpNetwork>,
+}
+
+impl ForwardedHeaders {
+    /// Creates a new Instance that trusts the Headers sent by Clients
+    /// in the given Networks
+    pub fn new(trusted: Vec<IpNetwork>) -> Self {
+        Self { trusted }
+    }
+
+    /// Parses the given CIDRs of the trusted Proxies, invalid ones are
+    /// logged and skipped
+    pub fn from_cidrs(raw: &[String]) -> Self {
+        let mut trusted = Vec::new();
+        for cidr in raw.iter() {
+            match cidr.parse() {
+                Ok(network) => trusted.push(network),
+                Err(e) => tracing::error!("Parsing trusted Proxy CIDR '{}': {}", cidr, e),
+            };
+        }
+        Self::new(trusted)
+    }
+
+    /// Checks if the given IP belongs to a trusted Proxy
+    pub fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted.iter().any(|net| net.contains(*ip))
+    }
+
+    /// Updates the Headers of the Request, that was received on the
+    /// Connection with the given Info, and returns the Info for the
+    /// Request, which contains the Scheme reported by a trusted Proxy
+    pub fn apply(&self, req: &mut Request<'_>, info: &ConnectionInfo) -> ConnectionInfo {
+        let scheme = if info.is_tls() { "https" } else { "http" };
+        let client_ip = info.client_ip().copied();
+        let trusted = client_ip.map(|ip| self.is_trusted(&ip)).unwrap_or(false);
+        let host = req.headers().get("Host").map(|h| h.to_string());
+
+        let headers = req.header_mut();
+        let mut forwarded_for: Vec<String> = take_all(headers, "X-Forwarded-For")
+            .iter()
+            .flat_map(|v| v.split(','))
+            .map(|a| a.trim().to_owned())
+            .filter(|a| !a.is_empty())
+            .collect();
+        let mut proto = take_all(headers, "X-Forwarded-Proto").into_iter().next();
+        let mut forwarded_host = take_all(headers, "X-Forwarded-Host").into_iter().next();
+        let mut forwarded = take_all(headers, "Forwarded");
+        take_all(headers, "X-Real-IP");
+
+        if !trusted {
+            take_all(headers, "X-Forwarded-Prefix");
+            take_all(headers, "X-Replaced-Path");
+            forwarded_for.clear();
+            proto = None;
+            forwarded_host = None;
+            forwarded.clear();
+        }
+
+        if let Some(ip) = client_ip {
+            forwarded_for.push(ip.to_string());
+        }
+        // The real Client is the last Address in the Chain that was not
+        // added by one of the trusted Proxies
+        let real_ip = forwarded_for
+            .iter()
+            .rev()
+            .find(|a| match a.parse::<IpAddr>() {
+                Ok(ip) => !self.is_trusted(&ip),
+                Err(_) => true,
+            })
+            .or_else(|| forwarded_for.first())
+            .cloned();
+
+        let mut element = Vec::new();
+        if let Some(ip) = client_ip {
+            element.push(format!("for={}", forwarded_node(&ip)));
+        }
+        // A Host that is not a valid Authority could otherwise end the
+        // quoted Value and inject other Parameters or Elements
+        if let Some(host) = host.as_ref().filter(|h| is_authority(h)) {
+            element.push(format!("host=\"{}\"", host));
+        }
+        element.push(format!("proto={}", scheme));
+        forwarded.push(element.join(";"));
+
+        if !forwarded_for.is_empty() {
+            headers.set("X-Forwarded-For", forwarded_for.join(", "));
+        }
+        if let Some(ip) = real_ip {
+            headers.set("X-Real-IP", ip);
+        }
+        let forwarded_scheme = proto.as_ref().map(|p| p.trim().to_ascii_lowercase());
+        headers.set(
+            "X-Forwarded-Proto",
+            proto.unwrap_or_else(|| scheme.to_owned()),
+        );
+        if let Some(host) = forwarded_host.or(host) {
+            headers.set("X-Forwarded-Host", host);
+        }
+        headers.set("Forwarded", forwarded.join(", "));
+
+        info.clone().with_forwarded_scheme(forwarded_scheme)
+    }
+}
+
+/// Removes all the Headers with the given Key and returns their Values
+fn take_all(headers: &mut stream_httparse::Headers<'_>, key: &'static str) -> Vec<String> {
+    let mut result = Vec::new();
+    while let Some(value) = headers.get(key) {
+        result.push(value.to_string());
+        headers.remove(key);
+    }
+    result
+}
+
+/// Checks if the Host only consists of the Characters allowed in the
+/// Authority of a URI, see RFC 3986
+fn is_authority(host: &str) -> bool {
+    !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-._~%!$&'()*+,;=:@[]".contains(c))
+}
+
+/// Formats the IP as a Node for the Forwarded-Header, where IPv6
+/// Addresses have to be quoted, see RFC 7239
+fn forwarded_node(ip: &IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("\"[{}]\"", ip),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stream_httparse::{Headers, Method};
+
+    use super::*;
+
+    fn request(headers: &[(&'static str, &'static str)]) -> Request<'static> {
+        let mut tmp = Headers::new();
+        for (key, value) in headers {
+            tmp.set(*key, *value);
+        }
+        Request::new("HTTP/1.1", Method::GET, "/", tmp, &[])
+    }
+
+    fn header(req: &Request, key: &str) -> Option<String> {
+        req.headers().get(key).map(|v| v.to_string())
+    }
+
+    #[test]
+    fn untrusted_client() {
+        let forwarded = ForwardedHeaders::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let info = ConnectionInfo::new(Some("192.168.0.1".parse().unwrap()));
+        let mut req = request(&[
+            ("Host", "example.com"),
+            ("X-Forwarded-For", "1.2.3.4"),
+            ("X-Forwarded-Proto", "https"),
+            ("X-Real-IP", "1.2.3.4"),
+            ("Forwarded", "for=1.2.3.4"),
+            ("X-Forwarded-Prefix", "/admin"),
+            ("X-Replaced-Path", "/admin"),
+        ]);
+
+        let req_info = forwarded.apply(&mut req, &info);
+        assert_eq!("http", req_info.scheme());
+        assert_eq!(None, header(&req, "X-Forwarded-Prefix"));
+        assert_eq!(None, header(&req, "X-Replaced-Path"));
+
+        assert_eq!(
+            Some("192.168.0.1".to_owned()),
+            header(&req, "X-Forwarded-For")
+        );
+        assert_eq!(Some("192.168.0.1".to_owned()), header(&req, "X-Real-IP"));
+        assert_eq!(Some("http".to_owned()), header(&req, "X-Forwarded-Proto"));
+        assert_eq!(
+            Some("example.com".to_owned()),
+            header(&req, "X-Forwarded-Host")
+        );
+        assert_eq!(
+            Some("for=192.168.0.1;host=\"example.com\";proto=http".to_owned()),
+            header(&req, "Forwarded")
+        );
+    }
+
+    #[test]
+    fn trusted_proxy() {
+        let forwarded = ForwardedHeaders::new(vec!["10.0.0.0/8".parse().unwrap()]);
+        let info = ConnectionInfo::new(Some("10.0.0.2".parse().unwrap())).with_tls(true);
+        let mut req = request(&[
+            ("Host", "internal"),
+            ("X-Forwarded-For", "1.2.3.4, 10.0.0.1"),
+            ("X-Forwarded-Proto", "http"),
+            ("X-Forwarded-Host", "example.com"),
+            ("Forwarded", "for=1.2.3.4"),
+            ("X-Forwarded-Prefix", "/api"),
+        ]);
+
+        // The Connection to the trusted Proxy uses TLS, but the Client did not
+        let req_info = forwarded.apply(&mut req, &info);
+        assert_eq!("http", req_info.scheme());
+        assert_eq!(Some("/api".to_owned()), header(&req, "X-Forwarded-Prefix"));
+
+        assert_eq!(
+            Some("1.2.3.4, 10.0.0.1, 10.0.0.2".to_owned()),
+            header(&req, "X-Forwarded-For")
+        );
+        assert_eq!(Some("1.2.3.4".to_owned()), header(&req, "X-Real-IP"));
+        assert_eq!(Some("http".to_owned()), header(&req, "X-Forwarded-Proto"));
+        assert_eq!(
+            Some("example.com".to_owned()),
+            header(&req, "X-Forwarded-Host")
+        );
+        assert_eq!(
+            Some("for=1.2.3.4, for=10.0.0.2;host=\"internal\";proto=https".to_owned()),
+            header(&req, "Forwarded")
+        );
+    }
+
+    #[test]
+    fn ipv6_client() {
+        let forwarded = ForwardedHeaders::default();
+        let info = ConnectionInfo::new(Some("2001:db8::1".parse().unwrap()));
+        let mut req = request(&[]);
+
+        forwarded.apply(&mut req, &info);
+
+        assert_eq!(
+            Some("2001:db8::1".to_owned()),
+            header(&req, "X-Forwarded-For")
+        );
+        assert_eq!(None, header(&req, "X-Forwarded-Host"));
+        assert_eq!(
+            Some("for=\"[2001:db8::1]\";proto=http".to_owned()),
+            header(&req, "Forwarded")
+        );
+    }
+
+    #[test]
+    fn invalid_host_omitted() {
+        let forwarded = ForwardedHeaders::default();
+        let info = ConnectionInfo::new(Some("192.168.0.1".parse().unwrap()));
+        let mut req = request(&[("Host", "a\";for=1.2.3.4")]);
+
+        forwarded.apply(&mut req, &info);
+
+        assert_eq!(
+            Some("for=192.168.0.1;proto=http".to_owned()),
+            header(&req, "Forwarded")
+        );
+
+        let mut req = request(&[("Host", "[::1]:8080")]);
+        forwarded.apply(&mut req, &info);
+        assert_eq!(
+            Some("for=192.168.0.1;host=\"[::1]:8080\";proto=http".to_owned()),
+            header(&req, "Forwarded")
+        );
+    }
+
+    #[test]
+    fn from_cidrs_skips_invalid() {
+        let forwarded =
+            ForwardedHeaders::from_cidrs(&["10.0.0.0/8".to_owned(), "invalid".to_owned()]);
+
+        assert_eq!(true, forwarded.is_trusted(&"10.1.2.3".parse().unwrap()));
+        assert_eq!(false, forwarded.is_trusted(&"192.168.0.1".parse().unwrap()));
+    }
+}
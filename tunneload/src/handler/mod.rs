@@ -3,3 +3,10 @@
 
 mod basic;
 pub use basic::BasicHandler;
+pub(crate) use basic::{chunked, header_end};
+
+mod timeouts;
+pub use timeouts::Timeouts;
+
+mod forwarded;
+pub use forwarded::ForwardedHeaders;
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use rules::Service;
+
+/// All the Timeouts used while handling a Connection
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timeouts {
+    /// The Time allowed for receiving the Headers of a Request
+    pub header_read: Duration,
+    /// The Time allowed between two Reads of the Body of a Request,
+    /// while it is forwarded
+    pub body_read: Duration,
+    /// The Time a Keep-Alive Connection may be idle before the
+    /// next Request starts
+    pub keep_alive_idle: Duration,
+    /// The Time allowed for establishing a Connection to a Service
+    pub upstream_connect: Duration,
+    /// The Time allowed for a Service to send its Response, after
+    /// the Request was sent
+    pub upstream_response: Duration,
+    /// The Time allowed between two Reads of the Body of the Response
+    /// from a Service, while it is forwarded
+    pub upstream_read: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            header_read: Duration::from_secs(10),
+            body_read: Duration::from_secs(60),
+            keep_alive_idle: Duration::from_secs(60),
+            upstream_connect: Duration::from_secs(5),
+            upstream_response: Duration::from_secs(60),
+            upstream_read: Duration::from_secs(60),
+        }
+    }
+}
+
+impl Timeouts {
+    /// The Timeouts for the given Service, where every Timeout configured
+    /// for the Service overwrites the global one
+    pub fn for_service(&self, service: &Service) -> Self {
+        let overwrites = match service.timeouts() {
+            Some(t) => t,
+            None => return self.clone(),
+        };
+
+        Self {
+            header_read: overwrites.header_read().unwrap_or(self.header_read),
+            body_read: overwrites.body_read().unwrap_or(self.body_read),
+            keep_alive_idle: overwrites.keep_alive_idle().unwrap_or(self.keep_alive_idle),
+            upstream_connect: overwrites.connect().unwrap_or(self.upstream_connect),
+            upstream_response: overwrites.response().unwrap_or(self.upstream_response),
+            upstream_read: overwrites.response_read().unwrap_or(self.upstream_read),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use general::{Group, Name};
+    use rules::ServiceTimeouts;
+
+    use super::*;
+
+    #[test]
+    fn service_overwrites() {
+        let timeouts = Timeouts::default();
+        let mut service = Service::new(Name::new("test", Group::Internal), vec![]);
+        assert_eq!(timeouts, timeouts.for_service(&service));
+
+        service.set_timeouts(ServiceTimeouts {
+            body_read_ms: Some(100),
+            connect_ms: Some(250),
+            response_read_ms: Some(500),
+            ..Default::default()
+        });
+        assert_eq!(
+            Timeouts {
+                body_read: Duration::from_millis(100),
+                upstream_connect: Duration::from_millis(250),
+                upstream_read: Duration::from_millis(500),
+                ..Default::default()
+            },
+            timeouts.for_service(&service)
+        );
+    }
+}
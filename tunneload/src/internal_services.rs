@@ -87,10 +87,11 @@ impl Internals {
         if config.dashboard {
             log::info!("Enabled the internal Dashboard");
 
-            let (_, service_list, middleware_list, action_plugin_list) =
+            let (rule_list, service_list, middleware_list, action_plugin_list) =
                 config_manager.get_config_lists();
             let mut internal_dashboard = Dashboard::new(
                 read_manager,
+                rule_list,
                 service_list,
                 middleware_list,
                 DashboardEntityList::new(),
@@ -7,7 +7,7 @@ use serde::ser::SerializeSeq;
 use serde_json::json;
 use stream_httparse::{Headers, Request, Response, StatusCode};
 
-use crate::configurator::{MiddlewareList, PluginList, ServiceList};
+use crate::configurator::{MiddlewareList, PluginList, RuleList, ServiceList};
 use general_traits::Sender;
 use rules::{Matcher, ReadManager, Rule, Service};
 
@@ -22,6 +22,7 @@ mod file;
 /// the Tunneler-Dashboard as an internal Service
 pub struct Dashboard {
     rules: ReadManager,
+    rule_list: RuleList,
     services: ServiceList,
     middlewares: MiddlewareList,
     acceptors: DashboardEntityList,
@@ -41,6 +42,7 @@ impl Dashboard {
     /// Creates a new Dashboard
     pub fn new(
         rules: ReadManager,
+        rule_list: RuleList,
         services: ServiceList,
         middlewares: MiddlewareList,
         acceptors: DashboardEntityList,
@@ -49,6 +51,7 @@ impl Dashboard {
     ) -> Self {
         Self {
             rules,
+            rule_list,
             services,
             middlewares,
             acceptors,
@@ -94,7 +97,7 @@ impl Dashboard {
             return api::handle_configurators(request, sender, &self.configurators).await;
         }
         if self.rules_matcher.matches(request) {
-            return api::handle_rules(request, sender, &self.rules).await;
+            return api::handle_rules(request, sender, &self.rules, &self.rule_list).await;
         }
         if self.services_matcher.matches(request) {
             return api::handle_services(request, sender, &self.services).await;
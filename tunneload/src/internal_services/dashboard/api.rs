@@ -1,10 +1,10 @@
 use serde::Serialize;
 use stream_httparse::{Headers, Request, Response, StatusCode};
 
-use crate::configurator::{MiddlewareList, PluginList, ServiceList};
+use crate::configurator::{MiddlewareList, PluginList, RuleList, ServiceList};
 use general_traits::Sender;
 use plugins::Plugin;
-use rules::{Middleware, ReadManager, Rule, Service};
+use rules::{Middleware, ReadManager, Rule, RuleConflict, Service};
 
 use super::DashboardEntityList;
 
@@ -57,12 +57,14 @@ pub async fn handle_configurators(
 #[derive(Debug, Serialize)]
 struct AllRulesResponse {
     rules: Vec<Rule>,
+    conflicts: Vec<RuleConflict>,
 }
 
 pub async fn handle_rules(
     _request: &Request<'_>,
     sender: &mut dyn Sender,
     rule_manager: &ReadManager,
+    rule_list: &RuleList,
 ) -> Result<(), ()> {
     let all_rules = rule_manager.get_all_rules().ok_or(())?;
 
@@ -71,7 +73,10 @@ pub async fn handle_rules(
         final_rules.push(Rule::clone(tmp.as_ref()));
     }
 
-    let raw_content = AllRulesResponse { rules: final_rules };
+    let raw_content = AllRulesResponse {
+        rules: final_rules,
+        conflicts: rule_list.conflicts(),
+    };
     let content = serde_json::to_vec(&raw_content).map_err(|_| ())?;
 
     let mut headers = Headers::new();
@@ -13,10 +13,44 @@ interface Middleware {
 	action: Action,
 };
 
+interface Strategy {
+	type: String,
+	weights: Object | undefined,
+	key: Object | undefined,
+};
+
+interface HealthCheck {
+	path: String | null,
+	interval_ms: Number,
+	timeout_ms: Number,
+	healthy_threshold: Number,
+	unhealthy_threshold: Number,
+};
+
+interface ServiceTimeouts {
+	header_read_ms: Number | null,
+	body_read_ms: Number | null,
+	keep_alive_idle_ms: Number | null,
+	connect_ms: Number | null,
+	response_ms: Number | null,
+	response_read_ms: Number | null,
+};
+
+interface OutlierDetection {
+	consecutive_failures: Number,
+	base_ejection_ms: Number,
+	max_ejection_ms: Number,
+};
+
 interface Service {
 	name: String,
 	addresses: Array<any>,
 	internal: Boolean,
+	strategy: Strategy | null,
+	health_check: HealthCheck | null,
+	health: Array<Boolean>,
+	timeouts: ServiceTimeouts | null,
+	outlier_detection: OutlierDetection | null,
 };
 
 interface TLS {
@@ -1,15 +1,15 @@
-use std::{env, sync::Arc, time::Duration};
+use std::{collections::HashMap, env, sync::Arc, time::Duration};
 
 use argser::FromArgs;
 use tokio::task::JoinHandle;
 
 use general_traits::Handler;
 use tunneload::{
-    acceptors::{tunneler, webserver},
+    acceptors::{tcp, tunneler, udp, webserver},
     cli,
     configurator::{self, Manager},
-    forwarder::BasicForwarder,
-    handler::BasicHandler,
+    forwarder::{BasicForwarder, ConnectionPool},
+    handler::{BasicHandler, ForwardedHeaders, Timeouts},
     internal_services::{DashboardEntityList, Internals, StatusHandler},
     metrics, tls,
 };
@@ -70,6 +70,10 @@ fn main() {
     let mut config_builder = configurator::Manager::builder();
     config_builder = config_builder.writer(write_manager);
 
+    // All the named Entrypoints, which the Rules may be restricted to
+    let acceptor_settings = AcceptorSettings::from_options(&config);
+    config_builder = config_builder.entrypoints(acceptor_settings.entrypoints.names());
+
     // Setup the TLS-Configuration
     let tls_config = tls::ConfigManager::new();
     config_builder = config_builder.tls(tls_config.clone());
@@ -94,6 +98,8 @@ fn main() {
 
     // Actually construct the Config-Manager
     let mut config_manager = config_builder.build();
+    let tcp_rules = config_manager.get_tcp_rules();
+    let udp_rules = config_manager.get_udp_rules();
 
     // Create the "Manager" for all the Internal-Services
     let mut internals = Internals::new();
@@ -123,17 +129,29 @@ fn main() {
     rt.spawn(config_manager.start());
 
     // Initialize the standard Forwarder and Handler for Requests
-    let forwarder = BasicForwarder::new();
+    let forwarder = BasicForwarder::with_pool(ConnectionPool::from(&config.pool));
+    BasicForwarder::register_metrics(&metrics_registry);
+    rt.spawn(forwarder.sweep_pool());
     let handler = BasicHandler::new(
         read_manager,
         forwarder,
         internals,
         Some(metrics_registry.clone()),
-    );
+    )
+    .with_timeouts(Timeouts::from(&config.timeouts))
+    .with_max_body_size(config.max_body_size.map(|size| size as usize))
+    .with_forwarded_headers(ForwardedHeaders::from_cidrs(&config.trusted_proxies));
 
     // Setup all the Acceptors
-    let acceptor_futures =
-        setup_acceptors(&rt, &config, handler.clone(), tls_config, metrics_registry);
+    let acceptor_futures = setup_acceptors(
+        &rt,
+        &acceptor_settings,
+        handler.clone(),
+        tcp_rules,
+        udp_rules,
+        tls_config,
+        metrics_registry,
+    );
     for plugin_acceptor in plugin_acceptors.into_iter() {
         rt.block_on(plugin_acceptor.start(handler.clone()));
     }
@@ -212,10 +230,32 @@ fn setup_configurators(
     config_builder
 }
 
+/// The Settings for all the Acceptors, taken from the parsed CLI-Options
+struct AcceptorSettings<'a> {
+    /// The named Entrypoints, split up by their Protocol
+    entrypoints: cli::Entrypoints,
+    /// The unnamed Tunneler-Acceptors
+    tunneler: &'a HashMap<String, cli::TunnelerOpts>,
+}
+
+impl<'a> AcceptorSettings<'a> {
+    fn from_options(config: &'a cli::Options) -> Self {
+        let mut entrypoints = cli::Entrypoints::split(&config.entrypoint);
+        entrypoints.extend(&config.webserver, &config.tcp, &config.udp);
+
+        Self {
+            entrypoints,
+            tunneler: &config.tunneler,
+        }
+    }
+}
+
 fn setup_acceptors<H>(
     rt: &tokio::runtime::Runtime,
-    config: &cli::Options,
+    settings: &AcceptorSettings<'_>,
     handler: H,
+    tcp_rules: configurator::TcpRuleList,
+    udp_rules: configurator::UdpRuleList,
     tls_config: tls::ConfigManager,
     metrics_registry: prometheus::Registry,
 ) -> Vec<JoinHandle<()>>
@@ -226,15 +266,30 @@ where
 
     acceptor_futures.extend(webserver::setup(
         rt,
-        &config.webserver,
+        &settings.entrypoints.webserver,
         tls_config.clone(),
         handler.clone(),
         &metrics_registry,
     ));
 
+    acceptor_futures.extend(tcp::setup(
+        rt,
+        &settings.entrypoints.tcp,
+        tcp_rules,
+        tls_config.clone(),
+        &metrics_registry,
+    ));
+
+    acceptor_futures.extend(udp::setup(
+        rt,
+        &settings.entrypoints.udp,
+        udp_rules,
+        &metrics_registry,
+    ));
+
     acceptor_futures.extend(tunneler::setup(
         rt,
-        &config.tunneler,
+        settings.tunneler,
         handler,
         tls_config,
         &metrics_registry,
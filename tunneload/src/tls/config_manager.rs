@@ -5,6 +5,11 @@ use std::{
     sync::Arc,
 };
 
+/// The ALPN-ID for HTTP/2
+pub const ALPN_H2: &[u8] = b"h2";
+/// The ALPN-ID for HTTP/1.1
+pub const ALPN_HTTP1: &[u8] = b"http/1.1";
+
 /// Manages all the Configuration options around TLS
 #[derive(Clone)]
 pub struct ConfigManager {
@@ -21,13 +26,7 @@ impl Debug for ConfigManager {
 impl ConfigManager {
     /// Creates a new Configuration Manager
     pub fn new() -> Self {
-        let server_conf = ServerConfig::builder()
-            .with_safe_default_cipher_suites()
-            .with_safe_default_kx_groups()
-            .with_safe_default_protocol_versions()
-            .expect("Creating Server Config")
-            .with_no_client_auth()
-            .with_cert_resolver(Arc::new(rustls::server::ResolvesServerCertUsingSni::new()));
+        let server_conf = Self::create_config(rustls::server::ResolvesServerCertUsingSni::new());
 
         Self {
             config: Arc::new(ArcSwap::from(Arc::new(server_conf))),
@@ -51,6 +50,20 @@ impl ConfigManager {
         inner.clone()
     }
 
+    /// Creates the TLS-Config that uses the given Resolver for its
+    /// Certificates and offers HTTP/2 as well as HTTP/1.1 over ALPN
+    fn create_config(resolver: rustls::server::ResolvesServerCertUsingSni) -> ServerConfig {
+        let mut config = ServerConfig::builder()
+            .with_safe_default_cipher_suites()
+            .with_safe_default_kx_groups()
+            .with_safe_default_protocol_versions()
+            .expect("Creating Server Config")
+            .with_no_client_auth()
+            .with_cert_resolver(Arc::new(resolver));
+        config.alpn_protocols = vec![ALPN_H2.to_vec(), ALPN_HTTP1.to_vec()];
+        config
+    }
+
     /// Creates a new Resolver with all the Keys from the given BTreeMap
     fn create_resolver(
         certs: &std::collections::BTreeMap<String, rustls::sign::CertifiedKey>,
@@ -78,13 +91,7 @@ impl ConfigManager {
         for (name, cert) in certs.drain(..) {
             inner_btree.insert(name, cert);
         }
-        let config = ServerConfig::builder()
-            .with_safe_default_cipher_suites()
-            .with_safe_default_kx_groups()
-            .with_safe_default_protocol_versions()
-            .expect("Creating Server Config")
-            .with_no_client_auth()
-            .with_cert_resolver(Arc::new(Self::create_resolver(&inner_btree)));
+        let config = Self::create_config(Self::create_resolver(&inner_btree));
 
         self.config.store(Arc::new(config));
     }
@@ -97,13 +104,7 @@ impl ConfigManager {
         };
         inner_btree.insert(cert.0, cert.1);
 
-        let config = ServerConfig::builder()
-            .with_safe_default_cipher_suites()
-            .with_safe_default_kx_groups()
-            .with_safe_default_protocol_versions()
-            .expect("Creating Server Config")
-            .with_no_client_auth()
-            .with_cert_resolver(Arc::new(Self::create_resolver(&inner_btree)));
+        let config = Self::create_config(Self::create_resolver(&inner_btree));
 
         self.config.store(Arc::new(config));
     }
@@ -116,13 +117,7 @@ impl ConfigManager {
         };
         inner_btree.remove(domain);
 
-        let config = ServerConfig::builder()
-            .with_safe_default_cipher_suites()
-            .with_safe_default_kx_groups()
-            .with_safe_default_protocol_versions()
-            .expect("Creating Server Config")
-            .with_no_client_auth()
-            .with_cert_resolver(Arc::new(Self::create_resolver(&inner_btree)));
+        let config = Self::create_config(Self::create_resolver(&inner_btree));
 
         self.config.store(Arc::new(config));
     }
@@ -142,3 +137,19 @@ impl Default for ConfigManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alpn_protocols() {
+        let manager = ConfigManager::new();
+
+        let expected = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        assert_eq!(expected, manager.get_config().alpn_protocols);
+
+        manager.remove_cert("example.com");
+        assert_eq!(expected, manager.get_config().alpn_protocols);
+    }
+}
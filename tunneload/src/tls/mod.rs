@@ -11,6 +11,6 @@ mod create_sender_receiver;
 pub use create_sender_receiver::create_sender_receiver;
 
 mod config_manager;
-pub use config_manager::ConfigManager;
+pub use config_manager::{ConfigManager, ALPN_H2, ALPN_HTTP1};
 
 pub mod auto;
@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use std::{
     fmt::{Debug, Formatter},
     io::Read,
+    net::{IpAddr, SocketAddr},
 };
 
 /// All Data received over this Receiver is encrypted using TLS
@@ -36,6 +37,13 @@ where
         }
     }
 
+    /// The Protocol that was negotiated using ALPN during the Handshake,
+    /// if any
+    pub fn alpn_protocol(&self) -> Option<Vec<u8>> {
+        let tls_session = self.session.lock().ok()?;
+        tls_session.alpn_protocol().map(|p| p.to_vec())
+    }
+
     fn read_from_buf(&self, buf: &mut [u8]) -> Option<std::io::Result<usize>> {
         let mut tls_session = self.session.lock().ok()?;
         if tls_session.wants_read() {
@@ -52,6 +60,26 @@ impl<R> ReceiverTrait for Receiver<R>
 where
     R: ReceiverTrait + Send,
 {
+    fn peer_ip(&self) -> Option<IpAddr> {
+        self.og_read.peer_ip()
+    }
+
+    fn peer_address(&self) -> Option<SocketAddr> {
+        self.og_read.peer_address()
+    }
+
+    fn local_address(&self) -> Option<SocketAddr> {
+        self.og_read.local_address()
+    }
+
+    fn is_tls(&self) -> bool {
+        true
+    }
+
+    fn entrypoint(&self) -> Option<&str> {
+        self.og_read.entrypoint()
+    }
+
     async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         if let Some(res) = self.read_from_buf(buf) {
             return res;
@@ -6,4 +6,6 @@
 pub mod webserver;
 
 pub mod files;
+pub mod http2;
 pub mod kubernetes;
+pub mod proxy_protocol;
@@ -0,0 +1,138 @@
+//! Helpers for translating between HTTP/2 and the HTTP/1.1 Messages,
+//! that are used by the rest of the Load-Balancer
+
+use bytes::Bytes;
+use h2::{Reason, SendStream};
+use http::{HeaderMap, HeaderValue};
+
+/// The Headers that only apply to a single HTTP/1.1 Connection and
+/// must not be sent over HTTP/2
+const CONNECTION_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Removes all the Headers that are not allowed to be sent over HTTP/2,
+/// which includes the 'TE'-Header unless it only contains "trailers"
+pub fn remove_connection_headers(headers: &mut HeaderMap) {
+    for key in CONNECTION_HEADERS {
+        headers.remove(*key);
+    }
+
+    let te_trailers = headers
+        .get_all(http::header::TE)
+        .iter()
+        .all(|v| v.as_bytes().eq_ignore_ascii_case(b"trailers"));
+    if !te_trailers {
+        headers.remove(http::header::TE);
+    }
+}
+
+/// Parses the given HTTP/1.1 Header-Lines, each ending with '\r\n',
+/// returns None if one of them is invalid
+pub fn parse_headers(raw: &str) -> Option<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for line in raw.split("\r\n").filter(|l| !l.is_empty()) {
+        let (key, value) = line.split_once(':')?;
+        let key = http::header::HeaderName::from_bytes(key.trim().as_bytes()).ok()?;
+        let value = HeaderValue::from_str(value.trim()).ok()?;
+        headers.append(key, value);
+    }
+    Some(headers)
+}
+
+/// Serializes the Headers as HTTP/1.1 Header-Lines
+pub fn serialize_headers(headers: &HeaderMap, out: &mut Vec<u8>) {
+    for (key, value) in headers.iter() {
+        out.extend_from_slice(key.as_str().as_bytes());
+        out.extend_from_slice(b": ");
+        out.extend_from_slice(value.as_bytes());
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+/// Serializes the Data as a single Chunk of a chunked Body
+pub fn serialize_chunk(data: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(format!("{:x}\r\n", data.len()).as_bytes());
+    out.extend_from_slice(data);
+    out.extend_from_slice(b"\r\n");
+}
+
+/// Serializes the last Chunk of a chunked Body, including the given
+/// Trailers
+pub fn serialize_last_chunk(trailers: Option<&HeaderMap>, out: &mut Vec<u8>) {
+    out.extend_from_slice(b"0\r\n");
+    if let Some(trailers) = trailers {
+        serialize_headers(trailers, out);
+    }
+    out.extend_from_slice(b"\r\n");
+}
+
+/// Sends the Data on the Stream, while waiting for the other Side to
+/// grant enough Capacity for it, so that the Data is never buffered
+/// as a whole
+pub async fn send_data(stream: &mut SendStream<Bytes>, mut data: Bytes) -> Result<(), h2::Error> {
+    while !data.is_empty() {
+        stream.reserve_capacity(data.len());
+        let capacity = match futures::future::poll_fn(|cx| stream.poll_capacity(cx)).await {
+            Some(c) => c?,
+            None => return Err(h2::Error::from(Reason::CANCEL)),
+        };
+
+        let part = data.split_to(std::cmp::min(capacity, data.len()));
+        stream.send_data(part, false)?;
+    }
+    Ok(())
+}
+
+/// Ends the Stream, with the given Trailers if there are any
+pub fn end_stream(stream: &mut SendStream<Bytes>, trailers: &[u8]) -> Result<(), h2::Error> {
+    let trailers = std::str::from_utf8(trailers).ok().and_then(parse_headers);
+    match trailers {
+        Some(trailers) if !trailers.is_empty() => stream.send_trailers(trailers),
+        _ => stream.send_data(Bytes::new(), true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_headers() {
+        let mut headers = parse_headers(
+            "Connection: keep-alive\r\nTE: trailers\r\nContent-Type: application/grpc\r\n",
+        )
+        .unwrap();
+        remove_connection_headers(&mut headers);
+        assert_eq!(2, headers.len());
+        assert_eq!("trailers", headers.get("te").unwrap());
+
+        let mut headers = parse_headers("TE: gzip\r\n").unwrap();
+        remove_connection_headers(&mut headers);
+        assert_eq!(true, headers.is_empty());
+    }
+
+    #[test]
+    fn parse_invalid_headers() {
+        assert_eq!(true, parse_headers("Invalid\r\n").is_none());
+        assert_eq!(true, parse_headers("Invalid Key: value\r\n").is_none());
+    }
+
+    #[test]
+    fn chunks() {
+        let mut out = Vec::new();
+        serialize_chunk(b"some data", &mut out);
+        serialize_last_chunk(
+            Some(&parse_headers("grpc-status: 0\r\n").unwrap()),
+            &mut out,
+        );
+        assert_eq!(
+            b"9\r\nsome data\r\n0\r\ngrpc-status: 0\r\n\r\n".to_vec(),
+            out
+        );
+    }
+}
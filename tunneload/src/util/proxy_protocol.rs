@@ -0,0 +1,363 @@
+//! Parses and serializes the Headers of the PROXY-Protocol, in Version 1
+//! and 2, which pass on the Addresses of the original Connection when
+//! it was accepted by another Proxy
+//!
+//! Spec: https://www.haproxy.org/download/2.4/doc/proxy-protocol.txt
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use rules::ProxyProtocol;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// The Signature at the Start of every Version 2 Header
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+/// The Prefix of every Version 1 Header
+const V1_PREFIX: &[u8] = b"PROXY ";
+/// The maximum Length of a Version 1 Header, including the final CRLF
+const V1_MAX_LENGTH: usize = 107;
+
+/// The Addresses of the original Connection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProxyAddresses {
+    /// The Address of the Client
+    pub source: SocketAddr,
+    /// The Address the Client connected to
+    pub destination: SocketAddr,
+}
+
+/// The Errors returned when reading a PROXY-Header
+#[derive(Debug)]
+pub enum ProxyHeaderError {
+    /// The Header is malformed
+    Invalid,
+    /// The Connection returned an Error
+    IO(std::io::Error),
+}
+
+#[derive(Debug, PartialEq)]
+enum Parsed {
+    /// More Data is needed to parse the Header
+    Incomplete,
+    /// The Data does not start with a PROXY-Header
+    NotProxy,
+    /// The Header with the given Length was parsed, the Addresses are
+    /// only set if the Header contained any
+    Header {
+        length: usize,
+        addresses: Option<ProxyAddresses>,
+    },
+}
+
+fn parse_v1(line: &[u8]) -> Result<Option<ProxyAddresses>, ProxyHeaderError> {
+    let line = std::str::from_utf8(line).map_err(|_| ProxyHeaderError::Invalid)?;
+    let mut parts = line.split(' ').skip(1);
+
+    match parts.next() {
+        Some("TCP4") | Some("TCP6") => {}
+        Some("UNKNOWN") => return Ok(None),
+        _ => return Err(ProxyHeaderError::Invalid),
+    };
+
+    let mut next = || parts.next().ok_or(ProxyHeaderError::Invalid);
+    let source_ip: IpAddr = next()?.parse().map_err(|_| ProxyHeaderError::Invalid)?;
+    let destination_ip: IpAddr = next()?.parse().map_err(|_| ProxyHeaderError::Invalid)?;
+    let source_port: u16 = next()?.parse().map_err(|_| ProxyHeaderError::Invalid)?;
+    let destination_port: u16 = next()?.parse().map_err(|_| ProxyHeaderError::Invalid)?;
+
+    Ok(Some(ProxyAddresses {
+        source: SocketAddr::new(source_ip, source_port),
+        destination: SocketAddr::new(destination_ip, destination_port),
+    }))
+}
+
+fn parse_v2(header: &[u8]) -> Result<Option<ProxyAddresses>, ProxyHeaderError> {
+    // The LOCAL-Command is used for Connections from the Proxy itself
+    if header[12] & 0x0F == 0x00 {
+        return Ok(None);
+    }
+    if header[12] & 0x0F != 0x01 {
+        return Err(ProxyHeaderError::Invalid);
+    }
+
+    let body = &header[16..];
+    let port = |offset: usize| u16::from_be_bytes([body[offset], body[offset + 1]]);
+    match header[13] >> 4 {
+        // IPv4
+        0x1 if body.len() >= 12 => {
+            let source = Ipv4Addr::new(body[0], body[1], body[2], body[3]);
+            let destination = Ipv4Addr::new(body[4], body[5], body[6], body[7]);
+            Ok(Some(ProxyAddresses {
+                source: SocketAddr::new(source.into(), port(8)),
+                destination: SocketAddr::new(destination.into(), port(10)),
+            }))
+        }
+        // IPv6
+        0x2 if body.len() >= 36 => {
+            let mut source = [0; 16];
+            source.copy_from_slice(&body[0..16]);
+            let mut destination = [0; 16];
+            destination.copy_from_slice(&body[16..32]);
+            Ok(Some(ProxyAddresses {
+                source: SocketAddr::new(Ipv6Addr::from(source).into(), port(32)),
+                destination: SocketAddr::new(Ipv6Addr::from(destination).into(), port(34)),
+            }))
+        }
+        0x1 | 0x2 => Err(ProxyHeaderError::Invalid),
+        // Unix-Sockets and unspecified Addresses are not passed on
+        _ => Ok(None),
+    }
+}
+
+/// Checks if the Data so far matches the Start of the given Prefix
+fn matches_prefix(data: &[u8], prefix: &[u8]) -> bool {
+    let length = std::cmp::min(data.len(), prefix.len());
+    data[..length] == prefix[..length]
+}
+
+fn parse(data: &[u8]) -> Result<Parsed, ProxyHeaderError> {
+    if matches_prefix(data, V2_SIGNATURE) {
+        if data.len() < 16 {
+            return Ok(Parsed::Incomplete);
+        }
+        if data[12] >> 4 != 0x2 {
+            return Err(ProxyHeaderError::Invalid);
+        }
+
+        let length = 16 + u16::from_be_bytes([data[14], data[15]]) as usize;
+        if data.len() < length {
+            return Ok(Parsed::Incomplete);
+        }
+        let addresses = parse_v2(&data[..length])?;
+        return Ok(Parsed::Header { length, addresses });
+    }
+
+    if matches_prefix(data, V1_PREFIX) {
+        let search = &data[..std::cmp::min(data.len(), V1_MAX_LENGTH)];
+        return match search.windows(2).position(|w| w == b"\r\n") {
+            Some(end) => Ok(Parsed::Header {
+                length: end + 2,
+                addresses: parse_v1(&data[..end])?,
+            }),
+            None if data.len() >= V1_MAX_LENGTH => Err(ProxyHeaderError::Invalid),
+            None => Ok(Parsed::Incomplete),
+        };
+    }
+
+    Ok(Parsed::NotProxy)
+}
+
+/// Reads the PROXY-Header, if there is one, from the Start of the
+/// Connection. Returns the Addresses from the Header and all the Data
+/// that was read after the Header or all the Data that was read if there
+/// is no Header
+pub async fn read_header<R>(
+    reader: &mut R,
+) -> Result<(Option<ProxyAddresses>, Vec<u8>), ProxyHeaderError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut data = Vec::with_capacity(256);
+    let mut buffer = [0; 256];
+    loop {
+        match parse(&data)? {
+            Parsed::NotProxy => return Ok((None, data)),
+            Parsed::Header { length, addresses } => {
+                return Ok((addresses, data.split_off(length)));
+            }
+            Parsed::Incomplete => {}
+        };
+
+        let read = reader
+            .read(&mut buffer)
+            .await
+            .map_err(ProxyHeaderError::IO)?;
+        if read == 0 {
+            return Ok((None, data));
+        }
+        data.extend_from_slice(&buffer[..read]);
+    }
+}
+
+/// Serializes the Header for the given Addresses in Version 1, if no
+/// Addresses are given, the Addresses are marked as unknown
+pub fn encode_v1(addresses: Option<&ProxyAddresses>) -> Vec<u8> {
+    let addresses = addresses.filter(|a| a.source.is_ipv4() == a.destination.is_ipv4());
+    match addresses {
+        Some(a) => format!(
+            "PROXY {} {} {} {} {}\r\n",
+            if a.source.is_ipv4() { "TCP4" } else { "TCP6" },
+            a.source.ip(),
+            a.destination.ip(),
+            a.source.port(),
+            a.destination.port()
+        )
+        .into_bytes(),
+        None => b"PROXY UNKNOWN\r\n".to_vec(),
+    }
+}
+
+/// Serializes the Header for the given Addresses in Version 2, if no
+/// Addresses are given, the LOCAL-Command is used
+pub fn encode_v2(addresses: Option<&ProxyAddresses>) -> Vec<u8> {
+    let mut result = V2_SIGNATURE.to_vec();
+
+    let mut body = Vec::with_capacity(36);
+    let family = match addresses.map(|a| (a.source, a.destination)) {
+        Some((SocketAddr::V4(source), SocketAddr::V4(destination))) => {
+            body.extend_from_slice(&source.ip().octets());
+            body.extend_from_slice(&destination.ip().octets());
+            body.extend_from_slice(&source.port().to_be_bytes());
+            body.extend_from_slice(&destination.port().to_be_bytes());
+            0x11
+        }
+        Some((source, destination)) => {
+            let ipv6 = |addr: SocketAddr| match addr.ip() {
+                IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+                IpAddr::V6(ip) => ip,
+            };
+            body.extend_from_slice(&ipv6(source).octets());
+            body.extend_from_slice(&ipv6(destination).octets());
+            body.extend_from_slice(&source.port().to_be_bytes());
+            body.extend_from_slice(&destination.port().to_be_bytes());
+            0x21
+        }
+        None => 0x00,
+    };
+
+    let command = if addresses.is_some() { 0x21 } else { 0x20 };
+    result.push(command);
+    result.push(family);
+    result.extend_from_slice(&(body.len() as u16).to_be_bytes());
+    result.extend_from_slice(&body);
+    result
+}
+
+/// Serializes the Header for the given Addresses in the given Version
+pub fn encode(version: ProxyProtocol, addresses: Option<&ProxyAddresses>) -> Vec<u8> {
+    match version {
+        ProxyProtocol::V1 => encode_v1(addresses),
+        ProxyProtocol::V2 => encode_v2(addresses),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(source: &str, destination: &str) -> ProxyAddresses {
+        ProxyAddresses {
+            source: source.parse().unwrap(),
+            destination: destination.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn parse_v1_header() {
+        let data = b"PROXY TCP4 192.168.0.1 10.0.0.1 56324 443\r\nGET / HTTP/1.1\r\n";
+        assert_eq!(
+            Parsed::Header {
+                length: 43,
+                addresses: Some(addresses("192.168.0.1:56324", "10.0.0.1:443")),
+            },
+            parse(data).unwrap()
+        );
+
+        assert_eq!(
+            Parsed::Header {
+                length: 15,
+                addresses: None,
+            },
+            parse(b"PROXY UNKNOWN\r\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_incomplete() {
+        assert_eq!(Parsed::Incomplete, parse(b"").unwrap());
+        assert_eq!(Parsed::Incomplete, parse(b"PRO").unwrap());
+        assert_eq!(Parsed::Incomplete, parse(b"PROXY TCP4 1.2").unwrap());
+        assert_eq!(Parsed::Incomplete, parse(&V2_SIGNATURE[..8]).unwrap());
+    }
+
+    #[test]
+    fn parse_not_proxy() {
+        assert_eq!(Parsed::NotProxy, parse(b"GET / HTTP/1.1\r\n").unwrap());
+        assert_eq!(Parsed::NotProxy, parse(b"\x16\x03\x01").unwrap());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(true, parse(b"PROXY TCP4 invalid\r\n").is_err());
+        assert_eq!(true, parse(b"PROXY SCTP 1.2.3.4 1.2.3.4 1 1\r\n").is_err());
+        assert_eq!(
+            true,
+            parse(&[V1_PREFIX, &[b'a'; 128][..]].concat()).is_err()
+        );
+    }
+
+    #[test]
+    fn v1_roundtrip() {
+        let tmp = addresses("[2001:db8::1]:1234", "[2001:db8::2]:80");
+        let encoded = encode_v1(Some(&tmp));
+        assert_eq!(
+            b"PROXY TCP6 2001:db8::1 2001:db8::2 1234 80\r\n".to_vec(),
+            encoded
+        );
+        assert_eq!(
+            Parsed::Header {
+                length: encoded.len(),
+                addresses: Some(tmp),
+            },
+            parse(&encoded).unwrap()
+        );
+
+        let mixed = addresses("1.2.3.4:1234", "[2001:db8::2]:80");
+        assert_eq!(b"PROXY UNKNOWN\r\n".to_vec(), encode_v1(Some(&mixed)));
+    }
+
+    #[test]
+    fn v2_roundtrip() {
+        for tmp in [
+            addresses("192.168.0.1:56324", "10.0.0.1:443"),
+            addresses("[2001:db8::1]:1234", "[2001:db8::2]:80"),
+        ] {
+            let encoded = encode_v2(Some(&tmp));
+            assert_eq!(
+                Parsed::Header {
+                    length: encoded.len(),
+                    addresses: Some(tmp),
+                },
+                parse(&encoded).unwrap()
+            );
+        }
+
+        let local = encode_v2(None);
+        assert_eq!(16, local.len());
+        assert_eq!(
+            Parsed::Header {
+                length: 16,
+                addresses: None,
+            },
+            parse(&local).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn read_with_remaining_data() {
+        let mut data = encode_v2(Some(&addresses("1.2.3.4:1234", "5.6.7.8:80")));
+        data.extend_from_slice(b"GET / HTTP/1.1\r\n\r\n");
+
+        let (result, remaining) = read_header(&mut &data[..]).await.unwrap();
+        assert_eq!(Some(addresses("1.2.3.4:1234", "5.6.7.8:80")), result);
+        assert_eq!(b"GET / HTTP/1.1\r\n\r\n".to_vec(), remaining);
+    }
+
+    #[tokio::test]
+    async fn read_without_header() {
+        let data = b"GET / HTTP/1.1\r\n\r\n".to_vec();
+
+        let (result, remaining) = read_header(&mut &data[..]).await.unwrap();
+        assert_eq!(None, result);
+        assert_eq!(data, remaining);
+    }
+}
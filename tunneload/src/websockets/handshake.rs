@@ -5,7 +5,7 @@ use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 
 use crate::forwarder::ServiceConnection;
 use general_traits::Sender;
-use rules::Rule;
+use rules::{ConnectionInfo, Rule};
 
 mod client_initial;
 
@@ -54,6 +54,7 @@ pub async fn handle<S>(
     initial: &Request<'_>,
     sender: &mut S,
     rule: &Rule,
+    conn_info: &ConnectionInfo,
     resp_parser: &mut RespParser,
 ) -> Option<(OwnedReadHalf, OwnedWriteHalf)>
 where
@@ -71,7 +72,9 @@ where
     // Actually "handle" the initial Data and possibly check
     // against certain filters, etc.
 
-    let mut connection = match rule.service().connect().await {
+    // The Endpoint is only held for the Handshake, so long running
+    // Websocket-Connections are not counted as outstanding Requests
+    let (mut connection, _endpoint) = match rule.service().connect_with(initial, conn_info).await {
         Ok(c) => c,
         Err(e) => {
             tracing::error!("Connecting to Service: {:?}", e);